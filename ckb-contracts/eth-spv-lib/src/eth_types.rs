@@ -8,7 +8,6 @@ use rlp::{
     Decodable as RlpDecodable, DecoderError as RlpDecoderError, Encodable as RlpEncodable, Rlp,
     RlpStream,
 };
-use rlp_derive::{RlpDecodable as RlpDecodableDerive, RlpEncodable as RlpEncodableDerive};
 
 macro_rules! arr_declare_wrapper_and_serde {
     ($name: ident, $len: expr) => {
@@ -160,12 +159,31 @@ pub struct BlockHeader {
     pub mix_hash: H256,
     ///
     pub nonce: H64,
+    /// EIP-1559 base fee, appended since london
+    pub base_fee_per_gas: Option<U256>,
+    /// EIP-4895 withdrawals root, appended since shanghai
+    pub withdrawals_root: Option<H256>,
+    /// EIP-4844 blob gas used, appended since cancun
+    pub blob_gas_used: Option<u64>,
+    /// EIP-4844 excess blob gas, appended since cancun
+    pub excess_blob_gas: Option<u64>,
+    /// EIP-4788 parent beacon block root, appended since cancun
+    pub parent_beacon_block_root: Option<H256>,
+    /// EIP-7685 requests hash, appended since prague
+    pub requests_hash: Option<H256>,
+    /// the raw rlp of the fields appended by the forks after the known ones
+    pub unknown_fields: Vec<Vec<u8>>,
     ///
     pub hash: Option<H256>,
     ///
     pub partial_hash: Option<H256>,
 }
 
+/// the number of the fields of the header before london.
+pub const PRE_LONDON_HEADER_FIELDS_COUNT: usize = 15;
+/// the number of the fields known by `BlockHeader`, the later ones are kept as unknown fields.
+pub const KNOWN_HEADER_FIELDS_COUNT: usize = 21;
+
 impl BlockHeader {
     ///
     pub fn extra_data(&self) -> H256 {
@@ -174,8 +192,40 @@ impl BlockHeader {
         H256(data.into())
     }
 
+    /// the raw rlp of the fields appended after the pre-london ones. a fork only appends fields
+    /// after the ones of the earlier forks, so the first absent field ends the header.
+    pub fn appended_fields(&self) -> Vec<Vec<u8>> {
+        let mut fields = Vec::new();
+        match &self.base_fee_per_gas {
+            Some(v) => fields.push(rlp::encode(v)),
+            None => return fields,
+        }
+        match &self.withdrawals_root {
+            Some(v) => fields.push(rlp::encode(v)),
+            None => return fields,
+        }
+        match (&self.blob_gas_used, &self.excess_blob_gas) {
+            (Some(used), Some(excess)) => {
+                fields.push(rlp::encode(used));
+                fields.push(rlp::encode(excess));
+            }
+            _ => return fields,
+        }
+        match &self.parent_beacon_block_root {
+            Some(v) => fields.push(rlp::encode(v)),
+            None => return fields,
+        }
+        match &self.requests_hash {
+            Some(v) => fields.push(rlp::encode(v)),
+            None => return fields,
+        }
+        fields.extend(self.unknown_fields.iter().cloned());
+        fields
+    }
+
     fn stream_rlp(&self, stream: &mut RlpStream, partial: bool) {
-        stream.begin_list(13 + if !partial { 2 } else { 0 });
+        let appended_fields = self.appended_fields();
+        stream.begin_list(13 + if !partial { 2 } else { 0 } + appended_fields.len());
 
         stream.append(&self.parent_hash);
         stream.append(&self.uncles_hash);
@@ -195,6 +245,10 @@ impl BlockHeader {
             stream.append(&self.mix_hash);
             stream.append(&self.nonce);
         }
+
+        for field in appended_fields.iter() {
+            stream.append_raw(field, 1);
+        }
     }
 }
 
@@ -206,6 +260,14 @@ impl RlpEncodable for BlockHeader {
 
 impl RlpDecodable for BlockHeader {
     fn decode(serialized: &Rlp) -> Result<Self, RlpDecoderError> {
+        let fields_count = serialized.item_count()?;
+        if fields_count < PRE_LONDON_HEADER_FIELDS_COUNT {
+            return Err(RlpDecoderError::RlpIncorrectListLen);
+        }
+        let mut unknown_fields = Vec::new();
+        for index in KNOWN_HEADER_FIELDS_COUNT..fields_count {
+            unknown_fields.push(serialized.at(index)?.as_raw().to_vec());
+        }
         let mut block_header = BlockHeader {
             parent_hash: serialized.val_at(0)?,
             uncles_hash: serialized.val_at(1)?,
@@ -222,6 +284,13 @@ impl RlpDecodable for BlockHeader {
             extra_data: serialized.val_at(12)?,
             mix_hash: serialized.val_at(13)?,
             nonce: serialized.val_at(14)?,
+            base_fee_per_gas: optional_val_at(serialized, 15)?,
+            withdrawals_root: optional_val_at(serialized, 16)?,
+            blob_gas_used: optional_val_at(serialized, 17)?,
+            excess_blob_gas: optional_val_at(serialized, 18)?,
+            parent_beacon_block_root: optional_val_at(serialized, 19)?,
+            requests_hash: optional_val_at(serialized, 20)?,
+            unknown_fields,
             hash: Some(keccak256(serialized.as_raw()).into()),
             partial_hash: None,
         };
@@ -239,6 +308,14 @@ impl RlpDecodable for BlockHeader {
     }
 }
 
+fn optional_val_at<T: RlpDecodable>(rlp: &Rlp, index: usize) -> Result<Option<T>, RlpDecoderError> {
+    if index < rlp.item_count()? {
+        Ok(Some(rlp.val_at(index)?))
+    } else {
+        Ok(None)
+    }
+}
+
 /// Log
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct LogEntry {
@@ -271,8 +348,10 @@ impl rlp::Encodable for LogEntry {
 }
 
 /// Receipt
-#[derive(Debug, Clone, PartialEq, Eq, RlpEncodableDerive, RlpDecodableDerive)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Receipt {
+    /// EIP-2718 transaction type, 0 for the legacy receipt
+    pub tx_type: u8,
     ///
     pub status: bool,
     ///
@@ -283,6 +362,67 @@ pub struct Receipt {
     pub logs: Vec<LogEntry>,
 }
 
+/// the legacy transaction type, the receipt has no type prefix.
+pub const LEGACY_TX_TYPE: u8 = 0;
+/// the highest EIP-2718 transaction type, the first byte of a legacy receipt is an rlp list
+/// prefix which is at least 0xc0.
+pub const MAX_TYPED_TX_TYPE: u8 = 0x7f;
+
+impl Receipt {
+    /// Decode the receipt stored in the receipts trie. The legacy receipt is the rlp of the
+    /// receipt, the typed receipt is the EIP-2718 envelope `tx_type || rlp(receipt)`.
+    pub fn decode_envelope(data: &[u8]) -> Result<Self, RlpDecoderError> {
+        match data.first() {
+            None => Err(RlpDecoderError::RlpIsTooShort),
+            Some(&tx_type) if tx_type <= MAX_TYPED_TX_TYPE => {
+                if tx_type == LEGACY_TX_TYPE {
+                    return Err(RlpDecoderError::Custom("invalid receipt type"));
+                }
+                let mut receipt: Receipt = rlp::decode(&data[1..])?;
+                receipt.tx_type = tx_type;
+                Ok(receipt)
+            }
+            Some(_) => rlp::decode(data),
+        }
+    }
+
+    /// Encode the receipt as it is stored in the receipts trie.
+    pub fn encode_envelope(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        if self.tx_type != LEGACY_TX_TYPE {
+            data.push(self.tx_type);
+        }
+        data.extend_from_slice(&rlp::encode(self));
+        data
+    }
+}
+
+/// The rlp of the receipt body, without the type prefix of the typed receipt.
+impl RlpDecodable for Receipt {
+    fn decode(rlp: &Rlp) -> Result<Self, RlpDecoderError> {
+        if rlp.item_count()? != 4 {
+            return Err(RlpDecoderError::RlpIncorrectListLen);
+        }
+        Ok(Receipt {
+            tx_type: LEGACY_TX_TYPE,
+            status: rlp.val_at(0)?,
+            gas_used: rlp.val_at(1)?,
+            log_bloom: rlp.val_at(2)?,
+            logs: rlp.list_at(3)?,
+        })
+    }
+}
+
+impl RlpEncodable for Receipt {
+    fn rlp_append(&self, stream: &mut RlpStream) {
+        stream.begin_list(4);
+        stream.append(&self.status);
+        stream.append(&self.gas_used);
+        stream.append(&self.log_bloom);
+        stream.append_list::<LogEntry, _>(&self.logs);
+    }
+}
+
 /// keccak256
 pub fn keccak256(data: &[u8]) -> [u8; 32] {
     let mut buffer = [0u8; 32];
@@ -7,14 +7,15 @@ use rlp::Rlp;
 use alloc::{vec, vec::Vec};
 
 /// verify the log entry is valid.
+/// the receipt_data is the value in the receipts trie, the typed receipt keeps its type prefix.
 pub fn verify_log_entry(
     receipt_index: u64,
     receipt_data: Vec<u8>,
     receipts_root: H256,
     proof: Vec<Vec<u8>>,
 ) -> Receipt {
-    let receipt: Receipt = rlp::decode(receipt_data.as_slice()).expect("invalid receipt data");
-    // Verify the trie proof is valid.
+    let receipt = Receipt::decode_envelope(receipt_data.as_slice()).expect("invalid receipt data");
+    // Verify the trie proof is valid, the raw receipt data is what the trie stores.
     assert!(
         verify_trie_proof(
             receipts_root,
@@ -27,6 +28,24 @@ pub fn verify_log_entry(
     receipt
 }
 
+/// verify the transaction is in the block, and return its hash.
+/// the tx_data is the value in the transactions trie, the typed transaction keeps its type prefix,
+/// so its keccak256 hash is the transaction hash.
+pub fn verify_transaction(
+    tx_index: u64,
+    tx_data: Vec<u8>,
+    transactions_root: H256,
+    proof: Vec<Vec<u8>>,
+) -> H256 {
+    assert!(!proof.is_empty(), "transaction proof is invalid");
+    let tx_hash = keccak256(tx_data.as_slice());
+    assert!(
+        verify_trie_proof(transactions_root, rlp::encode(&tx_index), proof, tx_data),
+        "transaction proof is invalid"
+    );
+    tx_hash.into()
+}
+
 /// Iterate the proof following the key.
 /// Return True if the value at the leaf is equal to the expected value.
 /// @param expected_root is the expected root of the current proof node.
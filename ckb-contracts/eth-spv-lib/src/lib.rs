@@ -12,3 +12,6 @@ pub mod eth_types;
 
 /// `ethspv` provides higher-levels of abstraction for evaluating SPV proofs.
 pub mod ethspv;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,212 @@
+extern crate std;
+
+use crate::eth_types::*;
+use crate::ethspv::verify_transaction;
+use std::vec;
+use std::vec::Vec;
+
+const HEADERS: &str = include_str!("../test-data/headers.txt");
+const TRANSACTIONS: &str = include_str!("../test-data/transactions.txt");
+
+fn get_header_fixture(name: &str) -> (H256, Vec<u8>) {
+    get_fixture(HEADERS, name)
+}
+
+fn get_transaction_fixture(name: &str) -> (H256, Vec<u8>) {
+    get_fixture(TRANSACTIONS, name)
+}
+
+fn get_fixture(fixtures: &str, name: &str) -> (H256, Vec<u8>) {
+    let line = fixtures
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .find(|line| line.split_whitespace().next() == Some(name))
+        .expect("header fixture not found");
+    let items: Vec<&str> = line.split_whitespace().collect();
+    let hash = hex::decode(items[1]).expect("invalid hash hex");
+    let raw = hex::decode(items[2]).expect("invalid raw hex");
+    (hash.into(), raw)
+}
+
+// the trie leaf node of the value, whose path is the rest nibbles of the key with the leaf prefix
+fn leaf_node(nibbles: &[u8], value: &[u8]) -> Vec<u8> {
+    let (mut path, rest) = if nibbles.len() % 2 == 1 {
+        (vec![0x30 | nibbles[0]], &nibbles[1..])
+    } else {
+        (vec![0x20], nibbles)
+    };
+    for pair in rest.chunks(2) {
+        path.push(pair[0] << 4 | pair[1]);
+    }
+    let mut stream = rlp::RlpStream::new_list(2);
+    stream.append(&path);
+    stream.append(&value.to_vec());
+    stream.out()
+}
+
+fn check_round_trip(raw: &[u8]) -> BlockHeader {
+    let header: BlockHeader = rlp::decode(raw).expect("decode header");
+    assert_eq!(header.hash, Some(keccak256(raw).into()));
+    assert_eq!(rlp::encode(&header), raw);
+    assert_eq!(
+        rlp::Rlp::new(raw).item_count().unwrap(),
+        PRE_LONDON_HEADER_FIELDS_COUNT + header.appended_fields().len()
+    );
+    header
+}
+
+fn check_fixture(name: &str) -> BlockHeader {
+    let (hash, raw) = get_header_fixture(name);
+    let header = check_round_trip(raw.as_slice());
+    assert_eq!(header.hash, Some(hash));
+    header
+}
+
+// the header of the fixture with the fields kept or appended, its hash is not the one on chain.
+fn rebuild_header(name: &str, fields_count: usize, appended: &[Vec<u8>]) -> Vec<u8> {
+    let (_, raw) = get_header_fixture(name);
+    let rlp = rlp::Rlp::new(raw.as_slice());
+    let mut stream = rlp::RlpStream::new_list(fields_count + appended.len());
+    for index in 0..fields_count {
+        stream.append_raw(rlp.at(index).unwrap().as_raw(), 1);
+    }
+    for field in appended {
+        stream.append_raw(field, 1);
+    }
+    stream.out()
+}
+
+#[test]
+fn test_pre_london_header() {
+    for name in ["mainnet_genesis", "mainnet_1"].iter() {
+        let header = check_fixture(name);
+        assert_eq!(header.base_fee_per_gas, None);
+        assert!(header.appended_fields().is_empty());
+    }
+    let header = check_fixture("mainnet_1");
+    assert_eq!(header.number, 1);
+    assert_eq!(header.timestamp, 1438269988);
+}
+
+// there is no london or shanghai header on chain in the fixtures, the layouts are checked with the
+// fields of the cancun header.
+#[test]
+fn test_london_header() {
+    let raw = rebuild_header("mainnet_cancun", PRE_LONDON_HEADER_FIELDS_COUNT + 1, &[]);
+    let header = check_round_trip(raw.as_slice());
+    assert_eq!(
+        header.base_fee_per_gas,
+        Some(U256(ethereum_types::U256::from(0x886b221adu64)))
+    );
+    assert_eq!(header.withdrawals_root, None);
+}
+
+#[test]
+fn test_shanghai_header() {
+    let raw = rebuild_header("mainnet_cancun", PRE_LONDON_HEADER_FIELDS_COUNT + 2, &[]);
+    let header = check_round_trip(raw.as_slice());
+    assert!(header.withdrawals_root.is_some());
+    assert_eq!(header.blob_gas_used, None);
+}
+
+#[test]
+fn test_cancun_header() {
+    let header = check_fixture("mainnet_cancun");
+    assert_eq!(header.number, 19449567);
+    assert_eq!(header.timestamp, 0x65f5f4c3);
+    assert!(header.withdrawals_root.is_some());
+    assert_eq!(header.blob_gas_used, Some(0));
+    assert_eq!(header.excess_blob_gas, Some(0));
+    assert!(header.parent_beacon_block_root.is_some());
+    assert_eq!(header.requests_hash, None);
+}
+
+#[test]
+fn test_prague_header() {
+    let header = check_fixture("testnet_prague");
+    assert_eq!(header.number, 411443);
+    // the sha256 of the empty requests
+    assert_eq!(header.requests_hash, Some(hash256(&[]).into()));
+    assert!(header.unknown_fields.is_empty());
+}
+
+#[test]
+fn test_header_with_unknown_fields() {
+    let fields_count = rlp::Rlp::new(get_header_fixture("testnet_prague").1.as_slice())
+        .item_count()
+        .unwrap();
+    let raw = rebuild_header("testnet_prague", fields_count, &[rlp::encode(&7u64)]);
+    let header = check_round_trip(raw.as_slice());
+    assert_eq!(header.unknown_fields, [rlp::encode(&7u64)]);
+}
+
+#[test]
+fn test_header_partial_hash_excludes_seal() {
+    let (_, raw) = get_header_fixture("mainnet_cancun");
+    let header: BlockHeader = rlp::decode(raw.as_slice()).unwrap();
+    let mut other = header.clone();
+    other.mix_hash = H256::from([1u8; 32]);
+    other.nonce = H64::from([1u8; 8]);
+    let other: BlockHeader = rlp::decode(rlp::encode(&other).as_slice()).unwrap();
+    assert_ne!(header.hash, other.hash);
+    assert_eq!(header.partial_hash, other.partial_hash);
+}
+
+#[test]
+fn test_header_too_few_fields() {
+    let (_, raw) = get_header_fixture("mainnet_1");
+    let rlp = rlp::Rlp::new(raw.as_slice());
+    let mut stream = rlp::RlpStream::new_list(PRE_LONDON_HEADER_FIELDS_COUNT - 1);
+    for index in 0..PRE_LONDON_HEADER_FIELDS_COUNT - 1 {
+        stream.append_raw(rlp.at(index).unwrap().as_raw(), 1);
+    }
+    let res: Result<BlockHeader, _> = rlp::decode(stream.out().as_slice());
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_verify_single_transaction() {
+    let (hash, raw) = get_transaction_fixture("mainnet_eip1559");
+    // the only tx of the block, the nibbles of its key rlp(0) are [8, 0]
+    let leaf = leaf_node(&[8, 0], &raw);
+    let root: H256 = keccak256(&leaf).into();
+    assert_eq!(verify_transaction(0, raw, root, vec![leaf]), hash);
+}
+
+#[test]
+fn test_verify_transaction_in_branch() {
+    let (hash0, raw0) = get_transaction_fixture("mainnet_eip1559");
+    let (hash1, raw1) = get_transaction_fixture("mainnet_legacy");
+    // the keys rlp(0) = 0x80 and rlp(1) = 0x01 fork at the first nibble
+    let leaf0 = leaf_node(&[0], &raw0);
+    let leaf1 = leaf_node(&[1], &raw1);
+    let mut stream = rlp::RlpStream::new_list(17);
+    for nibble in 0..16 {
+        match nibble {
+            0 => stream.append(&keccak256(&leaf1).to_vec()),
+            8 => stream.append(&keccak256(&leaf0).to_vec()),
+            _ => stream.append_empty_data(),
+        };
+    }
+    stream.append_empty_data();
+    let branch = stream.out();
+    let root: H256 = keccak256(&branch).into();
+
+    assert_eq!(
+        verify_transaction(0, raw0, root.clone(), vec![branch.clone(), leaf0]),
+        hash0
+    );
+    assert_eq!(
+        verify_transaction(1, raw1, root, vec![branch, leaf1]),
+        hash1
+    );
+}
+
+#[test]
+#[should_panic(expected = "transaction proof is invalid")]
+fn test_verify_transaction_with_wrong_index() {
+    let (_, raw) = get_transaction_fixture("mainnet_eip1559");
+    let leaf = leaf_node(&[8, 0], &raw);
+    let root: H256 = keccak256(&leaf).into();
+    verify_transaction(1, raw, root, vec![leaf]);
+}
@@ -5,13 +5,29 @@ use contracts_helper::debug;
 use eth_spv_lib::eth_types::*;
 use eth_spv_lib::ethspv;
 use force_eth_types::config::CONFIRM;
+use force_eth_types::consumed_event_registry::{
+    consumed_event_key, is_registry_mode, CONSUMED_EVENT_VALUE,
+};
 use force_eth_types::eth_lock_event::ETHLockEvent;
-use force_eth_types::generated::eth_bridge_lock_cell::ETHBridgeLockArgs;
+use force_eth_types::generated::basic::{Byte32Reader, Uint64Reader};
+use force_eth_types::generated::eth_bridge_lock_cell::{
+    ETHBridgeLockArgs, ETHBridgeLockArgsReader,
+};
+use force_eth_types::generated::eth_bridge_status_cell::ETHBridgeStatusCellDataReader;
+use force_eth_types::generated::eth_consumed_event_registry_cell::{
+    ETHConsumedEventRegistryCellDataReader, ETHConsumedEventRegistryWitnessReader,
+    ETHTransactionProofVecReader,
+};
 use force_eth_types::generated::eth_header_cell::ETHHeaderCellMerkleDataReader;
-use force_eth_types::generated::witness::{ETHSPVProofReader, MintTokenWitnessReader};
+use force_eth_types::generated::eth_token_allowlist_cell::ETHTokenAllowlistCellDataReader;
+use force_eth_types::generated::witness::{
+    ETHSPVProofReader, MintTokenBatchWitnessReader, MintTokenWitnessReader,
+};
 use force_eth_types::hasher::Blake2bHasher;
 use molecule::prelude::*;
+use sparse_merkle_tree::H256;
 use std::convert::TryInto;
+use std::prelude::v1::*;
 
 /// In manage mode, mint associated sudt is forbidden.
 /// Owners can do options like destroy the cell or supply capacity for it,
@@ -32,6 +48,15 @@ pub fn verify_mint_token<T: Adapter>(data_loader: &T, witness: &MintTokenWitness
     verify_eth_receipt_info(data_loader, dep_index, eth_receipt_info);
 }
 
+/// In batch mint mode, we verify several lock events in one tx the same way as mint mode.
+/// 1. Verify all the headers against the light client cell with one combined merkle proof.
+/// 2. Verify the i-th input is the replay resist cell of the i-th lock event.
+/// 3. Verify the output 2i is the recipient sudt cell of the i-th lock event.
+pub fn verify_batch_mint_token<T: Adapter>(data_loader: &T, witness: &MintTokenBatchWitnessReader) {
+    let (dep_index, eth_receipt_infos) = verify_batch_witness(data_loader, witness);
+    verify_batch_eth_receipt_infos(data_loader, dep_index, eth_receipt_infos);
+}
+
 /// Verify eth witness data.
 /// 1. Verify that the header of the user's cross-chain tx is on the main chain.
 /// 2. Verify that the user's cross-chain transaction is legal and really exists (based on spv proof).
@@ -43,7 +68,7 @@ fn verify_witness<T: Adapter>(
     let proof = witness.spv_proof().raw_data();
     debug!("proof {:?}", proof);
     let cell_dep_index_list = witness.cell_dep_index_list().raw_data();
-    assert_eq!(cell_dep_index_list.len(), 1);
+    assert_eq!(cell_dep_index_list.len(), 3);
 
     let merkle_proof = witness.merkle_proof().raw_data();
     let lock_event = verify_eth_spv_proof(data_loader, proof, cell_dep_index_list, merkle_proof);
@@ -63,23 +88,55 @@ fn verify_eth_spv_proof<T: Adapter>(
     cell_dep_index_list: &[u8],
     merkle_proof: &[u8],
 ) -> ETHLockEvent {
+    let (proof_reader, header) = parse_eth_spv_proof(proof);
+    // debug!("the spv proof header data: {:?}", header);
+
+    //verify the header is on main chain.
+    verify_eth_header_on_main_chain(data_loader, &[&header], cell_dep_index_list, merkle_proof);
+
+    get_eth_receipt_info(proof_reader, header)
+}
+
+fn verify_batch_witness<T: Adapter>(
+    data_loader: &T,
+    witness: &MintTokenBatchWitnessReader,
+) -> (u8, Vec<ETHLockEvent>) {
+    let spv_proofs = witness.spv_proofs();
+    if spv_proofs.is_empty() {
+        panic!("batch witness has no spv proof");
+    }
+    let cell_dep_index_list = witness.cell_dep_index_list().raw_data();
+    assert_eq!(cell_dep_index_list.len(), 3);
+
+    let proofs = (0..spv_proofs.len())
+        .map(|i| parse_eth_spv_proof(spv_proofs.get_unchecked(i).raw_data()))
+        .collect::<Vec<_>>();
+    let headers = proofs.iter().map(|(_, header)| header).collect::<Vec<_>>();
+
+    //verify all the headers are on main chain with the combined merkle proof.
+    let merkle_proof = witness.merkle_proof().raw_data();
+    verify_eth_header_on_main_chain(data_loader, &headers, cell_dep_index_list, merkle_proof);
+
+    let lock_events = proofs
+        .into_iter()
+        .map(|(proof_reader, header)| get_eth_receipt_info(proof_reader, header))
+        .collect();
+    (cell_dep_index_list[0], lock_events)
+}
+
+fn parse_eth_spv_proof(proof: &[u8]) -> (ETHSPVProofReader, BlockHeader) {
     if ETHSPVProofReader::verify(proof, false).is_err() {
         panic!("eth spv proof is invalid")
     }
     let proof_reader = ETHSPVProofReader::new_unchecked(proof);
     let header: BlockHeader =
         rlp::decode(proof_reader.header_data().raw_data()).expect("invalid header data");
-    // debug!("the spv proof header data: {:?}", header);
-
-    //verify the header is on main chain.
-    verify_eth_header_on_main_chain(data_loader, &header, cell_dep_index_list, merkle_proof);
-
-    get_eth_receipt_info(proof_reader, header)
+    (proof_reader, header)
 }
 
 fn verify_eth_header_on_main_chain<T: Adapter>(
     data_loader: &T,
-    header: &BlockHeader,
+    headers: &[&BlockHeader],
     cell_dep_index_list: &[u8],
     merkle_proof: &[u8],
 ) {
@@ -96,32 +153,183 @@ fn verify_eth_header_on_main_chain<T: Adapter>(
     let eth_cell_data_reader = ETHHeaderCellMerkleDataReader::new_unchecked(&dep_data);
     debug!("eth_cell_data_reader: {:?}", eth_cell_data_reader);
 
-    let mut light_client_latest_height = [0u8; 8];
-    light_client_latest_height.copy_from_slice(eth_cell_data_reader.latest_height().raw_data());
-    let light_client_latest_height: u64 = u64::from_le_bytes(light_client_latest_height);
+    verify_bridge_not_paused(
+        data_loader,
+        cell_dep_index_list,
+        eth_cell_data_reader
+            .bridge_status_typescript_hash()
+            .raw_data(),
+    );
+    verify_token_allowed(
+        data_loader,
+        cell_dep_index_list,
+        eth_cell_data_reader
+            .token_allowlist_typescript_hash()
+            .raw_data(),
+    );
 
-    if header.number + CONFIRM as u64 > light_client_latest_height {
-        panic!("header is not confirmed on light client yet");
-    }
+    // the confirm of the light client cell can only raise the compile-time minimum CONFIRM
+    let mut light_client_confirm = [0u8; 8];
+    light_client_confirm.copy_from_slice(eth_cell_data_reader.confirm().raw_data());
+    let confirm = core::cmp::max(u64::from_le_bytes(light_client_confirm), CONFIRM as u64);
 
-    let mut merkle_root = [0u8; 32];
-    merkle_root.copy_from_slice(eth_cell_data_reader.merkle_root().raw_data());
+    // the headers lower than start_height are pruned from the light client
+    let mut light_client_start_height = [0u8; 8];
+    light_client_start_height.copy_from_slice(eth_cell_data_reader.start_height().raw_data());
+    let light_client_start_height: u64 = u64::from_le_bytes(light_client_start_height);
+
+    // the proof may be built against the current root or one of the previous roots kept in
+    // history, so the proof built with a lagging smt snapshot is still valid.
+    let mut roots = vec![parse_header_root(
+        eth_cell_data_reader.merkle_root(),
+        eth_cell_data_reader.latest_height(),
+    )];
+    let history = eth_cell_data_reader.history();
+    for root in [
+        history.nth0(),
+        history.nth1(),
+        history.nth2(),
+        history.nth3(),
+    ]
+    .iter()
+    {
+        let (merkle_root, latest_height) =
+            parse_header_root(root.merkle_root(), root.latest_height());
+        // the empty slots of history are zero
+        if latest_height != 0 {
+            roots.push((merkle_root, latest_height));
+        }
+    }
 
     let compiled_merkle_proof = sparse_merkle_tree::CompiledMerkleProof(merkle_proof.to_vec());
 
-    let mut compiled_leaves = vec![];
+    // several lock events may be in the same block, its header is only proved once.
+    let mut leaves: Vec<([u8; 32], [u8; 32])> = vec![];
+    for header in headers {
+        if header.number < light_client_start_height {
+            panic!("header is pruned from light client");
+        }
+
+        let mut leaf_index = [0u8; 32];
+        leaf_index[..8].copy_from_slice(header.number.to_le_bytes().as_ref());
+
+        let mut leaf_value = [0u8; 32];
+        leaf_value.copy_from_slice(header.hash.expect("header hash is none").0.as_bytes());
+
+        match leaves.iter().find(|(index, _)| index == &leaf_index) {
+            Some((_, value)) => assert_eq!(
+                value, &leaf_value,
+                "headers of the same height are different"
+            ),
+            None => leaves.push((leaf_index, leaf_value)),
+        }
+    }
+    let compiled_leaves: Vec<(H256, H256)> = leaves
+        .into_iter()
+        .map(|(index, value)| (index.into(), value.into()))
+        .collect();
+
+    let mut confirmed = false;
+    for (merkle_root, latest_height) in roots {
+        if headers
+            .iter()
+            .any(|header| header.number.saturating_add(confirm) > latest_height)
+        {
+            continue;
+        }
+        confirmed = true;
+        if compiled_merkle_proof
+            .verify::<Blake2bHasher>(&merkle_root.into(), compiled_leaves.clone())
+            .expect("verify compiled proof")
+        {
+            return;
+        }
+    }
+    if !confirmed {
+        panic!("header is not confirmed on light client yet");
+    }
+    panic!("merkle proof not match any root of light client");
+}
+
+/// The bridge status cell is pinned by the light client cell with its typescript hash,
+/// and its index in cell deps is the second one of cell_dep_index_list.
+/// Mints are refused while the bridge is paused.
+fn verify_bridge_not_paused<T: Adapter>(
+    data_loader: &T,
+    cell_dep_index_list: &[u8],
+    bridge_status_typescript_hash: &[u8],
+) {
+    let dep_index = *cell_dep_index_list
+        .get(1)
+        .expect("bridge status cell dep index not found");
+    let typescript_hash = data_loader
+        .load_dep_cell_typescript_hash(dep_index.into())
+        .expect("load cell dep typescript hash failed")
+        .expect("bridge status cell typescript is none");
+    assert_eq!(
+        &typescript_hash[..],
+        bridge_status_typescript_hash,
+        "bridge status cell not match"
+    );
 
-    let mut leaf_index = [0u8; 32];
-    leaf_index[..8].copy_from_slice(header.number.to_le_bytes().as_ref());
+    let data = data_loader
+        .load_cell_dep_data(dep_index.into())
+        .expect("load cell dep data failed");
+    if ETHBridgeStatusCellDataReader::verify(&data, false).is_err() {
+        panic!("bridge status cell data invalid");
+    }
+    if ETHBridgeStatusCellDataReader::new_unchecked(&data)
+        .paused()
+        .as_slice()[0]
+        != 0
+    {
+        panic!("bridge is paused");
+    }
+}
 
-    let mut leaf_value = [0u8; 32];
-    leaf_value.copy_from_slice(header.hash.expect("header hash is none").0.as_bytes());
+/// The token allowlist cell is pinned by the light client cell with its typescript hash,
+/// and its index in cell deps is the third one of cell_dep_index_list.
+/// Only the eth tokens in the allowlist can be minted.
+fn verify_token_allowed<T: Adapter>(
+    data_loader: &T,
+    cell_dep_index_list: &[u8],
+    token_allowlist_typescript_hash: &[u8],
+) {
+    let dep_index = *cell_dep_index_list
+        .get(2)
+        .expect("token allowlist cell dep index not found");
+    let typescript_hash = data_loader
+        .load_dep_cell_typescript_hash(dep_index.into())
+        .expect("load cell dep typescript hash failed")
+        .expect("token allowlist cell typescript is none");
+    assert_eq!(
+        &typescript_hash[..],
+        token_allowlist_typescript_hash,
+        "token allowlist cell not match"
+    );
 
-    compiled_leaves.push((leaf_index.into(), leaf_value.into()));
+    let data = data_loader
+        .load_cell_dep_data(dep_index.into())
+        .expect("load cell dep data failed");
+    if ETHTokenAllowlistCellDataReader::verify(&data, false).is_err() {
+        panic!("token allowlist cell data invalid");
+    }
+    let script_args = data_loader.load_script_args().unwrap();
+    let bridge_args = ETHBridgeLockArgs::new_unchecked(script_args);
+    if !ETHTokenAllowlistCellDataReader::new_unchecked(&data)
+        .iter()
+        .any(|token| token.as_slice() == bridge_args.eth_token_address().as_slice())
+    {
+        panic!("token is not in the allowlist");
+    }
+}
 
-    assert!(compiled_merkle_proof
-        .verify::<Blake2bHasher>(&merkle_root.into(), compiled_leaves)
-        .expect("verify compiled proof"));
+fn parse_header_root(merkle_root: Byte32Reader, latest_height: Uint64Reader) -> ([u8; 32], u64) {
+    let mut merkle_root_raw = [0u8; 32];
+    merkle_root_raw.copy_from_slice(merkle_root.raw_data());
+    let mut latest_height_raw = [0u8; 8];
+    latest_height_raw.copy_from_slice(latest_height.raw_data());
+    (merkle_root_raw, u64::from_le_bytes(latest_height_raw))
 }
 
 fn get_eth_receipt_info(proof_reader: ETHSPVProofReader, header: BlockHeader) -> ETHLockEvent {
@@ -159,7 +367,8 @@ fn get_eth_receipt_info(proof_reader: ETHSPVProofReader, header: BlockHeader) ->
 }
 
 /// Verify eth receipt info.
-/// 1. Verify replay_resist_cell_id exists in inputs.
+/// 1. Verify replay_resist_cell_id exists in inputs, or the registry cell is in inputs if the
+///    lock event is in registry mode.
 /// 2. verify contract_address equals to args.contract_address.
 /// 3. Verify token_address equals to args.token_address.
 /// 4. Verify dep cell typescript hash equals to args.light_client_typescript_hash.
@@ -173,31 +382,15 @@ fn verify_eth_receipt_info<T: Adapter>(
         "replay_resist_outpoint: {:?}",
         hex::encode(eth_receipt_info.replay_resist_outpoint.as_slice())
     );
-    if !data_loader.outpoint_exists_in_inputs(eth_receipt_info.replay_resist_outpoint.as_ref()) {
+    if is_registry_mode(eth_receipt_info.replay_resist_outpoint.as_ref()) {
+        verify_registry_mode_mint(data_loader, dep_index);
+    } else if !data_loader
+        .outpoint_exists_in_inputs(eth_receipt_info.replay_resist_outpoint.as_ref())
+    {
         panic!("replay_resist_cell_id not exists in inputs");
     }
 
-    let script_args = data_loader.load_script_args().unwrap();
-    let bridge_args = ETHBridgeLockArgs::new_unchecked(script_args);
-
-    assert_eq!(
-        bridge_args.eth_contract_address().as_slice(),
-        &eth_receipt_info.contract_address
-    );
-    assert_eq!(
-        bridge_args.eth_token_address().as_slice(),
-        &eth_receipt_info.token
-    );
-
-    let light_client_typescript_hash = data_loader
-        .load_dep_cell_typescript_hash(dep_index as usize)
-        .unwrap()
-        .unwrap();
-
-    assert_eq!(
-        bridge_args.light_client_typescript_hash().as_slice(),
-        &light_client_typescript_hash
-    );
+    verify_eth_lock_event_source(data_loader, dep_index, &[&eth_receipt_info]);
 
     let udt_typescript = data_loader.get_associated_udt_script();
     let udt_script_slice = udt_typescript.as_slice();
@@ -249,3 +442,353 @@ fn verify_eth_receipt_info<T: Adapter>(
         "recipient amount less than expected(mint_amount - bridge_fee)"
     );
 }
+
+/// Verify the lock events come from the eth contract, token and light client of this bridge.
+fn verify_eth_lock_event_source<T: Adapter>(
+    data_loader: &T,
+    dep_index: u8,
+    eth_receipt_infos: &[&ETHLockEvent],
+) {
+    let script_args = data_loader.load_script_args().unwrap();
+    let bridge_args = ETHBridgeLockArgs::new_unchecked(script_args);
+
+    for eth_receipt_info in eth_receipt_infos {
+        assert_eq!(
+            bridge_args.eth_contract_address().as_slice(),
+            &eth_receipt_info.contract_address
+        );
+        assert_eq!(
+            bridge_args.eth_token_address().as_slice(),
+            &eth_receipt_info.token
+        );
+    }
+
+    let light_client_typescript_hash = data_loader
+        .load_dep_cell_typescript_hash(dep_index as usize)
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        bridge_args.light_client_typescript_hash().as_slice(),
+        &light_client_typescript_hash
+    );
+}
+
+/// Verify eth receipt infos of batch mint mode.
+/// 1. Verify the replay_resist_cell_id of each lock event is the input at the same index, or the
+///    registry cell is in inputs if the lock event is in registry mode.
+/// 2. Verify the lock events come from this bridge.
+/// 3. Verify the output 2i is the recipient sudt cell of the i-th lock event, whose amount is
+///    not less than token_amount - bridge_fee.
+/// 4. Verify the total amount of sudt minted equals to the sum of token_amount.
+fn verify_batch_eth_receipt_infos<T: Adapter>(
+    data_loader: &T,
+    dep_index: u8,
+    eth_receipt_infos: Vec<ETHLockEvent>,
+) {
+    let mut registry_mode = false;
+    for (index, eth_receipt_info) in eth_receipt_infos.iter().enumerate() {
+        debug!(
+            "replay_resist_outpoint: {:?}",
+            hex::encode(eth_receipt_info.replay_resist_outpoint.as_slice())
+        );
+        // the lock events in registry mode are checked against the registry by its cell lock
+        if is_registry_mode(eth_receipt_info.replay_resist_outpoint.as_ref()) {
+            registry_mode = true;
+            continue;
+        }
+        if eth_receipt_infos[..index]
+            .iter()
+            .any(|info| info.replay_resist_outpoint == eth_receipt_info.replay_resist_outpoint)
+        {
+            panic!("replay_resist_cell_id is used by more than one lock event");
+        }
+        let input_outpoint = data_loader
+            .load_input_outpoint(index)
+            .expect("replay_resist_cell_id not exists in inputs");
+        if input_outpoint != eth_receipt_info.replay_resist_outpoint {
+            panic!("replay_resist_cell_id not match the input of the same index");
+        }
+    }
+    if registry_mode {
+        verify_registry_mode_mint(data_loader, dep_index);
+    }
+
+    verify_eth_lock_event_source(
+        data_loader,
+        dep_index,
+        &eth_receipt_infos.iter().collect::<Vec<_>>(),
+    );
+
+    let udt_typescript = data_loader.get_associated_udt_script();
+    let udt_script_slice = udt_typescript.as_slice();
+    let mut expected_mint_amount = 0u128;
+    for eth_receipt_info in eth_receipt_infos.iter() {
+        let locked_amount: u128 = eth_receipt_info
+            .locked_amount
+            .try_into()
+            .expect("locked amount overflow");
+        expected_mint_amount = expected_mint_amount
+            .checked_add(locked_amount)
+            .expect("locked amount overflow");
+    }
+    let mut mint_amount = 0u128;
+    let mut recipient_count = 0;
+    for (index, (output_type, output_lock, output_data)) in QueryIter::new(
+        |index, source| data_loader.load_cell_type_lock_data(index, source),
+        Source::Output,
+    )
+    .into_iter()
+    .enumerate()
+    {
+        let is_udt = output_type.is_some() && udt_script_slice == output_type.unwrap().as_slice();
+        let mut amount = 0u128;
+        if is_udt {
+            let mut amount_raw = [0u8; 16];
+            amount_raw.copy_from_slice(&output_data[..16]);
+            amount = u128::from_le_bytes(amount_raw);
+            mint_amount += amount;
+        }
+        if index % 2 != 0 || index / 2 >= eth_receipt_infos.len() {
+            continue;
+        }
+        let eth_receipt_info = &eth_receipt_infos[index / 2];
+        if !is_udt || output_lock.as_slice() != eth_receipt_info.recipient_lockscript.as_slice() {
+            panic!("recipient sudt cell not match the lock event of the same index");
+        }
+        assert_eq!(
+            &output_data[16..],
+            eth_receipt_info.sudt_extra_data.as_slice(),
+            "recipient sudt cell extra data not match"
+        );
+        let locked_amount: u128 = eth_receipt_info.locked_amount.try_into().unwrap();
+        let bridge_fee: u128 = eth_receipt_info
+            .bridge_fee
+            .try_into()
+            .expect("bridge fee overflow");
+        assert!(
+            amount >= locked_amount - bridge_fee,
+            "recipient amount less than expected(mint_amount - bridge_fee)"
+        );
+        recipient_count += 1;
+    }
+    assert_eq!(
+        recipient_count,
+        eth_receipt_infos.len(),
+        "recipient sudt cell not found"
+    );
+    assert_eq!(
+        mint_amount, expected_mint_amount,
+        "mint token amount not equal to expected"
+    );
+}
+
+/// The lock events whose replay_resist_outpoint is REGISTRY_MODE_OUTPOINT are minted without
+/// replay resist cells, the consumed event registry cell records them instead.
+/// 1. Verify the first input is in this script group, so its witness is the one checked by the
+///    registry cell lock.
+/// 2. Verify the registry cell pinned by the light client cell is in inputs.
+fn verify_registry_mode_mint<T: Adapter>(data_loader: &T, dep_index: u8) {
+    let first_input_lock_hash = data_loader
+        .load_cell_lock_hash(0, Source::Input)
+        .expect("load first input lock hash failed");
+    if first_input_lock_hash != data_loader.load_script_hash() {
+        panic!("lock event in registry mode should be minted by the first input");
+    }
+
+    // the light client cell data is verified with the headers
+    let dep_data = data_loader
+        .load_cell_dep_data(dep_index.into())
+        .expect("load cell dep data failed");
+    let registry_typescript_hash = ETHHeaderCellMerkleDataReader::new_unchecked(&dep_data)
+        .consumed_event_registry_typescript_hash()
+        .raw_data();
+    if !data_loader.typescript_hash_exists_in_inputs(registry_typescript_hash) {
+        panic!("consumed event registry cell not found in inputs");
+    }
+}
+
+/// The consumed event registry cell is locked by this script with the light client typescript
+/// hash as args. It can only be unlocked by the mint tx of the lock events in registry mode.
+/// 1. Verify the first input is a bridge cell of the same light client, whose witness is the mint
+///    witness.
+/// 2. Verify the registry cell is re-created in outputs with the same lock and no less capacity.
+/// 3. Verify the lock txs of the lock events in registry mode are in their blocks, with the
+///    transaction proofs in the witness of the registry cell.
+/// 4. Verify the lock events in registry mode are not in the old root and are in the new root,
+///    with the compiled merkle proof in the witness of the registry cell.
+pub fn verify_consumed_event_registry<T: Adapter>(data_loader: &T) {
+    let script = data_loader.load_script().expect("load script failed");
+    let (_, first_input_lock, _) = data_loader
+        .load_cell_type_lock_data(0, Source::Input)
+        .expect("load first input failed");
+    let first_input_args = first_input_lock.args().raw_data();
+    if first_input_lock.code_hash().as_slice() != script.code_hash().as_slice()
+        || first_input_lock.hash_type().as_slice() != script.hash_type().as_slice()
+        || ETHBridgeLockArgsReader::verify(&first_input_args, false).is_err()
+    {
+        panic!("the first input should be a bridge cell");
+    }
+    assert_eq!(
+        ETHBridgeLockArgsReader::new_unchecked(&first_input_args)
+            .light_client_typescript_hash()
+            .as_slice(),
+        script.args().raw_data().as_ref(),
+        "the first input should be a bridge cell of the same light client"
+    );
+
+    let mint_witness = data_loader
+        .load_witness_lock(0, Source::Input)
+        .expect("load mint witness failed")
+        .expect("mint witness lock field is none");
+    let registry_witness = data_loader
+        .load_witness_lock(0, Source::GroupInput)
+        .expect("load consumed event registry witness failed")
+        .expect("consumed event registry witness is none");
+    if ETHConsumedEventRegistryWitnessReader::verify(&registry_witness, false).is_err() {
+        panic!("consumed event registry witness is invalid");
+    }
+    let registry_witness = ETHConsumedEventRegistryWitnessReader::new_unchecked(&registry_witness);
+    let keys = get_consumed_event_keys(&mint_witness, registry_witness.transaction_proofs());
+
+    let input_data = data_loader.load_input_data();
+    let (input_type, input_lock, _) = data_loader
+        .load_cell_type_lock_data(0, Source::GroupInput)
+        .expect("load consumed event registry cell failed");
+    let input_type = input_type.expect("consumed event registry cell typescript is none");
+    let (output_index, (_, output_lock, output_data)) = QueryIter::new(
+        |index, source| data_loader.load_cell_type_lock_data(index, source),
+        Source::Output,
+    )
+    .enumerate()
+    .find(|(_, (output_type, _, _))| {
+        output_type
+            .as_ref()
+            .map(|script| script.as_slice() == input_type.as_slice())
+            .unwrap_or(false)
+    })
+    .expect("consumed event registry cell not found in outputs");
+    assert_eq!(
+        output_lock.as_slice(),
+        input_lock.as_slice(),
+        "consumed event registry cell lock should not be changed"
+    );
+    let input_capacity = data_loader
+        .load_cell_capacity(0, Source::GroupInput)
+        .expect("load consumed event registry cell capacity failed");
+    let output_capacity = data_loader
+        .load_cell_capacity(output_index, Source::Output)
+        .expect("load consumed event registry cell capacity failed");
+    assert!(
+        output_capacity >= input_capacity,
+        "consumed event registry cell capacity should not be decreased"
+    );
+
+    let old_root = parse_consumed_events_root(&input_data);
+    let new_root = parse_consumed_events_root(&output_data);
+    let compiled_merkle_proof = sparse_merkle_tree::CompiledMerkleProof(
+        registry_witness.merkle_proof().raw_data().to_vec(),
+    );
+    let unconsumed_leaves: Vec<(H256, H256)> = keys
+        .iter()
+        .map(|key| ((*key).into(), H256::zero()))
+        .collect();
+    if !compiled_merkle_proof
+        .verify::<Blake2bHasher>(&old_root.into(), unconsumed_leaves)
+        .expect("verify compiled proof")
+    {
+        panic!("lock event is already minted");
+    }
+    let consumed_leaves: Vec<(H256, H256)> = keys
+        .iter()
+        .map(|key| ((*key).into(), CONSUMED_EVENT_VALUE.into()))
+        .collect();
+    if !compiled_merkle_proof
+        .verify::<Blake2bHasher>(&new_root.into(), consumed_leaves)
+        .expect("verify compiled proof")
+    {
+        panic!("consumed event registry root not match");
+    }
+}
+
+/// Get the registry keys of the lock events in registry mode from the mint witness, whose spv
+/// proofs are verified by the bridge cell lock. The i-th transaction proof proves the lock tx of
+/// the i-th lock event in registry mode, which is at the receipt index of the block.
+fn get_consumed_event_keys(
+    mint_witness: &[u8],
+    transaction_proofs: ETHTransactionProofVecReader,
+) -> Vec<[u8; 32]> {
+    let spv_proofs = if MintTokenWitnessReader::verify(mint_witness, false).is_ok() {
+        let witness = MintTokenWitnessReader::new_unchecked(mint_witness);
+        let mode: u8 = witness.mode().into();
+        if mode != 0 {
+            panic!("the first input is not in mint mode");
+        }
+        vec![witness.spv_proof().raw_data()]
+    } else {
+        MintTokenBatchWitnessReader::verify(mint_witness, false).expect("mint witness is invalid");
+        let spv_proofs = MintTokenBatchWitnessReader::new_unchecked(mint_witness).spv_proofs();
+        (0..spv_proofs.len())
+            .map(|i| spv_proofs.get_unchecked(i).raw_data())
+            .collect()
+    };
+
+    let mut keys: Vec<[u8; 32]> = vec![];
+    for spv_proof in spv_proofs {
+        let (proof_reader, header) = parse_eth_spv_proof(spv_proof);
+        let mut receipt_index = [0u8; 8];
+        receipt_index.copy_from_slice(proof_reader.receipt_index().raw_data());
+        let mut log_index = [0u8; 8];
+        log_index.copy_from_slice(proof_reader.log_index().raw_data());
+        let transactions_root = header.transactions_root.clone();
+
+        let lock_event = get_eth_receipt_info(proof_reader, header);
+        if !is_registry_mode(lock_event.replay_resist_outpoint.as_ref()) {
+            continue;
+        }
+        let transaction_proof = transaction_proofs
+            .get(keys.len())
+            .expect("transaction proof of lock event not found");
+        let proof = (0..transaction_proof.proof().len())
+            .map(|i| {
+                transaction_proof
+                    .proof()
+                    .get_unchecked(i)
+                    .raw_data()
+                    .to_vec()
+            })
+            .collect();
+        // it will panic inside the function if the proof is invalid
+        let tx_hash = ethspv::verify_transaction(
+            u64::from_le_bytes(receipt_index),
+            transaction_proof.transaction_data().raw_data().to_vec(),
+            transactions_root,
+            proof,
+        );
+        let key = consumed_event_key(tx_hash.0.as_bytes(), u64::from_le_bytes(log_index));
+        if keys.contains(&key) {
+            panic!("lock event is minted more than once");
+        }
+        keys.push(key);
+    }
+    if keys.is_empty() {
+        panic!("no lock event is minted in registry mode");
+    }
+    if transaction_proofs.len() != keys.len() {
+        panic!("transaction proofs not match the lock events in registry mode");
+    }
+    keys
+}
+
+fn parse_consumed_events_root(data: &[u8]) -> [u8; 32] {
+    if ETHConsumedEventRegistryCellDataReader::verify(data, false).is_err() {
+        panic!("consumed event registry cell data invalid");
+    }
+    let mut root = [0u8; 32];
+    root.copy_from_slice(
+        ETHConsumedEventRegistryCellDataReader::new_unchecked(data)
+            .consumed_events_root()
+            .raw_data(),
+    );
+    root
+}
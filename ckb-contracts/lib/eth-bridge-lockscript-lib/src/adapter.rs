@@ -7,7 +7,7 @@ use ckb_std::ckb_constants::Source;
 use ckb_std::ckb_types::{
     bytes::Bytes,
     packed::{Byte32, Script},
-    prelude::Pack,
+    prelude::{Pack, Unpack},
 };
 use ckb_std::error::SysError;
 use ckb_std::high_level::QueryIter;
@@ -34,6 +34,8 @@ pub trait Adapter {
 
     fn outpoint_exists_in_inputs(&self, outpoint: &[u8]) -> bool;
 
+    fn load_input_outpoint(&self, index: usize) -> Result<Vec<u8>, SysError>;
+
     /// load cell type, lock, data at the same time.
     fn load_cell_type_lock_data(
         &self,
@@ -53,6 +55,18 @@ pub trait Adapter {
     fn load_script_args(&self) -> Result<Bytes, SysError>;
 
     fn load_dep_cell_typescript_hash(&self, index: usize) -> Result<Option<[u8; 32]>, SysError>;
+
+    fn load_script(&self) -> Result<Script, SysError>;
+
+    fn load_cell_lock_hash(&self, index: usize, source: Source) -> Result<[u8; 32], SysError>;
+
+    fn load_cell_capacity(&self, index: usize, source: Source) -> Result<u64, SysError>;
+
+    /// load the lock field of the witness args, which is none if the field is empty.
+    fn load_witness_lock(&self, index: usize, source: Source) -> Result<Option<Bytes>, SysError>;
+
+    /// check whether there is any input typescript hash matches the given one
+    fn typescript_hash_exists_in_inputs(&self, hash: &[u8]) -> bool;
 }
 
 pub struct ChainAdapter<T: DataLoader> {
@@ -119,6 +133,11 @@ where
         .any(|outpoint| outpoint.as_slice() == data)
     }
 
+    fn load_input_outpoint(&self, index: usize) -> Result<Vec<u8>, SysError> {
+        let outpoint = self.chain.load_input_out_point(index, Source::Input)?;
+        Ok(outpoint.as_slice().to_vec())
+    }
+
     fn load_cell_type_lock_data(
         &self,
         index: usize,
@@ -136,4 +155,30 @@ where
     fn load_dep_cell_typescript_hash(&self, index: usize) -> Result<Option<[u8; 32]>, SysError> {
         self.chain.load_cell_type_hash(index, Source::CellDep)
     }
+
+    fn load_script(&self) -> Result<Script, SysError> {
+        self.chain.load_script()
+    }
+
+    fn load_cell_lock_hash(&self, index: usize, source: Source) -> Result<[u8; 32], SysError> {
+        self.chain.load_cell_lock_hash(index, source)
+    }
+
+    fn load_cell_capacity(&self, index: usize, source: Source) -> Result<u64, SysError> {
+        Ok(self.chain.load_cell(index, source)?.capacity().unpack())
+    }
+
+    fn load_witness_lock(&self, index: usize, source: Source) -> Result<Option<Bytes>, SysError> {
+        let witness_args = self.chain.load_witness_args(index, source)?;
+        Ok(witness_args.lock().to_opt().map(|lock| lock.raw_data()))
+    }
+
+    fn typescript_hash_exists_in_inputs(&self, data: &[u8]) -> bool {
+        QueryIter::new(
+            |index, source| self.chain.load_cell_type_hash(index, source),
+            Source::Input,
+        )
+        .filter_map(|hash_opt| hash_opt)
+        .any(|hash| hash.as_ref() == data)
+    }
 }
@@ -10,7 +10,7 @@ pub mod adapter;
 mod test;
 
 use adapter::Adapter;
-use force_eth_types::generated::witness::MintTokenWitnessReader;
+use force_eth_types::generated::witness::{MintTokenBatchWitnessReader, MintTokenWitnessReader};
 use molecule::prelude::Reader;
 
 #[cfg(target_arch = "riscv64")]
@@ -22,12 +22,27 @@ pub fn verify() -> i8 {
 }
 
 pub fn _verify<T: Adapter>(data_loader: T) {
+    // the script with the light client typescript hash as args locks the consumed event registry
+    let script_args = data_loader
+        .load_script_args()
+        .expect("load script args failed");
+    if script_args.len() == 32 {
+        actions::verify_consumed_event_registry(&data_loader);
+        return;
+    }
+
     // load and parse witness
     let witness_args = data_loader
         .load_input_witness_args()
         .expect("load witness args error");
     // debug!("witness args: {:?}", &witness_args);
-    MintTokenWitnessReader::verify(&witness_args, false).expect("witness is invalid");
+    if MintTokenWitnessReader::verify(&witness_args, false).is_err() {
+        // the batch witness is always in mint mode
+        MintTokenBatchWitnessReader::verify(&witness_args, false).expect("witness is invalid");
+        let witness = MintTokenBatchWitnessReader::new_unchecked(&witness_args);
+        actions::verify_batch_mint_token(&data_loader, &witness);
+        return;
+    }
     let witness = MintTokenWitnessReader::new_unchecked(&witness_args);
     // debug!("witness: {:?}", witness);
 
@@ -1,27 +1,52 @@
 use crate::_verify;
 use crate::adapter::*;
+use ckb_std::ckb_constants::Source;
 use ckb_std::ckb_types::packed::{self, CellOutput, OutPoint, Script, WitnessArgs};
 use ckb_std::ckb_types::prelude::Pack;
 use ckb_std::error::SysError;
 use contracts_helper::data_loader::MockDataLoader;
+use eth_spv_lib::eth_types::{keccak256, BlockHeader};
 use force_eth_types::config::{SUDT_CODE_HASH, SUDT_HASH_TYPE};
+use force_eth_types::consumed_event_registry::{consumed_event_key, CONSUMED_EVENT_VALUE};
 use force_eth_types::eth_recipient_cell::ETHAddress;
 use force_eth_types::generated::{
-    basic, eth_bridge_lock_cell::ETHBridgeLockArgs, eth_header_cell::ETHHeaderCellMerkleData,
-    witness::MintTokenWitness,
+    basic,
+    eth_bridge_lock_cell::ETHBridgeLockArgs,
+    eth_bridge_status_cell::ETHBridgeStatusCellData,
+    eth_consumed_event_registry_cell::{
+        ETHConsumedEventRegistryCellData, ETHConsumedEventRegistryWitness, ETHTransactionProof,
+        ETHTransactionProofVec,
+    },
+    eth_header_cell::{ETHHeaderCellMerkleData, ETHHeaderRoot, ETHHeaderRootHistory},
+    eth_token_allowlist_cell::ETHTokenAllowlistCellData,
+    witness::{ETHSPVProof, ETHSPVProofReader, MintTokenBatchWitness, MintTokenWitness},
 };
 use force_eth_types::hasher::Blake2bHasher;
 use molecule::bytes::Bytes;
-use molecule::prelude::{Builder, Byte, Entity};
+use molecule::prelude::{Builder, Byte, Entity, Reader};
+use rlp::RlpStream;
 use sparse_merkle_tree::{default_store::DefaultStore, SparseMerkleTree, H256};
 use std::convert::TryFrom;
 
 type SMT = SparseMerkleTree<Blake2bHasher, H256, DefaultStore<H256>>;
 
+const BRIDGE_STATUS_TYPESCRIPT_HASH: [u8; 32] = [4u8; 32];
+const TOKEN_ALLOWLIST_TYPESCRIPT_HASH: [u8; 32] = [6u8; 32];
+const CONSUMED_EVENT_REGISTRY_TYPESCRIPT_HASH: [u8; 32] = [7u8; 32];
+// a mainnet eip1559 tx, as the lock tx of the lock events in registry mode
+const LOCK_TX_DATA: &str = "02f86f0102843b9aca0085029e7822d68298f094d9e1459a7a482635700cbc20bbaf52d495ab9c9680841b55ba3ac080a0c199674fcb29f353693dd779c017823b954b3c69dffa3cd6b2a6ff7888798039a028ca912de909e7e6cdef9cdcaf24c54dd8c1032946dfa1d85c206b32a9064fe8";
+
 #[derive(Clone)]
 struct MintTestParams {
     block_number: u128,
+    start_number: u128,
     latest_number: u128,
+    confirm: u128,
+    history_latest_number: u128,
+    paused: bool,
+    bridge_status_typescript_hash: [u8; 32],
+    allowed_tokens: Vec<String>,
+    token_allowlist_typescript_hash: [u8; 32],
     block_hash: String,
     recipient_lockscript: Vec<u8>,
     recipient_amount: u128,
@@ -34,7 +59,13 @@ struct MintTestParams {
 
 fn get_correct_params() -> MintTestParams {
     let block_number = 45u128;
+    let start_number = 0u128;
     let latest_number = 100u128;
+    let confirm = 0u128;
+    let history_latest_number = 0u128;
+    let paused = false;
+    let bridge_status_typescript_hash = BRIDGE_STATUS_TYPESCRIPT_HASH;
+    let token_allowlist_typescript_hash = TOKEN_ALLOWLIST_TYPESCRIPT_HASH;
     let block_hash = "9485ad52a452389c67ea2364ae42c7a8772c54da7fa2929a6b2bf5261874298e".to_string();
     let recipient_lockscript = [
         73u8, 0, 0, 0, 16, 0, 0, 0, 48, 0, 0, 0, 49, 0, 0, 0, 155, 215, 224, 111, 62, 207, 75, 224,
@@ -49,6 +80,7 @@ fn get_correct_params() -> MintTestParams {
     let spv_proof = "f70800001800000020000000280000007903000095050000000000000000000000000000000000004d030000f9034a0182ba55b9010000000000000020100000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000000000000000000100000000000000000000000000000000000000000000000000000000020000000000000000000800000000000000000000000000000000000000000000000000000000000008000000000000000000000000000000000000000004000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000410000020000000000000000000000000000000002000000000000000000000000000000000f90240f9023d94cd62e77cfe0386343c15c13528675aae9925d7aef863a0413055b58d692937cc2a7d80ca019c17e8d01175e58d11f157ae9124078b01d6a00000000000000000000000000000000000000000000000000000000000000000a000000000000000000000000017c4b5ce0605f63732bfd175fece7ac6b4620fd2b901c00000000000000000000000000000000000000000000000000000000000000064000000000000000000000000000000000000000000000000000000000000000800000000000000000000000000000000000000000000000000000000000000a0000000000000000000000000000000000000000000000000000000000000012000000000000000000000000000000000000000000000000000000000000001800000000000000000000000000000000000000000000000000000000000000049490000001000000030000000310000009bd7e06f3ecf4be0f2fcd2188b23f1b9fcc88e5d4b65a8637b17723bbda3cce80114000000a4bf8e4c7f6f65f35dd3cc30c8fc45c8e99a171c00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000024ce2af4461cc6062998febffea311866388e8c869af0cf89ce832dadcd3521f270000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000f737564745f65787472615f64617461000000000000000000000000000000000018020000f90215a036d2422d8abce3d513b6a0f20e7488b7279cedda3bd63ad0fc1638557203a621a01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d493479417c4b5ce0605f63732bfd175fece7ac6b4620fd2a030c80a651cf02f1f53b298be2717084f760591ef0bbb9893840b213bf040de95a0ac6299aea5a7cd8c5a7f26e8c91551582dfd5a49f2e8ec2f4e29abeb7c150d0ca08bd3944f470118f235922d306a8a7f1d92cf4d1924bb30bf7f4f530728f7b72db901000000000000002010000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000002000000000000000000080000000000000000000000000000000000000000000000000000000000000800000000000000000000000000000000000000000400000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000041000002000000000000000000000000000000000200000000000000000000000000000000083020a492d8657098f88691582ba558460099dcc99d883010917846765746888676f312e31352e33856c696e7578a016733dcbdcbef8e6591fb73c78e160af91811658cd49a589efac8f4b41f9fab08809058be5d324de32620300000800000056030000f90353822080b9034df9034a0182ba55b9010000000000000020100000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000000000000000000100000000000000000000000000000000000000000000000000000000020000000000000000000800000000000000000000000000000000000000000000000000000000000008000000000000000000000000000000000000000004000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000410000020000000000000000000000000000000002000000000000000000000000000000000f90240f9023d94cd62e77cfe0386343c15c13528675aae9925d7aef863a0413055b58d692937cc2a7d80ca019c17e8d01175e58d11f157ae9124078b01d6a00000000000000000000000000000000000000000000000000000000000000000a000000000000000000000000017c4b5ce0605f63732bfd175fece7ac6b4620fd2b901c00000000000000000000000000000000000000000000000000000000000000064000000000000000000000000000000000000000000000000000000000000000800000000000000000000000000000000000000000000000000000000000000a0000000000000000000000000000000000000000000000000000000000000012000000000000000000000000000000000000000000000000000000000000001800000000000000000000000000000000000000000000000000000000000000049490000001000000030000000310000009bd7e06f3ecf4be0f2fcd2188b23f1b9fcc88e5d4b65a8637b17723bbda3cce80114000000a4bf8e4c7f6f65f35dd3cc30c8fc45c8e99a171c00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000024ce2af4461cc6062998febffea311866388e8c869af0cf89ce832dadcd3521f270000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000f737564745f65787472615f646174610000000000000000000000000000000000".to_string();
     let eth_locker_address = "cD62E77cFE0386343c15C13528675aae9925D7Ae".to_string();
     let eth_token_address = "0000000000000000000000000000000000000000".to_string();
+    let allowed_tokens = vec![eth_token_address.clone()];
 
     let bridge_lockscript = generate_lock_script(
         eth_locker_address.as_str(),
@@ -58,7 +90,14 @@ fn get_correct_params() -> MintTestParams {
 
     MintTestParams {
         block_number,
+        start_number,
         latest_number,
+        confirm,
+        history_latest_number,
+        paused,
+        bridge_status_typescript_hash,
+        allowed_tokens,
+        token_allowlist_typescript_hash,
         block_hash,
         recipient_lockscript,
         recipient_amount,
@@ -82,6 +121,11 @@ fn generate_manage_mode_mock(typescript: Script) -> MockDataLoader {
         .times(1)
         .returning(move |_, _| Ok(witness_args.clone()));
 
+    let lock_script = get_correct_params().bridge_lockscript;
+    mock.expect_load_script()
+        .times(1)
+        .returning(move || Ok(lock_script.clone()));
+
     mock.expect_load_script_hash()
         .times(1)
         .returning(|| Ok([2u8; 32]));
@@ -113,13 +157,36 @@ fn generate_mint_mode_mock(mint_test_params: MintTestParams) -> MockDataLoader {
 
     let light_client_data = generate_light_client_data(
         mint_test_params.block_number,
+        mint_test_params.start_number,
         mint_test_params.latest_number,
+        mint_test_params.confirm,
+        mint_test_params.history_latest_number,
         mint_test_params.block_hash.as_str(),
     );
     mock.expect_load_cell_data()
         .times(1)
         .returning(move |_, _| Ok(light_client_data.clone().to_vec()));
 
+    let bridge_status_typescript_hash = mint_test_params.bridge_status_typescript_hash;
+    mock.expect_load_cell_type_hash()
+        .times(1)
+        .returning(move |_, _| Ok(Some(bridge_status_typescript_hash)));
+
+    let bridge_status_data = generate_bridge_status_data(mint_test_params.paused);
+    mock.expect_load_cell_data()
+        .times(1)
+        .returning(move |_, _| Ok(bridge_status_data.to_vec()));
+
+    let token_allowlist_typescript_hash = mint_test_params.token_allowlist_typescript_hash;
+    mock.expect_load_cell_type_hash()
+        .times(1)
+        .returning(move |_, _| Ok(Some(token_allowlist_typescript_hash)));
+
+    let token_allowlist_data = generate_token_allowlist_data(&mint_test_params.allowed_tokens);
+    mock.expect_load_cell_data()
+        .times(1)
+        .returning(move |_, _| Ok(token_allowlist_data.to_vec()));
+
     let correct_input_outpoint = mint_test_params.replay_resist_outpoint.as_str();
     let outpoint =
         OutPoint::from_slice(hex::decode(correct_input_outpoint).unwrap().as_slice()).unwrap();
@@ -129,7 +196,7 @@ fn generate_mint_mode_mock(mint_test_params: MintTestParams) -> MockDataLoader {
 
     let lock_script = mint_test_params.bridge_lockscript;
     mock.expect_load_script()
-        .times(1)
+        .times(3)
         .returning(move || Ok(lock_script.clone()));
 
     mock.expect_load_cell_type_hash()
@@ -155,7 +222,7 @@ fn generate_mint_mode_mock(mint_test_params: MintTestParams) -> MockDataLoader {
     mock
 }
 
-fn generate_smt_tree(block_number: u128, block_hash_raw: &str) -> SMT {
+fn generate_smt_tree(blocks: &[(u128, &str)]) -> SMT {
     let mut smt_tree = SMT::default();
 
     for i in 0u8..100 {
@@ -163,10 +230,12 @@ fn generate_smt_tree(block_number: u128, block_hash_raw: &str) -> SMT {
         key[0] = i.to_le_bytes()[0];
         let mut value = [i; 32];
 
-        if i == block_number as u8 {
-            let mut block_hash = [0u8; 32];
-            block_hash.copy_from_slice(hex::decode(block_hash_raw).unwrap().as_slice());
-            value = block_hash;
+        for (block_number, block_hash_raw) in blocks {
+            if i == *block_number as u8 {
+                let mut block_hash = [0u8; 32];
+                block_hash.copy_from_slice(hex::decode(block_hash_raw).unwrap().as_slice());
+                value = block_hash;
+            }
         }
 
         smt_tree.update(key.into(), value.into()).unwrap();
@@ -174,6 +243,12 @@ fn generate_smt_tree(block_number: u128, block_hash_raw: &str) -> SMT {
     smt_tree
 }
 
+fn generate_smt_key(block_number: u128) -> H256 {
+    let mut key = [0u8; 32];
+    key[..16].copy_from_slice(block_number.to_le_bytes().as_ref());
+    key.into()
+}
+
 fn generate_mint_token_witness(
     spv_proof: &str,
     block_number: u128,
@@ -181,7 +256,7 @@ fn generate_mint_token_witness(
 ) -> WitnessArgs {
     let correct_spv_proof = hex::decode(spv_proof).unwrap();
 
-    let smt_tree = generate_smt_tree(block_number, block_hash);
+    let smt_tree = generate_smt_tree(&[(block_number, block_hash)]);
 
     let mut key = [0u8; 32];
     let mut height = [0u8; 16];
@@ -198,7 +273,7 @@ fn generate_mint_token_witness(
 
     let witness = MintTokenWitness::new_builder()
         .mode(Byte::new(0u8))
-        .cell_dep_index_list([0u8].to_vec().into())
+        .cell_dep_index_list([0u8, 1, 2].to_vec().into())
         .spv_proof(correct_spv_proof.into())
         .merkle_proof(compiled_merkle_proof.0.into())
         .build();
@@ -207,19 +282,264 @@ fn generate_mint_token_witness(
         .build()
 }
 
-fn generate_light_client_data(block_number: u128, latest_number: u128, block_hash: &str) -> Bytes {
-    let smt_tree = generate_smt_tree(block_number, block_hash);
+fn generate_mint_token_batch_witness(params_list: &[MintTestParams]) -> WitnessArgs {
+    let blocks = params_list
+        .iter()
+        .map(|params| (params.block_number, params.block_hash.as_str()))
+        .collect::<Vec<_>>();
+    let smt_tree = generate_smt_tree(&blocks);
+
+    let mut keys = blocks
+        .iter()
+        .map(|(block_number, _)| generate_smt_key(*block_number))
+        .collect::<Vec<_>>();
+    keys.sort();
+    keys.dedup();
+    let leaves = keys
+        .iter()
+        .map(|key| (*key, smt_tree.get(key).unwrap()))
+        .collect();
+    let compiled_merkle_proof = smt_tree
+        .merkle_proof(keys)
+        .unwrap()
+        .compile(leaves)
+        .unwrap();
 
-    let merkle_root = smt_tree.root();
+    let mut spv_proofs = basic::BytesVec::new_builder();
+    for params in params_list {
+        let spv_proof = hex::decode(params.spv_proof.as_str()).unwrap();
+        spv_proofs = spv_proofs.push(spv_proof.into());
+    }
+    let witness = MintTokenBatchWitness::new_builder()
+        .spv_proofs(spv_proofs.build())
+        .cell_dep_index_list([0u8, 1, 2].to_vec().into())
+        .merkle_proof(compiled_merkle_proof.0.into())
+        .build();
+    WitnessArgs::new_builder()
+        .lock(Some(witness.as_bytes()).pack())
+        .build()
+}
+
+// the batch mint tx consumes the replay resist cells in inputs, and outputs are the given cells.
+fn generate_batch_mint_mode_mock(
+    params_list: &[MintTestParams],
+    input_outpoints: Vec<String>,
+    outputs: Vec<(CellOutput, Vec<u8>)>,
+) -> MockDataLoader {
+    let mut mock = MockDataLoader::new();
+
+    let witness_args = generate_mint_token_batch_witness(params_list);
+    mock.expect_load_witness_args()
+        .times(1)
+        .returning(move |_, _| Ok(witness_args.clone()));
 
-    let data = ETHHeaderCellMerkleData::new_builder()
+    let blocks = params_list
+        .iter()
+        .map(|params| (params.block_number, params.block_hash.as_str()))
+        .collect::<Vec<_>>();
+    let merkle_root = *generate_smt_tree(&blocks).root();
+    let light_client_data = ETHHeaderCellMerkleData::new_builder()
         .merkle_root(basic::Byte32::from_slice(merkle_root.as_slice()).unwrap())
-        .latest_height((latest_number as u64).into())
+        .start_height((params_list[0].start_number as u64).into())
+        .latest_height((params_list[0].latest_number as u64).into())
+        .confirm((params_list[0].confirm as u64).into())
+        .bridge_status_typescript_hash(
+            basic::Byte32::from_slice(&BRIDGE_STATUS_TYPESCRIPT_HASH).unwrap(),
+        )
+        .token_allowlist_typescript_hash(
+            basic::Byte32::from_slice(&TOKEN_ALLOWLIST_TYPESCRIPT_HASH).unwrap(),
+        )
+        .consumed_event_registry_typescript_hash(
+            basic::Byte32::from_slice(&CONSUMED_EVENT_REGISTRY_TYPESCRIPT_HASH).unwrap(),
+        )
+        .build()
+        .as_bytes();
+    let bridge_status_data = generate_bridge_status_data(params_list[0].paused);
+    let token_allowlist_data = generate_token_allowlist_data(&params_list[0].allowed_tokens);
+    let output_data = outputs
+        .iter()
+        .map(|(_, data)| data.clone())
+        .collect::<Vec<_>>();
+    mock.expect_load_cell_data()
+        .returning(move |index, source| match source {
+            Source::CellDep if index == 1 => Ok(bridge_status_data.to_vec()),
+            Source::CellDep if index == 2 => Ok(token_allowlist_data.to_vec()),
+            Source::CellDep => Ok(light_client_data.to_vec()),
+            _ => output_data
+                .get(index)
+                .cloned()
+                .ok_or(SysError::IndexOutOfBound),
+        });
+
+    mock.expect_load_input_out_point()
+        .returning(move |index, _| match input_outpoints.get(index) {
+            Some(outpoint) => {
+                Ok(OutPoint::from_slice(hex::decode(outpoint).unwrap().as_slice()).unwrap())
+            }
+            None => Err(SysError::IndexOutOfBound),
+        });
+
+    let lock_script = params_list[0].bridge_lockscript.clone();
+    mock.expect_load_script()
+        .returning(move || Ok(lock_script.clone()));
+
+    mock.expect_load_cell_type_hash()
+        .returning(|index, source| match source {
+            Source::CellDep if index == 1 => Ok(Some(BRIDGE_STATUS_TYPESCRIPT_HASH)),
+            Source::CellDep if index == 2 => Ok(Some(TOKEN_ALLOWLIST_TYPESCRIPT_HASH)),
+            _ => Ok(Some([1u8; 32])),
+        });
+
+    mock.expect_load_script_hash().returning(|| Ok([2u8; 32]));
+
+    let output_cells = outputs
+        .into_iter()
+        .map(|(cell, _)| cell)
+        .collect::<Vec<_>>();
+    mock.expect_load_cell().returning(move |index, _| {
+        output_cells
+            .get(index)
+            .cloned()
+            .ok_or(SysError::IndexOutOfBound)
+    });
+
+    mock
+}
+
+// rebuild the spv proof with a lock event of the replay_resist_outpoint in the block of
+// block_number. return the params of the new lock event.
+fn get_params_of_lock_event(replay_resist_outpoint: &str, block_number: u128) -> MintTestParams {
+    let mut mint_test_params = get_correct_params();
+    let spv_proof = hex::decode(mint_test_params.spv_proof.as_str()).unwrap();
+    let proof_reader = ETHSPVProofReader::new_unchecked(&spv_proof);
+
+    let old_outpoint = hex::decode(mint_test_params.replay_resist_outpoint.as_str()).unwrap();
+    let new_outpoint = hex::decode(replay_resist_outpoint).unwrap();
+    let mut receipt_data = proof_reader.receipt_data().raw_data().to_vec();
+    let position = receipt_data
+        .windows(old_outpoint.len())
+        .position(|window| window == old_outpoint.as_slice())
+        .unwrap();
+    receipt_data[position..position + old_outpoint.len()].copy_from_slice(&new_outpoint);
+
+    let mut header: BlockHeader = rlp::decode(proof_reader.header_data().raw_data()).unwrap();
+    header.number = block_number as u64;
+    let proof = ETHSPVProof::new_builder()
+        .log_index(proof_reader.log_index().to_entity())
+        .receipt_index(proof_reader.receipt_index().to_entity())
+        .receipt_data(receipt_data.clone().into())
+        .header_data(rlp::encode(&header).into())
+        .proof(proof_reader.proof().to_entity())
         .build();
 
+    let (spv_proof, block_hash) = generate_single_receipt_spv_proof(
+        hex::encode(proof.as_slice()).as_str(),
+        receipt_data.clone(),
+        receipt_data,
+    );
+    mint_test_params.block_number = block_number;
+    mint_test_params.block_hash = block_hash;
+    mint_test_params.replay_resist_outpoint = replay_resist_outpoint.to_string();
+    mint_test_params.spv_proof = spv_proof;
+    mint_test_params
+}
+
+fn get_batch_params() -> Vec<MintTestParams> {
+    vec![
+        get_correct_params(),
+        get_params_of_lock_event(
+            "010101010101010101010101010101010101010101010101010101010101010100000000",
+            46,
+        ),
+    ]
+}
+
+fn get_batch_outputs(params_list: &[MintTestParams]) -> Vec<(CellOutput, Vec<u8>)> {
+    let mut outputs = vec![];
+    for params in params_list {
+        outputs.push(generate_sudt_cell(
+            params.recipient_lockscript.as_slice(),
+            params.recipient_amount,
+        ));
+        // the re-created bridge cell
+        outputs.push((CellOutput::default(), vec![]));
+    }
+    outputs
+}
+
+fn get_outpoints(params_list: &[MintTestParams]) -> Vec<String> {
+    params_list
+        .iter()
+        .map(|params| params.replay_resist_outpoint.clone())
+        .collect()
+}
+
+// the root proving the block is put in history if history_latest_number is not zero
+fn generate_light_client_data(
+    block_number: u128,
+    start_number: u128,
+    latest_number: u128,
+    confirm: u128,
+    history_latest_number: u128,
+    block_hash: &str,
+) -> Bytes {
+    let smt_tree = generate_smt_tree(&[(block_number, block_hash)]);
+
+    let merkle_root = smt_tree.root();
+
+    let builder = ETHHeaderCellMerkleData::new_builder()
+        .start_height((start_number as u64).into())
+        .latest_height((latest_number as u64).into())
+        .confirm((confirm as u64).into())
+        .bridge_status_typescript_hash(
+            basic::Byte32::from_slice(&BRIDGE_STATUS_TYPESCRIPT_HASH).unwrap(),
+        )
+        .token_allowlist_typescript_hash(
+            basic::Byte32::from_slice(&TOKEN_ALLOWLIST_TYPESCRIPT_HASH).unwrap(),
+        )
+        .consumed_event_registry_typescript_hash(
+            basic::Byte32::from_slice(&CONSUMED_EVENT_REGISTRY_TYPESCRIPT_HASH).unwrap(),
+        );
+    let data = if history_latest_number == 0 {
+        builder
+            .merkle_root(basic::Byte32::from_slice(merkle_root.as_slice()).unwrap())
+            .build()
+    } else {
+        let history_root = ETHHeaderRoot::new_builder()
+            .merkle_root(basic::Byte32::from_slice(merkle_root.as_slice()).unwrap())
+            .latest_height((history_latest_number as u64).into())
+            .build();
+        builder
+            .merkle_root(basic::Byte32::from_slice(&[3u8; 32]).unwrap())
+            .history(
+                ETHHeaderRootHistory::new_builder()
+                    .nth0(history_root)
+                    .build(),
+            )
+            .build()
+    };
+
     data.as_bytes()
 }
 
+fn generate_bridge_status_data(paused: bool) -> Bytes {
+    ETHBridgeStatusCellData::new_builder()
+        .paused(Byte::new(paused as u8))
+        .build()
+        .as_bytes()
+}
+
+fn generate_token_allowlist_data(tokens: &[String]) -> Bytes {
+    let tokens = tokens
+        .iter()
+        .map(|token| basic::ETHAddress::from_slice(&hex::decode(token).unwrap()).unwrap())
+        .collect::<Vec<_>>();
+    ETHTokenAllowlistCellData::new_builder()
+        .set(tokens)
+        .build()
+        .as_bytes()
+}
+
 fn generate_lock_script(
     contract_address: &str,
     token_address: &str,
@@ -244,6 +564,63 @@ fn generate_lock_script(
         .build()
 }
 
+// the receipt data of the spv proof with the EIP-2718 type prefix of tx_type
+fn generate_typed_receipt_data(spv_proof: &str, tx_type: u8) -> Vec<u8> {
+    let spv_proof = hex::decode(spv_proof).unwrap();
+    let proof_reader = ETHSPVProofReader::new_unchecked(&spv_proof);
+    let mut receipt_data = vec![tx_type];
+    receipt_data.extend_from_slice(proof_reader.receipt_data().raw_data());
+    receipt_data
+}
+
+// rebuild the spv proof of a block with the single receipt whose trie value is receipt_trie_value,
+// the header is updated with the new receipts root. return the spv proof and the block hash.
+fn generate_single_receipt_spv_proof(
+    spv_proof: &str,
+    receipt_data: Vec<u8>,
+    receipt_trie_value: Vec<u8>,
+) -> (String, String) {
+    let spv_proof = hex::decode(spv_proof).unwrap();
+    let proof_reader = ETHSPVProofReader::new_unchecked(&spv_proof);
+    let leaf = generate_single_leaf_trie(receipt_trie_value);
+
+    let mut header: BlockHeader = rlp::decode(proof_reader.header_data().raw_data()).unwrap();
+    header.receipts_root = keccak256(leaf.as_slice()).into();
+    let header_data = rlp::encode(&header);
+    let block_hash = hex::encode(keccak256(header_data.as_slice()));
+
+    let proof = ETHSPVProof::new_builder()
+        .log_index(proof_reader.log_index().to_entity())
+        .receipt_index(proof_reader.receipt_index().to_entity())
+        .receipt_data(receipt_data.into())
+        .header_data(header_data.into())
+        .proof(basic::BytesVec::new_builder().push(leaf.into()).build())
+        .build();
+    (hex::encode(proof.as_slice()), block_hash)
+}
+
+// the leaf node of the trie with the single value at index 0, whose key rlp(0) = 0x80 is 0x2080
+// with the even leaf prefix. it is the root node, so the proof is the leaf only.
+fn generate_single_leaf_trie(value: Vec<u8>) -> Vec<u8> {
+    let mut stream = RlpStream::new_list(2);
+    stream.append(&vec![0x20u8, 0x80]);
+    stream.append(&value);
+    stream.out()
+}
+
+fn get_typed_receipt_params(tx_type: u8) -> MintTestParams {
+    let mut mint_test_params = get_correct_params();
+    let receipt_data = generate_typed_receipt_data(mint_test_params.spv_proof.as_str(), tx_type);
+    let (spv_proof, block_hash) = generate_single_receipt_spv_proof(
+        mint_test_params.spv_proof.as_str(),
+        receipt_data.clone(),
+        receipt_data,
+    );
+    mint_test_params.spv_proof = spv_proof;
+    mint_test_params.block_hash = block_hash;
+    mint_test_params
+}
+
 fn generate_sudt_cell(
     recipient_lockscript: &[u8],
     recipient_amount: u128,
@@ -269,6 +646,227 @@ fn generate_sudt_cell(
     (cell, output_data)
 }
 
+// the lock event in registry mode, whose replay_resist_outpoint is all zero
+fn get_registry_mode_params() -> MintTestParams {
+    get_registry_mode_params_in_block(45)
+}
+
+// the lock event in registry mode in the given block, whose transactions trie has the lock tx only.
+// the lock tx in another block is the one included again after a reorg.
+fn get_registry_mode_params_in_block(block_number: u128) -> MintTestParams {
+    let mut mint_test_params = get_params_of_lock_event(
+        "000000000000000000000000000000000000000000000000000000000000000000000000",
+        block_number,
+    );
+    let spv_proof = hex::decode(mint_test_params.spv_proof.as_str()).unwrap();
+    let proof_reader = ETHSPVProofReader::new_unchecked(&spv_proof);
+    let leaf = generate_single_leaf_trie(hex::decode(LOCK_TX_DATA).unwrap());
+    let mut header: BlockHeader = rlp::decode(proof_reader.header_data().raw_data()).unwrap();
+    header.transactions_root = keccak256(leaf.as_slice()).into();
+    let header_data = rlp::encode(&header);
+    let proof = ETHSPVProof::new_builder()
+        .log_index(proof_reader.log_index().to_entity())
+        .receipt_index(proof_reader.receipt_index().to_entity())
+        .receipt_data(proof_reader.receipt_data().to_entity())
+        .header_data(header_data.to_vec().into())
+        .proof(proof_reader.proof().to_entity())
+        .build();
+    mint_test_params.block_hash = hex::encode(keccak256(header_data.as_ref()));
+    mint_test_params.spv_proof = hex::encode(proof.as_slice());
+    mint_test_params
+}
+
+fn generate_consumed_event_key(params: &MintTestParams) -> [u8; 32] {
+    let spv_proof = hex::decode(params.spv_proof.as_str()).unwrap();
+    let proof_reader = ETHSPVProofReader::new_unchecked(&spv_proof);
+    let mut log_index = [0u8; 8];
+    log_index.copy_from_slice(proof_reader.log_index().raw_data());
+    let tx_hash = keccak256(hex::decode(LOCK_TX_DATA).unwrap().as_slice());
+    consumed_event_key(&tx_hash, u64::from_le_bytes(log_index))
+}
+
+// the witness lock of the registry cell, with the proof of the lock tx in the single tx trie.
+fn generate_registry_witness(transaction_data: Vec<u8>, registry_proof: Vec<u8>) -> Bytes {
+    let leaf = generate_single_leaf_trie(hex::decode(LOCK_TX_DATA).unwrap());
+    let transaction_proof = ETHTransactionProof::new_builder()
+        .transaction_data(transaction_data.into())
+        .proof(basic::BytesVec::new_builder().push(leaf.into()).build())
+        .build();
+    ETHConsumedEventRegistryWitness::new_builder()
+        .transaction_proofs(
+            ETHTransactionProofVec::new_builder()
+                .push(transaction_proof)
+                .build(),
+        )
+        .merkle_proof(registry_proof.into())
+        .build()
+        .as_bytes()
+}
+
+// the registry keeps a consumed lock event besides the given ones. return the old root, the new
+// root and the compiled merkle proof of consuming the keys.
+fn generate_registry_transition(
+    keys: &[[u8; 32]],
+    already_consumed: bool,
+) -> ([u8; 32], [u8; 32], Vec<u8>) {
+    let mut smt_tree = SMT::default();
+    smt_tree
+        .update([9u8; 32].into(), CONSUMED_EVENT_VALUE.into())
+        .unwrap();
+    if already_consumed {
+        for key in keys {
+            smt_tree
+                .update((*key).into(), CONSUMED_EVENT_VALUE.into())
+                .unwrap();
+        }
+    }
+    let old_root = *smt_tree.root();
+    let keys: Vec<H256> = keys.iter().map(|key| (*key).into()).collect();
+    let compiled_merkle_proof = smt_tree
+        .merkle_proof(keys.clone())
+        .unwrap()
+        .compile(keys.iter().map(|key| (*key, H256::zero())).collect())
+        .unwrap();
+    for key in keys {
+        smt_tree.update(key, CONSUMED_EVENT_VALUE.into()).unwrap();
+    }
+    let mut old_root_raw = [0u8; 32];
+    old_root_raw.copy_from_slice(old_root.as_slice());
+    let mut new_root_raw = [0u8; 32];
+    new_root_raw.copy_from_slice(smt_tree.root().as_slice());
+    (old_root_raw, new_root_raw, compiled_merkle_proof.0)
+}
+
+fn generate_registry_lock_script() -> Script {
+    Script::new_builder()
+        .args(Bytes::from([1u8; 32].to_vec()).pack())
+        .build()
+}
+
+fn generate_registry_cell(root: [u8; 32]) -> (CellOutput, Vec<u8>) {
+    let typescript = Script::new_builder()
+        .args(Bytes::from([7u8; 32].to_vec()).pack())
+        .build();
+    let cell = CellOutput::new_builder()
+        .capacity(1000u64.pack())
+        .lock(generate_registry_lock_script())
+        .type_(Some(typescript).pack())
+        .build();
+    let data = ETHConsumedEventRegistryCellData::new_builder()
+        .consumed_events_root(basic::Byte32::from_slice(&root).unwrap())
+        .build()
+        .as_bytes()
+        .to_vec();
+    (cell, data)
+}
+
+// the registry mode mint tx, whose inputs are the bridge cell and the registry cell, outputs are
+// the recipient sudt cell, the re-created bridge cell and registry cell. the script is the one
+// being verified, which is the bridge lockscript or the registry lockscript.
+fn generate_registry_mode_mock(
+    params: &MintTestParams,
+    script: Script,
+    first_input_lock: Script,
+    registry_transition: ([u8; 32], [u8; 32], Vec<u8>),
+    transaction_data: Vec<u8>,
+) -> MockDataLoader {
+    let mut mock = MockDataLoader::new();
+    let (old_root, new_root, registry_proof) = registry_transition;
+    let verify_registry = script.args().raw_data().len() == 32;
+
+    let mint_witness_args = generate_mint_token_witness(
+        params.spv_proof.as_str(),
+        params.block_number,
+        params.block_hash.as_str(),
+    );
+    let registry_witness_args = WitnessArgs::new_builder()
+        .lock(Some(generate_registry_witness(transaction_data, registry_proof)).pack())
+        .build();
+    mock.expect_load_witness_args()
+        .returning(move |index, source| match source {
+            Source::GroupInput if verify_registry && index == 0 => {
+                Ok(registry_witness_args.clone())
+            }
+            _ if index == 0 => Ok(mint_witness_args.clone()),
+            _ => Err(SysError::IndexOutOfBound),
+        });
+
+    let (sudt_cell, sudt_data) = generate_sudt_cell(
+        params.recipient_lockscript.as_slice(),
+        params.recipient_amount,
+    );
+    let bridge_cell = CellOutput::new_builder().lock(first_input_lock).build();
+    let (input_registry_cell, input_registry_data) = generate_registry_cell(old_root);
+    let (output_registry_cell, output_registry_data) = generate_registry_cell(new_root);
+    let inputs = vec![
+        (bridge_cell.clone(), vec![]),
+        (input_registry_cell, input_registry_data),
+    ];
+    let outputs = vec![
+        (sudt_cell, sudt_data),
+        (bridge_cell, vec![]),
+        (output_registry_cell, output_registry_data),
+    ];
+    let get_cell = move |index: usize, source: Source| match source {
+        Source::Input => inputs.get(index).cloned().ok_or(SysError::IndexOutOfBound),
+        Source::GroupInput if verify_registry => inputs
+            .get(index + 1)
+            .cloned()
+            .ok_or(SysError::IndexOutOfBound),
+        Source::GroupInput => inputs
+            .get(index)
+            .filter(|_| index == 0)
+            .cloned()
+            .ok_or(SysError::IndexOutOfBound),
+        Source::Output => outputs.get(index).cloned().ok_or(SysError::IndexOutOfBound),
+        _ => Err(SysError::IndexOutOfBound),
+    };
+    let get_cell_clone = get_cell.clone();
+    mock.expect_load_cell()
+        .returning(move |index, source| get_cell_clone(index, source).map(|(cell, _)| cell));
+
+    let light_client_data = generate_light_client_data(
+        params.block_number,
+        params.start_number,
+        params.latest_number,
+        params.confirm,
+        params.history_latest_number,
+        params.block_hash.as_str(),
+    );
+    let bridge_status_data = generate_bridge_status_data(params.paused);
+    let token_allowlist_data = generate_token_allowlist_data(&params.allowed_tokens);
+    mock.expect_load_cell_data()
+        .returning(move |index, source| match source {
+            Source::CellDep if index == 1 => Ok(bridge_status_data.to_vec()),
+            Source::CellDep if index == 2 => Ok(token_allowlist_data.to_vec()),
+            Source::CellDep => Ok(light_client_data.to_vec()),
+            _ => get_cell(index, source).map(|(_, data)| data),
+        });
+
+    mock.expect_load_cell_type_hash()
+        .returning(|index, source| match source {
+            Source::CellDep if index == 1 => Ok(Some(BRIDGE_STATUS_TYPESCRIPT_HASH)),
+            Source::CellDep if index == 2 => Ok(Some(TOKEN_ALLOWLIST_TYPESCRIPT_HASH)),
+            Source::CellDep => Ok(Some([1u8; 32])),
+            Source::Input if index == 0 => Ok(None),
+            Source::Input if index == 1 => Ok(Some(CONSUMED_EVENT_REGISTRY_TYPESCRIPT_HASH)),
+            _ => Err(SysError::IndexOutOfBound),
+        });
+
+    mock.expect_load_cell_lock_hash()
+        .returning(|index, _| match index {
+            0 => Ok([2u8; 32]),
+            _ => Ok([3u8; 32]),
+        });
+
+    mock.expect_load_script_hash().returning(|| Ok([2u8; 32]));
+
+    mock.expect_load_script()
+        .returning(move || Ok(script.clone()));
+
+    mock
+}
+
 #[test]
 fn test_manage_mode_correct() {
     let mock = generate_manage_mode_mock(Script::default());
@@ -311,12 +909,70 @@ fn test_mint_mode_correct() {
     _verify(adapter);
 }
 
+#[test]
+fn test_mint_mode_correct_with_eip2930_receipt() {
+    let mint_test_params = get_typed_receipt_params(1);
+
+    let mut mock = generate_mint_mode_mock(mint_test_params);
+
+    mock.expect_load_cell()
+        .times(1)
+        .returning(|_, _| Err(SysError::IndexOutOfBound));
+
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+fn test_mint_mode_correct_with_eip1559_receipt() {
+    let mint_test_params = get_typed_receipt_params(2);
+
+    let mut mock = generate_mint_mode_mock(mint_test_params);
+
+    mock.expect_load_cell()
+        .times(1)
+        .returning(|_, _| Err(SysError::IndexOutOfBound));
+
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "receipt proof is invalid")]
+fn test_mint_mode_typed_receipt_without_type_prefix() {
+    let mut mint_test_params = get_correct_params();
+    let spv_proof = hex::decode(mint_test_params.spv_proof.as_str()).unwrap();
+    let legacy_receipt_data = ETHSPVProofReader::new_unchecked(&spv_proof)
+        .receipt_data()
+        .raw_data()
+        .to_vec();
+    let typed_receipt_data = generate_typed_receipt_data(mint_test_params.spv_proof.as_str(), 2);
+    let (spv_proof, block_hash) = generate_single_receipt_spv_proof(
+        mint_test_params.spv_proof.as_str(),
+        legacy_receipt_data,
+        typed_receipt_data,
+    );
+    mint_test_params.spv_proof = spv_proof;
+    mint_test_params.block_hash = block_hash;
+
+    let mock = generate_mint_mode_mock(mint_test_params);
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
 #[test]
 #[should_panic(expected = "eth spv proof is invalid")]
 fn test_mint_mode_invalid_proof() {
     let mut mock = MockAdapter::new();
+    let bridge_lockscript = get_correct_params().bridge_lockscript;
+    mock.expect_load_script_args()
+        .times(1)
+        .returning(move || Ok(bridge_lockscript.args().raw_data()));
     let witness = MintTokenWitness::new_builder()
-        .cell_dep_index_list([0u8].to_vec().into())
+        .cell_dep_index_list([0u8, 1, 2].to_vec().into())
         .build();
     mock.expect_load_input_witness_args()
         .times(1)
@@ -331,6 +987,10 @@ fn test_mint_mode_eth_cell_data_invalid() {
 
     let mut mock = MockDataLoader::new();
 
+    let bridge_lockscript = mint_test_params.bridge_lockscript.clone();
+    mock.expect_load_script()
+        .returning(move || Ok(bridge_lockscript.clone()));
+
     let witness_args = generate_mint_token_witness(
         mint_test_params.spv_proof.as_str(),
         mint_test_params.block_number,
@@ -349,6 +1009,130 @@ fn test_mint_mode_eth_cell_data_invalid() {
     _verify(adapter);
 }
 
+#[test]
+#[should_panic(expected = "header is pruned from light client")]
+fn test_mint_mode_header_pruned_from_light_client() {
+    let mut mint_test_params = get_correct_params();
+    mint_test_params.start_number = mint_test_params.block_number + 1;
+
+    let mock = generate_mint_mode_mock(mint_test_params);
+
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+fn test_mint_mode_header_confirmed_on_light_client() {
+    let mut mint_test_params = get_correct_params();
+    mint_test_params.confirm = mint_test_params.latest_number - mint_test_params.block_number;
+
+    let mut mock = generate_mint_mode_mock(mint_test_params);
+
+    mock.expect_load_cell()
+        .times(1)
+        .returning(|_, _| Err(SysError::IndexOutOfBound));
+
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "header is not confirmed on light client yet")]
+fn test_mint_mode_header_not_confirmed_on_light_client() {
+    let mut mint_test_params = get_correct_params();
+    mint_test_params.confirm = mint_test_params.latest_number - mint_test_params.block_number + 1;
+
+    let mock = generate_mint_mode_mock(mint_test_params);
+
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+fn test_mint_mode_proof_against_history_root() {
+    let mut mint_test_params = get_correct_params();
+    mint_test_params.history_latest_number = mint_test_params.latest_number - 10;
+
+    let mut mock = generate_mint_mode_mock(mint_test_params);
+
+    mock.expect_load_cell()
+        .times(1)
+        .returning(|_, _| Err(SysError::IndexOutOfBound));
+
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "merkle proof not match any root of light client")]
+fn test_mint_mode_history_root_not_confirmed() {
+    let mut mint_test_params = get_correct_params();
+    mint_test_params.confirm = 10;
+    mint_test_params.history_latest_number = mint_test_params.block_number + 9;
+
+    let mock = generate_mint_mode_mock(mint_test_params);
+
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "bridge is paused")]
+fn test_mint_mode_bridge_paused() {
+    let mut mint_test_params = get_correct_params();
+    mint_test_params.paused = true;
+
+    let mock = generate_mint_mode_mock(mint_test_params);
+
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "bridge status cell not match")]
+fn test_mint_mode_bridge_status_cell_not_match() {
+    let mut mint_test_params = get_correct_params();
+    mint_test_params.bridge_status_typescript_hash = [5u8; 32];
+
+    let mock = generate_mint_mode_mock(mint_test_params);
+
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "token is not in the allowlist")]
+fn test_mint_mode_token_not_in_allowlist() {
+    let mut mint_test_params = get_correct_params();
+    mint_test_params.allowed_tokens = vec!["cD62E77cFE0386343c15C13528675aae9925D7Ae".to_string()];
+
+    let mock = generate_mint_mode_mock(mint_test_params);
+
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "token allowlist cell not match")]
+fn test_mint_mode_token_allowlist_cell_not_match() {
+    let mut mint_test_params = get_correct_params();
+    mint_test_params.token_allowlist_typescript_hash = [5u8; 32];
+
+    let mock = generate_mint_mode_mock(mint_test_params);
+
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
 #[test]
 #[should_panic(expected = "replay_resist_cell_id not exists in inputs")]
 fn test_mint_mode_replay_resist_cell_not_exist() {
@@ -387,10 +1171,244 @@ fn test_mint_mode_mint_more_than_one_sudt_for_recipient() {
     _verify(adapter);
 }
 
+#[test]
+fn test_batch_mint_mode_correct() {
+    let params_list = get_batch_params();
+    let mock = generate_batch_mint_mode_mock(
+        &params_list,
+        get_outpoints(&params_list),
+        get_batch_outputs(&params_list),
+    );
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "replay_resist_cell_id is used by more than one lock event")]
+fn test_batch_mint_mode_duplicated_lock_event() {
+    let params_list = vec![get_correct_params(), get_correct_params()];
+    let mock = generate_batch_mint_mode_mock(
+        &params_list,
+        get_outpoints(&params_list),
+        get_batch_outputs(&params_list),
+    );
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "bridge is paused")]
+fn test_batch_mint_mode_bridge_paused() {
+    let mut params_list = get_batch_params();
+    params_list[0].paused = true;
+    let mock = generate_batch_mint_mode_mock(
+        &params_list,
+        get_outpoints(&params_list),
+        get_batch_outputs(&params_list),
+    );
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "headers of the same height are different")]
+fn test_batch_mint_mode_different_headers_of_same_height() {
+    let mut params_list = get_batch_params();
+    params_list[1] = get_params_of_lock_event(
+        params_list[1].replay_resist_outpoint.as_str(),
+        params_list[0].block_number,
+    );
+    let mock = generate_batch_mint_mode_mock(
+        &params_list,
+        get_outpoints(&params_list),
+        get_batch_outputs(&params_list),
+    );
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "replay_resist_cell_id not match the input of the same index")]
+fn test_batch_mint_mode_inputs_out_of_order() {
+    let params_list = get_batch_params();
+    let mut outpoints = get_outpoints(&params_list);
+    outpoints.reverse();
+    let mock =
+        generate_batch_mint_mode_mock(&params_list, outpoints, get_batch_outputs(&params_list));
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "recipient sudt cell not match the lock event of the same index")]
+fn test_batch_mint_mode_recipient_sudt_out_of_place() {
+    let params_list = get_batch_params();
+    let mut outputs = get_batch_outputs(&params_list);
+    outputs.swap(1, 2);
+    let mock = generate_batch_mint_mode_mock(&params_list, get_outpoints(&params_list), outputs);
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "mint token amount not equal to expected")]
+fn test_batch_mint_mode_mint_more_than_locked() {
+    let params_list = get_batch_params();
+    let mut outputs = get_batch_outputs(&params_list);
+    outputs.push(generate_sudt_cell(
+        params_list[0].recipient_lockscript.as_slice(),
+        1,
+    ));
+    let mock = generate_batch_mint_mode_mock(&params_list, get_outpoints(&params_list), outputs);
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+fn test_registry_mode_mint_correct() {
+    let params = get_registry_mode_params();
+    let keys = [generate_consumed_event_key(&params)];
+    let mock = generate_registry_mode_mock(
+        &params,
+        params.bridge_lockscript.clone(),
+        params.bridge_lockscript.clone(),
+        generate_registry_transition(&keys, false),
+        hex::decode(LOCK_TX_DATA).unwrap(),
+    );
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+fn test_consumed_event_registry_correct() {
+    let params = get_registry_mode_params();
+    let keys = [generate_consumed_event_key(&params)];
+    let mock = generate_registry_mode_mock(
+        &params,
+        generate_registry_lock_script(),
+        params.bridge_lockscript.clone(),
+        generate_registry_transition(&keys, false),
+        hex::decode(LOCK_TX_DATA).unwrap(),
+    );
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "lock event is already minted")]
+fn test_consumed_event_registry_lock_event_already_minted() {
+    let params = get_registry_mode_params();
+    let keys = [generate_consumed_event_key(&params)];
+    let mock = generate_registry_mode_mock(
+        &params,
+        generate_registry_lock_script(),
+        params.bridge_lockscript.clone(),
+        generate_registry_transition(&keys, true),
+        hex::decode(LOCK_TX_DATA).unwrap(),
+    );
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "lock event is already minted")]
+fn test_consumed_event_registry_lock_tx_reorged_to_another_block() {
+    let params = get_registry_mode_params();
+    let reorged_params = get_registry_mode_params_in_block(46);
+    assert_ne!(params.block_hash, reorged_params.block_hash);
+    // the lock event minted from the block before the reorg has the same key
+    let keys = [generate_consumed_event_key(&params)];
+    let mock = generate_registry_mode_mock(
+        &reorged_params,
+        generate_registry_lock_script(),
+        reorged_params.bridge_lockscript.clone(),
+        generate_registry_transition(&keys, true),
+        hex::decode(LOCK_TX_DATA).unwrap(),
+    );
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "transaction proof is invalid")]
+fn test_consumed_event_registry_lock_tx_not_in_block() {
+    let params = get_registry_mode_params();
+    let mut transaction_data = hex::decode(LOCK_TX_DATA).unwrap();
+    transaction_data[1] = 5;
+    let tx_hash = keccak256(transaction_data.as_slice());
+    let mut log_index = [0u8; 8];
+    log_index.copy_from_slice(
+        ETHSPVProofReader::new_unchecked(&hex::decode(params.spv_proof.as_str()).unwrap())
+            .log_index()
+            .raw_data(),
+    );
+    let keys = [consumed_event_key(&tx_hash, u64::from_le_bytes(log_index))];
+    let mock = generate_registry_mode_mock(
+        &params,
+        generate_registry_lock_script(),
+        params.bridge_lockscript.clone(),
+        generate_registry_transition(&keys, false),
+        transaction_data,
+    );
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "consumed event registry root not match")]
+fn test_consumed_event_registry_root_not_match() {
+    let params = get_registry_mode_params();
+    let keys = [generate_consumed_event_key(&params)];
+    let (old_root, _, proof) = generate_registry_transition(&keys, false);
+    let mock = generate_registry_mode_mock(
+        &params,
+        generate_registry_lock_script(),
+        params.bridge_lockscript.clone(),
+        (old_root, old_root, proof),
+        hex::decode(LOCK_TX_DATA).unwrap(),
+    );
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "the first input should be a bridge cell")]
+fn test_consumed_event_registry_unlocked_without_mint() {
+    let params = get_registry_mode_params();
+    let keys = [generate_consumed_event_key(&params)];
+    let mock = generate_registry_mode_mock(
+        &params,
+        generate_registry_lock_script(),
+        Script::default(),
+        generate_registry_transition(&keys, false),
+        hex::decode(LOCK_TX_DATA).unwrap(),
+    );
+    let adapter = crate::adapter::ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
 #[test]
 #[should_panic(expected = "proof witness lock field is none")]
 fn test_mock_chain() {
     let mut mock_chain = MockDataLoader::new();
+    let lock_script = get_correct_params().bridge_lockscript;
+    mock_chain
+        .expect_load_script()
+        .returning(move || Ok(lock_script.clone()));
     mock_chain
         .expect_load_witness_args()
         .returning(|_index, _source| Ok(WitnessArgs::default()));
@@ -1,7 +1,10 @@
 use crate::adapter::Adapter;
 use ckb_std::ckb_constants::Source;
 use ckb_std::error::SysError;
+use force_eth_types::bridge_fee::fee_outputs;
 use force_eth_types::generated::eth_bridge_type_cell::{ETHBridgeTypeArgs, ETHBridgeTypeData};
+use force_eth_types::generated::witness::MintTokenBatchWitnessReader;
+use molecule::bytes::Bytes;
 use molecule::prelude::*;
 
 pub fn verify_manage_mode<T: Adapter>(data_loader: &T, owner: &[u8]) {
@@ -27,37 +30,12 @@ pub fn verify_mint_token<T: Adapter>(
             .unwrap(),
         data_loader.load_script_hash(),
     );
+    // verify 1st output is recipient sudt cell, and the next outputs are fee sudt cells
+    let fee_outputs = fee_outputs(data).expect("fee recipients basis points invalid");
+    let mut index = verify_recipient_and_fee_sudt_cells(data_loader, script_args, &fee_outputs, 0);
     let udt_typescript =
         data_loader.get_associated_udt_script(script_args.bridge_lock_hash().as_slice());
     let sudt_typescript_slice = udt_typescript.as_slice();
-    // verify 1st output is recipient sudt cell
-    let first_output_typescript = data_loader
-        .load_cell_type(0, Source::Output)
-        .unwrap()
-        .unwrap();
-    assert_eq!(sudt_typescript_slice, first_output_typescript.as_slice());
-    let first_output_lock_hash = data_loader.load_cell_lock_hash(0, Source::Output).unwrap();
-    assert_eq!(
-        &first_output_lock_hash,
-        script_args.recipient_lock_hash().as_slice()
-    );
-    let mut index = 1;
-    // verify 2nd output is fee sudt cell
-    if data.fee().as_slice().iter().any(|&b| b != 0) {
-        let second_output_typescript = data_loader
-            .load_cell_type(index, Source::Output)
-            .unwrap()
-            .unwrap();
-        assert_eq!(sudt_typescript_slice, second_output_typescript.as_slice());
-        let second_output_lock_script = data_loader.load_cell_lock(index, Source::Output).unwrap();
-        assert_eq!(
-            second_output_lock_script.as_bytes().as_ref(),
-            data.owner_lock_script().raw_data().as_ref()
-        );
-        let second_output_data = data_loader.load_cell_data(index, Source::Output).unwrap();
-        assert_eq!(&second_output_data[..16], data.fee().as_slice());
-        index += 1;
-    }
     // verify there are no other sudt cell
     loop {
         let typescript_res = data_loader.load_cell_type(index, Source::Output);
@@ -74,3 +52,94 @@ pub fn verify_mint_token<T: Adapter>(
         index += 1;
     }
 }
+
+/// In batch mint mode, the i-th input is the bridge cell of the i-th lock event in the witness of
+/// the first input, which is verified by the bridge lockscript. The recipient sudt cell of it is
+/// the output 2i and the fee sudt cell is the output 2i+1, so the bridge cell whose fee is split
+/// across more than one recipient should be minted alone.
+pub fn verify_batch_mint_token<T: Adapter>(
+    data_loader: &T,
+    script_args: &ETHBridgeTypeArgs,
+    data: &ETHBridgeTypeData,
+) {
+    let script_hash = data_loader.load_script_hash();
+    let mut input_index = 0;
+    loop {
+        match data_loader.load_cell_type_hash(input_index, Source::Input) {
+            Err(SysError::IndexOutOfBound) => panic!("bridge cell not found in inputs"),
+            Err(_err) => panic!("iter input return an error"),
+            Ok(Some(type_hash)) if type_hash == script_hash => break,
+            Ok(_) => {}
+        }
+        input_index += 1;
+    }
+    assert_eq!(
+        &data_loader
+            .load_cell_lock_hash(input_index, Source::Input)
+            .unwrap(),
+        script_args.bridge_lock_hash().as_slice(),
+    );
+    assert_eq!(
+        &data_loader.load_cell_lock_hash(0, Source::Input).unwrap(),
+        script_args.bridge_lock_hash().as_slice(),
+    );
+    let first_witness_args = data_loader
+        .load_first_input_witness_args()
+        .expect("load first input witness args error");
+    MintTokenBatchWitnessReader::verify(&first_witness_args, false)
+        .expect("first input witness is not batch witness");
+    let first_witness = MintTokenBatchWitnessReader::new_unchecked(&first_witness_args);
+    if input_index >= first_witness.spv_proofs().len() {
+        panic!("bridge cell has no lock event in batch witness");
+    }
+    let fee_outputs = fee_outputs(data).expect("fee recipients basis points invalid");
+    if fee_outputs.len() > 1 {
+        panic!("split fee can not be minted in batch mode");
+    }
+    verify_recipient_and_fee_sudt_cells(data_loader, script_args, &fee_outputs, input_index * 2);
+}
+
+/// Verify the output at index is recipient sudt cell, and the next outputs are the fee sudt cells
+/// of the owner or the fee recipients. Return the index of the output after them.
+fn verify_recipient_and_fee_sudt_cells<T: Adapter>(
+    data_loader: &T,
+    script_args: &ETHBridgeTypeArgs,
+    fee_outputs: &[(Bytes, u128)],
+    mut index: usize,
+) -> usize {
+    let udt_typescript =
+        data_loader.get_associated_udt_script(script_args.bridge_lock_hash().as_slice());
+    let sudt_typescript_slice = udt_typescript.as_slice();
+    let recipient_output_typescript = data_loader
+        .load_cell_type(index, Source::Output)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        sudt_typescript_slice,
+        recipient_output_typescript.as_slice()
+    );
+    let recipient_output_lock_hash = data_loader
+        .load_cell_lock_hash(index, Source::Output)
+        .unwrap();
+    assert_eq!(
+        &recipient_output_lock_hash,
+        script_args.recipient_lock_hash().as_slice()
+    );
+    index += 1;
+    for (fee_lock_script, fee) in fee_outputs {
+        let fee_output_typescript = data_loader
+            .load_cell_type(index, Source::Output)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sudt_typescript_slice, fee_output_typescript.as_slice());
+        let fee_output_lock_script = data_loader.load_cell_lock(index, Source::Output).unwrap();
+        assert_eq!(
+            fee_output_lock_script.as_bytes().as_ref(),
+            fee_lock_script.as_ref()
+        );
+        let fee_output_data = data_loader.load_cell_data(index, Source::Output).unwrap();
+        assert_eq!(&fee_output_data[..16], &fee.to_le_bytes()[..]);
+        index += 1;
+    }
+    index
+}
@@ -12,6 +12,7 @@ use ckb_std::ckb_types::{
 use ckb_std::error::SysError;
 use ckb_std::high_level::QueryIter;
 use contracts_helper::data_loader::DataLoader;
+use force_eth_types::bridge_fee::parse_bridge_type_data;
 use force_eth_types::config::{SUDT_CODE_HASH, SUDT_HASH_TYPE};
 use force_eth_types::generated::eth_bridge_type_cell::{
     ETHBridgeTypeArgs, ETHBridgeTypeArgsReader, ETHBridgeTypeData,
@@ -25,6 +26,9 @@ pub trait Adapter {
 
     fn load_input_witness_args(&self) -> Result<Bytes, SysError>;
 
+    /// load the witness of the first input, which is the bridge cell in mint mode
+    fn load_first_input_witness_args(&self) -> Result<Bytes, SysError>;
+
     fn load_script_args(&self) -> ETHBridgeTypeArgs;
 
     fn load_data(&self) -> Option<ETHBridgeTypeData>;
@@ -71,6 +75,16 @@ where
         Ok(witness_args.raw_data())
     }
 
+    fn load_first_input_witness_args(&self) -> Result<Bytes, SysError> {
+        let witness_args = self
+            .chain
+            .load_witness_args(0, Source::Input)?
+            .lock()
+            .to_opt()
+            .expect("proof witness is none");
+        Ok(witness_args.raw_data())
+    }
+
     fn load_script_args(&self) -> ETHBridgeTypeArgs {
         let args = self.chain.load_script().unwrap().args().raw_data();
         ETHBridgeTypeArgsReader::verify(args.as_ref(), false).expect("invalid script args");
@@ -85,7 +99,7 @@ where
         .collect::<Vec<_>>();
         match data_list.len() {
             0 => None,
-            1 => Some(ETHBridgeTypeData::from_slice(&data_list[0]).expect("invalid data")),
+            1 => Some(parse_bridge_type_data(&data_list[0]).expect("invalid data")),
             _ => panic!("can not have more than one cell with this typescript"),
         }
     }
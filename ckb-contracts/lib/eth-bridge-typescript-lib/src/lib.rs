@@ -11,7 +11,7 @@ mod test;
 
 use adapter::Adapter;
 use contracts_helper::debug;
-use force_eth_types::generated::witness::MintTokenWitnessReader;
+use force_eth_types::generated::witness::{MintTokenBatchWitnessReader, MintTokenWitnessReader};
 use molecule::prelude::Reader;
 
 #[cfg(target_arch = "riscv64")]
@@ -34,13 +34,18 @@ pub fn _verify<T: Adapter>(data_loader: T) {
     let witness_args = data_loader
         .load_input_witness_args()
         .expect("load witness args error");
-    MintTokenWitnessReader::verify(&witness_args, false).expect("witness is invalid");
-    let witness = MintTokenWitnessReader::new_unchecked(&witness_args);
-    debug!("witness: {:?}", witness);
-
     // load script args
     let script_args = data_loader.load_script_args();
 
+    if MintTokenWitnessReader::verify(&witness_args, false).is_err() {
+        // the batch witness is always in mint mode
+        MintTokenBatchWitnessReader::verify(&witness_args, false).expect("witness is invalid");
+        actions::verify_batch_mint_token(&data_loader, &script_args, &data);
+        return;
+    }
+    let witness = MintTokenWitnessReader::new_unchecked(&witness_args);
+    debug!("witness: {:?}", witness);
+
     // check mode
     let mode: u8 = witness.mode().into();
     match mode {
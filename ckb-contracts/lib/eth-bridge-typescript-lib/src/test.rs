@@ -10,8 +10,11 @@ use ckb_std::error::SysError;
 use contracts_helper::data_loader::MockDataLoader;
 use force_eth_types::config::{SUDT_CODE_HASH, SUDT_HASH_TYPE};
 use force_eth_types::generated::basic::Byte32;
-use force_eth_types::generated::eth_bridge_type_cell::{ETHBridgeTypeArgs, ETHBridgeTypeData};
-use force_eth_types::generated::witness::MintTokenWitness;
+use force_eth_types::generated::basic::BytesVec;
+use force_eth_types::generated::eth_bridge_type_cell::{
+    ETHBridgeFeeRecipient, ETHBridgeFeeRecipientVec, ETHBridgeTypeArgs, ETHBridgeTypeData,
+};
+use force_eth_types::generated::witness::{MintTokenBatchWitness, MintTokenWitness};
 use molecule::prelude::Byte;
 use molecule::prelude::{Builder, Entity};
 
@@ -212,3 +215,264 @@ fn test_mint_token_when_mint_more_sudt_than_expected() {
 
     _verify(adapter);
 }
+
+fn generate_fee_recipients(recipients: &[(Script, u64)]) -> ETHBridgeFeeRecipientVec {
+    ETHBridgeFeeRecipientVec::new_builder()
+        .set(
+            recipients
+                .iter()
+                .map(|(lock_script, basis_points)| {
+                    ETHBridgeFeeRecipient::new_builder()
+                        .lock_script(lock_script.as_slice().to_vec().into())
+                        .basis_points((*basis_points).into())
+                        .build()
+                })
+                .collect(),
+        )
+        .build()
+}
+
+// the fee 10 of the bridge cell is split into 7 and 3 by the fee recipients, the outputs are the
+// recipient sudt cell and the fee sudt cells with the given amounts.
+fn generate_split_fee_mint_token_mock(fee_amounts: [u128; 2]) -> MockDataLoader {
+    let correct_input_lock_hash = [100u8; 32];
+    let correct_recipient_lock_hash = [101u8; 32];
+    let correct_input_type_hash = [102u8; 32];
+    let owner_lockscript = Script::new_builder().args([1u8; 1].pack()).build();
+    let fee_lockscripts = [
+        Script::new_builder().args([2u8; 1].pack()).build(),
+        Script::new_builder().args([3u8; 1].pack()).build(),
+    ];
+    let correct_sudt_script = Script::new_builder()
+        .code_hash(packed::Byte32::from_slice(SUDT_CODE_HASH.as_ref()).unwrap())
+        .hash_type(SUDT_HASH_TYPE.into())
+        .args(Bytes::from(correct_input_lock_hash.to_vec()).pack())
+        .build();
+
+    let mut mock = MockDataLoader::new();
+
+    let witness_args = WitnessArgs::new_builder()
+        .lock(Some(MintTokenWitness::default().as_bytes()).pack())
+        .build();
+    mock.expect_load_witness_args()
+        .times(1)
+        .returning(move |_, _| Ok(witness_args.clone()));
+
+    let args = ETHBridgeTypeArgs::new_builder()
+        .bridge_lock_hash(Byte32::new_unchecked(
+            correct_input_lock_hash.to_vec().into(),
+        ))
+        .recipient_lock_hash(Byte32::new_unchecked(
+            correct_recipient_lock_hash.to_vec().into(),
+        ))
+        .build();
+    let script = Script::new_builder().args(args.as_bytes().pack()).build();
+    mock.expect_load_script()
+        .times(1)
+        .returning(move || Ok(script.clone()));
+
+    let data = ETHBridgeTypeData::new_builder()
+        .owner_lock_script(owner_lockscript.as_slice().to_vec().into())
+        .fee(10u128.into())
+        .fee_recipients(generate_fee_recipients(&[
+            (fee_lockscripts[0].clone(), 7_000),
+            (fee_lockscripts[1].clone(), 3_000),
+        ]))
+        .build();
+    mock.expect_load_cell_data()
+        .returning(move |index, source| match (source, index) {
+            (Source::GroupInput, 0) => Ok(data.as_slice().to_vec()),
+            (Source::Output, 1) => Ok(fee_amounts[0].to_le_bytes().to_vec()),
+            (Source::Output, 2) => Ok(fee_amounts[1].to_le_bytes().to_vec()),
+            _ => Err(SysError::IndexOutOfBound),
+        });
+
+    mock.expect_load_cell_lock_hash()
+        .returning(move |_, source| {
+            if source == Source::Input {
+                Ok(correct_input_lock_hash)
+            } else {
+                Ok(correct_recipient_lock_hash)
+            }
+        });
+
+    mock.expect_load_cell_type_hash()
+        .times(1)
+        .returning(move |_, _| Ok(Some(correct_input_type_hash)));
+
+    mock.expect_load_script_hash()
+        .times(1)
+        .returning(move || Ok(correct_input_type_hash));
+
+    mock.expect_load_cell_lock()
+        .returning(move |index, _| Ok(fee_lockscripts[index - 1].clone()));
+
+    mock.expect_load_cell_type().returning(move |index, _| {
+        if index < 3 {
+            Ok(Some(correct_sudt_script.clone()))
+        } else {
+            Err(SysError::IndexOutOfBound)
+        }
+    });
+
+    mock
+}
+
+#[test]
+fn test_mint_token_with_split_fee() {
+    let mock = generate_split_fee_mint_token_mock([7, 3]);
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "assertion failed")]
+fn test_mint_token_when_split_fee_not_match() {
+    let mock = generate_split_fee_mint_token_mock([8, 2]);
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+// the bridge cell is the 2nd input of a batch mint tx, the witness of the 1st input has
+// spv_proofs_count lock events.
+fn generate_batch_mint_token_mock(
+    spv_proofs_count: usize,
+    fee_recipients: ETHBridgeFeeRecipientVec,
+) -> MockDataLoader {
+    let correct_input_lock_hash = [100u8; 32];
+    let correct_recipient_lock_hash = [101u8; 32];
+    let correct_input_type_hash = [102u8; 32];
+    let correct_owner_lockscript = Script::new_builder().args([1u8; 1].pack()).build();
+    let correct_sudt_script = Script::new_builder()
+        .code_hash(packed::Byte32::from_slice(SUDT_CODE_HASH.as_ref()).unwrap())
+        .hash_type(SUDT_HASH_TYPE.into())
+        .args(Bytes::from(correct_input_lock_hash.to_vec()).pack())
+        .build();
+
+    let mut mock = MockDataLoader::new();
+
+    let mut spv_proofs = BytesVec::new_builder();
+    for _ in 0..spv_proofs_count {
+        spv_proofs = spv_proofs.push(Default::default());
+    }
+    let first_witness_args = WitnessArgs::new_builder()
+        .lock(
+            Some(
+                MintTokenBatchWitness::new_builder()
+                    .spv_proofs(spv_proofs.build())
+                    .build()
+                    .as_bytes(),
+            )
+            .pack(),
+        )
+        .build();
+    let witness_args = WitnessArgs::new_builder()
+        .lock(Some(MintTokenBatchWitness::default().as_bytes()).pack())
+        .build();
+    mock.expect_load_witness_args()
+        .times(2)
+        .returning(move |_, source| {
+            if source == Source::Input {
+                Ok(first_witness_args.clone())
+            } else {
+                Ok(witness_args.clone())
+            }
+        });
+
+    let args = ETHBridgeTypeArgs::new_builder()
+        .bridge_lock_hash(Byte32::new_unchecked(
+            correct_input_lock_hash.to_vec().into(),
+        ))
+        .recipient_lock_hash(Byte32::new_unchecked(
+            correct_recipient_lock_hash.to_vec().into(),
+        ))
+        .build();
+    let script = Script::new_builder().args(args.as_bytes().pack()).build();
+    mock.expect_load_script()
+        .times(1)
+        .returning(move || Ok(script.clone()));
+
+    let data = ETHBridgeTypeData::new_builder()
+        .owner_lock_script(correct_owner_lockscript.as_slice().to_vec().into())
+        .fee(10u128.into())
+        .fee_recipients(fee_recipients)
+        .build();
+    mock.expect_load_cell_data()
+        .returning(move |index, source| match (source, index) {
+            (Source::GroupInput, 0) => Ok(data.as_slice().to_vec()),
+            (Source::Output, 3) => Ok(10u128.to_le_bytes().to_vec()),
+            _ => Err(SysError::IndexOutOfBound),
+        });
+
+    mock.expect_load_script_hash()
+        .times(1)
+        .returning(move || Ok(correct_input_type_hash));
+
+    mock.expect_load_cell_type_hash()
+        .times(2)
+        .returning(move |index, _| {
+            if index == 1 {
+                Ok(Some(correct_input_type_hash))
+            } else {
+                Ok(None)
+            }
+        });
+
+    mock.expect_load_cell_lock_hash()
+        .times(3)
+        .returning(move |index, source| match (source, index) {
+            (Source::Output, 2) => Ok(correct_recipient_lock_hash),
+            (Source::Output, _) => Ok([0u8; 32]),
+            _ => Ok(correct_input_lock_hash),
+        });
+
+    mock.expect_load_cell_type().returning(move |index, _| {
+        if index == 2 || index == 3 {
+            Ok(Some(correct_sudt_script.clone()))
+        } else {
+            Ok(None)
+        }
+    });
+
+    mock.expect_load_cell_lock()
+        .returning(move |_, _| Ok(correct_owner_lockscript.clone()));
+
+    mock
+}
+
+#[test]
+fn test_batch_mint_token() {
+    let mock = generate_batch_mint_token_mock(2, Default::default());
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "bridge cell has no lock event in batch witness")]
+fn test_batch_mint_token_when_bridge_cell_not_in_batch() {
+    let mock = generate_batch_mint_token_mock(1, Default::default());
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "split fee can not be minted in batch mode")]
+fn test_batch_mint_token_with_split_fee() {
+    let fee_recipients = generate_fee_recipients(&[
+        (Script::new_builder().args([2u8; 1].pack()).build(), 5_000),
+        (Script::new_builder().args([3u8; 1].pack()).build(), 5_000),
+    ]);
+    let mock = generate_batch_mint_token_mock(2, fee_recipients);
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
@@ -1,20 +1,184 @@
 use crate::adapter::Adapter;
 
 use ckb_std::ckb_constants::Source;
+use force_eth_types::generated::eth_header_cell::{
+    ETHHeaderCellMerkleDataReader, ETHHeaderRootHistoryReader,
+};
+use molecule::prelude::Reader;
 
-/// 1. Verify args equal to first input's outpoint.
-/// 2. Verify outputs only have 1 data cell.
+use std::prelude::v1::*;
+
+const OUTPOINT_LEN: usize = 36;
+const ARGS_LEN: usize = OUTPOINT_LEN + 32;
+
+/// The script args are the first input's outpoint followed by the owner lock hash.
+/// 1. Verify args equal to first input's outpoint when the cell is created.
+/// 2. Verify inputs and outputs only have 1 data cell.
+/// 3. Verify the cell is unlocked by the owner and locked by the owner again.
+/// 4. Verify the header window transition from input data to output data is valid.
 pub fn verify<T: Adapter>(data_loader: T) {
+    let script_args = data_loader.load_script_args();
+    assert_eq!(script_args.len(), ARGS_LEN, "invalid script args");
+    let owner_lock_hash = &script_args[OUTPOINT_LEN..];
+
     let input_data_len = data_loader.load_data_len_from_source(Source::GroupInput);
+    assert!(input_data_len <= 1);
     if input_data_len == 0 {
         assert_eq!(
             data_loader.load_first_outpoint().as_ref(),
-            data_loader.load_script_args().as_ref(),
+            &script_args[..OUTPOINT_LEN],
             "invalid first cell id"
         )
+    } else {
+        assert_eq!(
+            &data_loader.load_first_lock_hash_from_source(Source::GroupInput)[..],
+            owner_lock_hash,
+            "light client cell is not locked by the owner"
+        );
     }
 
     let output_data_len = data_loader.load_data_len_from_source(Source::GroupOutput);
 
     assert!(output_data_len <= 1);
+
+    if output_data_len == 1 {
+        assert_eq!(
+            &data_loader.load_first_lock_hash_from_source(Source::GroupOutput)[..],
+            owner_lock_hash,
+            "light client cell should be locked by the owner"
+        );
+        verify_header_window(&data_loader, input_data_len);
+    }
+}
+
+/// The light client keeps the headers in [start_height, latest_height].
+/// The relayer may advance start_height to prune the headers out of the retention window,
+/// so both start_height and latest_height can move forward, but never backwards.
+/// The confirm required by the bridge to mint, the bridge status cell and the token allowlist cell
+/// are set when the cell data is initialized, and can not be changed by the relayer afterwards,
+/// neither can the data be cleared.
+fn verify_header_window<T: Adapter>(data_loader: &T, input_data_len: usize) {
+    let input_data = if input_data_len == 0 {
+        vec![]
+    } else {
+        data_loader.load_first_data_from_source(Source::GroupInput)
+    };
+    let output_data = data_loader.load_first_data_from_source(Source::GroupOutput);
+    // the cell data is empty when the light client cell is created
+    if output_data.is_empty() {
+        assert!(input_data.is_empty(), "cell data should not be cleared");
+        return;
+    }
+    let (output_start_height, output_latest_height) = parse_header_window(&output_data);
+    assert!(
+        output_start_height <= output_latest_height,
+        "start height should not be greater than latest height"
+    );
+
+    // the data is initialized from the empty cell data, a zero confirm would let the bridge mint
+    // with the unconfirmed headers, and it can not be fixed afterwards
+    if input_data.is_empty() {
+        assert!(
+            parse_confirm(&output_data) > 0,
+            "confirm should be positive"
+        );
+        return;
+    }
+    let (input_start_height, input_latest_height) = parse_header_window(&input_data);
+    assert!(
+        output_start_height >= input_start_height,
+        "start height should not go backwards"
+    );
+    assert!(
+        output_latest_height >= input_latest_height,
+        "latest height should not go backwards"
+    );
+    assert_eq!(
+        parse_confirm(&input_data),
+        parse_confirm(&output_data),
+        "confirm should not be changed"
+    );
+    assert_eq!(
+        ETHHeaderCellMerkleDataReader::new_unchecked(&input_data)
+            .bridge_status_typescript_hash()
+            .as_slice(),
+        ETHHeaderCellMerkleDataReader::new_unchecked(&output_data)
+            .bridge_status_typescript_hash()
+            .as_slice(),
+        "bridge status cell should not be changed"
+    );
+    assert_eq!(
+        ETHHeaderCellMerkleDataReader::new_unchecked(&input_data)
+            .token_allowlist_typescript_hash()
+            .as_slice(),
+        ETHHeaderCellMerkleDataReader::new_unchecked(&output_data)
+            .token_allowlist_typescript_hash()
+            .as_slice(),
+        "token allowlist cell should not be changed"
+    );
+    assert_eq!(
+        ETHHeaderCellMerkleDataReader::new_unchecked(&input_data)
+            .consumed_event_registry_typescript_hash()
+            .as_slice(),
+        ETHHeaderCellMerkleDataReader::new_unchecked(&output_data)
+            .consumed_event_registry_typescript_hash()
+            .as_slice(),
+        "consumed event registry cell should not be changed"
+    );
+    verify_history(&input_data, &output_data);
+}
+
+/// The history keeps the previous roots, the latest first.
+/// When the root of the light client is updated, the root of input is pushed to the front of the
+/// history and the oldest one is dropped. Otherwise the history should be kept.
+fn verify_history(input_data: &[u8], output_data: &[u8]) {
+    let input_reader = ETHHeaderCellMerkleDataReader::new_unchecked(input_data);
+    let output_reader = ETHHeaderCellMerkleDataReader::new_unchecked(output_data);
+    let input_root = [
+        input_reader.merkle_root().as_slice(),
+        input_reader.latest_height().as_slice(),
+    ]
+    .concat();
+    let output_root = [
+        output_reader.merkle_root().as_slice(),
+        output_reader.latest_height().as_slice(),
+    ]
+    .concat();
+
+    let input_history = input_reader.history().as_slice();
+    let expected_history = if input_root == output_root {
+        input_history.to_vec()
+    } else {
+        let kept_len =
+            ETHHeaderRootHistoryReader::ITEM_SIZE * (ETHHeaderRootHistoryReader::ITEM_COUNT - 1);
+        [input_root.as_slice(), &input_history[..kept_len]].concat()
+    };
+    assert_eq!(
+        output_reader.history().as_slice(),
+        expected_history.as_slice(),
+        "history should be rotated from input"
+    );
+}
+
+fn parse_header_window(data: &[u8]) -> (u64, u64) {
+    if ETHHeaderCellMerkleDataReader::verify(data, false).is_err() {
+        panic!("eth cell data invalid");
+    }
+    let reader = ETHHeaderCellMerkleDataReader::new_unchecked(data);
+    let mut start_height = [0u8; 8];
+    start_height.copy_from_slice(reader.start_height().raw_data());
+    let mut latest_height = [0u8; 8];
+    latest_height.copy_from_slice(reader.latest_height().raw_data());
+    (
+        u64::from_le_bytes(start_height),
+        u64::from_le_bytes(latest_height),
+    )
+}
+
+// the data should have been verified by parse_header_window
+fn parse_confirm(data: &[u8]) -> u64 {
+    let reader = ETHHeaderCellMerkleDataReader::new_unchecked(data);
+    let mut confirm = [0u8; 8];
+    confirm.copy_from_slice(reader.confirm().raw_data());
+    u64::from_le_bytes(confirm)
 }
@@ -15,8 +15,10 @@ use std::prelude::v1::*;
 #[cfg_attr(feature = "std", automock)]
 pub trait Adapter {
     fn load_data_len_from_source(&self, source: Source) -> usize;
+    fn load_first_data_from_source(&self, source: Source) -> Vec<u8>;
     fn load_script_args(&self) -> Bytes;
     fn load_first_outpoint(&self) -> Bytes;
+    fn load_first_lock_hash_from_source(&self, source: Source) -> [u8; 32];
 }
 pub struct ChainAdapter<T: DataLoader> {
     pub chain: T,
@@ -34,6 +36,12 @@ where
         .count()
     }
 
+    fn load_first_data_from_source(&self, source: Source) -> Vec<u8> {
+        self.chain
+            .load_cell_data(0, source)
+            .expect("load cell data fail")
+    }
+
     fn load_script_args(&self) -> Bytes {
         self.chain
             .load_script()
@@ -48,4 +56,10 @@ where
             .expect("load input outpoint fail")
             .as_bytes()
     }
+
+    fn load_first_lock_hash_from_source(&self, source: Source) -> [u8; 32] {
+        self.chain
+            .load_cell_lock_hash(0, source)
+            .expect("load cell lock hash fail")
+    }
 }
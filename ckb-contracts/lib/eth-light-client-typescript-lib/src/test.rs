@@ -5,13 +5,53 @@ use ckb_std::ckb_types::packed::{OutPoint, Script};
 use ckb_std::ckb_types::prelude::Pack;
 use ckb_std::error::SysError;
 use contracts_helper::data_loader::MockDataLoader;
+use force_eth_types::generated::basic;
+use force_eth_types::generated::eth_header_cell::{
+    ETHHeaderCellMerkleData, ETHHeaderRoot, ETHHeaderRootHistory,
+};
 use molecule::prelude::{Builder, Entity};
 
-fn generate_init_correct_mock() -> MockDataLoader {
+const OWNER_LOCK_HASH: [u8; 32] = [2u8; 32];
+
+fn generate_script_args(first_outpoint: &[u8]) -> Vec<u8> {
+    let mut args = first_outpoint.to_vec();
+    args.extend_from_slice(&OWNER_LOCK_HASH);
+    args
+}
+
+fn generate_correct_script_args() -> Vec<u8> {
+    let first_outpoint = OutPoint::new_builder()
+        .index(0u32.pack())
+        .tx_hash([1u8; 32].pack())
+        .build();
+    generate_script_args(first_outpoint.as_slice())
+}
+
+fn expect_script_args(mock: &mut MockDataLoader, args: Vec<u8>) {
+    mock.expect_load_script()
+        .times(1)
+        .returning(move || Ok(Script::new_builder().args(args.pack()).build()));
+}
+
+fn expect_lock_hash(
+    mock: &mut MockDataLoader,
+    input_lock_hash: [u8; 32],
+    output_lock_hash: [u8; 32],
+    times: usize,
+) {
+    mock.expect_load_cell_lock_hash()
+        .times(times)
+        .returning(move |_, source| match source {
+            Source::GroupInput => Ok(input_lock_hash),
+            _ => Ok(output_lock_hash),
+        });
+}
+
+fn generate_init_mock(output_lock_hash: [u8; 32]) -> MockDataLoader {
     let mut mock = MockDataLoader::new();
 
     mock.expect_load_cell_data()
-        .times(3)
+        .times(4)
         .returning(move |index, source| {
             if source == Source::GroupOutput {
                 if index == 0 {
@@ -33,14 +73,20 @@ fn generate_init_correct_mock() -> MockDataLoader {
                 .build())
         });
 
+    expect_lock_hash(&mut mock, OWNER_LOCK_HASH, output_lock_hash, 1);
+
     mock
 }
 
+fn generate_init_correct_mock() -> MockDataLoader {
+    generate_init_mock(OWNER_LOCK_HASH)
+}
+
 fn generate_push_correct_mock() -> MockDataLoader {
     let mut mock = MockDataLoader::new();
 
     mock.expect_load_cell_data()
-        .times(4)
+        .times(6)
         .returning(move |index, _| {
             if index == 0 {
                 Ok(Default::default())
@@ -49,6 +95,75 @@ fn generate_push_correct_mock() -> MockDataLoader {
             }
         });
 
+    expect_script_args(&mut mock, generate_correct_script_args());
+    expect_lock_hash(&mut mock, OWNER_LOCK_HASH, OWNER_LOCK_HASH, 2);
+
+    mock
+}
+
+fn generate_merkle_data(start_height: u64, latest_height: u64) -> Vec<u8> {
+    generate_merkle_data_with_confirm(start_height, latest_height, 0)
+}
+
+fn generate_merkle_data_with_confirm(
+    start_height: u64,
+    latest_height: u64,
+    confirm: u64,
+) -> Vec<u8> {
+    ETHHeaderCellMerkleData::new_builder()
+        .merkle_root(basic::Byte32::from_slice(&[1u8; 32]).unwrap())
+        .start_height(start_height.into())
+        .latest_height(latest_height.into())
+        .confirm(confirm.into())
+        .build()
+        .as_slice()
+        .to_vec()
+}
+
+// push the root of input data to the front of the history of output data
+fn rotate_history(input_data: &[u8], output_data: Vec<u8>) -> Vec<u8> {
+    let input = ETHHeaderCellMerkleData::from_slice(input_data).unwrap();
+    let output = ETHHeaderCellMerkleData::from_slice(&output_data).unwrap();
+    let input_root = ETHHeaderRoot::new_builder()
+        .merkle_root(input.merkle_root())
+        .latest_height(input.latest_height())
+        .build();
+    let input_history = input.history();
+    let history = ETHHeaderRootHistory::new_builder()
+        .set([
+            input_root,
+            input_history.nth0(),
+            input_history.nth1(),
+            input_history.nth2(),
+        ])
+        .build();
+    output
+        .as_builder()
+        .history(history)
+        .build()
+        .as_slice()
+        .to_vec()
+}
+
+fn generate_push_mock_with_data(input_data: Vec<u8>, output_data: Vec<u8>) -> MockDataLoader {
+    let mut mock = MockDataLoader::new();
+
+    mock.expect_load_cell_data()
+        .times(6)
+        .returning(move |index, source| {
+            if index != 0 {
+                return Err(SysError::IndexOutOfBound);
+            }
+            match source {
+                Source::GroupInput => Ok(input_data.clone()),
+                Source::GroupOutput => Ok(output_data.clone()),
+                _ => Err(SysError::IndexOutOfBound),
+            }
+        });
+
+    expect_script_args(&mut mock, generate_correct_script_args());
+    expect_lock_hash(&mut mock, OWNER_LOCK_HASH, OWNER_LOCK_HASH, 2);
+
     mock
 }
 
@@ -56,18 +171,7 @@ fn generate_push_correct_mock() -> MockDataLoader {
 fn test_init_client_correct() {
     let mut mock = generate_init_correct_mock();
 
-    mock.expect_load_script().times(1).returning(|| {
-        Ok(Script::new_builder()
-            .args(
-                [
-                    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
-                    1, 1, 1, 1, 1, 1, 0, 0, 0, 0,
-                ]
-                .as_ref()
-                .pack(),
-            )
-            .build())
-    });
+    expect_script_args(&mut mock, generate_correct_script_args());
 
     let adapter = ChainAdapter { chain: mock };
 
@@ -77,7 +181,30 @@ fn test_init_client_correct() {
 #[test]
 #[should_panic(expected = "invalid first cell id")]
 fn test_init_client_wrong_when_args_first_input_outpoint_wrong() {
-    let mut mock = generate_init_correct_mock();
+    let mut mock = MockDataLoader::new();
+
+    mock.expect_load_cell_data()
+        .times(1)
+        .returning(|_, _| Err(SysError::IndexOutOfBound));
+    mock.expect_load_input_out_point()
+        .times(1)
+        .returning(|_, _| {
+            Ok(OutPoint::new_builder()
+                .index(0u32.pack())
+                .tx_hash([1u8; 32].pack())
+                .build())
+        });
+    expect_script_args(&mut mock, generate_script_args(&[3u8; 36]));
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "invalid script args")]
+fn test_init_client_wrong_when_args_len_wrong() {
+    let mut mock = MockDataLoader::new();
 
     mock.expect_load_script()
         .times(1)
@@ -88,6 +215,36 @@ fn test_init_client_wrong_when_args_first_input_outpoint_wrong() {
     _verify(adapter);
 }
 
+#[test]
+#[should_panic(expected = "light client cell should be locked by the owner")]
+fn test_init_client_wrong_when_output_not_locked_by_owner() {
+    let mut mock = MockDataLoader::new();
+
+    mock.expect_load_cell_data()
+        .times(3)
+        .returning(move |index, source| {
+            if source == Source::GroupOutput && index == 0 {
+                Ok(Default::default())
+            } else {
+                Err(SysError::IndexOutOfBound)
+            }
+        });
+    mock.expect_load_input_out_point()
+        .times(1)
+        .returning(|_, _| {
+            Ok(OutPoint::new_builder()
+                .index(0u32.pack())
+                .tx_hash([1u8; 32].pack())
+                .build())
+        });
+    expect_script_args(&mut mock, generate_correct_script_args());
+    expect_lock_hash(&mut mock, OWNER_LOCK_HASH, [3u8; 32], 1);
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
 #[test]
 fn test_push_client_correct() {
     let mock = generate_push_correct_mock();
@@ -96,3 +253,263 @@ fn test_push_client_correct() {
 
     _verify(adapter);
 }
+
+#[test]
+fn test_push_client_advance_start_height_correct() {
+    let input_data = generate_merkle_data(10, 100);
+    let output_data = rotate_history(&input_data, generate_merkle_data(20, 110));
+    let mock = generate_push_mock_with_data(input_data, output_data);
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "start height should not go backwards")]
+fn test_push_client_wrong_when_start_height_go_backwards() {
+    let mock =
+        generate_push_mock_with_data(generate_merkle_data(10, 100), generate_merkle_data(9, 110));
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "start height should not be greater than latest height")]
+fn test_push_client_wrong_when_start_height_greater_than_latest_height() {
+    let mock = generate_push_mock_with_data(
+        generate_merkle_data(10, 100),
+        generate_merkle_data(111, 110),
+    );
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+fn test_push_client_keep_confirm_correct() {
+    let input_data = generate_merkle_data_with_confirm(10, 100, 15);
+    let output_data = rotate_history(&input_data, generate_merkle_data_with_confirm(10, 110, 15));
+    let mock = generate_push_mock_with_data(input_data, output_data);
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+fn test_push_client_init_data_correct() {
+    let mock = generate_push_mock_with_data(vec![], generate_merkle_data_with_confirm(10, 100, 15));
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "confirm should be positive")]
+fn test_push_client_wrong_when_init_data_with_zero_confirm() {
+    let mock = generate_push_mock_with_data(vec![], generate_merkle_data_with_confirm(10, 100, 0));
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "confirm should not be changed")]
+fn test_push_client_wrong_when_confirm_changed() {
+    let mock = generate_push_mock_with_data(
+        generate_merkle_data_with_confirm(10, 100, 15),
+        generate_merkle_data_with_confirm(10, 110, 0),
+    );
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "bridge status cell should not be changed")]
+fn test_push_client_wrong_when_bridge_status_cell_changed() {
+    let input_data = generate_merkle_data(10, 100);
+    let output_data = ETHHeaderCellMerkleData::from_slice(&rotate_history(
+        &input_data,
+        generate_merkle_data(10, 110),
+    ))
+    .unwrap()
+    .as_builder()
+    .bridge_status_typescript_hash(basic::Byte32::from_slice(&[4u8; 32]).unwrap())
+    .build()
+    .as_slice()
+    .to_vec();
+    let mock = generate_push_mock_with_data(input_data, output_data);
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "token allowlist cell should not be changed")]
+fn test_push_client_wrong_when_token_allowlist_cell_changed() {
+    let input_data = generate_merkle_data(10, 100);
+    let output_data = ETHHeaderCellMerkleData::from_slice(&rotate_history(
+        &input_data,
+        generate_merkle_data(10, 110),
+    ))
+    .unwrap()
+    .as_builder()
+    .token_allowlist_typescript_hash(basic::Byte32::from_slice(&[6u8; 32]).unwrap())
+    .build()
+    .as_slice()
+    .to_vec();
+    let mock = generate_push_mock_with_data(input_data, output_data);
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "consumed event registry cell should not be changed")]
+fn test_push_client_wrong_when_consumed_event_registry_cell_changed() {
+    let input_data = generate_merkle_data(10, 100);
+    let output_data = ETHHeaderCellMerkleData::from_slice(&rotate_history(
+        &input_data,
+        generate_merkle_data(10, 110),
+    ))
+    .unwrap()
+    .as_builder()
+    .consumed_event_registry_typescript_hash(basic::Byte32::from_slice(&[7u8; 32]).unwrap())
+    .build()
+    .as_slice()
+    .to_vec();
+    let mock = generate_push_mock_with_data(input_data, output_data);
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "latest height should not go backwards")]
+fn test_push_client_wrong_when_latest_height_go_backwards() {
+    let mock =
+        generate_push_mock_with_data(generate_merkle_data(10, 100), generate_merkle_data(10, 99));
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "cell data should not be cleared")]
+fn test_push_client_wrong_when_data_cleared() {
+    let mock = generate_push_mock_with_data(generate_merkle_data(10, 100), vec![]);
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "light client cell is not locked by the owner")]
+fn test_push_client_wrong_when_input_not_locked_by_owner() {
+    let mut mock = MockDataLoader::new();
+
+    mock.expect_load_cell_data()
+        .times(2)
+        .returning(move |index, _| {
+            if index == 0 {
+                Ok(Default::default())
+            } else {
+                Err(SysError::IndexOutOfBound)
+            }
+        });
+    expect_script_args(&mut mock, generate_correct_script_args());
+    expect_lock_hash(&mut mock, [3u8; 32], OWNER_LOCK_HASH, 1);
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "light client cell should be locked by the owner")]
+fn test_push_client_wrong_when_output_not_locked_by_owner() {
+    let mut mock = MockDataLoader::new();
+
+    mock.expect_load_cell_data()
+        .times(4)
+        .returning(move |index, _| {
+            if index == 0 {
+                Ok(generate_merkle_data(10, 100))
+            } else {
+                Err(SysError::IndexOutOfBound)
+            }
+        });
+    expect_script_args(&mut mock, generate_correct_script_args());
+    expect_lock_hash(&mut mock, OWNER_LOCK_HASH, [3u8; 32], 2);
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+fn test_push_client_keep_history_when_root_not_changed() {
+    let input_data = rotate_history(&generate_merkle_data(5, 90), generate_merkle_data(10, 100));
+    let output_data = ETHHeaderCellMerkleData::from_slice(&input_data)
+        .unwrap()
+        .as_builder()
+        .start_height(20u64.into())
+        .build()
+        .as_slice()
+        .to_vec();
+    let mock = generate_push_mock_with_data(input_data, output_data);
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+fn test_push_client_rotate_full_history_correct() {
+    let mut input_data = generate_merkle_data(10, 100);
+    for latest_height in 101..106 {
+        input_data = rotate_history(&input_data, generate_merkle_data(10, latest_height));
+    }
+    let output_data = rotate_history(&input_data, generate_merkle_data(10, 110));
+    let mock = generate_push_mock_with_data(input_data, output_data);
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "history should be rotated from input")]
+fn test_push_client_wrong_when_history_not_rotated() {
+    let mock =
+        generate_push_mock_with_data(generate_merkle_data(10, 100), generate_merkle_data(10, 110));
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
+
+#[test]
+#[should_panic(expected = "history should be rotated from input")]
+fn test_push_client_wrong_when_history_changed_with_root_not_changed() {
+    let input_data = generate_merkle_data(10, 100);
+    let output_data = rotate_history(&input_data, generate_merkle_data(10, 100));
+    let mock = generate_push_mock_with_data(input_data, output_data);
+
+    let adapter = ChainAdapter { chain: mock };
+
+    _verify(adapter);
+}
@@ -35,6 +35,24 @@ fn test_tx_when_bridge_fee_is_less_than_expected() {
     case_runner::run_test(case);
 }
 
+#[test]
+fn test_tx_when_bridge_paused() {
+    let mut case = get_correct_case();
+    case.cell_deps[1] = CellDepView::ETHBridgeStatusCellDep(ETHBridgeStatusDep { paused: true });
+    case.expect_return_error_info = "bridge is paused".to_string();
+    case_runner::run_test(case);
+}
+
+#[test]
+fn test_tx_when_token_not_in_allowlist() {
+    let mut case = get_correct_case();
+    case.cell_deps[2] = CellDepView::ETHTokenAllowlistCellDep(ETHTokenAllowlistDep {
+        tokens: vec!["cD62E77cFE0386343c15C13528675aae9925D7Ae".to_string()],
+    });
+    case.expect_return_error_info = "token is not in the allowlist".to_string();
+    case_runner::run_test(case);
+}
+
 fn get_correct_case() -> TestCase {
     let recipient_lockscript = Script::from_slice(&[
         73u8, 0, 0, 0, 16, 0, 0, 0, 48, 0, 0, 0, 49, 0, 0, 0, 155, 215, 224, 111, 62, 207, 75, 224,
@@ -55,11 +73,17 @@ fn get_correct_case() -> TestCase {
     ];
 
     TestCase {
-        cell_deps: vec![CellDepView::ETHBridgeLockCellDep(ETHBridgeLockDep {
-            start_height: 0,
-            latest_height: 100,
-            merkle_root: [175u8, 67, 243, 141, 58, 48, 69, 47, 119, 171, 231, 65, 46, 177, 226, 106, 51, 80, 177, 154, 197, 96, 93, 198, 1, 140, 58, 88, 207, 8, 99, 82,],
-        })],
+        cell_deps: vec![
+            CellDepView::ETHBridgeLockCellDep(ETHBridgeLockDep {
+                start_height: 0,
+                latest_height: 100,
+                merkle_root: [175u8, 67, 243, 141, 58, 48, 69, 47, 119, 171, 231, 65, 46, 177, 226, 106, 51, 80, 177, 154, 197, 96, 93, 198, 1, 140, 58, 88, 207, 8, 99, 82,],
+            }),
+            CellDepView::ETHBridgeStatusCellDep(ETHBridgeStatusDep { paused: false }),
+            CellDepView::ETHTokenAllowlistCellDep(ETHTokenAllowlistDep {
+                tokens: vec!["0000000000000000000000000000000000000000".to_string()],
+            }),
+        ],
         script_cells: CustomCells {
             inputs: vec![CustomCell::ETHBridgeCustomCell(ETHBridgeCell {
                 capacity: 100 * CKB_UNITS,
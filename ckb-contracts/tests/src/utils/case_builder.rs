@@ -7,8 +7,10 @@ use core::convert::TryInto;
 use force_eth_types::{
     eth_recipient_cell::ETHAddress,
     generated::{
-        basic, eth_bridge_lock_cell::ETHBridgeLockArgs, eth_header_cell,
-        eth_recipient_cell::ETHRecipientCellData, witness::MintTokenWitness,
+        basic, eth_bridge_lock_cell::ETHBridgeLockArgs,
+        eth_bridge_status_cell::ETHBridgeStatusCellData, eth_header_cell,
+        eth_recipient_cell::ETHRecipientCellData,
+        eth_token_allowlist_cell::ETHTokenAllowlistCellData, witness::MintTokenWitness,
     },
     hasher::Blake2bHasher,
 };
@@ -114,6 +116,8 @@ impl CellBuilder for CustomCell {
 #[allow(dead_code)]
 pub enum CellDepView {
     ETHBridgeLockCellDep(ETHBridgeLockDep),
+    ETHBridgeStatusCellDep(ETHBridgeStatusDep),
+    ETHTokenAllowlistCellDep(ETHTokenAllowlistDep),
 }
 
 impl CellDepView {
@@ -122,6 +126,12 @@ impl CellDepView {
             CellDepView::ETHBridgeLockCellDep(cell_dep) => {
                 cell_dep.build_cell_dep(context, outpoints)
             }
+            CellDepView::ETHBridgeStatusCellDep(cell_dep) => {
+                cell_dep.build_cell_dep(context, outpoints)
+            }
+            CellDepView::ETHTokenAllowlistCellDep(cell_dep) => {
+                cell_dep.build_cell_dep(context, outpoints)
+            }
         }
     }
 }
@@ -138,6 +148,30 @@ impl ETHBridgeLockDep {
             .start_height((self.start_height.clone() as u64).into())
             .latest_height((self.latest_height.clone() as u64).into())
             .merkle_root(basic::Byte32::from_slice(self.merkle_root.clone().as_ref()).unwrap())
+            .bridge_status_typescript_hash(
+                basic::Byte32::from_slice(
+                    build_bridge_status_typescript(context, outpoints)
+                        .calc_script_hash()
+                        .as_slice(),
+                )
+                .unwrap(),
+            )
+            .token_allowlist_typescript_hash(
+                basic::Byte32::from_slice(
+                    build_token_allowlist_typescript(context, outpoints)
+                        .calc_script_hash()
+                        .as_slice(),
+                )
+                .unwrap(),
+            )
+            .consumed_event_registry_typescript_hash(
+                basic::Byte32::from_slice(
+                    build_consumed_event_registry_typescript(context, outpoints)
+                        .calc_script_hash()
+                        .as_slice(),
+                )
+                .unwrap(),
+            )
             .build();
 
         let light_client_typescript = context
@@ -156,6 +190,78 @@ impl ETHBridgeLockDep {
     }
 }
 
+pub struct ETHBridgeStatusDep {
+    pub paused: bool,
+}
+
+impl ETHBridgeStatusDep {
+    pub fn build_cell_dep(&self, context: &mut Context, outpoints: &OutpointsContext) -> CellDep {
+        let data = ETHBridgeStatusCellData::new_builder()
+            .paused(Byte::new(self.paused as u8))
+            .build();
+
+        let cell = CellOutput::new_builder()
+            .type_(Some(build_bridge_status_typescript(context, outpoints)).pack())
+            .capacity(Capacity::bytes(data.as_bytes().len()).unwrap().pack())
+            .build();
+        let data_out_point = context.create_cell(cell, data.as_bytes());
+        CellDep::new_builder().out_point(data_out_point).build()
+    }
+}
+
+// the governance cells are typed by the always success script in tests, told apart by the args
+fn build_bridge_status_typescript(context: &mut Context, outpoints: &OutpointsContext) -> Script {
+    context
+        .build_script(
+            &outpoints[ALWAYS_SUCCESS_OUTPOINT_KEY],
+            Bytes::from("bridge_status"),
+        )
+        .expect("build bridge status typescript")
+}
+
+pub struct ETHTokenAllowlistDep {
+    pub tokens: Vec<String>,
+}
+
+impl ETHTokenAllowlistDep {
+    pub fn build_cell_dep(&self, context: &mut Context, outpoints: &OutpointsContext) -> CellDep {
+        let tokens = self
+            .tokens
+            .iter()
+            .map(|token| str_to_eth_address(token))
+            .collect::<Vec<_>>();
+        let data = ETHTokenAllowlistCellData::new_builder().set(tokens).build();
+
+        let cell = CellOutput::new_builder()
+            .type_(Some(build_token_allowlist_typescript(context, outpoints)).pack())
+            .capacity(Capacity::bytes(data.as_bytes().len()).unwrap().pack())
+            .build();
+        let data_out_point = context.create_cell(cell, data.as_bytes());
+        CellDep::new_builder().out_point(data_out_point).build()
+    }
+}
+
+fn build_token_allowlist_typescript(context: &mut Context, outpoints: &OutpointsContext) -> Script {
+    context
+        .build_script(
+            &outpoints[ALWAYS_SUCCESS_OUTPOINT_KEY],
+            Bytes::from("token_allowlist"),
+        )
+        .expect("build token allowlist typescript")
+}
+
+fn build_consumed_event_registry_typescript(
+    context: &mut Context,
+    outpoints: &OutpointsContext,
+) -> Script {
+    context
+        .build_script(
+            &outpoints[ALWAYS_SUCCESS_OUTPOINT_KEY],
+            Bytes::from("consumed_event_registry"),
+        )
+        .expect("build consumed event registry typescript")
+}
+
 pub struct ETHRecipientCell {
     pub capacity: u64,
     pub data: ETHRecipientDataView,
@@ -494,7 +600,7 @@ impl ETHBridgeLockWitness {
 
         let witness = MintTokenWitness::new_builder()
             .mode(Byte::new(0u8))
-            .cell_dep_index_list([0u8].to_vec().into())
+            .cell_dep_index_list([0u8, 1, 2].to_vec().into())
             .spv_proof(correct_spv_proof.into())
             .merkle_proof(compiled_merkle_proof.0.into())
             .build();
@@ -0,0 +1,124 @@
+//! The bridge fee of a mint tx is paid to the owner of the bridge cell, or split across the fee
+//! recipients of the bridge cell by basis points.
+
+use crate::generated::eth_bridge_type_cell::{ETHBridgeTypeData, ETHLegacyBridgeTypeData};
+use molecule::bytes::Bytes;
+use molecule::error::VerificationResult;
+use molecule::prelude::*;
+use std::prelude::v1::*;
+
+/// The basis points of all the fee recipients of a bridge cell should sum to it.
+pub const BASIS_POINTS_DENOMINATOR: u64 = 10_000;
+
+/// Parse the data of a bridge cell. The bridge cells created before the fee recipients were added
+/// have the owner lock script and the fee only, they are read as having no fee recipients.
+pub fn parse_bridge_type_data(slice: &[u8]) -> VerificationResult<ETHBridgeTypeData> {
+    match ETHBridgeTypeData::from_compatible_slice(slice) {
+        Ok(data) => Ok(data),
+        Err(err) => {
+            let legacy_data = ETHLegacyBridgeTypeData::from_slice(slice).map_err(|_| err)?;
+            Ok(ETHBridgeTypeData::new_builder()
+                .owner_lock_script(legacy_data.owner_lock_script())
+                .fee(legacy_data.fee())
+                .build())
+        }
+    }
+}
+
+/// Split the fee by basis points, the last share takes the remainder of the rounding down.
+/// Return None if the basis points do not sum to BASIS_POINTS_DENOMINATOR.
+pub fn split_fee(fee: u128, basis_points: &[u64]) -> Option<Vec<u128>> {
+    let total = basis_points
+        .iter()
+        .try_fold(0u64, |total, &points| total.checked_add(points))?;
+    if total != BASIS_POINTS_DENOMINATOR {
+        return None;
+    }
+    let mut shares = vec![];
+    let mut remainder = fee;
+    for (index, &points) in basis_points.iter().enumerate() {
+        let share = if index == basis_points.len() - 1 {
+            remainder
+        } else {
+            // fee * points / BASIS_POINTS_DENOMINATOR without overflow
+            fee / BASIS_POINTS_DENOMINATOR as u128 * points as u128
+                + fee % BASIS_POINTS_DENOMINATOR as u128 * points as u128
+                    / BASIS_POINTS_DENOMINATOR as u128
+        };
+        remainder -= share;
+        shares.push(share);
+    }
+    Some(shares)
+}
+
+/// The lock scripts and amounts of the fee sudt cells which a mint tx of the bridge cell should
+/// create, in the order of the fee recipients. The whole fee goes to the owner if there are no fee
+/// recipients, and the shares which are zero have no cell. Return None if the basis points of the
+/// fee recipients are invalid.
+pub fn fee_outputs(data: &ETHBridgeTypeData) -> Option<Vec<(Bytes, u128)>> {
+    let mut fee = [0u8; 16];
+    fee.copy_from_slice(data.fee().raw_data().as_ref());
+    let fee = u128::from_le_bytes(fee);
+    let recipients = data.fee_recipients();
+    let outputs = if recipients.is_empty() {
+        vec![(data.owner_lock_script().raw_data(), fee)]
+    } else {
+        let basis_points = recipients
+            .clone()
+            .into_iter()
+            .map(|recipient| {
+                let mut points = [0u8; 8];
+                points.copy_from_slice(recipient.basis_points().raw_data().as_ref());
+                u64::from_le_bytes(points)
+            })
+            .collect::<Vec<_>>();
+        let shares = split_fee(fee, &basis_points)?;
+        recipients
+            .into_iter()
+            .zip(shares)
+            .map(|(recipient, share)| (recipient.lock_script().raw_data(), share))
+            .collect()
+    };
+    Some(
+        outputs
+            .into_iter()
+            .filter(|(_, amount)| *amount != 0)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_fee() {
+        assert_eq!(split_fee(100, &[10_000]), Some(vec![100]));
+        assert_eq!(split_fee(100, &[7_000, 3_000]), Some(vec![70, 30]));
+        // the last share takes the remainder of the rounding down
+        assert_eq!(split_fee(10, &[3_333, 3_333, 3_334]), Some(vec![3, 3, 4]));
+        assert_eq!(
+            split_fee(u128::MAX, &[5_000, 5_000]),
+            Some(vec![u128::MAX / 2, u128::MAX / 2 + 1])
+        );
+        assert_eq!(split_fee(100, &[7_000, 2_000]), None);
+        assert_eq!(split_fee(100, &[u64::MAX, 10_001]), None);
+        assert_eq!(split_fee(100, &[]), None);
+    }
+
+    #[test]
+    fn test_parse_legacy_bridge_type_data() {
+        let owner_lock_script = Bytes::from(vec![1u8; 53]);
+        let legacy_data = ETHLegacyBridgeTypeData::new_builder()
+            .owner_lock_script(owner_lock_script.to_vec().into())
+            .fee(100u128.into())
+            .build();
+        let data = parse_bridge_type_data(legacy_data.as_slice()).unwrap();
+        assert!(data.fee_recipients().is_empty());
+        assert_eq!(fee_outputs(&data), Some(vec![(owner_lock_script, 100)]));
+
+        let data = parse_bridge_type_data(data.as_slice()).unwrap();
+        assert!(data.fee_recipients().is_empty());
+        assert!(parse_bridge_type_data(&[1u8; 8]).is_err());
+    }
+}
@@ -0,0 +1,32 @@
+//! The lock events which are minted with the consumed event registry instead of a replay resist
+//! cell. The registry cell keeps the sparse merkle tree root of the consumed lock events, a mint
+//! tx proves its lock events are not in the tree and puts them in.
+//!
+//! A lock event is identified by the hash of its lock tx and its log index rather than the block it
+//! is in, so that the lock tx included again in another block after a reorg can not be minted
+//! twice. The spv proof proves the receipt only, the lock tx is proven by the transactions trie of
+//! the block in the witness of the registry cell.
+
+use blake2b_ref::Blake2bBuilder;
+
+/// The replay resist outpoint of the lock events in registry mode.
+pub const REGISTRY_MODE_OUTPOINT: [u8; 36] = [0u8; 36];
+
+/// The smt value of a consumed lock event, the value of the others is zero.
+pub const CONSUMED_EVENT_VALUE: [u8; 32] = [1u8; 32];
+
+const PERSONALIZATION: &[u8] = b"ckb-default-hash";
+
+pub fn is_registry_mode(replay_resist_outpoint: &[u8]) -> bool {
+    replay_resist_outpoint == REGISTRY_MODE_OUTPOINT.as_ref()
+}
+
+/// The smt key of the lock event, which is blake2b(tx_hash | log_index).
+pub fn consumed_event_key(tx_hash: &[u8], log_index: u64) -> [u8; 32] {
+    let mut blake2b = Blake2bBuilder::new(32).personal(PERSONALIZATION).build();
+    blake2b.update(tx_hash);
+    blake2b.update(&log_index.to_le_bytes());
+    let mut key = [0u8; 32];
+    blake2b.finalize(&mut key);
+    key
+}
@@ -0,0 +1,150 @@
+// Generated by Molecule 0.6.1
+
+use molecule::prelude::*;
+#[derive(Clone)]
+pub struct ETHBridgeStatusCellData(molecule::bytes::Bytes);
+impl ::core::fmt::LowerHex for ETHBridgeStatusCellData {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        use molecule::hex_string;
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        write!(f, "{}", hex_string(self.as_slice()))
+    }
+}
+impl ::core::fmt::Debug for ETHBridgeStatusCellData {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:#x})", Self::NAME, self)
+    }
+}
+impl ::core::fmt::Display for ETHBridgeStatusCellData {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{} {{ ", Self::NAME)?;
+        write!(f, "{}: {}", "paused", self.paused())?;
+        write!(f, " }}")
+    }
+}
+impl ::core::default::Default for ETHBridgeStatusCellData {
+    fn default() -> Self {
+        let v: Vec<u8> = vec![0];
+        ETHBridgeStatusCellData::new_unchecked(v.into())
+    }
+}
+impl ETHBridgeStatusCellData {
+    pub const TOTAL_SIZE: usize = 1;
+    pub const FIELD_SIZES: [usize; 1] = [1];
+    pub const FIELD_COUNT: usize = 1;
+    pub fn paused(&self) -> Byte {
+        Byte::new_unchecked(self.0.slice(0..1))
+    }
+    pub fn as_reader<'r>(&'r self) -> ETHBridgeStatusCellDataReader<'r> {
+        ETHBridgeStatusCellDataReader::new_unchecked(self.as_slice())
+    }
+}
+impl molecule::prelude::Entity for ETHBridgeStatusCellData {
+    type Builder = ETHBridgeStatusCellDataBuilder;
+    const NAME: &'static str = "ETHBridgeStatusCellData";
+    fn new_unchecked(data: molecule::bytes::Bytes) -> Self {
+        ETHBridgeStatusCellData(data)
+    }
+    fn as_bytes(&self) -> molecule::bytes::Bytes {
+        self.0.clone()
+    }
+    fn as_slice(&self) -> &[u8] {
+        &self.0[..]
+    }
+    fn from_slice(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+        ETHBridgeStatusCellDataReader::from_slice(slice).map(|reader| reader.to_entity())
+    }
+    fn from_compatible_slice(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+        ETHBridgeStatusCellDataReader::from_compatible_slice(slice).map(|reader| reader.to_entity())
+    }
+    fn new_builder() -> Self::Builder {
+        ::core::default::Default::default()
+    }
+    fn as_builder(self) -> Self::Builder {
+        Self::new_builder().paused(self.paused())
+    }
+}
+#[derive(Clone, Copy)]
+pub struct ETHBridgeStatusCellDataReader<'r>(&'r [u8]);
+impl<'r> ::core::fmt::LowerHex for ETHBridgeStatusCellDataReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        use molecule::hex_string;
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        write!(f, "{}", hex_string(self.as_slice()))
+    }
+}
+impl<'r> ::core::fmt::Debug for ETHBridgeStatusCellDataReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:#x})", Self::NAME, self)
+    }
+}
+impl<'r> ::core::fmt::Display for ETHBridgeStatusCellDataReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{} {{ ", Self::NAME)?;
+        write!(f, "{}: {}", "paused", self.paused())?;
+        write!(f, " }}")
+    }
+}
+impl<'r> ETHBridgeStatusCellDataReader<'r> {
+    pub const TOTAL_SIZE: usize = 1;
+    pub const FIELD_SIZES: [usize; 1] = [1];
+    pub const FIELD_COUNT: usize = 1;
+    pub fn paused(&self) -> ByteReader<'r> {
+        ByteReader::new_unchecked(&self.as_slice()[0..1])
+    }
+}
+impl<'r> molecule::prelude::Reader<'r> for ETHBridgeStatusCellDataReader<'r> {
+    type Entity = ETHBridgeStatusCellData;
+    const NAME: &'static str = "ETHBridgeStatusCellDataReader";
+    fn to_entity(&self) -> Self::Entity {
+        Self::Entity::new_unchecked(self.as_slice().to_owned().into())
+    }
+    fn new_unchecked(slice: &'r [u8]) -> Self {
+        ETHBridgeStatusCellDataReader(slice)
+    }
+    fn as_slice(&self) -> &'r [u8] {
+        self.0
+    }
+    fn verify(slice: &[u8], _compatible: bool) -> molecule::error::VerificationResult<()> {
+        use molecule::verification_error as ve;
+        let slice_len = slice.len();
+        if slice_len != Self::TOTAL_SIZE {
+            return ve!(Self, TotalSizeNotMatch, Self::TOTAL_SIZE, slice_len);
+        }
+        Ok(())
+    }
+}
+#[derive(Debug, Default)]
+pub struct ETHBridgeStatusCellDataBuilder {
+    pub(crate) paused: Byte,
+}
+impl ETHBridgeStatusCellDataBuilder {
+    pub const TOTAL_SIZE: usize = 1;
+    pub const FIELD_SIZES: [usize; 1] = [1];
+    pub const FIELD_COUNT: usize = 1;
+    pub fn paused(mut self, v: Byte) -> Self {
+        self.paused = v;
+        self
+    }
+}
+impl molecule::prelude::Builder for ETHBridgeStatusCellDataBuilder {
+    type Entity = ETHBridgeStatusCellData;
+    const NAME: &'static str = "ETHBridgeStatusCellDataBuilder";
+    fn expected_length(&self) -> usize {
+        Self::TOTAL_SIZE
+    }
+    fn write<W: ::molecule::io::Write>(&self, writer: &mut W) -> ::molecule::io::Result<()> {
+        writer.write_all(self.paused.as_slice())?;
+        Ok(())
+    }
+    fn build(&self) -> Self::Entity {
+        let mut inner = Vec::with_capacity(self.expected_length());
+        self.write(&mut inner)
+            .unwrap_or_else(|_| panic!("{} build should be ok", Self::NAME));
+        ETHBridgeStatusCellData::new_unchecked(inner.into())
+    }
+}
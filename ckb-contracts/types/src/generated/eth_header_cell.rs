@@ -3,6 +3,381 @@
 use super::basic::*;
 use molecule::prelude::*;
 #[derive(Clone)]
+pub struct ETHHeaderRoot(molecule::bytes::Bytes);
+impl ::core::fmt::LowerHex for ETHHeaderRoot {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        use molecule::hex_string;
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        write!(f, "{}", hex_string(self.as_slice()))
+    }
+}
+impl ::core::fmt::Debug for ETHHeaderRoot {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:#x})", Self::NAME, self)
+    }
+}
+impl ::core::fmt::Display for ETHHeaderRoot {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{} {{ ", Self::NAME)?;
+        write!(f, "{}: {}", "merkle_root", self.merkle_root())?;
+        write!(f, ", {}: {}", "latest_height", self.latest_height())?;
+        write!(f, " }}")
+    }
+}
+impl ::core::default::Default for ETHHeaderRoot {
+    fn default() -> Self {
+        let v: Vec<u8> = vec![
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+        ETHHeaderRoot::new_unchecked(v.into())
+    }
+}
+impl ETHHeaderRoot {
+    pub const TOTAL_SIZE: usize = 40;
+    pub const FIELD_SIZES: [usize; 2] = [32, 8];
+    pub const FIELD_COUNT: usize = 2;
+    pub fn merkle_root(&self) -> Byte32 {
+        Byte32::new_unchecked(self.0.slice(0..32))
+    }
+    pub fn latest_height(&self) -> Uint64 {
+        Uint64::new_unchecked(self.0.slice(32..40))
+    }
+    pub fn as_reader<'r>(&'r self) -> ETHHeaderRootReader<'r> {
+        ETHHeaderRootReader::new_unchecked(self.as_slice())
+    }
+}
+impl molecule::prelude::Entity for ETHHeaderRoot {
+    type Builder = ETHHeaderRootBuilder;
+    const NAME: &'static str = "ETHHeaderRoot";
+    fn new_unchecked(data: molecule::bytes::Bytes) -> Self {
+        ETHHeaderRoot(data)
+    }
+    fn as_bytes(&self) -> molecule::bytes::Bytes {
+        self.0.clone()
+    }
+    fn as_slice(&self) -> &[u8] {
+        &self.0[..]
+    }
+    fn from_slice(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+        ETHHeaderRootReader::from_slice(slice).map(|reader| reader.to_entity())
+    }
+    fn from_compatible_slice(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+        ETHHeaderRootReader::from_compatible_slice(slice).map(|reader| reader.to_entity())
+    }
+    fn new_builder() -> Self::Builder {
+        ::core::default::Default::default()
+    }
+    fn as_builder(self) -> Self::Builder {
+        Self::new_builder()
+            .merkle_root(self.merkle_root())
+            .latest_height(self.latest_height())
+    }
+}
+#[derive(Clone, Copy)]
+pub struct ETHHeaderRootReader<'r>(&'r [u8]);
+impl<'r> ::core::fmt::LowerHex for ETHHeaderRootReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        use molecule::hex_string;
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        write!(f, "{}", hex_string(self.as_slice()))
+    }
+}
+impl<'r> ::core::fmt::Debug for ETHHeaderRootReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:#x})", Self::NAME, self)
+    }
+}
+impl<'r> ::core::fmt::Display for ETHHeaderRootReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{} {{ ", Self::NAME)?;
+        write!(f, "{}: {}", "merkle_root", self.merkle_root())?;
+        write!(f, ", {}: {}", "latest_height", self.latest_height())?;
+        write!(f, " }}")
+    }
+}
+impl<'r> ETHHeaderRootReader<'r> {
+    pub const TOTAL_SIZE: usize = 40;
+    pub const FIELD_SIZES: [usize; 2] = [32, 8];
+    pub const FIELD_COUNT: usize = 2;
+    pub fn merkle_root(&self) -> Byte32Reader<'r> {
+        Byte32Reader::new_unchecked(&self.as_slice()[0..32])
+    }
+    pub fn latest_height(&self) -> Uint64Reader<'r> {
+        Uint64Reader::new_unchecked(&self.as_slice()[32..40])
+    }
+}
+impl<'r> molecule::prelude::Reader<'r> for ETHHeaderRootReader<'r> {
+    type Entity = ETHHeaderRoot;
+    const NAME: &'static str = "ETHHeaderRootReader";
+    fn to_entity(&self) -> Self::Entity {
+        Self::Entity::new_unchecked(self.as_slice().to_owned().into())
+    }
+    fn new_unchecked(slice: &'r [u8]) -> Self {
+        ETHHeaderRootReader(slice)
+    }
+    fn as_slice(&self) -> &'r [u8] {
+        self.0
+    }
+    fn verify(slice: &[u8], _compatible: bool) -> molecule::error::VerificationResult<()> {
+        use molecule::verification_error as ve;
+        let slice_len = slice.len();
+        if slice_len != Self::TOTAL_SIZE {
+            return ve!(Self, TotalSizeNotMatch, Self::TOTAL_SIZE, slice_len);
+        }
+        Ok(())
+    }
+}
+#[derive(Debug, Default)]
+pub struct ETHHeaderRootBuilder {
+    pub(crate) merkle_root: Byte32,
+    pub(crate) latest_height: Uint64,
+}
+impl ETHHeaderRootBuilder {
+    pub const TOTAL_SIZE: usize = 40;
+    pub const FIELD_SIZES: [usize; 2] = [32, 8];
+    pub const FIELD_COUNT: usize = 2;
+    pub fn merkle_root(mut self, v: Byte32) -> Self {
+        self.merkle_root = v;
+        self
+    }
+    pub fn latest_height(mut self, v: Uint64) -> Self {
+        self.latest_height = v;
+        self
+    }
+}
+impl molecule::prelude::Builder for ETHHeaderRootBuilder {
+    type Entity = ETHHeaderRoot;
+    const NAME: &'static str = "ETHHeaderRootBuilder";
+    fn expected_length(&self) -> usize {
+        Self::TOTAL_SIZE
+    }
+    fn write<W: ::molecule::io::Write>(&self, writer: &mut W) -> ::molecule::io::Result<()> {
+        writer.write_all(self.merkle_root.as_slice())?;
+        writer.write_all(self.latest_height.as_slice())?;
+        Ok(())
+    }
+    fn build(&self) -> Self::Entity {
+        let mut inner = Vec::with_capacity(self.expected_length());
+        self.write(&mut inner)
+            .unwrap_or_else(|_| panic!("{} build should be ok", Self::NAME));
+        ETHHeaderRoot::new_unchecked(inner.into())
+    }
+}
+#[derive(Clone)]
+pub struct ETHHeaderRootHistory(molecule::bytes::Bytes);
+impl ::core::fmt::LowerHex for ETHHeaderRootHistory {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        use molecule::hex_string;
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        write!(f, "{}", hex_string(self.as_slice()))
+    }
+}
+impl ::core::fmt::Debug for ETHHeaderRootHistory {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:#x})", Self::NAME, self)
+    }
+}
+impl ::core::fmt::Display for ETHHeaderRootHistory {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{} [", Self::NAME)?;
+        write!(f, "{}", self.nth0())?;
+        write!(f, ", {}", self.nth1())?;
+        write!(f, ", {}", self.nth2())?;
+        write!(f, ", {}", self.nth3())?;
+        write!(f, "]")
+    }
+}
+impl ::core::default::Default for ETHHeaderRootHistory {
+    fn default() -> Self {
+        let v: Vec<u8> = vec![
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+        ETHHeaderRootHistory::new_unchecked(v.into())
+    }
+}
+impl ETHHeaderRootHistory {
+    pub const TOTAL_SIZE: usize = 160;
+    pub const ITEM_SIZE: usize = 40;
+    pub const ITEM_COUNT: usize = 4;
+    pub fn nth0(&self) -> ETHHeaderRoot {
+        ETHHeaderRoot::new_unchecked(self.0.slice(0..40))
+    }
+    pub fn nth1(&self) -> ETHHeaderRoot {
+        ETHHeaderRoot::new_unchecked(self.0.slice(40..80))
+    }
+    pub fn nth2(&self) -> ETHHeaderRoot {
+        ETHHeaderRoot::new_unchecked(self.0.slice(80..120))
+    }
+    pub fn nth3(&self) -> ETHHeaderRoot {
+        ETHHeaderRoot::new_unchecked(self.0.slice(120..160))
+    }
+    pub fn as_reader<'r>(&'r self) -> ETHHeaderRootHistoryReader<'r> {
+        ETHHeaderRootHistoryReader::new_unchecked(self.as_slice())
+    }
+}
+impl molecule::prelude::Entity for ETHHeaderRootHistory {
+    type Builder = ETHHeaderRootHistoryBuilder;
+    const NAME: &'static str = "ETHHeaderRootHistory";
+    fn new_unchecked(data: molecule::bytes::Bytes) -> Self {
+        ETHHeaderRootHistory(data)
+    }
+    fn as_bytes(&self) -> molecule::bytes::Bytes {
+        self.0.clone()
+    }
+    fn as_slice(&self) -> &[u8] {
+        &self.0[..]
+    }
+    fn from_slice(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+        ETHHeaderRootHistoryReader::from_slice(slice).map(|reader| reader.to_entity())
+    }
+    fn from_compatible_slice(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+        ETHHeaderRootHistoryReader::from_compatible_slice(slice).map(|reader| reader.to_entity())
+    }
+    fn new_builder() -> Self::Builder {
+        ::core::default::Default::default()
+    }
+    fn as_builder(self) -> Self::Builder {
+        Self::new_builder().set([self.nth0(), self.nth1(), self.nth2(), self.nth3()])
+    }
+}
+#[derive(Clone, Copy)]
+pub struct ETHHeaderRootHistoryReader<'r>(&'r [u8]);
+impl<'r> ::core::fmt::LowerHex for ETHHeaderRootHistoryReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        use molecule::hex_string;
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        write!(f, "{}", hex_string(self.as_slice()))
+    }
+}
+impl<'r> ::core::fmt::Debug for ETHHeaderRootHistoryReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:#x})", Self::NAME, self)
+    }
+}
+impl<'r> ::core::fmt::Display for ETHHeaderRootHistoryReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{} [", Self::NAME)?;
+        write!(f, "{}", self.nth0())?;
+        write!(f, ", {}", self.nth1())?;
+        write!(f, ", {}", self.nth2())?;
+        write!(f, ", {}", self.nth3())?;
+        write!(f, "]")
+    }
+}
+impl<'r> ETHHeaderRootHistoryReader<'r> {
+    pub const TOTAL_SIZE: usize = 160;
+    pub const ITEM_SIZE: usize = 40;
+    pub const ITEM_COUNT: usize = 4;
+    pub fn nth0(&self) -> ETHHeaderRootReader<'r> {
+        ETHHeaderRootReader::new_unchecked(&self.as_slice()[0..40])
+    }
+    pub fn nth1(&self) -> ETHHeaderRootReader<'r> {
+        ETHHeaderRootReader::new_unchecked(&self.as_slice()[40..80])
+    }
+    pub fn nth2(&self) -> ETHHeaderRootReader<'r> {
+        ETHHeaderRootReader::new_unchecked(&self.as_slice()[80..120])
+    }
+    pub fn nth3(&self) -> ETHHeaderRootReader<'r> {
+        ETHHeaderRootReader::new_unchecked(&self.as_slice()[120..160])
+    }
+}
+impl<'r> molecule::prelude::Reader<'r> for ETHHeaderRootHistoryReader<'r> {
+    type Entity = ETHHeaderRootHistory;
+    const NAME: &'static str = "ETHHeaderRootHistoryReader";
+    fn to_entity(&self) -> Self::Entity {
+        Self::Entity::new_unchecked(self.as_slice().to_owned().into())
+    }
+    fn new_unchecked(slice: &'r [u8]) -> Self {
+        ETHHeaderRootHistoryReader(slice)
+    }
+    fn as_slice(&self) -> &'r [u8] {
+        self.0
+    }
+    fn verify(slice: &[u8], _compatible: bool) -> molecule::error::VerificationResult<()> {
+        use molecule::verification_error as ve;
+        let slice_len = slice.len();
+        if slice_len != Self::TOTAL_SIZE {
+            return ve!(Self, TotalSizeNotMatch, Self::TOTAL_SIZE, slice_len);
+        }
+        Ok(())
+    }
+}
+pub struct ETHHeaderRootHistoryBuilder(pub(crate) [ETHHeaderRoot; 4]);
+impl ::core::fmt::Debug for ETHHeaderRootHistoryBuilder {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:?})", Self::NAME, &self.0[..])
+    }
+}
+impl ::core::default::Default for ETHHeaderRootHistoryBuilder {
+    fn default() -> Self {
+        ETHHeaderRootHistoryBuilder([
+            ETHHeaderRoot::default(),
+            ETHHeaderRoot::default(),
+            ETHHeaderRoot::default(),
+            ETHHeaderRoot::default(),
+        ])
+    }
+}
+impl ETHHeaderRootHistoryBuilder {
+    pub const TOTAL_SIZE: usize = 160;
+    pub const ITEM_SIZE: usize = 40;
+    pub const ITEM_COUNT: usize = 4;
+    pub fn set(mut self, v: [ETHHeaderRoot; 4]) -> Self {
+        self.0 = v;
+        self
+    }
+    pub fn nth0(mut self, v: ETHHeaderRoot) -> Self {
+        self.0[0] = v;
+        self
+    }
+    pub fn nth1(mut self, v: ETHHeaderRoot) -> Self {
+        self.0[1] = v;
+        self
+    }
+    pub fn nth2(mut self, v: ETHHeaderRoot) -> Self {
+        self.0[2] = v;
+        self
+    }
+    pub fn nth3(mut self, v: ETHHeaderRoot) -> Self {
+        self.0[3] = v;
+        self
+    }
+}
+impl molecule::prelude::Builder for ETHHeaderRootHistoryBuilder {
+    type Entity = ETHHeaderRootHistory;
+    const NAME: &'static str = "ETHHeaderRootHistoryBuilder";
+    fn expected_length(&self) -> usize {
+        Self::TOTAL_SIZE
+    }
+    fn write<W: ::molecule::io::Write>(&self, writer: &mut W) -> ::molecule::io::Result<()> {
+        writer.write_all(self.0[0].as_slice())?;
+        writer.write_all(self.0[1].as_slice())?;
+        writer.write_all(self.0[2].as_slice())?;
+        writer.write_all(self.0[3].as_slice())?;
+        Ok(())
+    }
+    fn build(&self) -> Self::Entity {
+        let mut inner = Vec::with_capacity(self.expected_length());
+        self.write(&mut inner)
+            .unwrap_or_else(|_| panic!("{} build should be ok", Self::NAME));
+        ETHHeaderRootHistory::new_unchecked(inner.into())
+    }
+}
+#[derive(Clone)]
 pub struct ETHHeaderCellMerkleData(molecule::bytes::Bytes);
 impl ::core::fmt::LowerHex for ETHHeaderCellMerkleData {
     fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
@@ -24,6 +399,26 @@ impl ::core::fmt::Display for ETHHeaderCellMerkleData {
         write!(f, "{}: {}", "merkle_root", self.merkle_root())?;
         write!(f, ", {}: {}", "start_height", self.start_height())?;
         write!(f, ", {}: {}", "latest_height", self.latest_height())?;
+        write!(f, ", {}: {}", "confirm", self.confirm())?;
+        write!(
+            f,
+            ", {}: {}",
+            "bridge_status_typescript_hash",
+            self.bridge_status_typescript_hash()
+        )?;
+        write!(
+            f,
+            ", {}: {}",
+            "token_allowlist_typescript_hash",
+            self.token_allowlist_typescript_hash()
+        )?;
+        write!(
+            f,
+            ", {}: {}",
+            "consumed_event_registry_typescript_hash",
+            self.consumed_event_registry_typescript_hash()
+        )?;
+        write!(f, ", {}: {}", "history", self.history())?;
         write!(f, " }}")
     }
 }
@@ -31,15 +426,24 @@ impl ::core::default::Default for ETHHeaderCellMerkleData {
     fn default() -> Self {
         let v: Vec<u8> = vec![
             0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         ];
         ETHHeaderCellMerkleData::new_unchecked(v.into())
     }
 }
 impl ETHHeaderCellMerkleData {
-    pub const TOTAL_SIZE: usize = 48;
-    pub const FIELD_SIZES: [usize; 3] = [32, 8, 8];
-    pub const FIELD_COUNT: usize = 3;
+    pub const TOTAL_SIZE: usize = 312;
+    pub const FIELD_SIZES: [usize; 8] = [32, 8, 8, 8, 32, 32, 32, 160];
+    pub const FIELD_COUNT: usize = 8;
     pub fn merkle_root(&self) -> Byte32 {
         Byte32::new_unchecked(self.0.slice(0..32))
     }
@@ -49,6 +453,21 @@ impl ETHHeaderCellMerkleData {
     pub fn latest_height(&self) -> Uint64 {
         Uint64::new_unchecked(self.0.slice(40..48))
     }
+    pub fn confirm(&self) -> Uint64 {
+        Uint64::new_unchecked(self.0.slice(48..56))
+    }
+    pub fn bridge_status_typescript_hash(&self) -> Byte32 {
+        Byte32::new_unchecked(self.0.slice(56..88))
+    }
+    pub fn token_allowlist_typescript_hash(&self) -> Byte32 {
+        Byte32::new_unchecked(self.0.slice(88..120))
+    }
+    pub fn consumed_event_registry_typescript_hash(&self) -> Byte32 {
+        Byte32::new_unchecked(self.0.slice(120..152))
+    }
+    pub fn history(&self) -> ETHHeaderRootHistory {
+        ETHHeaderRootHistory::new_unchecked(self.0.slice(152..312))
+    }
     pub fn as_reader<'r>(&'r self) -> ETHHeaderCellMerkleDataReader<'r> {
         ETHHeaderCellMerkleDataReader::new_unchecked(self.as_slice())
     }
@@ -79,6 +498,11 @@ impl molecule::prelude::Entity for ETHHeaderCellMerkleData {
             .merkle_root(self.merkle_root())
             .start_height(self.start_height())
             .latest_height(self.latest_height())
+            .confirm(self.confirm())
+            .bridge_status_typescript_hash(self.bridge_status_typescript_hash())
+            .token_allowlist_typescript_hash(self.token_allowlist_typescript_hash())
+            .consumed_event_registry_typescript_hash(self.consumed_event_registry_typescript_hash())
+            .history(self.history())
     }
 }
 #[derive(Clone, Copy)]
@@ -103,13 +527,33 @@ impl<'r> ::core::fmt::Display for ETHHeaderCellMerkleDataReader<'r> {
         write!(f, "{}: {}", "merkle_root", self.merkle_root())?;
         write!(f, ", {}: {}", "start_height", self.start_height())?;
         write!(f, ", {}: {}", "latest_height", self.latest_height())?;
+        write!(f, ", {}: {}", "confirm", self.confirm())?;
+        write!(
+            f,
+            ", {}: {}",
+            "bridge_status_typescript_hash",
+            self.bridge_status_typescript_hash()
+        )?;
+        write!(
+            f,
+            ", {}: {}",
+            "token_allowlist_typescript_hash",
+            self.token_allowlist_typescript_hash()
+        )?;
+        write!(
+            f,
+            ", {}: {}",
+            "consumed_event_registry_typescript_hash",
+            self.consumed_event_registry_typescript_hash()
+        )?;
+        write!(f, ", {}: {}", "history", self.history())?;
         write!(f, " }}")
     }
 }
 impl<'r> ETHHeaderCellMerkleDataReader<'r> {
-    pub const TOTAL_SIZE: usize = 48;
-    pub const FIELD_SIZES: [usize; 3] = [32, 8, 8];
-    pub const FIELD_COUNT: usize = 3;
+    pub const TOTAL_SIZE: usize = 312;
+    pub const FIELD_SIZES: [usize; 8] = [32, 8, 8, 8, 32, 32, 32, 160];
+    pub const FIELD_COUNT: usize = 8;
     pub fn merkle_root(&self) -> Byte32Reader<'r> {
         Byte32Reader::new_unchecked(&self.as_slice()[0..32])
     }
@@ -119,6 +563,21 @@ impl<'r> ETHHeaderCellMerkleDataReader<'r> {
     pub fn latest_height(&self) -> Uint64Reader<'r> {
         Uint64Reader::new_unchecked(&self.as_slice()[40..48])
     }
+    pub fn confirm(&self) -> Uint64Reader<'r> {
+        Uint64Reader::new_unchecked(&self.as_slice()[48..56])
+    }
+    pub fn bridge_status_typescript_hash(&self) -> Byte32Reader<'r> {
+        Byte32Reader::new_unchecked(&self.as_slice()[56..88])
+    }
+    pub fn token_allowlist_typescript_hash(&self) -> Byte32Reader<'r> {
+        Byte32Reader::new_unchecked(&self.as_slice()[88..120])
+    }
+    pub fn consumed_event_registry_typescript_hash(&self) -> Byte32Reader<'r> {
+        Byte32Reader::new_unchecked(&self.as_slice()[120..152])
+    }
+    pub fn history(&self) -> ETHHeaderRootHistoryReader<'r> {
+        ETHHeaderRootHistoryReader::new_unchecked(&self.as_slice()[152..312])
+    }
 }
 impl<'r> molecule::prelude::Reader<'r> for ETHHeaderCellMerkleDataReader<'r> {
     type Entity = ETHHeaderCellMerkleData;
@@ -146,11 +605,16 @@ pub struct ETHHeaderCellMerkleDataBuilder {
     pub(crate) merkle_root: Byte32,
     pub(crate) start_height: Uint64,
     pub(crate) latest_height: Uint64,
+    pub(crate) confirm: Uint64,
+    pub(crate) bridge_status_typescript_hash: Byte32,
+    pub(crate) token_allowlist_typescript_hash: Byte32,
+    pub(crate) consumed_event_registry_typescript_hash: Byte32,
+    pub(crate) history: ETHHeaderRootHistory,
 }
 impl ETHHeaderCellMerkleDataBuilder {
-    pub const TOTAL_SIZE: usize = 48;
-    pub const FIELD_SIZES: [usize; 3] = [32, 8, 8];
-    pub const FIELD_COUNT: usize = 3;
+    pub const TOTAL_SIZE: usize = 312;
+    pub const FIELD_SIZES: [usize; 8] = [32, 8, 8, 8, 32, 32, 32, 160];
+    pub const FIELD_COUNT: usize = 8;
     pub fn merkle_root(mut self, v: Byte32) -> Self {
         self.merkle_root = v;
         self
@@ -163,6 +627,26 @@ impl ETHHeaderCellMerkleDataBuilder {
         self.latest_height = v;
         self
     }
+    pub fn confirm(mut self, v: Uint64) -> Self {
+        self.confirm = v;
+        self
+    }
+    pub fn bridge_status_typescript_hash(mut self, v: Byte32) -> Self {
+        self.bridge_status_typescript_hash = v;
+        self
+    }
+    pub fn token_allowlist_typescript_hash(mut self, v: Byte32) -> Self {
+        self.token_allowlist_typescript_hash = v;
+        self
+    }
+    pub fn consumed_event_registry_typescript_hash(mut self, v: Byte32) -> Self {
+        self.consumed_event_registry_typescript_hash = v;
+        self
+    }
+    pub fn history(mut self, v: ETHHeaderRootHistory) -> Self {
+        self.history = v;
+        self
+    }
 }
 impl molecule::prelude::Builder for ETHHeaderCellMerkleDataBuilder {
     type Entity = ETHHeaderCellMerkleData;
@@ -174,6 +658,11 @@ impl molecule::prelude::Builder for ETHHeaderCellMerkleDataBuilder {
         writer.write_all(self.merkle_root.as_slice())?;
         writer.write_all(self.start_height.as_slice())?;
         writer.write_all(self.latest_height.as_slice())?;
+        writer.write_all(self.confirm.as_slice())?;
+        writer.write_all(self.bridge_status_typescript_hash.as_slice())?;
+        writer.write_all(self.token_allowlist_typescript_hash.as_slice())?;
+        writer.write_all(self.consumed_event_registry_typescript_hash.as_slice())?;
+        writer.write_all(self.history.as_slice())?;
         Ok(())
     }
     fn build(&self) -> Self::Entity {
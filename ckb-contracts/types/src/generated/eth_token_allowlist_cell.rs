@@ -0,0 +1,277 @@
+// Generated by Molecule 0.6.1
+
+use super::basic::*;
+use molecule::prelude::*;
+#[derive(Clone)]
+pub struct ETHTokenAllowlistCellData(molecule::bytes::Bytes);
+impl ::core::fmt::LowerHex for ETHTokenAllowlistCellData {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        use molecule::hex_string;
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        write!(f, "{}", hex_string(self.as_slice()))
+    }
+}
+impl ::core::fmt::Debug for ETHTokenAllowlistCellData {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:#x})", Self::NAME, self)
+    }
+}
+impl ::core::fmt::Display for ETHTokenAllowlistCellData {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{} [", Self::NAME)?;
+        for i in 0..self.len() {
+            if i == 0 {
+                write!(f, "{}", self.get_unchecked(i))?;
+            } else {
+                write!(f, ", {}", self.get_unchecked(i))?;
+            }
+        }
+        write!(f, "]")
+    }
+}
+impl ::core::default::Default for ETHTokenAllowlistCellData {
+    fn default() -> Self {
+        let v: Vec<u8> = vec![0, 0, 0, 0];
+        ETHTokenAllowlistCellData::new_unchecked(v.into())
+    }
+}
+impl ETHTokenAllowlistCellData {
+    pub const ITEM_SIZE: usize = 20;
+    pub fn total_size(&self) -> usize {
+        molecule::NUMBER_SIZE * (self.item_count() + 1)
+    }
+    pub fn item_count(&self) -> usize {
+        molecule::unpack_number(self.as_slice()) as usize
+    }
+    pub fn len(&self) -> usize {
+        self.item_count()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn get(&self, idx: usize) -> Option<ETHAddress> {
+        if idx >= self.len() {
+            None
+        } else {
+            Some(self.get_unchecked(idx))
+        }
+    }
+    pub fn get_unchecked(&self, idx: usize) -> ETHAddress {
+        let start = molecule::NUMBER_SIZE + Self::ITEM_SIZE * idx;
+        let end = start + Self::ITEM_SIZE;
+        ETHAddress::new_unchecked(self.0.slice(start..end))
+    }
+    pub fn as_reader<'r>(&'r self) -> ETHTokenAllowlistCellDataReader<'r> {
+        ETHTokenAllowlistCellDataReader::new_unchecked(self.as_slice())
+    }
+}
+impl molecule::prelude::Entity for ETHTokenAllowlistCellData {
+    type Builder = ETHTokenAllowlistCellDataBuilder;
+    const NAME: &'static str = "ETHTokenAllowlistCellData";
+    fn new_unchecked(data: molecule::bytes::Bytes) -> Self {
+        ETHTokenAllowlistCellData(data)
+    }
+    fn as_bytes(&self) -> molecule::bytes::Bytes {
+        self.0.clone()
+    }
+    fn as_slice(&self) -> &[u8] {
+        &self.0[..]
+    }
+    fn from_slice(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+        ETHTokenAllowlistCellDataReader::from_slice(slice).map(|reader| reader.to_entity())
+    }
+    fn from_compatible_slice(slice: &[u8]) -> molecule::error::VerificationResult<Self> {
+        ETHTokenAllowlistCellDataReader::from_compatible_slice(slice)
+            .map(|reader| reader.to_entity())
+    }
+    fn new_builder() -> Self::Builder {
+        ::core::default::Default::default()
+    }
+    fn as_builder(self) -> Self::Builder {
+        Self::new_builder().extend(self.into_iter())
+    }
+}
+#[derive(Clone, Copy)]
+pub struct ETHTokenAllowlistCellDataReader<'r>(&'r [u8]);
+impl<'r> ::core::fmt::LowerHex for ETHTokenAllowlistCellDataReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        use molecule::hex_string;
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        write!(f, "{}", hex_string(self.as_slice()))
+    }
+}
+impl<'r> ::core::fmt::Debug for ETHTokenAllowlistCellDataReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{}({:#x})", Self::NAME, self)
+    }
+}
+impl<'r> ::core::fmt::Display for ETHTokenAllowlistCellDataReader<'r> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        write!(f, "{} [", Self::NAME)?;
+        for i in 0..self.len() {
+            if i == 0 {
+                write!(f, "{}", self.get_unchecked(i))?;
+            } else {
+                write!(f, ", {}", self.get_unchecked(i))?;
+            }
+        }
+        write!(f, "]")
+    }
+}
+impl<'r> ETHTokenAllowlistCellDataReader<'r> {
+    pub const ITEM_SIZE: usize = 20;
+    pub fn total_size(&self) -> usize {
+        molecule::NUMBER_SIZE * (self.item_count() + 1)
+    }
+    pub fn item_count(&self) -> usize {
+        molecule::unpack_number(self.as_slice()) as usize
+    }
+    pub fn len(&self) -> usize {
+        self.item_count()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn get(&self, idx: usize) -> Option<ETHAddressReader<'r>> {
+        if idx >= self.len() {
+            None
+        } else {
+            Some(self.get_unchecked(idx))
+        }
+    }
+    pub fn get_unchecked(&self, idx: usize) -> ETHAddressReader<'r> {
+        let start = molecule::NUMBER_SIZE + Self::ITEM_SIZE * idx;
+        let end = start + Self::ITEM_SIZE;
+        ETHAddressReader::new_unchecked(&self.as_slice()[start..end])
+    }
+}
+impl<'r> molecule::prelude::Reader<'r> for ETHTokenAllowlistCellDataReader<'r> {
+    type Entity = ETHTokenAllowlistCellData;
+    const NAME: &'static str = "ETHTokenAllowlistCellDataReader";
+    fn to_entity(&self) -> Self::Entity {
+        Self::Entity::new_unchecked(self.as_slice().to_owned().into())
+    }
+    fn new_unchecked(slice: &'r [u8]) -> Self {
+        ETHTokenAllowlistCellDataReader(slice)
+    }
+    fn as_slice(&self) -> &'r [u8] {
+        self.0
+    }
+    fn verify(slice: &[u8], _compatible: bool) -> molecule::error::VerificationResult<()> {
+        use molecule::verification_error as ve;
+        let slice_len = slice.len();
+        if slice_len < molecule::NUMBER_SIZE {
+            return ve!(Self, HeaderIsBroken, molecule::NUMBER_SIZE, slice_len);
+        }
+        let item_count = molecule::unpack_number(slice) as usize;
+        if item_count == 0 {
+            if slice_len != molecule::NUMBER_SIZE {
+                return ve!(Self, TotalSizeNotMatch, molecule::NUMBER_SIZE, slice_len);
+            }
+            return Ok(());
+        }
+        let total_size = molecule::NUMBER_SIZE + Self::ITEM_SIZE * item_count;
+        if slice_len != total_size {
+            return ve!(Self, TotalSizeNotMatch, total_size, slice_len);
+        }
+        Ok(())
+    }
+}
+#[derive(Debug, Default)]
+pub struct ETHTokenAllowlistCellDataBuilder(pub(crate) Vec<ETHAddress>);
+impl ETHTokenAllowlistCellDataBuilder {
+    pub const ITEM_SIZE: usize = 20;
+    pub fn set(mut self, v: Vec<ETHAddress>) -> Self {
+        self.0 = v;
+        self
+    }
+    pub fn push(mut self, v: ETHAddress) -> Self {
+        self.0.push(v);
+        self
+    }
+    pub fn extend<T: ::core::iter::IntoIterator<Item = ETHAddress>>(mut self, iter: T) -> Self {
+        for elem in iter {
+            self.0.push(elem);
+        }
+        self
+    }
+}
+impl molecule::prelude::Builder for ETHTokenAllowlistCellDataBuilder {
+    type Entity = ETHTokenAllowlistCellData;
+    const NAME: &'static str = "ETHTokenAllowlistCellDataBuilder";
+    fn expected_length(&self) -> usize {
+        molecule::NUMBER_SIZE + Self::ITEM_SIZE * self.0.len()
+    }
+    fn write<W: ::molecule::io::Write>(&self, writer: &mut W) -> ::molecule::io::Result<()> {
+        writer.write_all(&molecule::pack_number(self.0.len() as molecule::Number))?;
+        for inner in &self.0[..] {
+            writer.write_all(inner.as_slice())?;
+        }
+        Ok(())
+    }
+    fn build(&self) -> Self::Entity {
+        let mut inner = Vec::with_capacity(self.expected_length());
+        self.write(&mut inner)
+            .unwrap_or_else(|_| panic!("{} build should be ok", Self::NAME));
+        ETHTokenAllowlistCellData::new_unchecked(inner.into())
+    }
+}
+pub struct ETHTokenAllowlistCellDataIterator(ETHTokenAllowlistCellData, usize, usize);
+impl ::core::iter::Iterator for ETHTokenAllowlistCellDataIterator {
+    type Item = ETHAddress;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.1 >= self.2 {
+            None
+        } else {
+            let ret = self.0.get_unchecked(self.1);
+            self.1 += 1;
+            Some(ret)
+        }
+    }
+}
+impl ::core::iter::ExactSizeIterator for ETHTokenAllowlistCellDataIterator {
+    fn len(&self) -> usize {
+        self.2 - self.1
+    }
+}
+impl ::core::iter::IntoIterator for ETHTokenAllowlistCellData {
+    type Item = ETHAddress;
+    type IntoIter = ETHTokenAllowlistCellDataIterator;
+    fn into_iter(self) -> Self::IntoIter {
+        let len = self.len();
+        ETHTokenAllowlistCellDataIterator(self, 0, len)
+    }
+}
+impl<'r> ETHTokenAllowlistCellDataReader<'r> {
+    pub fn iter<'t>(&'t self) -> ETHTokenAllowlistCellDataReaderIterator<'t, 'r> {
+        ETHTokenAllowlistCellDataReaderIterator(&self, 0, self.len())
+    }
+}
+pub struct ETHTokenAllowlistCellDataReaderIterator<'t, 'r>(
+    &'t ETHTokenAllowlistCellDataReader<'r>,
+    usize,
+    usize,
+);
+impl<'t: 'r, 'r> ::core::iter::Iterator for ETHTokenAllowlistCellDataReaderIterator<'t, 'r> {
+    type Item = ETHAddressReader<'t>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.1 >= self.2 {
+            None
+        } else {
+            let ret = self.0.get_unchecked(self.1);
+            self.1 += 1;
+            Some(ret)
+        }
+    }
+}
+impl<'t: 'r, 'r> ::core::iter::ExactSizeIterator
+    for ETHTokenAllowlistCellDataReaderIterator<'t, 'r>
+{
+    fn len(&self) -> usize {
+        self.2 - self.1
+    }
+}
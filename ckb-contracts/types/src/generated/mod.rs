@@ -3,10 +3,16 @@ pub mod basic;
 #[allow(clippy::all)]
 pub mod eth_bridge_lock_cell;
 #[allow(clippy::all)]
+pub mod eth_bridge_status_cell;
+#[allow(clippy::all)]
 pub mod eth_bridge_type_cell;
 #[allow(clippy::all)]
+pub mod eth_consumed_event_registry_cell;
+#[allow(clippy::all)]
 pub mod eth_header_cell;
 #[allow(clippy::all)]
 pub mod eth_recipient_cell;
 #[allow(clippy::all)]
+pub mod eth_token_allowlist_cell;
+#[allow(clippy::all)]
 pub mod witness;
@@ -3,7 +3,9 @@
 extern crate alloc;
 extern crate no_std_compat as std;
 
+pub mod bridge_fee;
 pub mod config;
+pub mod consumed_event_registry;
 pub mod convert;
 pub mod eth_lock_event;
 pub mod eth_recipient_cell;
@@ -117,6 +117,7 @@ async function deploy() {
   deployedContracts.eth_token_locker_addr = lockerAddr;
   deployedContracts.eth_ckb_chain_addr = CKBChainAddr;
   deployedContracts.ckb_relay_mutlisig_threshold.threshold = multisigThreshold;
+  deployedContracts.ckb_relay_mutlisig_threshold.validators = validators;
   const new_config = TOML.stringify(forceConfig);
   fs.writeFileSync(forceConfigPath, new_config);
   console.error('write eth addr into config successfully');
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use force_eth_lib::dapp::indexer::DexFilter;
+use force_eth_lib::dapp::proof_server;
 use force_eth_lib::dapp::server::start;
 use force_eth_lib::dapp::CkbHeaderIndexer;
 use force_eth_lib::dapp::CkbIndexer;
@@ -20,6 +21,7 @@ pub async fn dapp_handle(command: DappCommand) -> Result<()> {
         DappCommand::EthTxRelayer(args) => eth_tx_relay(args).await,
         DappCommand::CkbHeaderIndexer(args) => ckb_header_indexer(args).await,
         DappCommand::EthHeaderIndexer(args) => eth_header_indexer(args).await,
+        DappCommand::EthProofServer(args) => eth_proof_server(args).await,
     }
 }
 
@@ -87,6 +89,7 @@ async fn eth_tx_relay(args: EthTxRelayerArgs) -> Result<()> {
         args.network,
         args.private_key_path,
         args.mint_concurrency,
+        args.mint_batch_size,
         args.minimum_cell_capacity,
         args.db_path,
         args.rocksdb_path,
@@ -129,3 +132,13 @@ async fn eth_header_indexer(args: EthHeaderIndexerArgs) -> Result<()> {
         tokio::time::delay_for(std::time::Duration::from_secs(10)).await;
     }
 }
+
+async fn eth_proof_server(args: EthProofServerArgs) -> Result<()> {
+    proof_server::start(
+        args.config_path,
+        args.network,
+        args.rocksdb_path,
+        args.listen_url,
+    )
+    .await
+}
@@ -9,6 +9,7 @@ pub enum DappCommand {
     EthTxRelayer(EthTxRelayerArgs),
     CkbHeaderIndexer(CkbHeaderIndexerArgs),
     EthHeaderIndexer(EthHeaderIndexerArgs),
+    EthProofServer(EthProofServerArgs),
 }
 
 #[derive(Clap, Clone, Debug)]
@@ -82,6 +83,8 @@ pub struct EthTxRelayerArgs {
     pub private_key_path: String,
     #[clap(long, default_value = "100")]
     pub mint_concurrency: u64,
+    #[clap(long, default_value = "1")]
+    pub mint_batch_size: u64,
     #[clap(long, default_value = "1000")]
     pub minimum_cell_capacity: u64,
     #[clap(long, default_value = "mysql://root:@127.0.0.1:3306/forcedb")]
@@ -111,3 +114,15 @@ pub struct EthHeaderIndexerArgs {
     #[clap(long, default_value = "~/.force-bridge/dapp-lib/eth-rocksdb")]
     pub rocksdb_path: String,
 }
+
+#[derive(Clap, Clone, Debug)]
+pub struct EthProofServerArgs {
+    #[clap(long, default_value = "~/.force-bridge/config.toml")]
+    pub config_path: String,
+    #[clap(long)]
+    pub network: Option<String>,
+    #[clap(long, default_value = "~/.force-bridge/dapp-lib/eth-rocksdb")]
+    pub rocksdb_path: String,
+    #[clap(short, long, default_value = "127.0.0.1:3031")]
+    pub listen_url: String,
+}
@@ -2,10 +2,15 @@ use anyhow::{anyhow, bail, Result};
 use dapp::dapp_handle;
 use force_eth_lib::header_relay::ckb_relay::CKBRelayer;
 use force_eth_lib::header_relay::eth_relay::{wait_header_sync_success, ETHRelayer};
+use force_eth_lib::header_relay::eth_smt::rebuild_eth_smt;
+use force_eth_lib::header_relay::multisig_signer;
 use force_eth_lib::monitor::relay_monitor::{AccountMonitorArgs, RelayMonitor};
+use force_eth_lib::monitor::supply_reconciler::{parse_reconcile_tokens, SupplyReconciler};
+use force_eth_lib::transfer::consumed_event_registry::rebuild_consumed_event_registry;
 use force_eth_lib::transfer::to_ckb::{
-    self, approve, generate_eth_spv_proof_json, get_or_create_bridge_cell, init_multi_sign_address,
-    lock_eth, lock_token, recycle_bridge_cell, recycle_recipient_cell, send_eth_spv_proof_tx,
+    self, approve, generate_eth_spv_proof_json, get_or_create_bridge_cell,
+    get_replay_resist_outpoint, init_multi_sign_address, lock_eth, lock_token, recycle_bridge_cell,
+    recycle_recipient_cell, send_eth_spv_proof_tx, set_bridge_paused, set_token_allowed,
 };
 use force_eth_lib::transfer::to_eth::{
     burn, get_balance, get_ckb_proof_info, init_light_client, transfer_sudt, unlock_with_retry,
@@ -14,12 +19,15 @@ use force_eth_lib::transfer::to_eth::{
 use force_eth_lib::util::ckb_tx_generator::Generator;
 use force_eth_lib::util::ckb_util::parse_privkey_path;
 use force_eth_lib::util::config::{self, ForceConfig};
-use force_eth_lib::util::eth_util::{convert_eth_address, parse_private_key};
+use force_eth_lib::util::eth_util::{
+    convert_eth_address, parse_private_key, EthFeeOptions, FeeMode,
+};
 use force_eth_lib::util::transfer;
 use log::{debug, error, info};
 use serde_json::json;
 use shellexpand::tilde;
 use types::*;
+use web3::types::U256;
 
 pub mod dapp;
 pub mod types;
@@ -52,10 +60,20 @@ pub async fn handler(opt: Opts) -> Result<()> {
         SubCommand::Transfer(args) => transfer_handler(args).await,
         SubCommand::QuerySudtBlance(args) => query_sudt_balance_handler(args).await,
         SubCommand::EthRelay(args) => eth_relay_handler(args).await,
+        SubCommand::RebuildEthSmt(args) => rebuild_eth_smt_handler(args).await,
+        SubCommand::RebuildConsumedEventRegistry(args) => {
+            rebuild_consumed_event_registry_handler(args).await
+        }
         SubCommand::CkbRelay(args) => ckb_relay_handler(args).await,
+        SubCommand::MultisigSigner(args) => multisig_signer_handler(args).await,
         SubCommand::RelayerMonitor(args) => relayer_monitor(args).await,
+        SubCommand::ReconcileSupply(args) => reconcile_supply_handler(args).await,
         SubCommand::RecycleBridgeCell(args) => recycle_bridge_cell_handler(args).await,
         SubCommand::RecycleRecipientCell(args) => recycle_recipient_cell_handler(args).await,
+        SubCommand::Pause(args) => bridge_status_handler(args, true).await,
+        SubCommand::Resume(args) => bridge_status_handler(args, false).await,
+        SubCommand::AddAllowedToken(args) => token_allowlist_handler(args, true).await,
+        SubCommand::RemoveAllowedToken(args) => token_allowlist_handler(args, false).await,
         SubCommand::Dapp(dapp_command) => dapp_handle(dapp_command).await,
     }
 }
@@ -120,6 +138,17 @@ pub async fn deploy_ckb(args: DeployCKBArgs) -> Result<()> {
 }
 
 pub async fn create_bridge_cell_handler(args: CreateBridgeCellArgs) -> Result<()> {
+    let mut fee_recipients = vec![];
+    for fee_recipient in args.fee_recipient.iter() {
+        let parts = fee_recipient.split(':').collect::<Vec<_>>();
+        if parts.len() != 2 {
+            bail!("fee recipient should be <address>:<basis_points>");
+        }
+        let basis_points = parts[1]
+            .parse::<u64>()
+            .map_err(|e| anyhow!("invalid basis points of fee recipient: {}", e))?;
+        fee_recipients.push((parts[0].to_string(), basis_points));
+    }
     let outpoint_hex = get_or_create_bridge_cell(
         args.config_path,
         args.network,
@@ -129,6 +158,7 @@ pub async fn create_bridge_cell_handler(args: CreateBridgeCellArgs) -> Result<()
         args.eth_token_address,
         args.recipient_address.clone(),
         args.bridge_fee,
+        fee_recipients,
         args.simple_typescript,
         args.number,
         args.force_create,
@@ -175,7 +205,12 @@ pub async fn approve_handler(args: ApproveArgs) -> Result<()> {
         args.network,
         args.private_key_path,
         args.erc20_addr,
-        args.gas_price,
+        get_fee_options(
+            args.fee_mode,
+            args.gas_price,
+            args.max_fee_per_gas,
+            args.max_priority_fee_per_gas,
+        ),
         args.wait,
     )
     .await
@@ -195,8 +230,13 @@ pub async fn lock_token_handler(args: LockTokenArgs) -> Result<()> {
         args.amount,
         args.bridge_fee,
         args.sudt_extra_data,
-        args.replay_resist_outpoint,
-        args.gas_price,
+        get_replay_resist_outpoint(args.replay_resist_outpoint, args.registry_mode)?,
+        get_fee_options(
+            args.fee_mode,
+            args.gas_price,
+            args.max_fee_per_gas,
+            args.max_priority_fee_per_gas,
+        ),
         args.wait,
     )
     .await
@@ -215,8 +255,13 @@ pub async fn lock_eth_handler(args: LockEthArgs) -> Result<()> {
         args.amount,
         args.bridge_fee,
         args.sudt_extra_data.unwrap_or_default(),
-        args.replay_resist_outpoint,
-        args.gas_price,
+        get_replay_resist_outpoint(args.replay_resist_outpoint, args.registry_mode)?,
+        get_fee_options(
+            args.fee_mode,
+            args.gas_price,
+            args.max_fee_per_gas,
+            args.max_priority_fee_per_gas,
+        ),
         args.wait,
     )
     .await
@@ -330,7 +375,12 @@ pub async fn unlock_handler(args: UnlockArgs) -> Result<()> {
         deployed_contracts.eth_ckb_chain_addr.clone(),
         deployed_contracts.eth_token_locker_addr.clone(),
         force_config.ckb_rocksdb_path,
-        0,
+        get_fee_options(
+            args.fee_mode,
+            args.gas_price,
+            args.max_fee_per_gas,
+            args.max_priority_fee_per_gas,
+        ),
         args.wait,
     )
     .await
@@ -379,7 +429,12 @@ pub async fn transfer_from_ckb_handler(args: TransferFromCkbArgs) -> Result<()>
         deployed_contracts.eth_ckb_chain_addr.clone(),
         deployed_contracts.eth_token_locker_addr.clone(),
         force_config.ckb_rocksdb_path,
-        0,
+        get_fee_options(
+            args.fee_mode,
+            args.gas_price,
+            args.max_fee_per_gas,
+            args.max_priority_fee_per_gas,
+        ),
         args.wait,
     )
     .await
@@ -431,7 +486,10 @@ pub async fn eth_relay_handler(args: EthRelayArgs) -> Result<()> {
         args.private_key_path,
         args.multisig_privkeys,
         args.confirm,
+        args.mint_confirm,
         args.delay,
+        args.retention_window,
+        args.multisig_signer_urls,
     )?;
     loop {
         let res = eth_relayer.start().await;
@@ -442,6 +500,92 @@ pub async fn eth_relay_handler(args: EthRelayArgs) -> Result<()> {
     }
 }
 
+pub async fn bridge_status_handler(args: BridgeStatusArgs, paused: bool) -> Result<()> {
+    debug!(
+        "bridge_status_handler args: {:?}, paused: {}",
+        &args, paused
+    );
+    let tx_hash = set_bridge_paused(
+        args.config_path,
+        args.network,
+        args.private_key_path,
+        args.multisig_privkeys,
+        paused,
+    )
+    .await?;
+    info!(
+        "{} the bridge successfully. tx_hash: {}",
+        if paused { "paused" } else { "resumed" },
+        tx_hash
+    );
+    Ok(())
+}
+
+pub async fn token_allowlist_handler(args: TokenAllowlistArgs, allowed: bool) -> Result<()> {
+    debug!(
+        "token_allowlist_handler args: {:?}, allowed: {}",
+        &args, allowed
+    );
+    let tx_hash = set_token_allowed(
+        args.config_path,
+        args.network,
+        args.private_key_path,
+        args.multisig_privkeys,
+        args.token.clone(),
+        allowed,
+    )
+    .await?;
+    info!(
+        "{} token {} {} the allowlist successfully. tx_hash: {}",
+        if allowed { "added" } else { "removed" },
+        args.token,
+        if allowed { "to" } else { "from" },
+        tx_hash
+    );
+    Ok(())
+}
+
+pub async fn rebuild_eth_smt_handler(args: RebuildEthSmtArgs) -> Result<()> {
+    debug!("rebuild_eth_smt_handler args: {:?}", &args);
+    let report = rebuild_eth_smt(args.config_path, args.network, args.dry_run).await?;
+    println!(
+        "{}",
+        json!({
+            "start_height": report.light_client.start_height,
+            "latest_height": report.light_client.latest_height,
+            "cell_merkle_root": hex::encode(report.light_client.merkle_root),
+            "local_merkle_root": hex::encode(report.local_root),
+            "first_mismatch_height": report.first_mismatch_height,
+            "consistent": report.is_consistent(),
+        })
+    );
+    if args.dry_run && !report.is_consistent() {
+        bail!("the local eth smt is not in line with the light client cell")
+    }
+    Ok(())
+}
+
+pub async fn rebuild_consumed_event_registry_handler(
+    args: RebuildConsumedEventRegistryArgs,
+) -> Result<()> {
+    debug!("rebuild_consumed_event_registry_handler args: {:?}", &args);
+    let report =
+        rebuild_consumed_event_registry(args.config_path, args.network, args.dry_run).await?;
+    println!(
+        "{}",
+        json!({
+            "cell_root": hex::encode(report.cell_root),
+            "local_root": hex::encode(report.local_root),
+            "consumed_events": report.consumed_events,
+            "consistent": report.is_consistent(),
+        })
+    );
+    if args.dry_run && !report.is_consistent() {
+        bail!("the local consumed event registry smt is not in line with the registry cell")
+    }
+    Ok(())
+}
+
 pub async fn ckb_relay_handler(args: CkbRelayArgs) -> Result<()> {
     debug!("ckb_relay_handler args: {:?}", &args);
     let config_path = tilde(args.config_path.as_str()).into_owned();
@@ -450,8 +594,14 @@ pub async fn ckb_relay_handler(args: CkbRelayArgs) -> Result<()> {
         args.network,
         args.private_key_path,
         args.mutlisig_privkeys,
-        args.gas_price,
+        get_fee_options(
+            args.fee_mode,
+            args.gas_price,
+            args.max_fee_per_gas,
+            args.max_priority_fee_per_gas,
+        ),
         args.confirm,
+        args.multisig_signer_urls,
     )?;
 
     let mut consecutive_failures = 0;
@@ -468,6 +618,21 @@ pub async fn ckb_relay_handler(args: CkbRelayArgs) -> Result<()> {
     bail!("5 consecutive failures when relay headers")
 }
 
+pub async fn multisig_signer_handler(args: MultisigSignerArgs) -> Result<()> {
+    debug!("multisig_signer_handler args: {:?}", &args);
+    multisig_signer::start(
+        args.config_path,
+        args.network,
+        args.ckb_private_key_path,
+        args.eth_private_key_path,
+        args.eth_confirm,
+        args.ckb_confirm,
+        args.mint_confirm,
+        args.listen_url,
+    )
+    .await
+}
+
 pub async fn relayer_monitor(args: RelayerMonitorArgs) -> Result<()> {
     let force_config = ForceConfig::new(args.config_path.as_str())?;
     let deployed_contracts = force_config
@@ -489,6 +654,23 @@ pub async fn relayer_monitor(args: RelayerMonitorArgs) -> Result<()> {
         &args.network,
     )
     .await?;
+    let supply_reconciler = if args.mode == "supply" {
+        Some(
+            SupplyReconciler::new(
+                ckb_rpc_url.clone(),
+                ckb_indexer_url.clone(),
+                eth_rpc_url.clone(),
+                args.db_path
+                    .clone()
+                    .ok_or_else(|| anyhow!("the db_path can not be none in supply mode"))?,
+                deployed_contracts.clone(),
+                parse_reconcile_tokens(&args.reconcile_token)?,
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
     let mut relay_monitor = RelayMonitor::new(
         ckb_rpc_url,
         ckb_indexer_url,
@@ -507,6 +689,8 @@ pub async fn relayer_monitor(args: RelayerMonitorArgs) -> Result<()> {
         args.eth_indexer_conservator,
         args.ckb_indexer_conservator,
         args.db_path,
+        supply_reconciler,
+        args.supply_conservator,
         account_monitor_args,
     )
     .await?;
@@ -519,6 +703,43 @@ pub async fn relayer_monitor(args: RelayerMonitorArgs) -> Result<()> {
     }
 }
 
+pub async fn reconcile_supply_handler(args: ReconcileSupplyArgs) -> Result<()> {
+    debug!("reconcile_supply_handler args: {:?}", &args);
+    let force_config = ForceConfig::new(args.config_path.as_str())?;
+    let deployed_contracts = force_config
+        .deployed_contracts
+        .as_ref()
+        .ok_or_else(|| anyhow!("contracts should be deployed"))?;
+    let mut reconciler = SupplyReconciler::new(
+        force_config.get_ckb_rpc_url(&args.network)?,
+        force_config.get_ckb_indexer_url(&args.network)?,
+        force_config.get_ethereum_rpc_url(&args.network)?,
+        args.db_path,
+        deployed_contracts.clone(),
+        parse_reconcile_tokens(&args.token)?,
+    )
+    .await?;
+    let reports = reconciler.reconcile().await?;
+    for (supply, exceeded) in reports.iter() {
+        println!(
+            "{}",
+            json!({
+                "token": hex::encode(supply.token),
+                "locker_balance": supply.locker_balance.to_string(),
+                "ckb_supply": supply.ckb_supply.to_string(),
+                "in_flight_locks": supply.in_flight_locks.to_string(),
+                "in_flight_burns": supply.in_flight_burns.to_string(),
+                "drift": supply.drift().to_string(),
+                "exceeded": exceeded,
+            })
+        );
+    }
+    if reports.iter().any(|(_, exceeded)| *exceeded) {
+        bail!("the supply drift exceeds the threshold")
+    }
+    Ok(())
+}
+
 pub async fn recycle_recipient_cell_handler(args: RecycleRecipientCellArgs) -> Result<()> {
     let force_config = ForceConfig::new(args.config_path.as_str())?;
     let deployed_contracts = force_config
@@ -541,3 +762,17 @@ pub async fn recycle_recipient_cell_handler(args: RecycleRecipientCellArgs) -> R
     info!("recycle recipient cell successfully for {}", tx_hash,);
     Ok(())
 }
+
+fn get_fee_options(
+    fee_mode: FeeMode,
+    gas_price: u64,
+    max_fee_per_gas: u64,
+    max_priority_fee_per_gas: u64,
+) -> EthFeeOptions {
+    EthFeeOptions {
+        fee_mode,
+        gas_price: U256::from(gas_price),
+        max_fee_per_gas: U256::from(max_fee_per_gas),
+        max_priority_fee_per_gas: U256::from(max_priority_fee_per_gas),
+    }
+}
@@ -1,5 +1,6 @@
 use super::dapp::types::DappCommand;
 use clap::Clap;
+use force_eth_lib::util::eth_util::FeeMode;
 
 #[derive(Clap, Clone, Debug)]
 #[clap(version = "0.1", author = "LeonLi000 <matrix.skygirl@gmail.com>")]
@@ -29,10 +30,18 @@ pub enum SubCommand {
     Unlock(UnlockArgs),
     QuerySudtBlance(SudtGetBalanceArgs),
     EthRelay(EthRelayArgs),
+    RebuildEthSmt(RebuildEthSmtArgs),
+    RebuildConsumedEventRegistry(RebuildConsumedEventRegistryArgs),
     CkbRelay(CkbRelayArgs),
+    MultisigSigner(MultisigSignerArgs),
     RelayerMonitor(RelayerMonitorArgs),
+    ReconcileSupply(ReconcileSupplyArgs),
     RecycleBridgeCell(RecycleBridgeCellArgs),
     RecycleRecipientCell(RecycleRecipientCellArgs),
+    Pause(BridgeStatusArgs),
+    Resume(BridgeStatusArgs),
+    AddAllowedToken(TokenAllowlistArgs),
+    RemoveAllowedToken(TokenAllowlistArgs),
     Dapp(DappCommand),
 }
 
@@ -54,6 +63,10 @@ pub struct CreateBridgeCellArgs {
     pub number: usize,
     #[clap(long, default_value = "0")]
     pub bridge_fee: u128,
+    // the bridge fee is split across the fee recipients by basis points, each in the format of
+    // <address>:<basis_points>. the owner takes the whole fee if there are no fee recipients.
+    #[clap(long)]
+    pub fee_recipient: Vec<String>,
     #[clap(short = 's', long)]
     pub simple_typescript: bool,
     #[clap(long)]
@@ -173,6 +186,12 @@ pub struct ApproveArgs {
     pub erc20_addr: String,
     #[clap(short, long, default_value = "0")]
     pub gas_price: u64,
+    #[clap(long, default_value = "legacy")]
+    pub fee_mode: FeeMode,
+    #[clap(long, default_value = "0")]
+    pub max_fee_per_gas: u64,
+    #[clap(long, default_value = "0")]
+    pub max_priority_fee_per_gas: u64,
     #[clap(long)]
     pub wait: bool,
 }
@@ -195,10 +214,19 @@ pub struct LockTokenArgs {
     pub sudt_extra_data: String,
     #[clap(long)]
     pub ckb_recipient_address: String,
-    #[clap(long)]
-    pub replay_resist_outpoint: String,
+    #[clap(long, required_unless_present = "registry-mode")]
+    pub replay_resist_outpoint: Option<String>,
+    // mint the lock event with the consumed event registry instead of a replay resist cell
+    #[clap(long, conflicts_with = "replay-resist-outpoint")]
+    pub registry_mode: bool,
     #[clap(short, long, default_value = "0")]
     pub gas_price: u64,
+    #[clap(long, default_value = "legacy")]
+    pub fee_mode: FeeMode,
+    #[clap(long, default_value = "0")]
+    pub max_fee_per_gas: u64,
+    #[clap(long, default_value = "0")]
+    pub max_priority_fee_per_gas: u64,
     #[clap(long)]
     pub wait: bool,
 }
@@ -219,10 +247,19 @@ pub struct LockEthArgs {
     pub sudt_extra_data: Option<String>,
     #[clap(long)]
     pub ckb_recipient_address: String,
-    #[clap(long)]
-    pub replay_resist_outpoint: String,
+    #[clap(long, required_unless_present = "registry-mode")]
+    pub replay_resist_outpoint: Option<String>,
+    // mint the lock event with the consumed event registry instead of a replay resist cell
+    #[clap(long, conflicts_with = "replay-resist-outpoint")]
+    pub registry_mode: bool,
     #[clap(short, long, default_value = "0")]
     pub gas_price: u64,
+    #[clap(long, default_value = "legacy")]
+    pub fee_mode: FeeMode,
+    #[clap(long, default_value = "0")]
+    pub max_fee_per_gas: u64,
+    #[clap(long, default_value = "0")]
+    pub max_priority_fee_per_gas: u64,
     #[clap(long)]
     pub wait: bool,
 }
@@ -269,6 +306,12 @@ pub struct TransferFromCkbArgs {
     pub unlock_fee: u128,
     #[clap(short, long, default_value = "0")]
     pub gas_price: u64,
+    #[clap(long, default_value = "legacy")]
+    pub fee_mode: FeeMode,
+    #[clap(long, default_value = "0")]
+    pub max_fee_per_gas: u64,
+    #[clap(long, default_value = "0")]
+    pub max_priority_fee_per_gas: u64,
     #[clap(long)]
     pub wait: bool,
 }
@@ -315,6 +358,12 @@ pub struct UnlockArgs {
     pub burn_tx_hash: String,
     #[clap(short, long, default_value = "0")]
     pub gas_price: u64,
+    #[clap(long, default_value = "legacy")]
+    pub fee_mode: FeeMode,
+    #[clap(long, default_value = "0")]
+    pub max_fee_per_gas: u64,
+    #[clap(long, default_value = "0")]
+    pub max_priority_fee_per_gas: u64,
     #[clap(long)]
     pub wait: bool,
 }
@@ -331,8 +380,60 @@ pub struct EthRelayArgs {
     pub multisig_privkeys: Vec<String>,
     #[clap(long, default_value = "15")]
     pub confirm: u64,
+    #[clap(long, default_value = "15")]
+    pub mint_confirm: u64,
     #[clap(long, default_value = "300")]
     pub delay: u64,
+    #[clap(long)]
+    pub retention_window: Option<u64>,
+    #[clap(long)]
+    pub multisig_signer_urls: Vec<String>,
+}
+
+#[derive(Clap, Clone, Debug)]
+pub struct BridgeStatusArgs {
+    #[clap(long, default_value = "~/.force-bridge/config.toml")]
+    pub config_path: String,
+    #[clap(long)]
+    pub network: Option<String>,
+    #[clap(short = 'k', long)]
+    pub private_key_path: String,
+    #[clap(long)]
+    pub multisig_privkeys: Vec<String>,
+}
+
+#[derive(Clap, Clone, Debug)]
+pub struct TokenAllowlistArgs {
+    #[clap(long, default_value = "~/.force-bridge/config.toml")]
+    pub config_path: String,
+    #[clap(long)]
+    pub network: Option<String>,
+    #[clap(short = 'k', long)]
+    pub private_key_path: String,
+    #[clap(long)]
+    pub multisig_privkeys: Vec<String>,
+    #[clap(long)]
+    pub token: String,
+}
+
+#[derive(Clap, Clone, Debug)]
+pub struct RebuildEthSmtArgs {
+    #[clap(long, default_value = "~/.force-bridge/config.toml")]
+    pub config_path: String,
+    #[clap(long)]
+    pub network: Option<String>,
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Clap, Clone, Debug)]
+pub struct RebuildConsumedEventRegistryArgs {
+    #[clap(long, default_value = "~/.force-bridge/config.toml")]
+    pub config_path: String,
+    #[clap(long)]
+    pub network: Option<String>,
+    #[clap(long)]
+    pub dry_run: bool,
 }
 
 #[derive(Clap, Clone, Debug)]
@@ -349,10 +450,38 @@ pub struct CkbRelayArgs {
     pub max_tx_count: u64,
     #[clap(short, long, default_value = "0")]
     pub gas_price: u64,
+    #[clap(long, default_value = "legacy")]
+    pub fee_mode: FeeMode,
+    #[clap(long, default_value = "0")]
+    pub max_fee_per_gas: u64,
+    #[clap(long, default_value = "0")]
+    pub max_priority_fee_per_gas: u64,
     #[clap(long)]
     pub mutlisig_privkeys: Vec<String>,
     #[clap(long, default_value = "15")]
     pub confirm: u64,
+    #[clap(long)]
+    pub multisig_signer_urls: Vec<String>,
+}
+
+#[derive(Clap, Clone, Debug)]
+pub struct MultisigSignerArgs {
+    #[clap(long, default_value = "~/.force-bridge/config.toml")]
+    pub config_path: String,
+    #[clap(long)]
+    pub network: Option<String>,
+    #[clap(long)]
+    pub ckb_private_key_path: Option<String>,
+    #[clap(long)]
+    pub eth_private_key_path: Option<String>,
+    #[clap(long, default_value = "15")]
+    pub eth_confirm: u64,
+    #[clap(long, default_value = "15")]
+    pub ckb_confirm: u64,
+    #[clap(long, default_value = "15")]
+    pub mint_confirm: u64,
+    #[clap(long, default_value = "127.0.0.1:3040")]
+    pub listen_url: String,
 }
 
 #[derive(Clap, Clone, Debug)]
@@ -437,4 +566,23 @@ pub struct RelayerMonitorArgs {
     pub eth_balance_conservator: String,
     #[clap(long)]
     pub ckb_balance_conservator: String,
+    // the tokens to reconcile in supply mode, each in the format of
+    // <token_address>:<drift_threshold>.
+    #[clap(long)]
+    pub reconcile_token: Vec<String>,
+    #[clap(long)]
+    pub supply_conservator: Option<Vec<String>>,
+}
+
+#[derive(Clap, Clone, Debug)]
+pub struct ReconcileSupplyArgs {
+    #[clap(long, default_value = "~/.force-bridge/config.toml")]
+    pub config_path: String,
+    #[clap(long)]
+    pub network: Option<String>,
+    #[clap(long)]
+    pub db_path: String,
+    // each in the format of <token_address>:<drift_threshold>
+    #[clap(long)]
+    pub token: Vec<String>,
 }
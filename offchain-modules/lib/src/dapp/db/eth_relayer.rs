@@ -5,8 +5,10 @@ use sqlx::Done;
 #[derive(sqlx::FromRow, Debug, Default)]
 pub struct MintTask {
     pub lock_tx_hash: String,
+    pub log_index: u64,
     pub lock_tx_proof: String,
     pub block_number: u64,
+    pub status: String,
 }
 
 #[derive(sqlx::FromRow, Debug)]
@@ -43,7 +45,7 @@ pub async fn get_mint_tasks(
     end_block: u64,
 ) -> Result<Vec<MintTask>> {
     let sql = r#"
-SELECT eth_lock_tx_hash as lock_tx_hash, eth_spv_proof as lock_tx_proof, eth_block_number as block_number
+SELECT eth_lock_tx_hash as lock_tx_hash, log_index, eth_spv_proof as lock_tx_proof, eth_block_number as block_number, status
 FROM eth_to_ckb
 WHERE status = ? AND eth_block_number > ? AND eth_block_number <= ?
     "#;
@@ -56,15 +58,19 @@ WHERE status = ? AND eth_block_number > ? AND eth_block_number <= ?
     Ok(tasks)
 }
 
+// the held and the released tasks are retried too, the held ones are relayed once the rate limit
+// window frees up.
 pub async fn get_retry_tasks(pool: &MySqlPool) -> Result<Vec<MintTask>> {
     let sql = r#"
-SELECT block_number, lock_tx_hash, lock_tx_proof
+SELECT block_number, lock_tx_hash, log_index, lock_tx_proof, status
 FROM eth_tx_relayer
-WHERE status = ? or status = ?
+WHERE status = ? or status = ? or status = ? or status = ?
     "#;
     let tasks = sqlx::query_as::<_, MintTask>(sql)
         .bind("pending")
         .bind("retryable")
+        .bind("held")
+        .bind("released")
         .fetch_all(pool)
         .await?;
     Ok(tasks)
@@ -73,13 +79,14 @@ WHERE status = ? or status = ?
 pub async fn store_mint_tasks(pool: &MySqlPool, tasks: &[MintTask]) -> Result<()> {
     let mut tx = pool.begin().await?;
     let sql = r#"
-INSERT INTO eth_tx_relayer (block_number, lock_tx_hash, lock_tx_proof)
-VALUES (?,?,?)
+INSERT INTO eth_tx_relayer (block_number, lock_tx_hash, log_index, lock_tx_proof)
+VALUES (?,?,?,?)
     "#;
     for task in tasks.iter() {
         sqlx::query(sql)
             .bind(task.block_number)
             .bind(task.lock_tx_hash.clone())
+            .bind(task.log_index)
             .bind(task.lock_tx_proof.clone())
             .execute(&mut tx)
             .await?;
@@ -92,6 +99,7 @@ VALUES (?,?,?)
 pub async fn update_relayed_tx(
     pool: &MySqlPool,
     lock_tx_hash: String,
+    log_index: u64,
     status: String,
     mut err_msg: String,
 ) -> Result<bool> {
@@ -101,25 +109,53 @@ pub async fn update_relayed_tx(
     let sql = r#"
 UPDATE eth_tx_relayer
 SET status = ?, err_msg = ?
-WHERE lock_tx_hash = ?
+WHERE lock_tx_hash = ? AND log_index = ?
     "#;
     let rows_affected = sqlx::query(sql)
         .bind(status)
         .bind(err_msg)
         .bind(lock_tx_hash)
+        .bind(log_index)
         .execute(pool)
         .await?
         .rows_affected();
     Ok(rows_affected > 0)
 }
 
-pub async fn delete_relayed_tx(pool: &MySqlPool, lock_tx_hash: String) -> Result<bool> {
+pub async fn delete_relayed_tx(
+    pool: &MySqlPool,
+    lock_tx_hash: String,
+    log_index: u64,
+) -> Result<bool> {
     let sql = r#"
 DELETE FROM eth_tx_relayer
-WHERE lock_tx_hash = ?
+WHERE lock_tx_hash = ? AND log_index = ?
+    "#;
+    let rows_affected = sqlx::query(sql)
+        .bind(lock_tx_hash)
+        .bind(log_index)
+        .execute(pool)
+        .await?
+        .rows_affected();
+    Ok(rows_affected > 0)
+}
+
+// record the mint tx of the lock event once it is committed, the ckb indexer marks the lock event
+// success later. the supply reconciler does not count the lock event in flight meanwhile.
+pub async fn set_mint_tx_hash(
+    pool: &MySqlPool,
+    lock_tx_hash: &str,
+    log_index: u64,
+    ckb_tx_hash: &str,
+) -> Result<bool> {
+    let sql = r#"
+UPDATE eth_to_ckb SET ckb_tx_hash = ?
+WHERE eth_lock_tx_hash = ? AND log_index = ? AND ckb_tx_hash IS NULL
     "#;
     let rows_affected = sqlx::query(sql)
+        .bind(ckb_tx_hash)
         .bind(lock_tx_hash)
+        .bind(log_index)
         .execute(pool)
         .await?
         .rows_affected();
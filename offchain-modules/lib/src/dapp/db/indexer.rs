@@ -7,6 +7,7 @@ use sqlx::{MySql, Transaction};
 pub struct EthToCkbRecord {
     pub id: u64,
     pub eth_lock_tx_hash: String,
+    pub log_index: u64,
     pub status: String,
     pub token_addr: String,
     pub sender_addr: String,
@@ -256,6 +257,41 @@ pub async fn get_height_info(pool: &MySqlPool, id: u8) -> Result<CrossChainHeigh
     Ok(ret)
 }
 
+#[derive(sqlx::FromRow, Debug)]
+pub struct InFlightAmount {
+    pub amount: String,
+}
+
+// the amounts in hex of the lock events of the token which are not minted on ckb. the rejected
+// ones are counted too, as their tokens stay in the locker. the ones whose mint tx is committed are
+// in the sudt supply already, though the ckb indexer has not marked them success yet.
+pub async fn get_in_flight_lock_amounts(pool: &MySqlPool, token_addr: &str) -> Result<Vec<String>> {
+    let sql = r#"
+SELECT locked_amount as amount
+FROM eth_to_ckb
+WHERE token_addr = ? AND status <> 'success' AND ckb_tx_hash IS NULL
+    "#;
+    let ret = sqlx::query_as::<_, InFlightAmount>(sql)
+        .bind(token_addr)
+        .fetch_all(pool)
+        .await?;
+    Ok(ret.into_iter().map(|v| v.amount).collect())
+}
+
+// the amounts in hex of the burns of the token which are not unlocked on ethereum.
+pub async fn get_in_flight_burn_amounts(pool: &MySqlPool, token_addr: &str) -> Result<Vec<String>> {
+    let sql = r#"
+SELECT token_amount as amount
+FROM ckb_to_eth
+WHERE token_addr = ? AND status <> 'success'
+    "#;
+    let ret = sqlx::query_as::<_, InFlightAmount>(sql)
+        .bind(token_addr)
+        .fetch_all(pool)
+        .await?;
+    Ok(ret.into_iter().map(|v| v.amount).collect())
+}
+
 pub async fn update_cross_chain_height_info(
     pool: &mut Transaction<'_, MySql>,
     info: &CrossChainHeightInfo,
@@ -297,14 +333,37 @@ where replay_resist_outpoint = ?"#;
     Ok(ret)
 }
 
-pub async fn is_eth_to_ckb_record_exist(pool: &MySqlPool, eth_tx_hash: &str) -> Result<bool> {
+// whether the replay resist outpoint is used by a lock event indexed before the block. the events
+// of the later blocks are deleted when the chain is re-organized.
+pub async fn is_replay_resist_outpoint_used(
+    pool: &MySqlPool,
+    outpoint: &str,
+    block_number: u64,
+) -> Result<bool> {
+    let sql = r#"SELECT *
+FROM eth_to_ckb
+where replay_resist_outpoint = ? and eth_block_number < ?"#;
+    let ret = sqlx::query_as::<_, EthToCkbRecord>(sql)
+        .bind(outpoint)
+        .bind(block_number)
+        .fetch_all(pool)
+        .await?;
+    Ok(!ret.is_empty())
+}
+
+pub async fn is_eth_to_ckb_record_exist(
+    pool: &MySqlPool,
+    eth_tx_hash: &str,
+    log_index: u64,
+) -> Result<bool> {
     let sql = r#"
 SELECT *
 FROM eth_to_ckb
-where eth_lock_tx_hash = ?
+where eth_lock_tx_hash = ? AND log_index = ?
         "#;
     let ret = sqlx::query_as::<_, EthToCkbRecord>(sql)
         .bind(eth_tx_hash)
+        .bind(log_index)
         .fetch_all(pool)
         .await?;
     Ok(!ret.is_empty())
@@ -341,18 +400,19 @@ pub async fn create_eth_to_ckb_record(
 ) -> Result<()> {
     let mut sql = String::from(
         r"
-INSERT INTO eth_to_ckb ( eth_lock_tx_hash, status, token_addr, sender_addr, locked_amount, bridge_fee,
+INSERT INTO eth_to_ckb ( eth_lock_tx_hash, log_index, status, token_addr, sender_addr, locked_amount, bridge_fee,
 ckb_recipient_lockscript, sudt_extra_data, ckb_tx_hash, eth_spv_proof, eth_block_number, replay_resist_outpoint)
 VALUES ",
     );
     for _ in records {
-        sql = format!("{}{}", sql, "( ?,?,?,?,?,?,?,?,?,?,?,?),");
+        sql = format!("{}{}", sql, "( ?,?,?,?,?,?,?,?,?,?,?,?,?),");
     }
     let len = sql.len() - 1;
     let mut ret = sqlx::query(&sql[..len]);
     for record in records {
         ret = ret
             .bind(record.eth_lock_tx_hash.clone())
+            .bind(record.log_index)
             .bind(record.status.clone())
             .bind(record.token_addr.clone())
             .bind(record.sender_addr.clone())
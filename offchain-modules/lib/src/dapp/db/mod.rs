@@ -1,3 +1,4 @@
 pub mod eth_relayer;
 pub mod indexer;
+pub mod rate_limit;
 pub mod server;
@@ -0,0 +1,84 @@
+use anyhow::{anyhow, Result};
+use sqlx::{MySql, Transaction};
+
+pub const ETH_TO_CKB: &str = "eth_to_ckb";
+pub const CKB_TO_ETH: &str = "ckb_to_eth";
+
+#[derive(sqlx::FromRow, Debug)]
+pub struct LedgerAmount {
+    pub amount: String,
+}
+
+pub async fn is_volume_reserved(
+    pool: &mut Transaction<'_, MySql>,
+    direction: &str,
+    tx_hash: &str,
+    log_index: u64,
+) -> Result<bool> {
+    let sql = r#"
+SELECT amount FROM rate_limit_ledger
+WHERE direction = ? AND tx_hash = ? AND log_index = ?
+    "#;
+    let ret = sqlx::query_as::<_, LedgerAmount>(sql)
+        .bind(direction)
+        .bind(tx_hash)
+        .bind(log_index)
+        .fetch_optional(pool)
+        .await?;
+    Ok(ret.is_some())
+}
+
+// the volume of the token reserved in the last window_secs seconds. the amounts are summed here as
+// they are u128 in decimal strings. the rows and the gap of the window are locked till the
+// transaction ends, so that the reservations of the token are checked against the cap one by one.
+pub async fn get_window_volume(
+    pool: &mut Transaction<'_, MySql>,
+    direction: &str,
+    token: &str,
+    window_secs: u64,
+) -> Result<u128> {
+    let sql = r#"
+SELECT amount FROM rate_limit_ledger
+WHERE direction = ? AND token_addr = ? AND create_time > DATE_SUB(NOW(), INTERVAL ? SECOND)
+FOR UPDATE
+    "#;
+    let amounts = sqlx::query_as::<_, LedgerAmount>(sql)
+        .bind(direction)
+        .bind(token)
+        .bind(window_secs)
+        .fetch_all(pool)
+        .await?;
+    let mut volume = 0u128;
+    for item in amounts {
+        let amount = item
+            .amount
+            .parse::<u128>()
+            .map_err(|e| anyhow!("invalid amount {} in ledger: {}", item.amount, e))?;
+        volume = volume.saturating_add(amount);
+    }
+    Ok(volume)
+}
+
+// the reservation of a transfer is recorded once, the retries of it are not counted again.
+pub async fn reserve_volume(
+    pool: &mut Transaction<'_, MySql>,
+    direction: &str,
+    token: &str,
+    tx_hash: &str,
+    log_index: u64,
+    amount: u128,
+) -> Result<()> {
+    let sql = r#"
+INSERT IGNORE INTO rate_limit_ledger (direction, token_addr, tx_hash, log_index, amount)
+VALUES (?,?,?,?,?)
+    "#;
+    sqlx::query(sql)
+        .bind(direction)
+        .bind(token)
+        .bind(tx_hash)
+        .bind(log_index)
+        .bind(amount.to_string())
+        .execute(pool)
+        .await?;
+    Ok(())
+}
@@ -3,6 +3,7 @@ use crate::dapp::server::types::GetCkbToEthStatusResponse;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use sqlx::mysql::MySqlPool;
+use sqlx::Done;
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct CrosschainHistory {
@@ -22,6 +23,11 @@ pub struct ReplayResistCell {
     pub outpoint: String,
 }
 
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
+pub struct LockEventIndex {
+    pub log_index: u64,
+}
+
 #[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone)]
 pub struct RelayStatus {
     pub status: String,
@@ -113,18 +119,39 @@ WHERE id = ?
     Ok((outpoints.len(), outpoint.outpoint))
 }
 
+// the log indexes of the lock events of the lock tx, each of them is a transfer.
+pub async fn get_eth_to_ckb_log_indexes(
+    pool: &MySqlPool,
+    eth_lock_tx_hash: &str,
+) -> Result<Vec<u64>> {
+    let ret = sqlx::query_as::<_, LockEventIndex>(
+        r#"
+SELECT log_index
+FROM eth_to_ckb
+where eth_lock_tx_hash = ?
+order by log_index
+        "#,
+    )
+    .bind(eth_lock_tx_hash)
+    .fetch_all(pool)
+    .await?;
+    Ok(ret.into_iter().map(|item| item.log_index).collect())
+}
+
 pub async fn get_eth_to_ckb_indexer_status(
     pool: &MySqlPool,
     eth_lock_tx_hash: &str,
+    log_index: u64,
 ) -> Result<Option<EthToCkbRecord>> {
     let ret = sqlx::query_as::<_, EthToCkbRecord>(
         r#"
 SELECT *
 FROM eth_to_ckb
-where eth_lock_tx_hash = ?
+where eth_lock_tx_hash = ? AND log_index = ?
         "#,
     )
     .bind(eth_lock_tx_hash)
+    .bind(log_index)
     .fetch_optional(pool)
     .await?;
     Ok(ret)
@@ -133,15 +160,17 @@ where eth_lock_tx_hash = ?
 pub async fn get_eth_to_ckb_relay_status(
     pool: &MySqlPool,
     eth_lock_tx_hash: &str,
+    log_index: u64,
 ) -> Result<Option<RelayStatus>> {
     let ret = sqlx::query_as::<_, RelayStatus>(
         r#"
 SELECT status, err_msg
 FROM eth_tx_relayer
-where lock_tx_hash = ?
+where lock_tx_hash = ? AND log_index = ?
         "#,
     )
     .bind(eth_lock_tx_hash)
+    .bind(log_index)
     .fetch_optional(pool)
     .await?;
     Ok(ret)
@@ -194,3 +223,36 @@ where ckb_burn_tx_hash = ?
     .fetch_optional(pool)
     .await?)
 }
+
+// release the mint of the lock event held by the rate limit, return false if it is not held.
+pub async fn release_held_eth_to_ckb(
+    pool: &MySqlPool,
+    eth_lock_tx_hash: &str,
+    log_index: u64,
+) -> Result<bool> {
+    let sql = r#"
+UPDATE eth_tx_relayer SET status = 'released'
+WHERE lock_tx_hash = ? AND log_index = ? AND status = 'held'
+    "#;
+    let rows_affected = sqlx::query(sql)
+        .bind(eth_lock_tx_hash)
+        .bind(log_index)
+        .execute(pool)
+        .await?
+        .rows_affected();
+    Ok(rows_affected > 0)
+}
+
+// release the unlock of the burn tx held by the rate limit, return false if it is not held.
+pub async fn release_held_ckb_to_eth(pool: &MySqlPool, ckb_burn_tx_hash: &str) -> Result<bool> {
+    let sql = r#"
+UPDATE ckb_to_eth SET status = 'released'
+WHERE ckb_burn_tx_hash = ? AND status = 'held'
+    "#;
+    let rows_affected = sqlx::query(sql)
+        .bind(ckb_burn_tx_hash)
+        .execute(pool)
+        .await?
+        .rows_affected();
+    Ok(rows_affected > 0)
+}
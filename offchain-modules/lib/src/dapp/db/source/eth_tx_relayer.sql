@@ -4,12 +4,13 @@ CREATE TABLE `eth_tx_relayer` (
   `id` int(11) unsigned NOT NULL AUTO_INCREMENT,
   `block_number` bigint(11) unsigned NOT NULL,
   `lock_tx_hash` varchar(64) NOT NULL DEFAULT '',
+  `log_index` int(11) unsigned NOT NULL DEFAULT '0',
   `status` varchar(40) NOT NULL DEFAULT 'pending',
   `err_msg` varchar(2560) NOT NULL DEFAULT '',
   `lock_tx_proof` varchar(15360) NOT NULL DEFAULT '',
   `create_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
   `update_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
   PRIMARY KEY (`id`),
-  KEY `lock_tx_hash` (`lock_tx_hash`),
+  KEY `lock_tx_hash` (`lock_tx_hash`, `log_index`),
   KEY `block_number` (`block_number`)
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8;
@@ -37,6 +37,7 @@ DROP TABLE IF EXISTS `eth_to_ckb`;
 CREATE TABLE `eth_to_ckb` (
   `id` int(11) unsigned NOT NULL AUTO_INCREMENT,
   `eth_lock_tx_hash` varchar(64) NOT NULL DEFAULT '',
+  `log_index` int(11) unsigned NOT NULL DEFAULT '0',
   `status` varchar(40) NOT NULL DEFAULT '',
   `token_addr` varchar(40) NOT NULL DEFAULT '',
   `sender_addr` varchar(40) NOT NULL DEFAULT '',
@@ -52,7 +53,7 @@ CREATE TABLE `eth_to_ckb` (
   `create_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP,
   `update_time` timestamp NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
   PRIMARY KEY (`id`),
-  KEY `eth_lock_tx_hash` (`eth_lock_tx_hash`),
+  UNIQUE KEY `eth_lock_tx_hash` (`eth_lock_tx_hash`, `log_index`),
   KEY `replay_resist_outpoint` (`replay_resist_outpoint`),
   KEY `block_number` (`eth_block_number`),
   KEY `ckb_recipient_lockscript` (`ckb_recipient_lockscript`),
@@ -0,0 +1,14 @@
+DROP TABLE IF EXISTS `rate_limit_ledger`;
+
+CREATE TABLE `rate_limit_ledger` (
+	`id` INT ( 11 ) UNSIGNED NOT NULL AUTO_INCREMENT,
+	`direction` VARCHAR ( 40 ) NOT NULL,
+	`token_addr` VARCHAR ( 40 ) NOT NULL,
+	`tx_hash` VARCHAR ( 64 ) NOT NULL,
+	`log_index` INT ( 11 ) UNSIGNED NOT NULL DEFAULT '0',
+	`amount` VARCHAR ( 40 ) NOT NULL,
+	`create_time` TIMESTAMP NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY ( `id` ),
+	UNIQUE KEY `transfer` ( `direction`, `tx_hash`, `log_index` ),
+	KEY `window` ( `direction`, `token_addr`, `create_time` )
+) ENGINE = INNODB DEFAULT CHARSET = utf8;
@@ -313,6 +313,18 @@ impl CkbIndexer {
                     let recipient_addr: ETHAddress =
                         eth_recipient.eth_recipient_address.get_address().into();
                     let token_amount = eth_recipient.token_amount;
+                    // the burn out of the amount limits is recorded as rejected and never unlocked
+                    let status = match force_config.token_amount_limits.check(
+                        &hex::encode(token_addr.raw_data()),
+                        token_amount,
+                        eth_recipient.fee,
+                    ) {
+                        Ok(()) => "pending",
+                        Err(e) => {
+                            log::warn!("reject burn tx {}: {}", hash, e);
+                            "rejected"
+                        }
+                    };
                     // let ckb_unlock_token_param = parse_ckb_proof(
                     //     hash.as_str(),
                     //     String::from(self.rpc_client.url()),
@@ -329,7 +341,7 @@ impl CkbIndexer {
 
                     let record = CkbToEthRecord {
                         ckb_burn_tx_hash: hash,
-                        status: "pending".to_string(),
+                        status: status.to_string(),
                         token_addr: hex::encode(token_addr.raw_data().to_vec().as_slice()),
                         recipient_addr: hex::encode(recipient_addr.raw_data().to_vec().as_slice()),
                         token_amount: Uint128::from(token_amount).to_string(),
@@ -1,10 +1,10 @@
 use crate::util::ckb_util::{parse_cell, parse_merkle_cell_data};
 use crate::util::config::ForceConfig;
 use crate::util::eth_util::Web3Client;
-use crate::util::rocksdb::{RocksDBStore, RocksDBValue, SMT};
+use crate::util::rocksdb::{prune_smt_below, RocksDBStore, RocksDBValue, SMT};
 use anyhow::{anyhow, Result};
 use force_sdk::cell_collector::get_live_cell_by_typescript;
-use force_sdk::indexer::IndexerRpcClient;
+use force_sdk::indexer::{Cell, IndexerRpcClient};
 use log::info;
 use rocksdb::ops::{Get, Put};
 use shellexpand::tilde;
@@ -19,6 +19,7 @@ pub struct EthHeaderIndexer {
     pub eth_client: Web3Client,
     pub indexer_client: IndexerRpcClient,
     pub rocksdb_path: String,
+    pub snapshot_limit: usize,
 }
 
 impl EthHeaderIndexer {
@@ -39,10 +40,11 @@ impl EthHeaderIndexer {
             eth_client,
             indexer_client,
             rocksdb_path,
+            snapshot_limit: force_config.eth_smt_snapshot_limit,
         })
     }
 
-    pub async fn get_light_client_info(&mut self) -> Result<(u64, u64, [u8; 32])> {
+    pub async fn get_light_client_cell(&mut self) -> Result<Cell> {
         let config_path = tilde(self.config_path.as_str()).into_owned();
         let force_config = ForceConfig::new(config_path.as_str())?;
         let deployed_contracts = force_config
@@ -54,9 +56,13 @@ impl EthHeaderIndexer {
             .cell_script
             .as_str();
         let cell_script = parse_cell(light_client_cell_script)?;
-        let cell = get_live_cell_by_typescript(&mut self.indexer_client, cell_script)
+        get_live_cell_by_typescript(&mut self.indexer_client, cell_script)
             .map_err(|err| anyhow!(err))?
-            .ok_or_else(|| anyhow!("the cell is not exist"))?;
+            .ok_or_else(|| anyhow!("the cell is not exist"))
+    }
+
+    pub async fn get_light_client_info(&mut self) -> Result<(u64, u64, [u8; 32])> {
+        let cell = self.get_light_client_cell().await?;
         let ckb_cell_data = cell.output_data.as_bytes().to_vec();
         if !ckb_cell_data.is_empty() {
             let (start_height, latest_height, merkle_root) =
@@ -72,6 +78,8 @@ impl EthHeaderIndexer {
         anyhow::bail!("waiting for the block confirmed!")
     }
 
+    // read the root the indexer has synced to. the db is opened read only, so it can be called
+    // while the indexer is running in another process.
     pub fn get_merkle_root(&mut self) -> Result<[u8; 32]> {
         let eth_rocksdb_path = self.rocksdb_path.clone();
         let db_dir = tilde(eth_rocksdb_path.as_str()).into_owned();
@@ -79,8 +87,10 @@ impl EthHeaderIndexer {
         let merkle_root = match db_path.exists() {
             true => {
                 let rocksdb_store: RocksDBStore<RocksDBValue> =
-                    RocksDBStore::open(eth_rocksdb_path)?;
-                let db = rocksdb_store.db.ok_or_else(|| anyhow!("db is none"))?;
+                    RocksDBStore::open_readonly(eth_rocksdb_path)?;
+                let db = rocksdb_store
+                    .read_only_db
+                    .ok_or_else(|| anyhow!("db is none"))?;
                 let db_merkle_option = db
                     .get(ROCKSDB_MERKLE_ROOT_KEY.to_vec())
                     .map_err(|err| anyhow!(err))?;
@@ -131,11 +141,13 @@ impl EthHeaderIndexer {
         let db_path = Path::new(db_dir.as_str());
         let mut smt_tree = match db_path.exists() {
             false => {
-                let rocksdb_store = RocksDBStore::new(eth_rocksdb_path.clone())?;
+                let mut rocksdb_store = RocksDBStore::new(eth_rocksdb_path.clone())?;
+                rocksdb_store.set_snapshot_limit(self.snapshot_limit);
                 SMT::new(sparse_merkle_tree::H256::zero(), rocksdb_store)
             }
             true => {
-                let rocksdb_store = RocksDBStore::open(eth_rocksdb_path.clone())?;
+                let mut rocksdb_store = RocksDBStore::open(eth_rocksdb_path.clone())?;
+                rocksdb_store.set_snapshot_limit(self.snapshot_limit);
                 SMT::new(merkle_root.into(), rocksdb_store)
             }
         };
@@ -176,16 +188,29 @@ impl EthHeaderIndexer {
             number -= 1;
         }
 
+        // the relayer prunes the leaves below the start height of the light client cell, prune
+        // them here too so that the local root matches the cell root.
+        let pruned = prune_smt_below(&mut smt_tree, start_height)?;
+        if pruned > 0 {
+            info!(
+                "prune eth blocks from {} to {} out of the retention window",
+                start_height - pruned,
+                start_height - 1
+            );
+        }
+
+        // commit with the root to keep the snapshot, so the proofs can be built against it
+        // after the light client cell moves on.
+        let merkle_root: [u8; 32] = (*smt_tree.root()).into();
         let rocksdb_store = smt_tree.store_mut();
-        rocksdb_store.commit()?;
+        rocksdb_store.commit_with_root(merkle_root, latest_height)?;
         info!(
             "Successfully relayed the headers from {} to {}",
             number + 1,
             latest_height
         );
 
-        let merkle_root = smt_tree.root();
-        Ok((latest_height, (*merkle_root).into()))
+        Ok((latest_height, merkle_root))
     }
 
     pub async fn store_merkle_root(&mut self, merkle_root: [u8; 32]) -> Result<()> {
@@ -203,3 +228,62 @@ impl EthHeaderIndexer {
         Ok(())
     }
 }
+
+#[test]
+fn test_indexer_root_matches_cell_root_after_window_moves() {
+    use crate::util::rocksdb::{get_smt_key_for_height, prune_smt_range};
+
+    let new_smt = |name: &str| {
+        let path = std::env::temp_dir().join(format!(
+            "force-eth-header-indexer-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&path);
+        let store = RocksDBStore::new(path.to_str().unwrap().to_owned()).unwrap();
+        SMT::new(sparse_merkle_tree::H256::zero(), store)
+    };
+    let relay_headers = |smt_tree: &mut SMT, from_height: u64, to_height: u64| {
+        for height in from_height..=to_height {
+            let mut block_hash = [0xffu8; 32];
+            block_hash[..8].copy_from_slice(&height.to_le_bytes());
+            smt_tree
+                .update(get_smt_key_for_height(height).into(), block_hash.into())
+                .unwrap();
+        }
+    };
+
+    // the relayer moves the light client cell window from [0, 20] to [8, 25]
+    let mut relayer_smt = new_smt("relayer");
+    relay_headers(&mut relayer_smt, 0, 20);
+    let root: [u8; 32] = (*relayer_smt.root()).into();
+    relayer_smt.store_mut().commit_with_root(root, 20).unwrap();
+    relay_headers(&mut relayer_smt, 21, 25);
+    prune_smt_range(&mut relayer_smt, 0, 8).unwrap();
+    let cell_root: [u8; 32] = (*relayer_smt.root()).into();
+    relayer_smt
+        .store_mut()
+        .commit_with_root(cell_root, 25)
+        .unwrap();
+
+    // the indexer synced to the cell of [0, 20] only sees the cell of [8, 25]
+    let mut indexer_smt = new_smt("indexer");
+    relay_headers(&mut indexer_smt, 0, 20);
+    let root: [u8; 32] = (*indexer_smt.root()).into();
+    indexer_smt.store_mut().commit_with_root(root, 20).unwrap();
+    relay_headers(&mut indexer_smt, 21, 25);
+    assert_eq!(prune_smt_below(&mut indexer_smt, 8).unwrap(), 8);
+    let indexer_root: [u8; 32] = (*indexer_smt.root()).into();
+    assert_eq!(indexer_root, cell_root);
+    indexer_smt
+        .store_mut()
+        .commit_with_root(indexer_root, 25)
+        .unwrap();
+    assert_eq!(prune_smt_below(&mut indexer_smt, 8).unwrap(), 0);
+
+    // both are the smt built from the headers in the window only
+    let mut window_smt = new_smt("window");
+    relay_headers(&mut window_smt, 8, 25);
+    let window_root: [u8; 32] = (*window_smt.root()).into();
+    assert_eq!(window_root, cell_root);
+}
@@ -2,20 +2,21 @@ use crate::dapp::db::indexer::{
     create_eth_to_ckb_record, delete_eth_to_ckb_records, delete_eth_unconfirmed_block,
     get_eth_unconfirmed_block, get_eth_unconfirmed_blocks, get_height_info,
     get_max_eth_unconfirmed_block, insert_eth_unconfirmed_block, insert_eth_unconfirmed_blocks,
-    is_ckb_to_eth_record_exist, reset_ckb_to_eth_record_status, update_ckb_to_eth_record_status,
-    update_cross_chain_height_info, update_eth_unconfirmed_block, CrossChainHeightInfo,
-    EthToCkbRecord, EthUnConfirmedBlock,
+    is_ckb_to_eth_record_exist, is_replay_resist_outpoint_used, reset_ckb_to_eth_record_status,
+    update_ckb_to_eth_record_status, update_cross_chain_height_info, update_eth_unconfirmed_block,
+    CrossChainHeightInfo, EthToCkbRecord, EthUnConfirmedBlock,
 };
 use crate::dapp::indexer::IndexerFilter;
-use crate::transfer::to_ckb::to_eth_spv_proof_json;
+use crate::transfer::to_ckb::generate_eth_spv_proofs_json;
 use crate::util::ckb_util::{clear_0x, parse_cell, parse_merkle_cell_data};
-use crate::util::config::ForceConfig;
+use crate::util::config::{ForceConfig, TokenAmountLimits};
 use crate::util::eth_util::{convert_hex_to_h256, Web3Client};
 use crate::util::generated::ckb_tx_proof::CKBUnlockTokenParamReader;
 use anyhow::{anyhow, Result};
 use ckb_hash::blake2b_256;
 use ckb_jsonrpc_types::Uint128;
 use ethabi::{Function, Param, ParamType};
+use force_eth_types::consumed_event_registry::is_registry_mode;
 use force_sdk::cell_collector::get_live_cell_by_typescript;
 use force_sdk::indexer::IndexerRpcClient;
 use log::info;
@@ -35,6 +36,7 @@ pub struct EthIndexer<T> {
     pub indexer_client: IndexerRpcClient,
     pub indexer_filter: T,
     pub confirmed: usize,
+    pub token_amount_limits: TokenAmountLimits,
 }
 
 impl<T: IndexerFilter> EthIndexer<T> {
@@ -59,6 +61,7 @@ impl<T: IndexerFilter> EthIndexer<T> {
             indexer_client,
             indexer_filter,
             confirmed,
+            token_amount_limits: force_config.token_amount_limits,
         })
     }
 
@@ -272,17 +275,23 @@ impl<T: IndexerFilter> EthIndexer<T> {
             5,
             lock_contract_address.clone(),
         )?;
-        if !lock_vec.is_empty() {
-            for item in lock_vec {
-                self.handle_lock_event(
-                    &mut lock_records,
-                    lock_contract_address.clone(),
-                    &item,
-                    *start_block_number,
-                )
-                .await?;
+        // a tx may have several lock events, all of them are handled with the tx.
+        let mut lock_tx_hashes: Vec<String> = vec![];
+        for item in lock_vec {
+            let tx_hash = String::from(clear_0x(item.tx_hash.as_str()));
+            if !lock_tx_hashes.contains(&tx_hash) {
+                lock_tx_hashes.push(tx_hash);
             }
         }
+        for tx_hash in lock_tx_hashes {
+            self.handle_lock_event(
+                &mut lock_records,
+                lock_contract_address.clone(),
+                tx_hash,
+                *start_block_number,
+            )
+            .await?;
+        }
         if !unlock_vec.is_empty() {
             for item in unlock_vec {
                 self.handle_unlock_event(item.tx_hash, &mut unlock_records)
@@ -374,36 +383,77 @@ impl<T: IndexerFilter> EthIndexer<T> {
         Ok(())
     }
 
+    // record every lock event of the tx as a row keyed by the tx hash and log index. the replay
+    // resist outpoint of each event must be unique, the later events reusing one are skipped. the
+    // lock events in registry mode share the zero outpoint, the registry cell resists their replay.
     pub async fn handle_lock_event(
         &mut self,
         records: &mut Vec<EthToCkbRecord>,
         contract_addr: String,
-        eth_spv_proof: &EthSpvProof,
+        tx_hash: String,
         block_number: u64,
     ) -> Result<()> {
-        let eth_proof_json = to_eth_spv_proof_json(
-            eth_spv_proof.clone(),
-            contract_addr,
+        let eth_proofs_json = generate_eth_spv_proofs_json(
+            format!("0x{}", tx_hash),
             String::from(self.eth_client.url()),
+            contract_addr,
         )
         .await?;
-        // let proof_json = serde_json::to_string(&eth_proof_json)?;
-        let recipient_lockscript = hex::encode(eth_proof_json.recipient_lockscript);
-        if self.indexer_filter.filter(recipient_lockscript.clone()) {
+        for eth_proof_json in eth_proofs_json {
+            let recipient_lockscript = hex::encode(&eth_proof_json.recipient_lockscript);
+            if !self.indexer_filter.filter(recipient_lockscript.clone()) {
+                continue;
+            }
+            let replay_resist_outpoint = hex::encode(&eth_proof_json.replay_resist_outpoint);
+            if !is_registry_mode(&eth_proof_json.replay_resist_outpoint)
+                && (records
+                    .iter()
+                    .any(|r| r.replay_resist_outpoint == replay_resist_outpoint)
+                    || is_replay_resist_outpoint_used(
+                        &self.db,
+                        &replay_resist_outpoint,
+                        block_number,
+                    )
+                    .await?)
+            {
+                log::error!(
+                    "the replay resist outpoint {} of lock tx {} log {} is used by another lock event, skip it",
+                    replay_resist_outpoint,
+                    tx_hash,
+                    eth_proof_json.log_index
+                );
+                continue;
+            }
+            // the lock event out of the amount limits is recorded as rejected and never minted
+            let status = match self.token_amount_limits.check(
+                &hex::encode(eth_proof_json.token.as_bytes()),
+                eth_proof_json.lock_amount,
+                eth_proof_json.bridge_fee,
+            ) {
+                Ok(()) => "pending",
+                Err(e) => {
+                    log::warn!(
+                        "reject lock tx {} log {}: {}",
+                        tx_hash,
+                        eth_proof_json.log_index,
+                        e
+                    );
+                    "rejected"
+                }
+            };
             let record = EthToCkbRecord {
-                eth_lock_tx_hash: String::from(clear_0x(eth_spv_proof.tx_hash.clone().as_str())),
-                status: "pending".to_string(),
-                token_addr: hex::encode(eth_spv_proof.token.as_bytes()),
+                eth_lock_tx_hash: tx_hash.clone(),
+                log_index: eth_proof_json.log_index,
+                status: status.to_string(),
+                token_addr: hex::encode(eth_proof_json.token.as_bytes()),
                 ckb_recipient_lockscript: recipient_lockscript,
-                locked_amount: Uint128::from(eth_spv_proof.lock_amount).to_string(),
+                locked_amount: Uint128::from(eth_proof_json.lock_amount).to_string(),
                 eth_spv_proof: Some("".to_string()),
-                replay_resist_outpoint: hex::encode(
-                    eth_spv_proof.replay_resist_outpoint.as_slice(),
-                ),
+                replay_resist_outpoint,
                 eth_block_number: block_number,
-                sender_addr: hex::encode(eth_spv_proof.sender.as_bytes()),
-                sudt_extra_data: Some(hex::encode(eth_spv_proof.sudt_extra_data.as_slice())),
-                bridge_fee: Uint128::from(eth_spv_proof.bridge_fee).to_string(),
+                sender_addr: hex::encode(eth_proof_json.sender.as_bytes()),
+                sudt_extra_data: Some(hex::encode(eth_proof_json.sudt_extra_data.as_slice())),
+                bridge_fee: Uint128::from(eth_proof_json.bridge_fee).to_string(),
                 ..Default::default()
             };
             records.push(record);
@@ -1,5 +1,6 @@
 pub mod db;
 pub mod indexer;
+pub mod proof_server;
 pub mod relayer;
 pub mod server;
 
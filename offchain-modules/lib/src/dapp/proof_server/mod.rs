@@ -0,0 +1,140 @@
+use super::server::errors::RpcError;
+use super::EthHeaderIndexer;
+use crate::util::ckb_util::parse_merkle_cell_data;
+use crate::util::rocksdb::{get_smt_key_for_height, RocksDBStore, RocksDBValue, SMT};
+use actix_web::{get, post, web, App, HttpResponse, HttpServer, Responder};
+use anyhow::Result;
+use ckb_jsonrpc_types::{OutPoint, Uint64};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use shellexpand::tilde;
+use sparse_merkle_tree::traits::Value as SMTValue;
+
+// The proof server serves the smt inclusion proofs of eth headers from the rocksdb of the eth
+// header indexer, so that wallets can build the MintTokenWitness on their own.
+#[derive(Clone)]
+pub struct ProofServerState {
+    pub config_path: String,
+    pub network: Option<String>,
+    pub rocksdb_path: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GetEthHeaderProofArgs {
+    pub block_number: Uint64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EthHeaderProofResult {
+    pub block_number: Uint64,
+    pub block_hash: String,
+    pub merkle_root: String,
+    pub start_height: Uint64,
+    pub latest_height: Uint64,
+    pub light_client_cell: OutPoint,
+    pub compiled_merkle_proof: String,
+}
+
+#[post("/get_eth_header_proof")]
+pub async fn get_eth_header_proof(
+    data: web::Data<ProofServerState>,
+    args: web::Json<Value>,
+) -> actix_web::Result<HttpResponse, RpcError> {
+    let args: GetEthHeaderProofArgs = serde_json::from_value(args.into_inner())
+        .map_err(|e| RpcError::BadRequest(format!("invalid args: {}", e)))?;
+    let block_number: u64 = args.block_number.into();
+
+    let mut eth_header_indexer = EthHeaderIndexer::new(
+        data.config_path.clone(),
+        data.network.clone(),
+        data.rocksdb_path.clone(),
+    )
+    .await
+    .map_err(|e| RpcError::ServerError(format!("new eth header indexer error: {}", e)))?;
+    let cell = eth_header_indexer
+        .get_light_client_cell()
+        .await
+        .map_err(|e| RpcError::ServerError(format!("get light client cell error: {}", e)))?;
+    let cell_data = cell.output_data.as_bytes().to_vec();
+    if cell_data.is_empty() {
+        return Err(RpcError::ServerError(
+            "the light client cell is not initialized".to_string(),
+        ));
+    }
+    let (start_height, latest_height, merkle_root) = parse_merkle_cell_data(cell_data)
+        .map_err(|e| RpcError::ServerError(format!("parse light client cell error: {}", e)))?;
+    if block_number < start_height || block_number > latest_height {
+        return Err(RpcError::BadRequest(format!(
+            "block {} is out of the light client range [{}, {}]",
+            block_number, start_height, latest_height
+        )));
+    }
+
+    let indexer_merkle_root = eth_header_indexer
+        .get_merkle_root()
+        .map_err(|e| RpcError::ServerError(format!("get indexer merkle root error: {}", e)))?;
+    let rocksdb_store: RocksDBStore<RocksDBValue> =
+        RocksDBStore::open_readonly(data.rocksdb_path.clone())
+            .map_err(|e| RpcError::ServerError(format!("open rocksdb error: {}", e)))?;
+    let has_snapshot = rocksdb_store
+        .has_snapshot(merkle_root)
+        .map_err(|e| RpcError::ServerError(format!("get smt snapshot error: {}", e)))?;
+    if !has_snapshot {
+        return Err(RpcError::ServerError(format!(
+            "the eth header indexer is not synced with the light client cell, indexer root: {}, cell root: {}",
+            hex::encode(indexer_merkle_root),
+            hex::encode(merkle_root)
+        )));
+    }
+    let compiled_merkle_proof = rocksdb_store
+        .merkle_proof_for(merkle_root, &[block_number])
+        .map_err(|e| RpcError::ServerError(format!("build merkle proof error: {}", e)))?;
+    let smt_tree = SMT::new(merkle_root.into(), rocksdb_store);
+    let block_hash = smt_tree
+        .get(&get_smt_key_for_height(block_number).into())
+        .map_err(|e| RpcError::ServerError(format!("get block hash error: {}", e)))?;
+
+    Ok(HttpResponse::Ok().json(EthHeaderProofResult {
+        block_number: block_number.into(),
+        block_hash: hex::encode(block_hash.to_h256().as_slice()),
+        merkle_root: hex::encode(merkle_root),
+        start_height: start_height.into(),
+        latest_height: latest_height.into(),
+        light_client_cell: cell.out_point,
+        compiled_merkle_proof: hex::encode(compiled_merkle_proof.0),
+    }))
+}
+
+#[get("/")]
+pub async fn index() -> impl Responder {
+    "Nervos force bridge eth header proof server API endpoint"
+}
+
+pub async fn start(
+    config_path: String,
+    network: Option<String>,
+    rocksdb_path: String,
+    listen_url: String,
+) -> Result<()> {
+    let proof_server_state = ProofServerState {
+        config_path: tilde(config_path.as_str()).into_owned(),
+        network,
+        rocksdb_path,
+    };
+    let web_data = web::Data::new(proof_server_state);
+    let local = tokio::task::LocalSet::new();
+    let sys = actix_web::rt::System::run_in_tokio("proof-server", &local);
+    let _server_res = HttpServer::new(move || {
+        let cors = actix_cors::Cors::permissive();
+        App::new()
+            .wrap(cors)
+            .app_data(web_data.clone())
+            .service(get_eth_header_proof)
+            .service(index)
+    })
+    .bind(&listen_url)?
+    .run()
+    .await?;
+    sys.await?;
+    Ok(())
+}
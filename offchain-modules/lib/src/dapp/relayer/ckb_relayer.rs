@@ -1,7 +1,10 @@
+use crate::dapp::db::rate_limit::CKB_TO_ETH;
+use crate::dapp::relayer::rate_limiter::RateLimiter;
 use crate::dapp::relayer::{BATCH_UNLOCK_LIMIT, TOTAL_UNLOCK_LIMIT};
-use crate::transfer::to_eth::{get_ckb_proof_info, unlock};
+use crate::transfer::to_eth::{get_ckb_proof_info, unlock_with_tx_manager};
 use crate::util::config::ForceConfig;
-use crate::util::eth_util::{convert_eth_address, parse_private_key, Web3Client};
+use crate::util::eth_tx_manager::EthTxManager;
+use crate::util::eth_util::{convert_eth_address, parse_private_key, EthFeeOptions, Web3Client};
 use anyhow::{anyhow, Result};
 use ethereum_types::{H160, H256};
 use futures::future::join_all;
@@ -18,11 +21,13 @@ pub struct UnlockTask {
     pub ckb_burn_tx_hash: String,
     // pub ckb_spv_proof: String,
     pub ckb_raw_tx: String,
+    pub token_addr: String,
+    pub token_amount: String,
+    pub status: String,
 }
 
 pub struct CkbTxRelay {
     eth_token_locker_addr: String,
-    ethereum_rpc_url: String,
     ckb_rpc_url: String,
     rocksdb_path: String,
     eth_private_key: H256,
@@ -30,6 +35,8 @@ pub struct CkbTxRelay {
     contract_addr: H160,
     confirm_num: u64,
     db: MySqlPool,
+    tx_manager: EthTxManager,
+    rate_limiter: RateLimiter,
 }
 
 impl CkbTxRelay {
@@ -56,9 +63,11 @@ impl CkbTxRelay {
         let confirm_num = web3_client
             .get_locker_contract_confirm("numConfirmations_", token_locker_addr)
             .await?;
+        let tx_manager =
+            EthTxManager::new(ethereum_rpc_url, force_config.get_eth_tx_policy(&network)?);
+        let rate_limiter = RateLimiter::new(CKB_TO_ETH, force_config.token_amount_limits.clone());
         Ok(CkbTxRelay {
             eth_token_locker_addr,
-            ethereum_rpc_url,
             ckb_rpc_url,
             rocksdb_path,
             eth_private_key,
@@ -66,6 +75,8 @@ impl CkbTxRelay {
             contract_addr,
             confirm_num,
             db,
+            tx_manager,
+            rate_limiter,
         })
     }
 
@@ -84,17 +95,30 @@ impl CkbTxRelay {
             .web3_client
             .get_contract_height("latestBlockNumber", self.contract_addr)
             .await?;
-        let unlock_tasks =
-            get_unlock_tasks(&self.db, self.confirm_num, client_block_number).await?;
+        // the held tasks are older than the pending ones, they are admitted first. they are
+        // selected apart, so that the held ones never crowd the pending ones out of the limit.
+        let held_tasks =
+            get_held_unlock_tasks(&self.db, self.confirm_num, client_block_number).await?;
+        let mut unlock_tasks = self.rate_limit_unlock_tasks(held_tasks).await;
+        let limit = TOTAL_UNLOCK_LIMIT.saturating_sub(unlock_tasks.len() as u64);
+        // the held tasks admitted just now are pending again, they are not selected twice.
+        let pending_tasks =
+            get_unlock_tasks(&self.db, self.confirm_num, client_block_number, limit)
+                .await?
+                .into_iter()
+                .filter(|task| !unlock_tasks.iter().any(|held| held.id == task.id))
+                .collect();
+        unlock_tasks.extend(self.rate_limit_unlock_tasks(pending_tasks).await);
         let mut unlock_futures = vec![];
-        let nonce = self
-            .web3_client
-            .get_eth_nonce(&self.eth_private_key)
-            .await?;
         // let mut proofs = vec![];
         if unlock_tasks.is_empty() {
             return Ok(());
         }
+        let nonce = self.tx_manager.next_nonce(&self.eth_private_key).await?;
+        let fee = self
+            .tx_manager
+            .suggest_fee(&EthFeeOptions::default())
+            .await?;
         for i in 0..=unlock_tasks.len() / BATCH_UNLOCK_LIMIT {
             let tasks: &[UnlockTask];
             if BATCH_UNLOCK_LIMIT * (i + 1) > unlock_tasks.len() {
@@ -114,50 +138,114 @@ impl CkbTxRelay {
                 self.rocksdb_path.clone(),
             )
             .await?;
-            unlock_futures.push(unlock(
+            unlock_futures.push(unlock_with_tx_manager(
+                &self.tx_manager,
                 self.eth_private_key,
-                self.ethereum_rpc_url.clone(),
                 self.eth_token_locker_addr.clone(),
                 proof_info,
-                0,
                 nonce.add(i),
-                true,
+                fee.clone(),
             ));
         }
         if !unlock_futures.is_empty() {
             let now = Instant::now();
             let unlock_count = unlock_futures.len();
 
-            let timeout_future = tokio::time::delay_for(std::time::Duration::from_secs(1800));
-            let task_future = join_all(unlock_futures);
-            tokio::select! {
-                v = task_future => {
-                    for res in v.iter() {
-                       match res {
-                          Ok(hash) => info!("unlock hash : {}", hash),
-                          Err(error) => error!("unlock error : {:?}", error),
-                    }
-                  }
-                  info!("unlock {} txs elapsed {:?}", unlock_count, now.elapsed());
-               }
-                _ = timeout_future => {
-                    error!("batch relay ckb tx timeout");
+            // every unlock tx is bounded by the max wait of the tx manager
+            let v = join_all(unlock_futures).await;
+            for res in v.iter() {
+                match res {
+                    Ok(hash) => info!("unlock hash : {}", hash),
+                    Err(error) => error!("unlock error : {:?}", error),
                 }
             }
+            info!("unlock {} txs elapsed {:?}", unlock_count, now.elapsed());
         }
         Ok(())
     }
+
+    // hold the tasks over the rate limit of their tokens, the held tasks admitted this round are
+    // pending again. the task failed to check is skipped this round.
+    async fn rate_limit_unlock_tasks(&self, tasks: Vec<UnlockTask>) -> Vec<UnlockTask> {
+        let mut admitted_tasks = vec![];
+        for task in tasks {
+            match self.rate_limit_unlock_task(&task).await {
+                Ok(true) => admitted_tasks.push(task),
+                Ok(false) => {}
+                Err(e) => error!(
+                    "rate limit of burn tx {} error, skip it: {:?}",
+                    task.ckb_burn_tx_hash, e
+                ),
+            }
+        }
+        admitted_tasks
+    }
+
+    async fn rate_limit_unlock_task(&self, task: &UnlockTask) -> Result<bool> {
+        // the token amount is recorded in hex by the ckb indexer
+        let amount =
+            u128::from_str_radix(task.token_amount.trim_start_matches("0x"), 16).map_err(|e| {
+                anyhow!(
+                    "invalid token amount {} of burn tx {}: {}",
+                    task.token_amount,
+                    task.ckb_burn_tx_hash,
+                    e
+                )
+            })?;
+        let admitted = self
+            .rate_limiter
+            .admit(
+                &self.db,
+                &task.token_addr,
+                &task.ckb_burn_tx_hash,
+                0,
+                amount,
+                task.status == "released",
+            )
+            .await?;
+        if admitted && task.status == "held" {
+            update_unlock_task_status(&self.db, &task.ckb_burn_tx_hash, "held", "pending").await?;
+        } else if !admitted && task.status != "held" {
+            update_unlock_task_status(&self.db, &task.ckb_burn_tx_hash, "pending", "held").await?;
+        }
+        Ok(admitted)
+    }
 }
 
+// the pending and the released tasks, the oldest first.
 pub async fn get_unlock_tasks(
     pool: &MySqlPool,
     confirm: u64,
     height: u64,
+    limit: u64,
+) -> Result<Vec<UnlockTask>> {
+    let sql = r#"
+SELECT id, ckb_burn_tx_hash, ckb_raw_tx, token_addr, token_amount, status
+FROM ckb_to_eth
+WHERE status in ('pending', 'released') AND ckb_block_number + ? < ?
+ORDER BY id limit ?
+    "#;
+    let tasks = sqlx::query_as::<_, UnlockTask>(sql)
+        .bind(confirm)
+        .bind(height)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+    Ok(tasks)
+}
+
+// the held tasks are checked against the rate limit every round, they are unlocked once the rate
+// limit window frees up.
+pub async fn get_held_unlock_tasks(
+    pool: &MySqlPool,
+    confirm: u64,
+    height: u64,
 ) -> Result<Vec<UnlockTask>> {
     let sql = r#"
-SELECT id, ckb_burn_tx_hash, ckb_raw_tx
+SELECT id, ckb_burn_tx_hash, ckb_raw_tx, token_addr, token_amount, status
 FROM ckb_to_eth
-WHERE status = 'pending' AND ckb_block_number + ? < ? limit ?
+WHERE status = 'held' AND ckb_block_number + ? < ?
+ORDER BY id limit ?
     "#;
     let tasks = sqlx::query_as::<_, UnlockTask>(sql)
         .bind(confirm)
@@ -167,3 +255,23 @@ WHERE status = 'pending' AND ckb_block_number + ? < ? limit ?
         .await?;
     Ok(tasks)
 }
+
+// the status is updated only if it is not changed by the indexer meanwhile.
+pub async fn update_unlock_task_status(
+    pool: &MySqlPool,
+    ckb_burn_tx_hash: &str,
+    from_status: &str,
+    to_status: &str,
+) -> Result<()> {
+    let sql = r#"
+UPDATE ckb_to_eth SET status = ?
+WHERE ckb_burn_tx_hash = ? AND status = ?
+    "#;
+    sqlx::query(sql)
+        .bind(to_status)
+        .bind(ckb_burn_tx_hash)
+        .bind(from_status)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
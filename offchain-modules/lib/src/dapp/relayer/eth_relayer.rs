@@ -1,10 +1,16 @@
 use crate::dapp::db::eth_relayer::{
     delete_relayed_tx, get_mint_tasks, get_retry_tasks, last_relayed_number, latest_index_number,
-    store_mint_tasks, update_relayed_tx, MintTask,
+    set_mint_tx_hash, store_mint_tasks, update_relayed_tx, MintTask,
+};
+use crate::dapp::db::rate_limit::ETH_TO_CKB;
+use crate::dapp::relayer::rate_limiter::RateLimiter;
+use crate::transfer::to_ckb::{
+    generate_eth_spv_proof_json_of_log, send_eth_spv_proof_batch_tx, send_eth_spv_proof_tx,
 };
-use crate::transfer::to_ckb::{send_eth_spv_proof_tx, to_eth_spv_proof_json};
 use crate::util::ckb_tx_generator::Generator;
-use crate::util::ckb_util::{get_eth_client_tip_number, parse_privkey_path, ETHSPVProofJson};
+use crate::util::ckb_util::{
+    get_bridge_paused, get_eth_client_confirmed_number, parse_privkey_path, ETHSPVProofJson,
+};
 use crate::util::config::ForceConfig;
 use anyhow::{anyhow, bail, Result};
 use ckb_sdk::constants::ONE_CKB;
@@ -14,13 +20,14 @@ use ckb_types::core::Capacity;
 use ckb_types::packed::{CellOutput, OutPoint, Script};
 use ckb_types::prelude::Pack;
 use ckb_types::H256;
+use ethereum_types::H160;
+use force_eth_types::consumed_event_registry::is_registry_mode;
 use force_sdk::cell_collector::get_capacity_cells_for_mint;
 use force_sdk::tx_helper::TxHelper;
 use force_sdk::util::ensure_indexer_sync;
 use futures::future::join_all;
 use futures::stream::{FuturesOrdered, StreamExt};
 use molecule::prelude::{Builder, Entity};
-use rusty_receipt_proof_maker::generate_eth_proof;
 use secp256k1::SecretKey;
 use shellexpand::tilde;
 use sqlx::MySqlPool;
@@ -38,8 +45,10 @@ pub struct EthTxRelayer {
     pub private_key: SecretKey,
     pub db_pool: MySqlPool,
     pub mint_concurrency: u64,
+    pub mint_batch_size: u64,
     pub minimum_cell_capacity: u64,
     pub rocksdb_path: String,
+    pub rate_limiter: RateLimiter,
 }
 
 impl EthTxRelayer {
@@ -48,10 +57,14 @@ impl EthTxRelayer {
         network: Option<String>,
         private_key: String,
         mint_concurrency: u64,
+        mint_batch_size: u64,
         minimum_cell_capacity: u64,
         db_url: String,
         rocksdb_path: String,
     ) -> Result<Self> {
+        if mint_batch_size == 0 {
+            bail!("mint batch size should be greater than 0");
+        }
         let config_path = tilde(config_path.as_str()).into_owned();
         let force_config = ForceConfig::new(config_path.as_str())?;
         let token_locker_addr = force_config
@@ -66,6 +79,7 @@ impl EthTxRelayer {
         let ckb_indexer_url = force_config.get_ckb_indexer_url(&network)?;
         let private_key = parse_privkey_path(private_key.as_str(), &force_config, &network)?;
         let db_pool = MySqlPool::connect(db_url.as_str()).await?;
+        let rate_limiter = RateLimiter::new(ETH_TO_CKB, force_config.token_amount_limits.clone());
         Ok(EthTxRelayer {
             config_path,
             force_config,
@@ -77,8 +91,10 @@ impl EthTxRelayer {
             private_key,
             db_pool,
             mint_concurrency,
+            mint_batch_size,
             minimum_cell_capacity: minimum_cell_capacity * ONE_CKB,
             rocksdb_path,
+            rate_limiter,
         })
     }
 
@@ -96,6 +112,11 @@ impl EthTxRelayer {
     }
 
     async fn relay(&self, last_relayed_number: u64) -> Result<u64> {
+        // the mint txs are refused while the bridge is paused, the tasks are kept till resumed
+        if self.bridge_paused().await? {
+            log::info!("bridge is paused, jump to next relay round");
+            return Ok(last_relayed_number);
+        }
         let capacity_cells = self.capacity_cells_for_mint().await?;
         if capacity_cells.is_empty() {
             log::info!("generate capacity cells for mint success, jump to next relay round");
@@ -117,17 +138,33 @@ impl EthTxRelayer {
         let mut mint_tasks = self.update_mint_tasks(mint_tasks).await?;
         log::info!("total mint tasks: {}", mint_tasks.len());
         store_mint_tasks(&self.db_pool, &mint_tasks).await?;
-        mint_tasks.extend(retry_tasks);
+        // the held tasks are older than the new ones, they are admitted first.
+        let retry_tasks = self.rate_limit_mint_tasks(retry_tasks).await;
+        let mint_tasks = self.rate_limit_mint_tasks(mint_tasks).await;
+        // the new tasks are minted in batches, one capacity cell for each batch. the retry tasks
+        // are minted one by one, so that a task with irreparable error does not fail the others.
+        let mut mint_batches = self.batch_mint_tasks(mint_tasks)?;
+        mint_batches.extend(retry_tasks.into_iter().map(|task| vec![task]));
 
-        let mint_count = std::cmp::min(mint_tasks.len(), capacity_cells.len());
+        let mint_count = std::cmp::min(mint_batches.len(), capacity_cells.len());
         let mut mint_futures = vec![];
+        let mut registry_mode_mints = vec![];
         for i in 0..mint_count {
-            mint_futures.push(self.mint(&mint_tasks[i], &capacity_cells[i]));
+            if self.is_registry_mode_batch(&mint_batches[i])? {
+                registry_mode_mints.push((&mint_batches[i], &capacity_cells[i]));
+            } else {
+                mint_futures.push(self.mint(&mint_batches[i], &capacity_cells[i]));
+            }
         }
-        if !mint_futures.is_empty() {
+        if mint_count > 0 {
             log::info!("start send {} mint txs", mint_count);
             let now = Instant::now();
-            let res_all = join_all(mint_futures).await;
+            let mut res_all = join_all(mint_futures).await;
+            // the mint txs in registry mode spend the same registry cell, each of them is sent
+            // after the previous one is committed.
+            for (batch, capacity_cell) in registry_mode_mints {
+                res_all.push(self.mint(batch, capacity_cell).await);
+            }
             for res in res_all.iter() {
                 if let Err(error) = res {
                     log::error!("mint error : {:?}", error);
@@ -159,87 +196,205 @@ impl EthTxRelayer {
 
     async fn update_mint_task(&self, task: MintTask) -> Result<MintTask> {
         let tx_hash = "0x".to_string() + task.lock_tx_hash.as_str();
-        for retry in 0..5 {
-            let ret = generate_eth_proof(
-                tx_hash.clone(),
-                self.eth_rpc_url.clone(),
-                self.token_locker_addr.clone(),
-            );
-            match ret {
-                Ok(proof) => {
-                    let proof_json = to_eth_spv_proof_json(
-                        proof,
-                        self.token_locker_addr.clone(),
-                        self.eth_rpc_url.clone(),
-                    )
-                    .await?;
-                    let proof_json_string = serde_json::to_string(&proof_json)?;
-                    let mint_task = MintTask {
-                        block_number: task.block_number,
-                        lock_tx_hash: task.lock_tx_hash.clone(),
-                        lock_tx_proof: proof_json_string,
-                    };
-                    return Ok(mint_task);
-                }
-                Err(e) => {
-                    log::error!(
-                        "get eth receipt proof failed, retried {} times, err: {}",
-                        retry,
-                        e
-                    );
-                }
+        let proof_json = generate_eth_spv_proof_json_of_log(
+            tx_hash,
+            task.log_index,
+            self.eth_rpc_url.clone(),
+            self.token_locker_addr.clone(),
+        )
+        .await?;
+        let proof_json_string = serde_json::to_string(&proof_json)?;
+        Ok(MintTask {
+            block_number: task.block_number,
+            lock_tx_hash: task.lock_tx_hash.clone(),
+            log_index: task.log_index,
+            lock_tx_proof: proof_json_string,
+            status: task.status,
+        })
+    }
+
+    // hold the tasks over the rate limit of their tokens, the held tasks admitted this round are
+    // pending again. the task failed to check is skipped this round.
+    async fn rate_limit_mint_tasks(&self, tasks: Vec<MintTask>) -> Vec<MintTask> {
+        let mut admitted_tasks = vec![];
+        for task in tasks {
+            match self.rate_limit_mint_task(&task).await {
+                Ok(true) => admitted_tasks.push(task),
+                Ok(false) => {}
+                Err(e) => log::error!(
+                    "rate limit of lock tx {} log {} error, skip it: {:?}",
+                    task.lock_tx_hash,
+                    task.log_index,
+                    e
+                ),
+            }
+        }
+        admitted_tasks
+    }
+
+    async fn rate_limit_mint_task(&self, task: &MintTask) -> Result<bool> {
+        let lock_tx_proof: ETHSPVProofJson = serde_json::from_str(task.lock_tx_proof.as_str())?;
+        let admitted = self
+            .rate_limiter
+            .admit(
+                &self.db_pool,
+                &hex::encode(lock_tx_proof.token.as_bytes()),
+                &task.lock_tx_hash,
+                task.log_index,
+                lock_tx_proof.lock_amount,
+                task.status == "released",
+            )
+            .await?;
+        if admitted && task.status == "held" {
+            update_relayed_tx(
+                &self.db_pool,
+                task.lock_tx_hash.clone(),
+                task.log_index,
+                "pending".to_string(),
+                "".to_string(),
+            )
+            .await?;
+        } else if !admitted && task.status != "held" {
+            update_relayed_tx(
+                &self.db_pool,
+                task.lock_tx_hash.clone(),
+                task.log_index,
+                "held".to_string(),
+                "mint volume of the token exceeds the rate limit".to_string(),
+            )
+            .await?;
+        }
+        Ok(admitted)
+    }
+
+    // the tasks in registry mode are minted one in a batch, so the first one tells the batch.
+    fn is_registry_mode_batch(&self, tasks: &[MintTask]) -> Result<bool> {
+        match tasks.first() {
+            Some(task) => {
+                let lock_tx_proof: ETHSPVProofJson =
+                    serde_json::from_str(task.lock_tx_proof.as_str())?;
+                Ok(is_registry_mode(&lock_tx_proof.replay_resist_outpoint))
             }
+            None => Ok(false),
         }
-        Err(anyhow!(
-            "Failed to generate eth proof for lock tx:{}, after retry 5 times",
-            tx_hash.as_str()
-        ))
     }
 
-    async fn mint(&self, task: &MintTask, capacity_cell: &OutPoint) -> Result<()> {
-        if let Err(error) = self.try_mint(&task, capacity_cell).await {
-            if error.to_string().contains("irreparable error") {
+    // group the tasks of the same bridge lockscript into batches of mint_batch_size at most.
+    fn batch_mint_tasks(&self, tasks: Vec<MintTask>) -> Result<Vec<Vec<MintTask>>> {
+        let mut batches: Vec<((H160, H160), Vec<MintTask>)> = vec![];
+        let mut registry_mode_batches = vec![];
+        for task in tasks {
+            let lock_tx_proof: ETHSPVProofJson = serde_json::from_str(task.lock_tx_proof.as_str())?;
+            // the lock events in registry mode share the registry cell, mint them one by one.
+            if is_registry_mode(&lock_tx_proof.replay_resist_outpoint) {
+                registry_mode_batches.push(vec![task]);
+                continue;
+            }
+            let bridge = (lock_tx_proof.token, lock_tx_proof.eth_address);
+            match batches
+                .iter_mut()
+                .find(|(key, batch)| *key == bridge && (batch.len() as u64) < self.mint_batch_size)
+            {
+                Some((_, batch)) => batch.push(task),
+                None => batches.push((bridge, vec![task])),
+            }
+        }
+        Ok(batches
+            .into_iter()
+            .map(|(_, batch)| batch)
+            .chain(registry_mode_batches)
+            .collect())
+    }
+
+    async fn mint(&self, tasks: &[MintTask], capacity_cell: &OutPoint) -> Result<()> {
+        let result = self.try_mint(tasks, capacity_cell).await;
+        for task in tasks {
+            self.update_mint_result(task, tasks.len(), &result).await?;
+        }
+        Ok(())
+    }
+
+    async fn update_mint_result(
+        &self,
+        task: &MintTask,
+        batch_size: usize,
+        result: &Result<H256>,
+    ) -> Result<()> {
+        if let Err(error) = result {
+            // the error of a batch may be caused by any task of it, retry them one by one.
+            if error.to_string().contains("irreparable error") && batch_size == 1 {
                 update_relayed_tx(
                     &self.db_pool,
                     task.lock_tx_hash.clone(),
+                    task.log_index,
                     "irreparable error".to_string(),
                     error.to_string(),
                 )
                 .await?;
                 log::error!(
-                    "mint for lock tx {:?} failed with irreparable error: {:?}",
+                    "mint for lock tx {:?} log {} failed with irreparable error: {:?}",
                     task.lock_tx_hash,
+                    task.log_index,
                     error
                 );
             } else {
                 update_relayed_tx(
                     &self.db_pool,
                     task.lock_tx_hash.clone(),
+                    task.log_index,
                     "retryable".to_string(),
                     error.to_string(),
                 )
                 .await?;
                 log::error!(
-                    "mint for lock tx {:?} failed with retryable error: {:?}",
+                    "mint for lock tx {:?} log {} failed with retryable error: {:?}",
                     task.lock_tx_hash,
+                    task.log_index,
                     error
                 );
             }
-        } else {
-            delete_relayed_tx(&self.db_pool, task.lock_tx_hash.clone()).await?;
-            log::info!("mint for lock tx {:?} succeed", task.lock_tx_hash);
+        } else if let Ok(ckb_tx_hash) = result {
+            set_mint_tx_hash(
+                &self.db_pool,
+                &task.lock_tx_hash,
+                task.log_index,
+                &hex::encode(ckb_tx_hash.as_bytes()),
+            )
+            .await?;
+            delete_relayed_tx(&self.db_pool, task.lock_tx_hash.clone(), task.log_index).await?;
+            log::info!(
+                "mint for lock tx {:?} log {} succeed",
+                task.lock_tx_hash,
+                task.log_index
+            );
         }
         Ok(())
     }
 
-    async fn try_mint(&self, task: &MintTask, capacity_cell: &OutPoint) -> Result<H256> {
+    async fn try_mint(&self, tasks: &[MintTask], capacity_cell: &OutPoint) -> Result<H256> {
         let mut generator = self.get_generator().await?;
-        let lock_tx_proof: ETHSPVProofJson = serde_json::from_str(task.lock_tx_proof.as_str())?;
-        send_eth_spv_proof_tx(
+        let mut lock_tx_proofs = vec![];
+        for task in tasks {
+            let lock_tx_proof: ETHSPVProofJson = serde_json::from_str(task.lock_tx_proof.as_str())?;
+            lock_tx_proofs.push(lock_tx_proof);
+        }
+        if tasks.len() == 1 {
+            return send_eth_spv_proof_tx(
+                &mut generator,
+                self.config_path.clone(),
+                tasks[0].lock_tx_hash.clone(),
+                &lock_tx_proofs[0],
+                self.private_key,
+                Some(capacity_cell.clone()),
+                self.rocksdb_path.clone(),
+            )
+            .await;
+        }
+        send_eth_spv_proof_batch_tx(
             &mut generator,
             self.config_path.clone(),
-            task.lock_tx_hash.clone(),
-            &lock_tx_proof,
+            tasks.iter().map(|task| task.lock_tx_hash.clone()).collect(),
+            &lock_tx_proofs,
             self.private_key,
             Some(capacity_cell.clone()),
             self.rocksdb_path.clone(),
@@ -321,16 +476,23 @@ impl EthTxRelayer {
             .deployed_contracts
             .clone()
             .expect("force contracts deployed");
-        let tip_number = get_eth_client_tip_number(
+        get_eth_client_confirmed_number(
             &mut generator,
             force_contracts.light_client_cell_script.cell_script,
-        )?;
-        Ok(tip_number)
-        // if tip_number > (CONFIRM as u64) {
-        //     Ok(tip_number - (CONFIRM as u64))
-        // } else {
-        //     Ok(0)
-        // }
+        )
+    }
+
+    async fn bridge_paused(&self) -> Result<bool> {
+        let mut generator = self.get_generator().await?;
+        let force_contracts = self
+            .force_config
+            .deployed_contracts
+            .clone()
+            .expect("force contracts deployed");
+        get_bridge_paused(
+            &mut generator,
+            force_contracts.bridge_status_cell_script.cell_script,
+        )
     }
 
     async fn get_generator(&self) -> Result<Generator> {
@@ -1,5 +1,6 @@
 pub mod ckb_relayer;
 pub mod eth_relayer;
+pub mod rate_limiter;
 
 pub const BATCH_UNLOCK_LIMIT: usize = 10;
 pub const TOTAL_UNLOCK_LIMIT: u64 = 100;
@@ -0,0 +1,69 @@
+use crate::dapp::db::rate_limit::{get_window_volume, is_volume_reserved, reserve_volume};
+use crate::util::config::TokenAmountLimits;
+use anyhow::Result;
+use sqlx::MySqlPool;
+
+// the relayers consult the rate limit ledger before relaying a transfer. the transfers over the
+// rolling window cap of the token are held, till the window frees up or an operator releases them.
+pub struct RateLimiter {
+    direction: &'static str,
+    limits: TokenAmountLimits,
+}
+
+impl RateLimiter {
+    pub fn new(direction: &'static str, limits: TokenAmountLimits) -> Self {
+        RateLimiter { direction, limits }
+    }
+
+    // whether the transfer can be relayed now. the volume of an admitted transfer is reserved in
+    // the ledger, so that the retries of it are admitted without being counted again. the released
+    // transfers bypass the cap, but their volume is still counted. the check and the reservation
+    // are in one db transaction, so the concurrent relayers never reserve over the cap together.
+    pub async fn admit(
+        &self,
+        pool: &MySqlPool,
+        token: &str,
+        tx_hash: &str,
+        log_index: u64,
+        amount: u128,
+        released: bool,
+    ) -> Result<bool> {
+        let mut db_tx = pool.begin().await?;
+        if is_volume_reserved(&mut db_tx, self.direction, tx_hash, log_index).await? {
+            db_tx.commit().await?;
+            return Ok(true);
+        }
+        let token = token.trim_start_matches("0x").to_lowercase();
+        if !released {
+            if let Some((window_secs, cap)) = self.limits.rate_limit(&token) {
+                let volume =
+                    get_window_volume(&mut db_tx, self.direction, &token, window_secs).await?;
+                if volume.checked_add(amount).map_or(true, |total| total > cap) {
+                    log::warn!(
+                        "hold {} transfer {} of token {}: volume {} in the last {} secs, amount {}, cap {}",
+                        self.direction,
+                        tx_hash,
+                        token,
+                        volume,
+                        window_secs,
+                        amount,
+                        cap
+                    );
+                    db_tx.rollback().await?;
+                    return Ok(false);
+                }
+            }
+        }
+        reserve_volume(
+            &mut db_tx,
+            self.direction,
+            &token,
+            tx_hash,
+            log_index,
+            amount,
+        )
+        .await?;
+        db_tx.commit().await?;
+        Ok(true)
+    }
+}
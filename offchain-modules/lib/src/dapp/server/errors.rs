@@ -9,6 +9,8 @@ use tokio::sync::mpsc::error::TrySendError;
 pub enum RpcError {
     #[display(fmt = "bad request data: {}", _0)]
     BadRequest(String),
+    #[display(fmt = "unauthorized: {}", _0)]
+    Unauthorized(String),
     #[display(fmt = "too many request: {}", _0)]
     TooManyRequest(String),
     #[display(fmt = "server error: {}", _0)]
@@ -33,6 +35,7 @@ impl error::ResponseError for RpcError {
     fn status_code(&self) -> StatusCode {
         match &*self {
             Self::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
             Self::TooManyRequest(_) => StatusCode::TOO_MANY_REQUESTS,
             Self::ServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
@@ -4,11 +4,12 @@ use super::REPLAY_RESIST_CELL_NUMBER;
 use super::{DappState, ReplayResistTask};
 use crate::dapp::db::server::{self as db, add_replay_resist_cells, is_token_replay_resist_init};
 use crate::util::ckb_util::{
-    build_lockscript_from_address, get_sudt_type_script, parse_cell, parse_merkle_cell_data,
+    build_lockscript_from_address, get_bridge_paused, get_sudt_type_script, is_token_allowed,
+    parse_cell, parse_merkle_cell_data,
 };
 use crate::util::eth_util::{
-    build_lock_eth_payload, build_lock_token_payload, convert_eth_address, make_transaction,
-    rlp_transaction, Web3Client,
+    build_lock_eth_payload, build_lock_token_payload, convert_eth_address,
+    encode_unsigned_transaction, EthFee, EthFeeOptions, Web3Client,
 };
 use actix_web::{get, post, web, HttpResponse, Responder};
 use ckb_jsonrpc_types::{Script as ScriptJson, Uint128, Uint64};
@@ -20,6 +21,7 @@ use molecule::prelude::{Entity, Reader};
 use serde_json::{json, Value};
 use std::convert::TryFrom;
 use std::str::FromStr;
+use subtle::ConstantTimeEq;
 use tokio::sync::oneshot;
 use web3::types::{CallRequest, U256};
 
@@ -47,6 +49,26 @@ pub async fn init_token(
     if is_token_init {
         return Err(RpcError::BadRequest("token already inited".to_string()));
     }
+    let token_address = convert_eth_address(args.token_address.as_str())
+        .map_err(|e| RpcError::BadRequest(format!("invalid args: {}", e)))?;
+    let mut generator = data
+        .get_generator()
+        .await
+        .map_err(|e| RpcError::ServerError(format!("get_generator: {}", e)))?;
+    let allowed = is_token_allowed(
+        &mut generator,
+        data.deployed_contracts
+            .token_allowlist_cell_script
+            .cell_script
+            .clone(),
+        &token_address,
+    )
+    .map_err(|e| RpcError::ServerError(format!("get token allowlist fail: {:?}", e)))?;
+    if !allowed {
+        return Err(RpcError::BadRequest(
+            "token is not in the allowlist".to_string(),
+        ));
+    }
     let cells = data
         .get_or_create_bridge_cell(
             args.token_address.as_str(),
@@ -72,13 +94,38 @@ pub async fn lock(
     let args: LockArgs = serde_json::from_value(args.into_inner())
         .map_err(|e| RpcError::BadRequest(format!("invalid args: {}", e)))?;
     log::info!("lock args: {:?}", args);
+    // the locked assets can not be minted on ckb while the bridge is paused
+    let mut generator = data
+        .get_generator()
+        .await
+        .map_err(|e| RpcError::ServerError(format!("get_generator: {}", e)))?;
+    let paused = get_bridge_paused(
+        &mut generator,
+        data.deployed_contracts
+            .bridge_status_cell_script
+            .cell_script
+            .clone(),
+    )
+    .map_err(|e| RpcError::ServerError(format!("get bridge status fail: {:?}", e)))?;
+    if paused {
+        return Err(RpcError::BadRequest("bridge is paused".to_string()));
+    }
     let lock_sender = convert_eth_address(args.sender.as_str())
         .map_err(|e| RpcError::BadRequest(format!("sender address parse fail: {}", e)))?;
     let to = convert_eth_address(data.deployed_contracts.eth_token_locker_addr.as_str())
         .map_err(|e| RpcError::BadRequest(format!("lock contract address parse fail: {}", e)))?;
 
     let nonce = U256::from(u128::from(args.nonce));
-    let gas_price = U256::from(u128::from(args.gas_price));
+    let fee_options = EthFeeOptions {
+        fee_mode: args.fee_mode,
+        gas_price: U256::from(u128::from(args.gas_price)),
+        max_fee_per_gas: U256::from(args.max_fee_per_gas.map(u128::from).unwrap_or_default()),
+        max_priority_fee_per_gas: U256::from(
+            args.max_priority_fee_per_gas
+                .map(u128::from)
+                .unwrap_or_default(),
+        ),
+    };
     let amount = U256::from(u128::from(args.amount));
     let bridge_fee = U256::from(u128::from(args.bridge_fee));
     if args.token_address.len() != 40 {
@@ -86,6 +133,13 @@ pub async fn lock(
             "invalid args: token address string length should be 40".to_string(),
         ));
     }
+    data.token_amount_limits
+        .check(
+            &args.token_address,
+            u128::from(args.amount),
+            u128::from(args.bridge_fee),
+        )
+        .map_err(|e| RpcError::BadRequest(format!("lock amount out of limits: {}", e)))?;
     let is_token_init = is_token_replay_resist_init(&data.db, args.token_address.as_str())
         .await
         .map_err(|e| {
@@ -100,7 +154,17 @@ pub async fn lock(
     let recipient_lockscript = build_lockscript_from_address(&args.ckb_recipient_address)
         .map_err(|e| RpcError::BadRequest(format!("ckb recipient address parse fail: {}", e)))?;
 
-    let web3_client = data.get_web3_client().client().clone();
+    let mut web3_client = data.get_web3_client();
+    let fee = web3_client
+        .get_fee(&fee_options)
+        .await
+        .map_err(|e| RpcError::ServerError(format!("get tx fee failed: {:?}", e)))?;
+    let chain_id = web3_client
+        .client()
+        .eth()
+        .chain_id()
+        .await
+        .map_err(|e| RpcError::ServerError(format!("get chain id failed: {:?}", e)))?;
 
     let (sender, receiver) = oneshot::channel();
     let replay_resist_task = ReplayResistTask {
@@ -147,6 +211,7 @@ pub async fn lock(
         }
     };
     let gas_limit = web3_client
+        .client()
         .eth()
         .estimate_gas(
             CallRequest {
@@ -162,15 +227,37 @@ pub async fn lock(
         .await
         .map_err(|e| RpcError::ServerError(format!("estimate gas failed: {:?}", e)))?;
 
-    let raw_transaction = make_transaction(to, nonce, input_data, gas_price, gas_limit, eth_value);
+    let raw = encode_unsigned_transaction(
+        to,
+        nonce,
+        input_data.clone(),
+        &fee,
+        gas_limit,
+        eth_value,
+        chain_id,
+    )
+    .map_err(|e| RpcError::ServerError(format!("encode lock tx failed: {:?}", e)))?;
+    let (gas_price, max_fee_per_gas, max_priority_fee_per_gas) = match fee {
+        EthFee::Legacy { gas_price } | EthFee::Eip2930 { gas_price } => {
+            (Some(gas_price), None, None)
+        }
+        EthFee::Eip1559 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        } => (None, Some(max_fee_per_gas), Some(max_priority_fee_per_gas)),
+    };
     let result = LockResult {
-        nonce: raw_transaction.nonce,
-        to: raw_transaction.to,
-        value: raw_transaction.value,
-        gas_price: raw_transaction.gas_price,
-        gas: raw_transaction.gas,
-        data: hex::encode(raw_transaction.clone().data),
-        raw: rlp_transaction(&raw_transaction),
+        nonce,
+        to: Some(to),
+        value: eth_value,
+        fee_mode: fee.fee_mode(),
+        gas_price,
+        max_fee_per_gas,
+        max_priority_fee_per_gas,
+        chain_id,
+        gas: gas_limit,
+        data: hex::encode(input_data),
+        raw: hex::encode(raw),
     };
     Ok(HttpResponse::Ok().json(result))
 }
@@ -191,6 +278,13 @@ pub async fn burn(
     );
     let token_address = convert_eth_address(args.token_address.as_str())
         .map_err(|e| RpcError::BadRequest(format!("convert token address error: {}", e)))?;
+    data.token_amount_limits
+        .check(
+            &args.token_address,
+            u128::from(args.amount),
+            u128::from(args.unlock_fee),
+        )
+        .map_err(|e| RpcError::BadRequest(format!("burn amount out of limits: {}", e)))?;
     let lock_contract_address = convert_eth_address(
         data.deployed_contracts.eth_token_locker_addr.as_str(),
     )
@@ -256,14 +350,21 @@ pub async fn get_eth_to_ckb_status(
             "invalid args: lock tx hash string length should be 64".to_string(),
         ));
     }
-    let indexer_status = db::get_eth_to_ckb_indexer_status(&data.db, &args.eth_lock_tx_hash)
-        .await
-        .map_err(|e| RpcError::ServerError(format!("get_eth_to_ckb_indexer_status: {:?}", e)))?
-        .ok_or_else(|| {
-            RpcError::ServerError(format!("eth lock tx {} not found", &args.eth_lock_tx_hash))
-        })?;
+    let log_index =
+        resolve_eth_to_ckb_log_index(&data, &args.eth_lock_tx_hash, args.log_index).await?;
+    let indexer_status =
+        db::get_eth_to_ckb_indexer_status(&data.db, &args.eth_lock_tx_hash, log_index)
+            .await
+            .map_err(|e| RpcError::ServerError(format!("get_eth_to_ckb_indexer_status: {:?}", e)))?
+            .ok_or_else(|| {
+                RpcError::ServerError(format!(
+                    "lock event {} of eth lock tx {} not found",
+                    log_index, &args.eth_lock_tx_hash
+                ))
+            })?;
     let mut res = GetEthToCkbStatusResponse {
         eth_lock_tx_hash: indexer_status.eth_lock_tx_hash,
+        log_index: indexer_status.log_index,
         status: indexer_status.status.clone(),
         err_msg: "".to_string(),
         token_addr: indexer_status.token_addr,
@@ -279,9 +380,10 @@ pub async fn get_eth_to_ckb_status(
     if indexer_status.status == "success" {
         return Ok(HttpResponse::Ok().json(res));
     }
-    let relay_status_opt = db::get_eth_to_ckb_relay_status(&data.db, &args.eth_lock_tx_hash)
-        .await
-        .map_err(|e| RpcError::ServerError(format!("get_eth_to_ckb_relay_status: {:?}", e)))?;
+    let relay_status_opt =
+        db::get_eth_to_ckb_relay_status(&data.db, &args.eth_lock_tx_hash, log_index)
+            .await
+            .map_err(|e| RpcError::ServerError(format!("get_eth_to_ckb_relay_status: {:?}", e)))?;
     if relay_status_opt.is_none() || relay_status_opt.clone().unwrap().status == "retryable" {
         return Ok(HttpResponse::Ok().json(res));
     }
@@ -291,6 +393,31 @@ pub async fn get_eth_to_ckb_status(
     Ok(HttpResponse::Ok().json(res))
 }
 
+// the log index of the lock event of the eth lock tx, the given one or the only one of the tx.
+async fn resolve_eth_to_ckb_log_index(
+    data: &web::Data<DappState>,
+    eth_lock_tx_hash: &str,
+    log_index: Option<u64>,
+) -> actix_web::Result<u64, RpcError> {
+    if let Some(log_index) = log_index {
+        return Ok(log_index);
+    }
+    let log_indexes = db::get_eth_to_ckb_log_indexes(&data.db, eth_lock_tx_hash)
+        .await
+        .map_err(|e| RpcError::ServerError(format!("get_eth_to_ckb_log_indexes: {:?}", e)))?;
+    match log_indexes.as_slice() {
+        [] => Err(RpcError::ServerError(format!(
+            "eth lock tx {} not found",
+            eth_lock_tx_hash
+        ))),
+        [log_index] => Ok(*log_index),
+        _ => Err(RpcError::BadRequest(format!(
+            "invalid args: eth lock tx {} has lock events {:?}, log_index should be set",
+            eth_lock_tx_hash, log_indexes
+        ))),
+    }
+}
+
 #[post("/get_ckb_to_eth_status")]
 pub async fn get_ckb_to_eth_status(
     data: web::Data<DappState>,
@@ -309,6 +436,50 @@ pub async fn get_ckb_to_eth_status(
     Ok(HttpResponse::Ok().json(status))
 }
 
+#[post("/release_held_transfer")]
+pub async fn release_held_transfer(
+    data: web::Data<DappState>,
+    args: web::Json<Value>,
+) -> actix_web::Result<HttpResponse, RpcError> {
+    let args: ReleaseHeldTransferArgs = serde_json::from_value(args.into_inner())
+        .map_err(|e| RpcError::BadRequest(format!("invalid args: {}", e)))?;
+    log::info!(
+        "release_held_transfer args: eth lock tx {:?} log {:?}, ckb burn tx {:?}",
+        args.eth_lock_tx_hash,
+        args.log_index,
+        args.ckb_burn_tx_hash
+    );
+
+    // the token is compared in constant time, so that it can not be guessed by the response time
+    match &data.admin_token {
+        Some(admin_token)
+            if bool::from(admin_token.as_bytes().ct_eq(args.admin_token.as_bytes())) => {}
+        _ => return Err(RpcError::Unauthorized("invalid admin token".to_string())),
+    }
+    let released = match (&args.eth_lock_tx_hash, &args.ckb_burn_tx_hash) {
+        (Some(eth_lock_tx_hash), None) => {
+            let log_index =
+                resolve_eth_to_ckb_log_index(&data, eth_lock_tx_hash, args.log_index).await?;
+            db::release_held_eth_to_ckb(&data.db, eth_lock_tx_hash, log_index)
+                .await
+                .map_err(|e| RpcError::ServerError(format!("release_held_eth_to_ckb: {:?}", e)))?
+        }
+        (None, Some(ckb_burn_tx_hash)) => db::release_held_ckb_to_eth(&data.db, ckb_burn_tx_hash)
+            .await
+            .map_err(|e| RpcError::ServerError(format!("release_held_ckb_to_eth: {:?}", e)))?,
+        _ => {
+            return Err(RpcError::BadRequest(
+                "invalid args: one of eth_lock_tx_hash and ckb_burn_tx_hash should be set"
+                    .to_string(),
+            ))
+        }
+    };
+    if !released {
+        return Err(RpcError::BadRequest("the transfer is not held".to_string()));
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
 #[post("/get_crosschain_history")]
 pub async fn get_crosschain_history(
     data: web::Data<DappState>,
@@ -8,7 +8,7 @@ use super::db::server::{
 };
 use crate::transfer::to_ckb;
 use crate::util::ckb_tx_generator::Generator;
-use crate::util::config::{DeployedContracts, ForceConfig};
+use crate::util::config::{DeployedContracts, ForceConfig, TokenAmountLimits};
 use crate::util::eth_util::Web3Client;
 use actix_web::{App, HttpServer};
 use anyhow::{anyhow, bail, Result};
@@ -33,6 +33,8 @@ pub struct DappState {
     pub config_path: String,
     pub network: Option<String>,
     pub deployed_contracts: DeployedContracts,
+    pub token_amount_limits: TokenAmountLimits,
+    pub admin_token: Option<String>,
     pub init_token_privkey: String,
     pub refresh_cell_privkey: String,
     pub mint_privkey: String,
@@ -114,6 +116,8 @@ impl DappState {
             deployed_contracts: force_config
                 .deployed_contracts
                 .expect("contracts should be deployed"),
+            token_amount_limits: force_config.token_amount_limits,
+            admin_token: force_config.dapp_admin_token,
             network,
             db,
             replay_resist_sender,
@@ -203,6 +207,7 @@ impl DappState {
             token.to_string(),
             "".to_string(),
             0,
+            vec![],
             true,
             cell_num,
             is_create,
@@ -427,6 +432,7 @@ pub async fn start(
             .service(burn)
             .service(get_eth_to_ckb_status)
             .service(get_ckb_to_eth_status)
+            .service(release_held_transfer)
             .service(get_crosschain_history)
             .service(get_sudt_balance)
             .service(get_best_block_height)
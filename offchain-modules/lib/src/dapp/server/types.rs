@@ -1,5 +1,6 @@
 use super::errors::RpcError;
 use crate::dapp::db::server::CrosschainHistory;
+use crate::util::eth_util::FeeMode;
 use ckb_jsonrpc_types::Uint128;
 use ckb_jsonrpc_types::{Script as ScriptJson, TransactionView};
 use ckb_types::packed::Script;
@@ -24,6 +25,10 @@ pub struct LockArgs {
     pub sudt_extra_data: String,
     pub gas_price: Uint128,
     pub nonce: Uint128,
+    #[serde(default)]
+    pub fee_mode: FeeMode,
+    pub max_fee_per_gas: Option<Uint128>,
+    pub max_priority_fee_per_gas: Option<Uint128>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -31,9 +36,17 @@ pub struct LockResult {
     pub nonce: U256,
     pub to: Option<H160>,
     pub value: U256,
-    pub gas_price: U256,
+    pub fee_mode: FeeMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_gas: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_priority_fee_per_gas: Option<U256>,
+    pub chain_id: U256,
     pub gas: U256,
     pub data: String,
+    // the payload to sign, the legacy tx rlp or the EIP-2718 typed tx envelope
     pub raw: String,
 }
 
@@ -61,11 +74,15 @@ pub struct BurnResult {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GetEthToCkbStatusArgs {
     pub eth_lock_tx_hash: String,
+    // the log index of the lock event, it can be omitted if the lock tx has only one lock event
+    #[serde(default)]
+    pub log_index: Option<u64>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GetEthToCkbStatusResponse {
     pub eth_lock_tx_hash: String,
+    pub log_index: u64,
     pub status: String,
     pub err_msg: String,
     pub token_addr: String,
@@ -79,6 +96,16 @@ pub struct GetEthToCkbStatusResponse {
     pub replay_resist_outpoint: String,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReleaseHeldTransferArgs {
+    pub admin_token: String,
+    pub eth_lock_tx_hash: Option<String>,
+    // the log index of the lock event, it can be omitted if the lock tx has only one lock event
+    #[serde(default)]
+    pub log_index: Option<u64>,
+    pub ckb_burn_tx_hash: Option<String>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GetCkbToEthStatusArgs {
     pub ckb_burn_tx_hash: String,
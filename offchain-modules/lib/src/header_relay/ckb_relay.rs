@@ -1,43 +1,115 @@
+use crate::header_relay::multisig_signer::request_ckb_history_tx_root_signature;
 use crate::transfer::to_eth::{get_add_ckb_headers_func, get_msg_hash, get_msg_signature};
-use crate::util::ckb_proof_helper::CBMT;
 use crate::util::ckb_tx_generator::Generator;
 use crate::util::ckb_util::covert_to_h256;
-use crate::util::config::ForceConfig;
+use crate::util::config::{CKBRelayPolicy, ForceConfig};
+use crate::util::eth_tx_manager::EthTxManager;
 use crate::util::eth_util::{
-    convert_eth_address, parse_private_key, parse_secret_key, relay_header_transaction, Web3Client,
+    convert_eth_address, parse_private_key, parse_secret_key, recover_signature_address,
+    EthFeeOptions, Web3Client,
 };
+use crate::util::history_tx_root::{invalidate_history_nodes, HistoryTxRootAccumulator};
 use crate::util::rocksdb::open_rocksdb;
 use anyhow::{anyhow, bail, Result};
+use ckb_jsonrpc_types::BlockView;
 use ckb_sdk::HttpRpcClient;
 use ethabi::Token;
 use ethereum_types::U256;
 use force_eth_types::eth_recipient_cell::ETHRecipientDataView;
-use force_sdk::constants::{
-    BURN_TX_MAX_NUM, BURN_TX_MAX_WAITING_BLOCKS, MAINNET_CKB_WAITING_BLOCKS,
-    TESTNET_CKB_WAITING_BLOCKS,
-};
 use log::info;
 use rocksdb::ops::{Get, Put};
 use secp256k1::SecretKey;
-use std::time::Instant;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use web3::types::{H160, H256};
 
+// the key of the relay state in the ckb rocksdb, the tx roots are keyed by the 8 bytes block
+// number so they never collide.
+pub const CKB_RELAY_STATE_KEY: &[u8] = b"ckb_relay_state";
+
+// The scheduling state of the ckb relayer, persisted in the ckb rocksdb so that the pending burn
+// txs are not forgotten after a restart.
+#[derive(Deserialize, Serialize, Default, Debug, Clone)]
+pub struct CKBRelayState {
+    pub last_submit_height: u64,
+    pub last_burn_tx_height: u64,
+    pub waiting_burn_txs_count: u64,
+    // the block timestamp in milliseconds of the oldest burn tx waiting to be relayed
+    pub first_burn_tx_timestamp: Option<u64>,
+}
+
+impl CKBRelayState {
+    pub fn load(db_path: String) -> Result<Self> {
+        let db = open_rocksdb(db_path)?;
+        match db.get(CKB_RELAY_STATE_KEY).map_err(|err| anyhow!(err))? {
+            Some(v) => serde_json::from_slice(v.as_ref())
+                .map_err(|e| anyhow!("parse ckb relay state err: {}", e)),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn save(&self, db_path: String) -> Result<()> {
+        let db = open_rocksdb(db_path)?;
+        let value = serde_json::to_vec(self)?;
+        db.put(CKB_RELAY_STATE_KEY, value)
+            .map_err(|err| anyhow!(err))?;
+        Ok(())
+    }
+
+    pub fn should_relay(
+        &self,
+        policy: &CKBRelayPolicy,
+        confirmed_height: u64,
+        now_ms: u64,
+    ) -> bool {
+        if confirmed_height.saturating_sub(self.last_submit_height) > policy.waiting_blocks {
+            return true;
+        }
+        if self.waiting_burn_txs_count == 0 {
+            return false;
+        }
+        if self.waiting_burn_txs_count >= policy.burn_tx_max_num
+            && confirmed_height >= self.last_burn_tx_height
+        {
+            return true;
+        }
+        if confirmed_height.saturating_sub(self.last_burn_tx_height)
+            > policy.burn_tx_max_waiting_blocks
+        {
+            return true;
+        }
+        match (
+            policy.burn_tx_max_latency_secs,
+            self.first_burn_tx_timestamp,
+        ) {
+            (Some(max_latency), Some(timestamp)) => {
+                now_ms.saturating_sub(timestamp) >= max_latency * 1000
+            }
+            _ => false,
+        }
+    }
+}
+
 pub struct CKBRelayer {
     pub contract_addr: H160,
     pub priv_key: H256,
     pub network: String,
     pub ckb_client: Generator,
     pub web3_client: Web3Client,
-    pub gas_price: U256,
+    pub fee_options: EthFeeOptions,
     pub multisig_privkeys: Vec<SecretKey>,
     pub ckb_rpc_url: String,
     pub eth_rpc_url: String,
     pub ckb_init_height: u64,
     pub db_path: String,
-    pub last_burn_tx_height: u64,
-    pub last_submit_height: u64,
-    pub waiting_burn_txs_count: u64,
+    pub state: CKBRelayState,
+    pub policy: CKBRelayPolicy,
     pub confirm: u64,
+    pub multisig_signer_urls: Vec<String>,
+    pub multisig_threshold: usize,
+    pub validators: Vec<H160>,
+    pub tx_manager: EthTxManager,
 }
 
 impl CKBRelayer {
@@ -46,8 +118,9 @@ impl CKBRelayer {
         network: Option<String>,
         priv_key_path: String,
         multisig_privkeys: Vec<String>,
-        gas_price: u64,
+        fee_options: EthFeeOptions,
         confirm: u64,
+        multisig_signer_urls: Vec<String>,
     ) -> Result<CKBRelayer> {
         let force_config = ForceConfig::new(config_path.as_str())?;
         let deployed_contracts = force_config
@@ -55,11 +128,25 @@ impl CKBRelayer {
             .as_ref()
             .ok_or_else(|| anyhow!("contracts should be deployed"))?;
 
-        if multisig_privkeys.len() < deployed_contracts.ckb_relay_mutlisig_threshold.threshold {
+        let multisig_threshold = deployed_contracts.ckb_relay_mutlisig_threshold.threshold;
+        if multisig_privkeys.len() + multisig_signer_urls.len() < multisig_threshold {
             bail!(
-                "the mutlisig privkeys number is less. expect {}, actual {} ",
-                deployed_contracts.ckb_relay_mutlisig_threshold.threshold,
-                multisig_privkeys.len()
+                "the mutlisig privkeys and signers number is less. expect {}, actual {} ",
+                multisig_threshold,
+                multisig_privkeys.len() + multisig_signer_urls.len()
+            );
+        }
+        let validators = deployed_contracts
+            .ckb_relay_mutlisig_threshold
+            .validators
+            .iter()
+            .map(|validator| convert_eth_address(validator))
+            .collect::<Result<Vec<H160>>>()?;
+        if validators.len() < multisig_threshold {
+            bail!(
+                "the validators number is less. expect {}, actual {}",
+                multisig_threshold,
+                validators.len()
             );
         }
 
@@ -82,7 +169,10 @@ impl CKBRelayer {
             Generator::new(ckb_rpc_url.clone(), ckb_indexer_url, Default::default())
                 .map_err(|e| anyhow!("failed to crate generator: {}", e))?;
         let web3_client = Web3Client::new(eth_rpc_url.clone());
-        let gas_price = U256::from(gas_price);
+        let tx_manager = EthTxManager::new(
+            eth_rpc_url.clone(),
+            force_config.get_eth_tx_policy(&network)?,
+        );
 
         let ckb_init_height = CKBRelayer::get_ckb_contract_deloy_height(
             &mut ckb_client,
@@ -93,25 +183,28 @@ impl CKBRelayer {
                 .clone(),
         )?;
 
+        let policy = force_config.get_ckb_relay_policy(&network)?;
         let db_path = force_config.ckb_rocksdb_path;
-        let last_burn_tx_height = 0;
-        let last_submit_height = 0;
-        let waiting_burn_txs_count = 0;
+        let state = CKBRelayState::load(db_path.clone())?;
+        info!("load ckb relay state: {:?}, policy: {:?}", state, policy);
 
         Ok(CKBRelayer {
             ckb_rpc_url,
             eth_rpc_url,
             ckb_init_height,
             db_path,
-            last_burn_tx_height,
-            last_submit_height,
-            waiting_burn_txs_count,
+            state,
+            policy,
             contract_addr,
             priv_key,
             ckb_client,
             web3_client,
-            gas_price,
+            fee_options,
             confirm,
+            multisig_signer_urls,
+            multisig_threshold,
+            validators,
+            tx_manager,
             network: net,
             multisig_privkeys: multisig_privkeys
                 .iter()
@@ -141,39 +234,29 @@ impl CKBRelayer {
             return Ok(());
         }
         let confirmed_height = ckb_current_height - self.confirm;
-        let waiting_blocks = match self.network.as_str() {
-            "mainnet" => MAINNET_CKB_WAITING_BLOCKS,
-            _ => TESTNET_CKB_WAITING_BLOCKS,
-        };
-        // usually relay every 5000 blocks
-        // 20 burn txs will trigger relay
-        // burn txs can not wait over 100 blocks
-        if confirmed_height - self.last_submit_height > waiting_blocks
-            || (self.waiting_burn_txs_count >= BURN_TX_MAX_NUM
-                && confirmed_height >= self.last_burn_tx_height)
-            || (confirmed_height - self.last_burn_tx_height > BURN_TX_MAX_WAITING_BLOCKS
-                && self.waiting_burn_txs_count > 0)
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| anyhow!("get system time err: {}", e))?
+            .as_millis() as u64;
+        if self
+            .state
+            .should_relay(&self.policy, confirmed_height, now_ms)
         {
             let merkle_root = self.get_history_merkle_root(
                 self.ckb_init_height,
                 confirmed_height,
                 self.db_path.clone(),
             )?;
-            let nonce = self.web3_client.get_eth_nonce(&self.priv_key).await?;
-            let sign_tx = self
+            let nonce = self.tx_manager.next_nonce(&self.priv_key).await?;
+            let now = Instant::now();
+            let tx_hash = self
                 .relay_headers(self.ckb_init_height, confirmed_height, merkle_root, nonce)
                 .await?;
-            let task_future = relay_header_transaction(self.eth_rpc_url.clone(), sign_tx);
-            let timeout_future = tokio::time::delay_for(std::time::Duration::from_secs(1800));
-            let now = Instant::now();
-            tokio::select! {
-                v = task_future => { v?; }
-                _ = timeout_future => {
-                    bail!("relay headers timeout");
-                }
-            }
-            self.last_submit_height = confirmed_height;
-            self.waiting_burn_txs_count = 0;
+            info!("relay headers success. tx_hash : {:#x}", tx_hash);
+            self.state.last_submit_height = confirmed_height;
+            self.state.waiting_burn_txs_count = 0;
+            self.state.first_burn_tx_timestamp = None;
+            self.state.save(self.db_path.clone())?;
             info!("relay headers time elapsed: {:?}", now.elapsed());
         }
 
@@ -186,7 +269,7 @@ impl CKBRelayer {
         latest_block_number: u64,
         history_tx_root: [u8; 32],
         asec_nonce: U256,
-    ) -> Result<Vec<u8>> {
+    ) -> Result<H256> {
         info!("relay headers. init_block_number: {:?}, latest_block_number: {:?}, history_tx_root: {:?}, asec_nonce: {:?}",
             init_block_number,
             latest_block_number,
@@ -204,11 +287,48 @@ impl CKBRelayer {
             history_tx_root,
         )?;
 
-        let mut signatures: Vec<u8> = vec![];
+        // the contract only counts one signature of each validator, so the signatures are keyed
+        // by the index of the validator which signed them.
+        let mut signatures: BTreeMap<usize, Vec<u8>> = BTreeMap::new();
         for &privkey in self.multisig_privkeys.iter() {
-            let mut signature = get_msg_signature(&headers_msg_hash, privkey)?;
-            signatures.append(&mut signature);
+            let signature = get_msg_signature(&headers_msg_hash, privkey)?;
+            match verify_validator_signature(&headers_msg_hash, &signature, &self.validators) {
+                Ok(index) => {
+                    signatures.entry(index).or_insert(signature);
+                }
+                Err(e) => log::error!("invalid signature from multisig privkey: {}", e),
+            }
+        }
+        for url in self.multisig_signer_urls.iter() {
+            if signatures.len() >= self.multisig_threshold {
+                break;
+            }
+            match request_ckb_history_tx_root_signature(
+                url,
+                init_block_number,
+                latest_block_number,
+                history_tx_root,
+            )
+            .await
+            .and_then(|signature| {
+                let index =
+                    verify_validator_signature(&headers_msg_hash, &signature, &self.validators)?;
+                Ok((index, signature))
+            }) {
+                Ok((index, signature)) => {
+                    signatures.entry(index).or_insert(signature);
+                }
+                Err(e) => log::error!("request signature from signer {} failed: {}", url, e),
+            }
         }
+        if signatures.len() < self.multisig_threshold {
+            bail!(
+                "the signatures number is less. expect {}, actual {}",
+                self.multisig_threshold,
+                signatures.len()
+            );
+        }
+        let signatures = signatures.values().flatten().copied().collect::<Vec<u8>>();
         info!("msg signatures {}", hex::encode(&signatures));
 
         let add_headers_abi = add_headers_func.encode_input(&[
@@ -217,21 +337,17 @@ impl CKBRelayer {
             Token::FixedBytes(history_tx_root.to_vec()),
             Token::Bytes(signatures),
         ])?;
-        let gas_price = self.web3_client.client().eth().gas_price().await?.as_u128();
-
-        let signed_tx = self
-            .web3_client
-            .build_sign_tx(
+        let fee = self.tx_manager.suggest_fee(&self.fee_options).await?;
+        self.tx_manager
+            .send_transaction(
                 self.contract_addr,
                 self.priv_key,
                 add_headers_abi,
-                U256::from(gas_price),
-                None,
-                U256::from(0u64),
+                U256::zero(),
                 asec_nonce,
+                fee,
             )
-            .await?;
-        Ok(signed_tx)
+            .await
     }
 
     pub fn get_ckb_contract_deloy_height(
@@ -266,19 +382,7 @@ impl CKBRelayer {
         latest_height: u64,
         db_path: String,
     ) -> Result<[u8; 32]> {
-        let db = open_rocksdb(db_path)?;
-
-        let mut all_tx_roots = vec![];
-        for number in start_height..=latest_height {
-            let db_root = db
-                .get(number.to_le_bytes())
-                .map_err(|err| anyhow!(err))?
-                .ok_or_else(|| anyhow!("db ckb root should not be none"))?;
-            let mut db_root_raw = [0u8; 32];
-            db_root_raw.copy_from_slice(db_root.as_ref());
-            all_tx_roots.push(db_root_raw);
-        }
-        Ok(CBMT::build_merkle_root(&all_tx_roots))
+        get_history_merkle_root(start_height, latest_height, db_path)
     }
 
     pub fn store_history_transaction_root(
@@ -287,64 +391,155 @@ impl CKBRelayer {
         latest_height: u64,
         db_path: String,
     ) -> Result<()> {
-        let db = open_rocksdb(db_path)?;
         let mut rpc_client = HttpRpcClient::new(self.ckb_rpc_url.clone());
-
-        let mut index = latest_height;
-        while index >= start_height {
-            match rpc_client
-                .get_block_by_number(index)
-                .map_err(|e| anyhow!("get_header_by_number err: {:?}", e))?
-            {
-                Some(block_view) => {
-                    for tx in block_view.transactions {
-                        if tx.inner.outputs_data.is_empty() {
-                            continue;
-                        }
-                        let output_data = tx.inner.outputs_data[0].as_bytes();
-                        if ETHRecipientDataView::new(&output_data).is_ok() {
-                            self.last_burn_tx_height = index;
-                            self.waiting_burn_txs_count += 1;
-                            break;
-                        }
+        let state = &mut self.state;
+        store_history_tx_roots(
+            &mut rpc_client,
+            start_height,
+            latest_height,
+            db_path.clone(),
+            |index, block_view| {
+                for tx in block_view.transactions.iter() {
+                    if tx.inner.outputs_data.is_empty() {
+                        continue;
                     }
+                    let output_data = tx.inner.outputs_data[0].as_bytes();
+                    if ETHRecipientDataView::new(&output_data).is_ok() {
+                        let timestamp = block_view.header.inner.timestamp.value();
+                        state.last_burn_tx_height = index;
+                        state.waiting_burn_txs_count += 1;
+                        state.first_burn_tx_timestamp = Some(
+                            state
+                                .first_burn_tx_timestamp
+                                .map_or(timestamp, |t| std::cmp::min(t, timestamp)),
+                        );
+                        break;
+                    }
+                }
+            },
+        )?;
+        self.state.save(db_path)
+    }
+}
 
-                    let header_view = block_view.header;
+pub fn get_history_merkle_root(
+    start_height: u64,
+    latest_height: u64,
+    db_path: String,
+) -> Result<[u8; 32]> {
+    let mut accumulator = HistoryTxRootAccumulator::open(db_path)?;
+    let root = accumulator.get_root(start_height, latest_height)?;
+    accumulator.commit()?;
+    Ok(root)
+}
 
-                    let chain_root = header_view.inner.transactions_root.0;
+// Store the transactions root of the ckb blocks in [start_height, latest_height] to the rocksdb.
+// Walk down from latest_height until the first block already stored, on_new_block is called for
+// every block walked through.
+pub fn store_history_tx_roots<F: FnMut(u64, &BlockView)>(
+    rpc_client: &mut HttpRpcClient,
+    start_height: u64,
+    latest_height: u64,
+    db_path: String,
+    mut on_new_block: F,
+) -> Result<()> {
+    let db = open_rocksdb(db_path)?;
 
-                    let db_root_option = db.get(index.to_le_bytes()).map_err(|err| anyhow!(err))?;
+    let mut index = latest_height;
+    while index >= start_height {
+        match rpc_client
+            .get_block_by_number(index)
+            .map_err(|e| anyhow!("get_header_by_number err: {:?}", e))?
+        {
+            Some(block_view) => {
+                on_new_block(index, &block_view);
 
-                    let db_root = match db_root_option {
-                        Some(v) => {
-                            let mut db_root_raw = [0u8; 32];
-                            db_root_raw.copy_from_slice(v.as_ref());
-                            db_root_raw
-                        }
-                        None => [0u8; 32],
-                    };
+                let header_view = block_view.header;
 
-                    if chain_root.to_vec() != db_root {
-                        db.put(index.to_le_bytes(), chain_root.to_vec())
-                            .map_err(|err| anyhow!(err))?;
-                    } else {
-                        break;
+                let chain_root = header_view.inner.transactions_root.0;
+
+                let db_root_option = db.get(index.to_le_bytes()).map_err(|err| anyhow!(err))?;
+
+                let is_stored = db_root_option.is_some();
+                let db_root = match db_root_option {
+                    Some(v) => {
+                        let mut db_root_raw = [0u8; 32];
+                        db_root_raw.copy_from_slice(v.as_ref());
+                        db_root_raw
                     }
-                    index -= 1;
-                }
-                None => {
-                    bail!(
-                        "cannot get the block transactions root, block_number = {}",
-                        index
-                    );
+                    None => [0u8; 32],
+                };
+
+                if chain_root.to_vec() != db_root {
+                    // the block is reorged, drop the cached history nodes built on the old root
+                    if is_stored {
+                        invalidate_history_nodes(&db, index)?;
+                    }
+                    db.put(index.to_le_bytes(), chain_root.to_vec())
+                        .map_err(|err| anyhow!(err))?;
+                } else {
+                    break;
                 }
+                index -= 1;
+            }
+            None => {
+                bail!(
+                    "cannot get the block transactions root, block_number = {}",
+                    index
+                );
             }
         }
-        info!(
-            "store ckb headers from {:?} to {:?}",
-            index + 1,
-            latest_height
+    }
+    info!(
+        "store ckb headers from {:?} to {:?}",
+        index + 1,
+        latest_height
+    );
+    Ok(())
+}
+
+// return the index of the CKBChain validator which signed the message hash.
+fn verify_validator_signature(
+    msg_hash: &[u8; 32],
+    signature: &[u8],
+    validators: &[H160],
+) -> Result<usize> {
+    let address = recover_signature_address(msg_hash, signature)?;
+    validators
+        .iter()
+        .position(|validator| validator == &address)
+        .ok_or_else(|| {
+            anyhow!(
+                "{:#x} is not a validator of the ckb chain contract",
+                address
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::eth_util::secret_key_address;
+
+    #[test]
+    fn test_verify_validator_signature() {
+        let validator_key = SecretKey::from_slice(&[1u8; 32]).unwrap();
+        let other_key = SecretKey::from_slice(&[2u8; 32]).unwrap();
+        let validators = vec![
+            secret_key_address(&SecretKey::from_slice(&[3u8; 32]).unwrap()),
+            secret_key_address(&validator_key),
+        ];
+        let msg_hash = [4u8; 32];
+
+        let signature = get_msg_signature(&msg_hash, validator_key).unwrap();
+        assert_eq!(
+            verify_validator_signature(&msg_hash, &signature, &validators).unwrap(),
+            1
         );
-        Ok(())
+        // the signature over another message recovers another address
+        assert!(verify_validator_signature(&[5u8; 32], &signature, &validators).is_err());
+        let signature = get_msg_signature(&msg_hash, other_key).unwrap();
+        assert!(verify_validator_signature(&msg_hash, &signature, &validators).is_err());
+        assert!(verify_validator_signature(&msg_hash, &signature[..64], &validators).is_err());
     }
 }
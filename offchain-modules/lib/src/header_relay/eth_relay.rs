@@ -1,14 +1,20 @@
+use crate::header_relay::multisig_signer::{
+    get_multisig_member_lock_args, sign_with_multisig_signers,
+};
 use crate::util::ckb_tx_generator::Generator;
 use crate::util::ckb_util::{
-    parse_cell, parse_main_chain_headers, parse_merkle_cell_data, parse_privkey_path,
+    parse_cell, parse_main_chain_headers, parse_merkle_cell_confirm, parse_merkle_cell_data,
+    parse_privkey_path,
 };
-use crate::util::config::ForceConfig;
-use crate::util::eth_util::Web3Client;
+use crate::util::config::{DeployedContracts, ForceConfig};
+use crate::util::eth_util::{QuorumWeb3Client, Web3Client};
 use crate::util::rocksdb;
 use anyhow::{anyhow, Result};
+use ckb_hash::blake2b_256;
 use ckb_sdk::{Address, AddressPayload, SECP256K1};
 use ckb_types::core::TransactionView;
 use ckb_types::packed::Script;
+use ckb_types::H160;
 use ethereum_types::H256;
 use force_eth_types::generated::eth_header_cell::ETHHeaderCellMerkleDataReader;
 use force_sdk::cell_collector::get_live_cell_by_typescript;
@@ -21,14 +27,19 @@ use secp256k1::SecretKey;
 // use serde::export::Clone;
 use shellexpand::tilde;
 use sparse_merkle_tree::traits::Value;
+use std::collections::HashSet;
 use std::ops::Add;
 use std::str::FromStr;
 use web3::types::{Block, BlockHeader, U64};
 
 pub const HEADER_LIMIT_IN_TX: usize = 14;
+// the max number of leaves pruned from the smt in one relay, to bound the size of the smt updates
+// when the retention window is first enabled on a long light client.
+pub const MAX_PRUNE_HEIGHTS_PER_RELAY: u64 = 2000;
 
 pub struct ETHRelayer {
     pub eth_client: Web3Client,
+    pub quorum_client: QuorumWeb3Client,
     pub generator: Generator,
     pub config_path: String,
     pub config: ForceConfig,
@@ -36,7 +47,10 @@ pub struct ETHRelayer {
     pub multisig_privkeys: Vec<SecretKey>,
     pub secret_key: SecretKey,
     pub confirm: u64,
+    pub mint_confirm: u64,
     pub delay: u64,
+    pub retention_window: Option<u64>,
+    pub multisig_signer_urls: Vec<String>,
 }
 
 impl ETHRelayer {
@@ -46,8 +60,14 @@ impl ETHRelayer {
         priv_key_path: String,
         multisig_privkeys: Vec<String>,
         confirm: u64,
+        mint_confirm: u64,
         delay: u64,
+        retention_window: Option<u64>,
+        multisig_signer_urls: Vec<String>,
     ) -> Result<Self> {
+        if retention_window == Some(0) {
+            anyhow::bail!("retention window should be greater than 0");
+        }
         let config_path = tilde(config_path.as_str()).into_owned();
         let force_config = ForceConfig::new(config_path.as_str())?;
         let deployed_contracts = force_config
@@ -61,38 +81,51 @@ impl ETHRelayer {
         let generator = Generator::new(ckb_rpc_url, ckb_indexer_url, deployed_contracts.clone())
             .map_err(|e| anyhow::anyhow!(e))?;
         let eth_client = Web3Client::new(eth_rpc_url);
-        let mut addresses = vec![];
-        for item in deployed_contracts.multisig_address.addresses.clone() {
-            let address = Address::from_str(&item).unwrap();
-            addresses.push(address);
-        }
-        let sighash_addresses = addresses
-            .into_iter()
-            .map(|address| address.payload().clone())
-            .collect::<Vec<_>>();
-
-        let multisig_config = MultisigConfig::new_with(
-            sighash_addresses,
-            deployed_contracts.multisig_address.require_first_n,
-            deployed_contracts.multisig_address.threshold,
-        )
-        .map_err(|err| anyhow!(err))?;
+        let quorum_client = QuorumWeb3Client::from_config(&force_config, &network)?;
+        let multisig_config = get_light_client_multisig_config(deployed_contracts)?;
 
         let secret_key = parse_privkey_path(&priv_key_path, &force_config, &network)?;
+        let multisig_privkeys = multisig_privkeys
+            .into_iter()
+            .map(|k| parse_privkey_path(&k, &force_config, &network))
+            .collect::<Result<Vec<SecretKey>>>()?;
+
+        // the local keys of the multisig members and the signer daemons should be able to meet
+        // the threshold, assuming each daemon signs for a different member.
+        let member_lock_args = get_multisig_member_lock_args(&multisig_config);
+        let local_members = std::iter::once(&secret_key)
+            .chain(multisig_privkeys.iter())
+            .map(|key| {
+                let pubkey = secp256k1::PublicKey::from_secret_key(&SECP256K1, key);
+                H160::from_slice(&blake2b_256(&pubkey.serialize()[..])[0..20])
+                    .expect("blake160 of pubkey should be 20 bytes")
+            })
+            .filter(|lock_arg| member_lock_args.contains(lock_arg))
+            .collect::<HashSet<_>>();
+        let threshold = multisig_config.threshold() as usize;
+        if threshold == 0 || local_members.len() + multisig_signer_urls.len() < threshold {
+            anyhow::bail!(
+                "the multisig threshold {} can not be met by {} local member keys and {} signers",
+                threshold,
+                local_members.len(),
+                multisig_signer_urls.len()
+            );
+        }
 
         Ok(ETHRelayer {
             eth_client,
+            quorum_client,
             generator,
             config_path,
             multisig_config,
             secret_key,
-            multisig_privkeys: multisig_privkeys
-                .into_iter()
-                .map(|k| parse_privkey_path(&k, &force_config, &network))
-                .collect::<Result<Vec<SecretKey>>>()?,
+            multisig_privkeys,
             config: force_config,
             confirm,
+            mint_confirm,
             delay,
+            retention_window,
+            multisig_signer_urls,
         })
     }
 
@@ -177,13 +210,7 @@ impl ETHRelayer {
         cell_script: Script,
         mut latest_submit_header_number: u64,
     ) -> Result<u64> {
-        let tip_header_number: u64 = self
-            .eth_client
-            .client()
-            .eth()
-            .block_number()
-            .await?
-            .as_u64();
+        let tip_header_number: u64 = self.quorum_client.get_tip_number().await?;
         if tip_header_number <= self.confirm {
             info!("waiting for tip_header_number reach confirm limit. tip_header_number: {}, confirm: {}", tip_header_number, self.confirm);
             return Ok(latest_submit_header_number);
@@ -195,6 +222,7 @@ impl ETHRelayer {
 
         let force_config = ForceConfig::new(self.config_path.as_str())?;
         let db_path = force_config.eth_rocksdb_path;
+        let snapshot_limit = force_config.eth_smt_snapshot_limit;
         // make tx
         let cell =
             get_live_cell_by_typescript(&mut self.generator.indexer_client, cell_script.clone())
@@ -204,10 +232,16 @@ impl ETHRelayer {
         let last_cell_output_data = cell.output_data.as_bytes();
 
         let mut last_cell_latest_height = 0u64;
+        // the confirm is set when the light client cell is initialized and kept unchanged after
+        let mut mint_confirm = self.mint_confirm;
 
         let (start_height, mut smt_tree) = match last_cell_output_data.len() {
             0 => {
-                let rocksdb_store = rocksdb::RocksDBStore::new(db_path.clone())?;
+                // the contract rejects a zero confirm, as it can not be changed once initialized
+                if self.mint_confirm == 0 {
+                    anyhow::bail!("mint confirm should be greater than 0 to init the light client");
+                }
+                let (rocksdb_store, _) = open_or_create_rocksdb_store(db_path, snapshot_limit)?;
                 (
                     tip_header_number - self.confirm,
                     rocksdb::SMT::new(sparse_merkle_tree::H256::zero(), rocksdb_store),
@@ -217,76 +251,71 @@ impl ETHRelayer {
                 let (start_height, latest_height, merkle_root) =
                     parse_merkle_cell_data(last_cell_output_data.to_vec())?;
                 last_cell_latest_height = latest_height;
-                let rocksdb_store = rocksdb::RocksDBStore::open(db_path.clone())?;
+                mint_confirm = parse_merkle_cell_confirm(last_cell_output_data.to_vec())?;
+                if mint_confirm != self.mint_confirm {
+                    info!(
+                        "light client cell keeps its confirm {}, ignore mint confirm {}",
+                        mint_confirm, self.mint_confirm
+                    );
+                }
                 (
                     start_height,
-                    rocksdb::SMT::new(merkle_root.into(), rocksdb_store),
+                    reconcile_smt(
+                        &mut self.quorum_client,
+                        db_path,
+                        snapshot_limit,
+                        start_height,
+                        latest_height,
+                        merkle_root,
+                    )
+                    .await?,
                 )
             }
         };
 
         let confirmed_header_number = tip_header_number - self.confirm;
-        let mut index = confirmed_header_number;
-        while index >= start_height {
-            let block_number = U64([index]);
-
-            let mut key = [0u8; 32];
-            let mut height = [0u8; 8];
-            height.copy_from_slice(index.to_le_bytes().as_ref());
-            key[..8].clone_from_slice(&height);
-
-            let chain_block = self.eth_client.get_block(block_number.into()).await?;
-            let chain_block_hash = chain_block
-                .hash
-                .ok_or_else(|| anyhow!("the block number is not exist."))?;
-
-            let db_block_hash = smt_tree
-                .get(&key.into())
-                .map_err(|err| anyhow::anyhow!(err))?;
-
-            if db_block_hash.to_h256().as_slice() != chain_block_hash.0.as_ref() {
-                smt_tree
-                    .update(key.into(), chain_block_hash.0.into())
-                    .map_err(|err| anyhow::anyhow!(err))?;
-                log::info!("sync eth block {} to cache", index);
-            } else {
-                break;
-            }
-            index -= 1;
-        }
+        let new_start_height = self.get_new_start_height(start_height, confirmed_header_number);
+        let index = update_smt_window(
+            &mut self.quorum_client,
+            &mut smt_tree,
+            start_height,
+            new_start_height,
+            confirmed_header_number,
+        )
+        .await?;
         log::info!(
             "start relaying headers from {} to {}",
             index + 1,
             confirmed_header_number
         );
-
-        let new_merkle_root = smt_tree.root().as_slice();
+        let new_merkle_root: [u8; 32] = (*smt_tree.root()).into();
         let new_latest_height = confirmed_header_number;
         let unsigned_tx = self.generator.generate_eth_light_client_tx_naive(
             from_lockscript.clone(),
             cell.clone(),
             &new_merkle_root,
-            start_height,
+            new_start_height,
             new_latest_height,
+            mint_confirm,
         )?;
 
         let mut privkeys = vec![&self.secret_key];
         privkeys.extend(self.multisig_privkeys.iter());
-        let tx = sign_with_multi_key(
+        let tx = sign_with_multisig_signers(
             unsigned_tx,
             &mut self.generator.rpc_client,
             privkeys,
             self.multisig_config.clone(),
+            &self.multisig_signer_urls,
         )
-        .map_err(|err| anyhow::anyhow!(err))?;
-
-        // commit rocksdb before sending tx
-        let rocksdb_store = smt_tree.store_mut();
-        rocksdb_store.commit()?;
+        .await?;
 
+        // the smt updates stay staged in memory until the light client cell is updated on chain
         let send_tx_res =
             send_tx_sync_with_response(&mut self.generator.rpc_client, &tx, 180).await;
+        let rocksdb_store = smt_tree.store_mut();
         if let Err(e) = send_tx_res {
+            rocksdb_store.rollback();
             log::error!(
                 "relay eth header from {} to {} failed! err: {}",
                 last_cell_latest_height,
@@ -294,6 +323,7 @@ impl ETHRelayer {
                 e
             );
         } else {
+            rocksdb_store.commit_with_root(new_merkle_root, new_latest_height)?;
             info!(
                 "Successfully relayed the headers from {} to {}, tip header {}",
                 index, confirmed_header_number, tip_header_number
@@ -303,6 +333,22 @@ impl ETHRelayer {
         Ok(latest_submit_header_number)
     }
 
+    // The start height of the light client cell after relaying up to confirmed_header_number.
+    // It keeps the last `retention_window` heights and never goes backwards.
+    pub fn get_new_start_height(&self, start_height: u64, confirmed_header_number: u64) -> u64 {
+        match self.retention_window {
+            Some(window) if confirmed_header_number + 1 > window => {
+                let window_start = confirmed_header_number + 1 - window;
+                if window_start <= start_height {
+                    start_height
+                } else {
+                    std::cmp::min(window_start, start_height + MAX_PRUNE_HEIGHTS_PER_RELAY)
+                }
+            }
+            _ => start_height,
+        }
+    }
+
     // naive relay method. ignore the context, get the latest 500 headers on chain and replace the
     // light client cell
     pub async fn do_naive_relay_loop(&mut self, cell_script: Script) -> Result<()> {
@@ -377,9 +423,9 @@ impl ETHRelayer {
             );
             let headers = self
                 .eth_client
-                .get_blocks(start.as_u64(), latest_number.as_u64())
+                .get_blocks_with_extension(start.as_u64(), latest_number.as_u64())
                 .await?;
-            if headers[0].parent_hash
+            if headers[0].0.parent_hash
                 == current_block
                     .hash
                     .ok_or_else(|| anyhow!("the block hash is not exist."))?
@@ -437,7 +483,7 @@ impl ETHRelayer {
             update_cell_sync(&mut self.generator.indexer_client, &tx, 600, &mut cell)
                 .await
                 .map_err(|err| anyhow::anyhow!(err))?;
-            current_block = headers[headers.len() - 1].clone();
+            current_block = headers[headers.len() - 1].0.clone();
             number = current_block.number.unwrap();
             let ckb_cell_data = cell.clone().output_data.as_bytes().to_vec();
             let (un_confirmed, _) = parse_main_chain_headers(ckb_cell_data)?;
@@ -450,6 +496,155 @@ impl ETHRelayer {
     }
 }
 
+// Make the local smt consistent with the merkle root of the light client cell.
+// The local smt is behind the cell if the relayer crashed or timed out after the tx was sent,
+// and is ahead of the cell if the db was committed by a tx which was rejected later.
+// Walk down from the higher one of the two latest heights, reset the leaves to the cell's view
+// until the roots match. Leaves below start_height are pruned by the cell, so the walk goes on
+// under start_height to remove them as long as they are still in the local smt.
+pub async fn reconcile_smt(
+    quorum_client: &mut QuorumWeb3Client,
+    db_path: String,
+    snapshot_limit: usize,
+    start_height: u64,
+    latest_height: u64,
+    merkle_root: [u8; 32],
+) -> Result<rocksdb::SMT> {
+    let (rocksdb_store, is_new) = open_or_create_rocksdb_store(db_path, snapshot_limit)?;
+    let (local_root, local_height) = match rocksdb_store.get_committed_root()? {
+        Some(committed) => committed,
+        None if is_new => ([0u8; 32], 0),
+        // the db was written before the committed root is recorded, trust it as the old
+        // relayer did.
+        None => (merkle_root, latest_height),
+    };
+    let mut smt_tree = rocksdb::SMT::new(local_root.into(), rocksdb_store);
+    if local_root == merkle_root {
+        return Ok(smt_tree);
+    }
+    info!(
+        "local smt root {} at height {} is not in line with the light client cell root {} at height {}, start reconciling",
+        hex::encode(local_root),
+        local_height,
+        hex::encode(merkle_root),
+        latest_height
+    );
+
+    let mut index = std::cmp::max(local_height, latest_height);
+    loop {
+        let key = rocksdb::get_smt_key_for_height(index);
+        let db_value = smt_tree
+            .get(&key.into())
+            .map_err(|err| anyhow::anyhow!(err))?;
+        if index < start_height && db_value.to_h256().is_zero() {
+            break;
+        }
+        let value = if index > latest_height || index < start_height {
+            [0u8; 32]
+        } else {
+            quorum_client.get_block_hash(index).await?.0
+        };
+        if db_value.to_h256().as_slice() != value.as_ref() {
+            smt_tree
+                .update(key.into(), value.into())
+                .map_err(|err| anyhow::anyhow!(err))?;
+        }
+        if smt_tree.root().as_slice() == merkle_root.as_ref() {
+            smt_tree
+                .store_mut()
+                .commit_with_root(merkle_root, latest_height)?;
+            info!("reconciled the local smt at height {}", index);
+            return Ok(smt_tree);
+        }
+        if index == 0 {
+            break;
+        }
+        index -= 1;
+    }
+    anyhow::bail!(
+        "failed to reconcile the local smt with the light client cell root {}, please rebuild it with `force-cli rebuild-eth-smt`",
+        hex::encode(merkle_root)
+    )
+}
+
+// Update the smt to the header window [new_start_height, latest_height] on the ethereum chain.
+// Walk down from latest_height and update the leaves until the first one already in line with
+// the chain, then remove the leaves in [start_height, new_start_height) out of the window.
+// Return the height where the walk stops.
+pub async fn update_smt_window(
+    quorum_client: &mut QuorumWeb3Client,
+    smt_tree: &mut rocksdb::SMT,
+    start_height: u64,
+    new_start_height: u64,
+    latest_height: u64,
+) -> Result<u64> {
+    let mut index = latest_height;
+    while index >= new_start_height {
+        let key = rocksdb::get_smt_key_for_height(index);
+
+        let chain_block_hash = quorum_client.get_block_hash(index).await?;
+
+        let db_block_hash = smt_tree
+            .get(&key.into())
+            .map_err(|err| anyhow::anyhow!(err))?;
+
+        if db_block_hash.to_h256().as_slice() != chain_block_hash.0.as_ref() {
+            smt_tree
+                .update(key.into(), chain_block_hash.0.into())
+                .map_err(|err| anyhow::anyhow!(err))?;
+            log::info!("sync eth block {} to cache", index);
+        } else {
+            break;
+        }
+        index -= 1;
+    }
+    rocksdb::prune_smt_range(smt_tree, start_height, new_start_height)?;
+    if new_start_height > start_height {
+        log::info!(
+            "prune eth blocks from {} to {} out of the retention window",
+            start_height,
+            new_start_height - 1
+        );
+    }
+    Ok(index)
+}
+
+pub fn get_light_client_multisig_config(
+    deployed_contracts: &DeployedContracts,
+) -> Result<MultisigConfig> {
+    let mut addresses = vec![];
+    for item in deployed_contracts.multisig_address.addresses.clone() {
+        let address = Address::from_str(&item).map_err(|err| anyhow!(err))?;
+        addresses.push(address);
+    }
+    let sighash_addresses = addresses
+        .into_iter()
+        .map(|address| address.payload().clone())
+        .collect::<Vec<_>>();
+
+    MultisigConfig::new_with(
+        sighash_addresses,
+        deployed_contracts.multisig_address.require_first_n,
+        deployed_contracts.multisig_address.threshold,
+    )
+    .map_err(|err| anyhow!(err))
+}
+
+// open the smt store at db_path, create it if not exists. return whether the store is created.
+pub fn open_or_create_rocksdb_store(
+    db_path: String,
+    snapshot_limit: usize,
+) -> Result<(rocksdb::RocksDBStore<rocksdb::RocksDBValue>, bool)> {
+    let db_dir = tilde(db_path.as_str()).into_owned();
+    let (mut rocksdb_store, is_new) = if std::path::Path::new(db_dir.as_str()).exists() {
+        (rocksdb::RocksDBStore::open(db_path)?, false)
+    } else {
+        (rocksdb::RocksDBStore::new(db_path)?, true)
+    };
+    rocksdb_store.set_snapshot_limit(snapshot_limit);
+    Ok((rocksdb_store, is_new))
+}
+
 pub async fn update_cell_sync(
     index_client: &mut IndexerRpcClient,
     tx: &TransactionView,
@@ -543,8 +738,12 @@ pub async fn wait_header_sync_success(
         let latest_height_raw = cell_data_reader.latest_height().raw_data();
         best_block_height.copy_from_slice(latest_height_raw);
         let best_block_height = u64::from_le_bytes(best_block_height);
+        let mut confirm = [0u8; 8];
+        confirm.copy_from_slice(cell_data_reader.confirm().raw_data());
+        let confirm = u64::from_le_bytes(confirm);
 
-        if best_block_height >= header.number {
+        // the lockscript only accepts headers confirmed by the light client
+        if best_block_height >= header.number.saturating_add(confirm) {
             break;
         }
 
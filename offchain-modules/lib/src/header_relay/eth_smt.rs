@@ -0,0 +1,211 @@
+use crate::util::ckb_util::{parse_cell, parse_merkle_cell_data};
+use crate::util::config::ForceConfig;
+use crate::util::eth_util::QuorumWeb3Client;
+use crate::util::rocksdb::{get_smt_key_for_height, RocksDBStore, RocksDBValue, SMT};
+use anyhow::{anyhow, bail, Result};
+use force_sdk::cell_collector::get_live_cell_by_typescript;
+use force_sdk::indexer::IndexerRpcClient;
+use log::info;
+use shellexpand::tilde;
+use sparse_merkle_tree::traits::Value;
+use std::path::Path;
+
+// commit the staged smt nodes every COMMIT_INTERVAL heights to bound the memory usage.
+const COMMIT_INTERVAL: u64 = 1000;
+
+#[derive(Debug, Clone, Default)]
+pub struct LightClientInfo {
+    pub start_height: u64,
+    pub latest_height: u64,
+    pub merkle_root: [u8; 32],
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RebuildReport {
+    pub light_client: LightClientInfo,
+    // the root of the rebuilt smt, or of the local smt in dry run mode
+    pub local_root: [u8; 32],
+    // the first height whose local leaf is not in line with the ethereum chain, only for dry run
+    pub first_mismatch_height: Option<u64>,
+}
+
+impl RebuildReport {
+    pub fn is_consistent(&self) -> bool {
+        self.first_mismatch_height.is_none() && self.local_root == self.light_client.merkle_root
+    }
+}
+
+pub async fn get_light_client_info(
+    force_config: &ForceConfig,
+    network: &Option<String>,
+) -> Result<LightClientInfo> {
+    let deployed_contracts = force_config
+        .deployed_contracts
+        .as_ref()
+        .ok_or_else(|| anyhow!("contracts should be deployed"))?;
+    let cell_script = parse_cell(
+        deployed_contracts
+            .light_client_cell_script
+            .cell_script
+            .as_str(),
+    )?;
+    let mut indexer_client = IndexerRpcClient::new(force_config.get_ckb_indexer_url(network)?);
+    let cell = get_live_cell_by_typescript(&mut indexer_client, cell_script)
+        .map_err(|err| anyhow!(err))?
+        .ok_or_else(|| anyhow!("the light client cell is not exist"))?;
+    let cell_data = cell.output_data.as_bytes().to_vec();
+    if cell_data.is_empty() {
+        bail!("the light client cell is not initialized")
+    }
+    let (start_height, latest_height, merkle_root) = parse_merkle_cell_data(cell_data)?;
+    Ok(LightClientInfo {
+        start_height,
+        latest_height,
+        merkle_root,
+    })
+}
+
+// Rebuild the eth smt from the ethereum chain, replaying the block hashes from the light client
+// cell's start_height to its latest_height. The rebuilt db replaces `eth_rocksdb_path` only if
+// its root matches the light client cell, and the old db is kept at `<eth_rocksdb_path>.bak`.
+// In dry run mode, the local db is only compared with the chain and nothing is written.
+pub async fn rebuild_eth_smt(
+    config_path: String,
+    network: Option<String>,
+    dry_run: bool,
+) -> Result<RebuildReport> {
+    let config_path = tilde(config_path.as_str()).into_owned();
+    let force_config = ForceConfig::new(config_path.as_str())?;
+    let light_client = get_light_client_info(&force_config, &network).await?;
+    let mut eth_client = QuorumWeb3Client::from_config(&force_config, &network)?;
+    info!(
+        "light client cell start_height: {}, latest_height: {}, merkle_root: {}",
+        light_client.start_height,
+        light_client.latest_height,
+        hex::encode(light_client.merkle_root)
+    );
+    if dry_run {
+        verify_eth_smt(
+            &mut eth_client,
+            force_config.eth_rocksdb_path.clone(),
+            light_client,
+        )
+        .await
+    } else {
+        rebuild_and_replace_eth_smt(
+            &mut eth_client,
+            force_config.eth_rocksdb_path.clone(),
+            light_client,
+        )
+        .await
+    }
+}
+
+async fn verify_eth_smt(
+    eth_client: &mut QuorumWeb3Client,
+    db_path: String,
+    light_client: LightClientInfo,
+) -> Result<RebuildReport> {
+    let db_dir = tilde(db_path.as_str()).into_owned();
+    if !Path::new(db_dir.as_str()).exists() {
+        info!("the eth rocksdb {} is not exist", db_dir);
+        return Ok(RebuildReport {
+            first_mismatch_height: Some(light_client.start_height),
+            light_client,
+            local_root: [0u8; 32],
+        });
+    }
+    let rocksdb_store: RocksDBStore<RocksDBValue> = RocksDBStore::open_readonly(db_path)?;
+    let local_root = match rocksdb_store.get_committed_root()? {
+        Some((root, _)) => root,
+        None => light_client.merkle_root,
+    };
+    let smt_tree = SMT::new(local_root.into(), rocksdb_store);
+
+    let mut first_mismatch_height = None;
+    for height in light_client.start_height..=light_client.latest_height {
+        let chain_block_hash = eth_client.get_block_hash(height).await?;
+        let db_block_hash = smt_tree
+            .get(&get_smt_key_for_height(height).into())
+            .map_err(|err| anyhow!(err))?;
+        if db_block_hash.to_h256().as_slice() != chain_block_hash.0.as_ref() {
+            info!(
+                "mismatch at height {}, chain block hash: {}, db block hash: {}",
+                height,
+                hex::encode(chain_block_hash.0),
+                hex::encode(db_block_hash.to_h256().as_slice())
+            );
+            first_mismatch_height = Some(height);
+            break;
+        }
+    }
+    Ok(RebuildReport {
+        light_client,
+        local_root,
+        first_mismatch_height,
+    })
+}
+
+async fn rebuild_and_replace_eth_smt(
+    eth_client: &mut QuorumWeb3Client,
+    db_path: String,
+    light_client: LightClientInfo,
+) -> Result<RebuildReport> {
+    let db_dir = tilde(db_path.as_str()).into_owned();
+    let rebuild_dir = format!("{}.rebuild", db_dir);
+    let backup_dir = format!("{}.bak", db_dir);
+    if Path::new(rebuild_dir.as_str()).exists() {
+        std::fs::remove_dir_all(rebuild_dir.as_str())
+            .map_err(|e| anyhow!("remove {} err: {:?}", rebuild_dir, e))?;
+    }
+
+    let local_root = {
+        let rocksdb_store = RocksDBStore::new(rebuild_dir.clone())?;
+        let mut smt_tree = SMT::new(sparse_merkle_tree::H256::zero(), rocksdb_store);
+        for height in light_client.start_height..=light_client.latest_height {
+            let chain_block_hash = eth_client.get_block_hash(height).await?;
+            smt_tree
+                .update(
+                    get_smt_key_for_height(height).into(),
+                    chain_block_hash.0.into(),
+                )
+                .map_err(|err| anyhow!(err))?;
+            if (height - light_client.start_height + 1) % COMMIT_INTERVAL == 0 {
+                smt_tree.store_mut().commit()?;
+                info!("rebuilt eth smt to height {}", height);
+            }
+        }
+        let local_root: [u8; 32] = (*smt_tree.root()).into();
+        smt_tree
+            .store_mut()
+            .commit_with_root(local_root, light_client.latest_height)?;
+        local_root
+    };
+
+    if local_root != light_client.merkle_root {
+        std::fs::remove_dir_all(rebuild_dir.as_str())
+            .map_err(|e| anyhow!("remove {} err: {:?}", rebuild_dir, e))?;
+        bail!(
+            "the rebuilt root {} is not in line with the light client cell root {}, the local db is not replaced. run with dry run to find the first mismatch height",
+            hex::encode(local_root),
+            hex::encode(light_client.merkle_root)
+        );
+    }
+
+    if Path::new(db_dir.as_str()).exists() {
+        if Path::new(backup_dir.as_str()).exists() {
+            std::fs::remove_dir_all(backup_dir.as_str())
+                .map_err(|e| anyhow!("remove {} err: {:?}", backup_dir, e))?;
+        }
+        std::fs::rename(db_dir.as_str(), backup_dir.as_str())
+            .map_err(|e| anyhow!("backup {} err: {:?}", db_dir, e))?;
+        info!("the old eth rocksdb is moved to {}", backup_dir);
+    }
+    std::fs::rename(rebuild_dir.as_str(), db_dir.as_str())
+        .map_err(|e| anyhow!("replace {} err: {:?}", db_dir, e))?;
+    Ok(RebuildReport {
+        light_client,
+        local_root,
+        first_mismatch_height: None,
+    })
+}
@@ -1,2 +1,4 @@
 pub mod ckb_relay;
 pub mod eth_relay;
+pub mod eth_smt;
+pub mod multisig_signer;
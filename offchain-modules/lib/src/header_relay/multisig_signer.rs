@@ -0,0 +1,826 @@
+use crate::dapp::server::errors::RpcError;
+use crate::header_relay::ckb_relay::{get_history_merkle_root, store_history_tx_roots, CKBRelayer};
+use crate::header_relay::eth_relay::{
+    get_light_client_multisig_config, open_or_create_rocksdb_store, reconcile_smt,
+    update_smt_window,
+};
+use crate::transfer::to_eth::{get_msg_hash, get_msg_signature};
+use crate::util::ckb_tx_generator::Generator;
+use crate::util::ckb_util::{
+    get_cell_script_hash, parse_cell, parse_merkle_cell_bridge_status_hash,
+    parse_merkle_cell_confirm, parse_merkle_cell_consumed_event_registry_hash,
+    parse_merkle_cell_data, parse_merkle_cell_token_allowlist_hash, rotate_merkle_cell_history,
+};
+use crate::util::config::{DeployedContracts, ForceConfig};
+use crate::util::eth_util::{convert_eth_address, QuorumWeb3Client, Web3Client};
+use crate::util::rocksdb;
+use actix_web::{get, post, web, App, HttpResponse, HttpServer, Responder};
+use anyhow::{anyhow, bail, Result};
+use ckb_sdk::{AddressPayload, HttpRpcClient, SECP256K1};
+use ckb_types::bytes::Bytes;
+use ckb_types::core::TransactionView;
+use ckb_types::packed::{self, CellOutput, OutPoint, Script};
+use ckb_types::prelude::{Entity, Reader, Unpack};
+use ckb_types::{H160, H256};
+use force_eth_types::generated::eth_header_cell::ETHHeaderCellMerkleDataReader;
+use force_sdk::tx_helper::{MultisigConfig, TxHelper};
+use force_sdk::util::{
+    get_live_cell, get_live_cell_with_cache, get_privkey_signer, recover_lock_arg,
+};
+use secp256k1::SecretKey;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use shellexpand::tilde;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub const ETH_SIGNATURE_SIZE: usize = 65;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignEthLightClientTxArgs {
+    pub tx: ckb_jsonrpc_types::Transaction,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LockArgSignature {
+    pub lock_arg: String,
+    pub signature: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignEthLightClientTxResult {
+    pub signatures: Vec<LockArgSignature>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignCkbHistoryTxRootArgs {
+    pub init_block_number: u64,
+    pub latest_block_number: u64,
+    pub history_tx_root: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignCkbHistoryTxRootResult {
+    pub signature: String,
+}
+
+// The signer daemon run by each multisig member. It re-derives the eth header window or the ckb
+// history tx root from its own rpc and local db, and only signs what it has verified.
+#[derive(Clone)]
+pub struct MultisigSignerState {
+    pub config_path: String,
+    pub network: Option<String>,
+    pub ckb_privkey: Option<SecretKey>,
+    pub eth_privkey: Option<SecretKey>,
+    pub eth_confirm: u64,
+    pub ckb_confirm: u64,
+    // the confirm the relayer initializes the light client cell with
+    pub mint_confirm: u64,
+    // the local eth smt and ckb history db are updated by one request at a time
+    pub db_mutex: Arc<Mutex<()>>,
+}
+
+impl MultisigSignerState {
+    pub async fn sign_eth_light_client_tx(
+        &self,
+        tx: TransactionView,
+    ) -> Result<Vec<LockArgSignature>> {
+        let privkey = self
+            .ckb_privkey
+            .ok_or_else(|| anyhow!("the signer has no ckb private key"))?;
+        let force_config = ForceConfig::new(self.config_path.as_str())?;
+        let deployed_contracts = force_config
+            .deployed_contracts
+            .as_ref()
+            .ok_or_else(|| anyhow!("contracts should be deployed"))?;
+        let cell_script = parse_cell(
+            deployed_contracts
+                .light_client_cell_script
+                .cell_script
+                .as_str(),
+        )?;
+        let multisig_config = get_light_client_multisig_config(deployed_contracts)?;
+        let multisig_lockscript = Script::from(&multisig_config.to_address_payload(None));
+        let mut rpc_client = HttpRpcClient::new(force_config.get_ckb_rpc_url(&self.network)?);
+
+        // the multisig lock should only unlock the light client cell
+        let mut input_cell = None;
+        for input in tx.inputs().into_iter() {
+            let (output, data) = get_live_cell(&mut rpc_client, input.previous_output(), true)
+                .map_err(|err| anyhow!(err))?;
+            if output.lock().as_slice() != multisig_lockscript.as_slice() {
+                continue;
+            }
+            if !is_light_client_cell(&output, &cell_script) || input_cell.is_some() {
+                bail!("the multisig lock should only unlock one light client cell");
+            }
+            input_cell = Some((output, data));
+        }
+        let (input_output, input_data) =
+            input_cell.ok_or_else(|| anyhow!("no light client cell in inputs"))?;
+        let output = tx
+            .output(0)
+            .ok_or_else(|| anyhow!("no light client cell in outputs"))?;
+        if !is_light_client_cell(&output, &cell_script)
+            || output.lock().as_slice() != multisig_lockscript.as_slice()
+        {
+            bail!("the first output should be the light client cell locked by the multisig");
+        }
+        // the capacity of the light client cell should not be taken out by the relayer
+        let input_capacity: u64 = input_output.capacity().unpack();
+        let output_capacity: u64 = output.capacity().unpack();
+        if output_capacity < input_capacity {
+            bail!(
+                "the light client cell capacity should not decrease, from {} to {}",
+                input_capacity,
+                output_capacity
+            );
+        }
+        if tx
+            .outputs()
+            .into_iter()
+            .skip(1)
+            .any(|output| is_light_client_cell(&output, &cell_script))
+        {
+            bail!("only one light client cell is allowed in outputs");
+        }
+        let output_data: Bytes = tx
+            .outputs_data()
+            .get(0)
+            .ok_or_else(|| anyhow!("no light client cell data in outputs"))?
+            .unpack();
+        let (new_start_height, new_latest_height, new_merkle_root) =
+            parse_merkle_cell_data(output_data.to_vec())?;
+        if new_start_height > new_latest_height {
+            bail!(
+                "start height {} is greater than latest height {}",
+                new_start_height,
+                new_latest_height
+            );
+        }
+
+        let mut quorum_client = QuorumWeb3Client::from_config(&force_config, &self.network)?;
+        let tip_header_number = quorum_client.get_tip_number().await?;
+        if new_latest_height + self.eth_confirm > tip_header_number {
+            bail!(
+                "latest height {} is not confirmed. tip: {}, confirm: {}",
+                new_latest_height,
+                tip_header_number,
+                self.eth_confirm
+            );
+        }
+
+        let history =
+            rotate_merkle_cell_history(input_data.to_vec(), &new_merkle_root, new_latest_height)?;
+        if ETHHeaderCellMerkleDataReader::new_unchecked(&output_data)
+            .history()
+            .as_slice()
+            != history.as_slice()
+        {
+            bail!("history should be rotated from input");
+        }
+
+        let _guard = self.db_mutex.lock().await;
+        let db_path = force_config.eth_rocksdb_path.clone();
+        let snapshot_limit = force_config.eth_smt_snapshot_limit;
+        let (start_height, mut smt_tree) = if input_data.is_empty() {
+            verify_light_client_init_data(deployed_contracts, self.mint_confirm, &output_data)?;
+            let (mut rocksdb_store, _) = open_or_create_rocksdb_store(db_path, snapshot_limit)?;
+            // record the empty smt as committed, so that it is reconciled with the light client
+            // cell after the init tx is committed.
+            if rocksdb_store.get_committed_root()?.is_none() {
+                rocksdb_store.commit_with_root([0u8; 32], 0)?;
+            }
+            (
+                new_start_height,
+                rocksdb::SMT::new(sparse_merkle_tree::H256::zero(), rocksdb_store),
+            )
+        } else {
+            let (start_height, latest_height, merkle_root) =
+                parse_merkle_cell_data(input_data.to_vec())?;
+            if new_start_height < start_height {
+                bail!(
+                    "start height should not go backwards, from {} to {}",
+                    start_height,
+                    new_start_height
+                );
+            }
+            let confirm = parse_merkle_cell_confirm(input_data.to_vec())?;
+            let new_confirm = parse_merkle_cell_confirm(output_data.to_vec())?;
+            if new_confirm != confirm {
+                bail!(
+                    "confirm should not be changed, from {} to {}",
+                    confirm,
+                    new_confirm
+                );
+            }
+            if parse_merkle_cell_bridge_status_hash(input_data.to_vec())?
+                != parse_merkle_cell_bridge_status_hash(output_data.to_vec())?
+            {
+                bail!("bridge status cell should not be changed");
+            }
+            if parse_merkle_cell_token_allowlist_hash(input_data.to_vec())?
+                != parse_merkle_cell_token_allowlist_hash(output_data.to_vec())?
+            {
+                bail!("token allowlist cell should not be changed");
+            }
+            if parse_merkle_cell_consumed_event_registry_hash(input_data.to_vec())?
+                != parse_merkle_cell_consumed_event_registry_hash(output_data.to_vec())?
+            {
+                bail!("consumed event registry cell should not be changed");
+            }
+            let mut smt_tree = reconcile_smt(
+                &mut quorum_client,
+                db_path,
+                snapshot_limit,
+                start_height,
+                latest_height,
+                merkle_root,
+            )
+            .await?;
+            for height in (new_latest_height + 1)..=latest_height {
+                smt_tree
+                    .update(
+                        rocksdb::get_smt_key_for_height(height).into(),
+                        [0u8; 32].into(),
+                    )
+                    .map_err(|err| anyhow!(err))?;
+            }
+            (start_height, smt_tree)
+        };
+        update_smt_window(
+            &mut quorum_client,
+            &mut smt_tree,
+            start_height,
+            new_start_height,
+            new_latest_height,
+        )
+        .await?;
+        let local_merkle_root: [u8; 32] = (*smt_tree.root()).into();
+        // the signed tx may never be committed, so the smt updates are not committed here. the
+        // local smt is reconciled with the light client cell of the next tx to sign, which is
+        // the one on chain.
+        smt_tree.store_mut().rollback();
+        if local_merkle_root != new_merkle_root {
+            bail!(
+                "merkle root mismatch. local: {}, tx: {}",
+                hex::encode(local_merkle_root),
+                hex::encode(new_merkle_root)
+            );
+        }
+
+        let signatures = get_multisig_signatures(tx, &mut rpc_client, &privkey, multisig_config)?;
+        log::info!(
+            "sign eth light client tx, start height: {}, latest height: {}",
+            new_start_height,
+            new_latest_height
+        );
+        Ok(signatures
+            .into_iter()
+            .map(|(lock_arg, signature)| LockArgSignature {
+                lock_arg: hex::encode(lock_arg),
+                signature: hex::encode(signature),
+            })
+            .collect())
+    }
+
+    pub async fn sign_ckb_history_tx_root(
+        &self,
+        args: SignCkbHistoryTxRootArgs,
+    ) -> Result<Vec<u8>> {
+        let privkey = self
+            .eth_privkey
+            .ok_or_else(|| anyhow!("the signer has no eth private key"))?;
+        let force_config = ForceConfig::new(self.config_path.as_str())?;
+        let deployed_contracts = force_config
+            .deployed_contracts
+            .as_ref()
+            .ok_or_else(|| anyhow!("contracts should be deployed"))?;
+        let history_tx_root_raw = hex::decode(args.history_tx_root.trim_start_matches("0x"))
+            .map_err(|e| anyhow!("invalid history tx root: {}", e))?;
+        if history_tx_root_raw.len() != 32 {
+            bail!(
+                "invalid history tx root length {}",
+                history_tx_root_raw.len()
+            );
+        }
+        let mut history_tx_root = [0u8; 32];
+        history_tx_root.copy_from_slice(history_tx_root_raw.as_slice());
+
+        let mut ckb_client = Generator::new(
+            force_config.get_ckb_rpc_url(&self.network)?,
+            force_config.get_ckb_indexer_url(&self.network)?,
+            Default::default(),
+        )
+        .map_err(|e| anyhow!("failed to crate generator: {}", e))?;
+        let ckb_init_height = CKBRelayer::get_ckb_contract_deloy_height(
+            &mut ckb_client,
+            deployed_contracts
+                .recipient_typescript
+                .outpoint
+                .tx_hash
+                .clone(),
+        )?;
+        if args.init_block_number != ckb_init_height {
+            bail!(
+                "init block number {} is not the ckb contract deploy height {}",
+                args.init_block_number,
+                ckb_init_height
+            );
+        }
+        let ckb_tip_height = ckb_client
+            .rpc_client
+            .get_tip_block_number()
+            .map_err(|e| anyhow!("failed to get ckb current height : {}", e))?;
+        if args.latest_block_number + self.ckb_confirm > ckb_tip_height {
+            bail!(
+                "latest block number {} is not confirmed. tip: {}, confirm: {}",
+                args.latest_block_number,
+                ckb_tip_height,
+                self.ckb_confirm
+            );
+        }
+
+        let local_history_tx_root = {
+            let _guard = self.db_mutex.lock().await;
+            store_history_tx_roots(
+                &mut ckb_client.rpc_client,
+                args.init_block_number,
+                args.latest_block_number,
+                force_config.ckb_rocksdb_path.clone(),
+                |_, _| {},
+            )?;
+            get_history_merkle_root(
+                args.init_block_number,
+                args.latest_block_number,
+                force_config.ckb_rocksdb_path.clone(),
+            )?
+        };
+        if local_history_tx_root != history_tx_root {
+            bail!(
+                "history tx root mismatch. local: {}, request: {}",
+                hex::encode(local_history_tx_root),
+                hex::encode(history_tx_root)
+            );
+        }
+
+        let mut web3_client = Web3Client::new(force_config.get_ethereum_rpc_url(&self.network)?);
+        let chain_id = web3_client.client().eth().chain_id().await?;
+        let contract_addr = convert_eth_address(&deployed_contracts.eth_ckb_chain_addr)?;
+        let msg_hash = get_msg_hash(
+            chain_id,
+            contract_addr,
+            args.init_block_number,
+            args.latest_block_number,
+            history_tx_root,
+        )?;
+        log::info!(
+            "sign ckb history tx root, init block number: {}, latest block number: {}",
+            args.init_block_number,
+            args.latest_block_number
+        );
+        get_msg_signature(&msg_hash, privkey)
+    }
+}
+
+fn is_light_client_cell(output: &CellOutput, cell_script: &Script) -> bool {
+    match output.type_().to_opt() {
+        Some(type_script) => type_script.as_slice() == cell_script.as_slice(),
+        None => false,
+    }
+}
+
+// sign the multisig lock group of the tx with privkey, the other lock groups are not signed.
+#[allow(clippy::mutable_key_type)]
+fn get_multisig_signatures(
+    tx: TransactionView,
+    rpc_client: &mut HttpRpcClient,
+    privkey: &SecretKey,
+    multisig_config: MultisigConfig,
+) -> Result<Vec<(Bytes, Bytes)>> {
+    let multisig_lock_arg = Bytes::from(multisig_config.hash160().as_bytes().to_vec());
+    let mut live_cell_cache: HashMap<(OutPoint, bool), (CellOutput, Bytes)> = Default::default();
+    let get_live_cell_fn = |out_point: OutPoint, with_data: bool| {
+        get_live_cell_with_cache(&mut live_cell_cache, rpc_client, out_point, with_data)
+            .map(|(output, _)| output)
+    };
+    let mut tx_helper = TxHelper::new(tx);
+    tx_helper.add_multisig_config(multisig_config);
+    let signatures = tx_helper
+        .sign_inputs(get_privkey_signer(*privkey), get_live_cell_fn, true)
+        .map_err(|err| anyhow!(err))?;
+    Ok(signatures
+        .into_iter()
+        .filter(|(lock_arg, _)| lock_arg == &multisig_lock_arg)
+        .collect())
+}
+
+#[post("/sign_eth_light_client_tx")]
+pub async fn sign_eth_light_client_tx(
+    data: web::Data<MultisigSignerState>,
+    args: web::Json<Value>,
+) -> actix_web::Result<HttpResponse, RpcError> {
+    let args: SignEthLightClientTxArgs = serde_json::from_value(args.into_inner())
+        .map_err(|e| RpcError::BadRequest(format!("invalid args: {}", e)))?;
+    let tx = packed::Transaction::from(args.tx).into_view();
+    let signatures = data
+        .sign_eth_light_client_tx(tx)
+        .await
+        .map_err(|e| RpcError::BadRequest(format!("refuse to sign eth light client tx: {}", e)))?;
+    Ok(HttpResponse::Ok().json(SignEthLightClientTxResult { signatures }))
+}
+
+#[post("/sign_ckb_history_tx_root")]
+pub async fn sign_ckb_history_tx_root(
+    data: web::Data<MultisigSignerState>,
+    args: web::Json<Value>,
+) -> actix_web::Result<HttpResponse, RpcError> {
+    let args: SignCkbHistoryTxRootArgs = serde_json::from_value(args.into_inner())
+        .map_err(|e| RpcError::BadRequest(format!("invalid args: {}", e)))?;
+    let signature = data
+        .sign_ckb_history_tx_root(args)
+        .await
+        .map_err(|e| RpcError::BadRequest(format!("refuse to sign ckb history tx root: {}", e)))?;
+    Ok(HttpResponse::Ok().json(SignCkbHistoryTxRootResult {
+        signature: hex::encode(signature),
+    }))
+}
+
+#[get("/")]
+pub async fn index() -> impl Responder {
+    "Nervos force bridge multisig signer API endpoint"
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn start(
+    config_path: String,
+    network: Option<String>,
+    ckb_private_key_path: Option<String>,
+    eth_private_key_path: Option<String>,
+    eth_confirm: u64,
+    ckb_confirm: u64,
+    mint_confirm: u64,
+    listen_url: String,
+) -> Result<()> {
+    let config_path = tilde(config_path.as_str()).into_owned();
+    let force_config = ForceConfig::new(config_path.as_str())?;
+    let ckb_privkey = ckb_private_key_path
+        .map(|path| crate::util::ckb_util::parse_privkey_path(&path, &force_config, &network))
+        .transpose()?;
+    let eth_privkey = eth_private_key_path
+        .map(|path| {
+            crate::util::eth_util::parse_private_key(&path, &force_config, &network)
+                .and_then(crate::util::eth_util::parse_secret_key)
+        })
+        .transpose()?;
+    if ckb_privkey.is_none() && eth_privkey.is_none() {
+        bail!("at least one of ckb private key and eth private key should be provided");
+    }
+    if let Some(privkey) = ckb_privkey.as_ref() {
+        let pubkey = secp256k1::PublicKey::from_secret_key(&SECP256K1, privkey);
+        log::info!(
+            "sign eth light client tx with ckb pubkey {}",
+            hex::encode(pubkey.serialize().as_ref())
+        );
+    }
+    let multisig_signer_state = MultisigSignerState {
+        config_path,
+        network,
+        ckb_privkey,
+        eth_privkey,
+        eth_confirm,
+        ckb_confirm,
+        mint_confirm,
+        db_mutex: Arc::new(Mutex::new(())),
+    };
+    let web_data = web::Data::new(multisig_signer_state);
+    let local = tokio::task::LocalSet::new();
+    let sys = actix_web::rt::System::run_in_tokio("multisig-signer", &local);
+    let _server_res = HttpServer::new(move || {
+        App::new()
+            .app_data(web_data.clone())
+            .service(sign_eth_light_client_tx)
+            .service(sign_ckb_history_tx_root)
+            .service(index)
+    })
+    .bind(&listen_url)?
+    .run()
+    .await?;
+    sys.await?;
+    Ok(())
+}
+
+async fn post_signer<A: Serialize, R: DeserializeOwned>(
+    url: &str,
+    path: &str,
+    args: &A,
+) -> Result<R> {
+    let res = reqwest::Client::new()
+        .post(format!("{}/{}", url.trim_end_matches('/'), path).as_str())
+        .json(args)
+        .send()
+        .await?;
+    if !res.status().is_success() {
+        bail!(
+            "signer {} returns {}: {}",
+            url,
+            res.status(),
+            res.text().await?
+        );
+    }
+    Ok(res.json::<R>().await?)
+}
+
+pub async fn request_eth_light_client_signatures(
+    url: &str,
+    tx: &TransactionView,
+) -> Result<Vec<(Bytes, Bytes)>> {
+    let args = SignEthLightClientTxArgs {
+        tx: tx.data().into(),
+    };
+    let res: SignEthLightClientTxResult =
+        post_signer(url, "sign_eth_light_client_tx", &args).await?;
+    res.signatures
+        .into_iter()
+        .map(|s| {
+            Ok((
+                Bytes::from(hex::decode(s.lock_arg)?),
+                Bytes::from(hex::decode(s.signature)?),
+            ))
+        })
+        .collect()
+}
+
+pub async fn request_ckb_history_tx_root_signature(
+    url: &str,
+    init_block_number: u64,
+    latest_block_number: u64,
+    history_tx_root: [u8; 32],
+) -> Result<Vec<u8>> {
+    let args = SignCkbHistoryTxRootArgs {
+        init_block_number,
+        latest_block_number,
+        history_tx_root: hex::encode(history_tx_root),
+    };
+    let res: SignCkbHistoryTxRootResult =
+        post_signer(url, "sign_ckb_history_tx_root", &args).await?;
+    let signature = hex::decode(res.signature)?;
+    if signature.len() != ETH_SIGNATURE_SIZE {
+        bail!(
+            "invalid signature length {} from signer {}",
+            signature.len(),
+            url
+        );
+    }
+    Ok(signature)
+}
+
+// Sign the light client tx with the local keys, then gather the multisig signatures from the
+// signer daemons until the threshold is met. Each signature is verified to be signed by a distinct
+// member of the multisig config, the ones from the first members are preferred to meet the
+// require_first_n.
+#[allow(clippy::mutable_key_type)]
+pub async fn sign_with_multisig_signers(
+    tx: TransactionView,
+    rpc_client: &mut HttpRpcClient,
+    privkeys: Vec<&SecretKey>,
+    multisig_config: MultisigConfig,
+    signer_urls: &[String],
+) -> Result<TransactionView> {
+    let multisig_hash160 = multisig_config.hash160();
+    let multisig_lock_arg = Bytes::from(multisig_hash160.as_bytes().to_vec());
+    let threshold = multisig_config.threshold() as usize;
+    let member_lock_args = get_multisig_member_lock_args(&multisig_config);
+    let mut live_cell_cache: HashMap<(OutPoint, bool), (CellOutput, Bytes)> = Default::default();
+    let mut tx_helper = TxHelper::new(tx.clone());
+    tx_helper.add_multisig_config(multisig_config);
+    let message = {
+        let get_live_cell_fn = |out_point: OutPoint, with_data: bool| {
+            get_live_cell_with_cache(&mut live_cell_cache, rpc_client, out_point, with_data)
+                .map(|(output, _)| output)
+        };
+        tx_helper
+            .multisig_message(get_live_cell_fn, true, &multisig_hash160)
+            .map_err(|err| anyhow!(err))?
+    };
+    // the verified signatures by the index of the member in the multisig config
+    let mut multisig_signatures: BTreeMap<usize, Bytes> = BTreeMap::new();
+    for key in privkeys {
+        let get_live_cell_fn = |out_point: OutPoint, with_data: bool| {
+            get_live_cell_with_cache(&mut live_cell_cache, rpc_client, out_point, with_data)
+                .map(|(output, _)| output)
+        };
+        for (lock_arg, signature) in tx_helper
+            .sign_inputs(get_privkey_signer(*key), get_live_cell_fn, true)
+            .map_err(|err| anyhow!(err))?
+        {
+            if lock_arg == multisig_lock_arg {
+                let index = verify_multisig_signature(&message, &signature, &member_lock_args)?;
+                multisig_signatures.insert(index, signature);
+            } else {
+                tx_helper
+                    .add_signature(lock_arg, signature)
+                    .map_err(|err| anyhow!(err))?;
+            }
+        }
+    }
+    for url in signer_urls {
+        if multisig_signatures.len() >= threshold {
+            break;
+        }
+        match request_eth_light_client_signatures(url, &tx).await {
+            Ok(signatures) => {
+                for (lock_arg, signature) in signatures {
+                    if lock_arg != multisig_lock_arg {
+                        continue;
+                    }
+                    match verify_multisig_signature(&message, &signature, &member_lock_args) {
+                        Ok(index) => {
+                            multisig_signatures.entry(index).or_insert(signature);
+                        }
+                        Err(e) => log::error!("invalid signature from signer {}: {}", url, e),
+                    }
+                }
+            }
+            Err(e) => log::error!("request signature from signer {} failed: {}", url, e),
+        }
+    }
+    if multisig_signatures.len() < threshold {
+        bail!(
+            "only {} signatures of the multisig members are collected, threshold: {}",
+            multisig_signatures.len(),
+            threshold
+        );
+    }
+    for signature in multisig_signatures
+        .into_iter()
+        .map(|(_, s)| s)
+        .take(threshold)
+    {
+        tx_helper
+            .add_signature(multisig_lock_arg.clone(), signature)
+            .map_err(|err| anyhow!(err))?;
+    }
+    let get_live_cell_fn = |out_point: OutPoint, with_data: bool| {
+        get_live_cell_with_cache(&mut live_cell_cache, rpc_client, out_point, with_data)
+            .map(|(output, _)| output)
+    };
+    tx_helper
+        .build_tx(get_live_cell_fn, true)
+        .map_err(|err| anyhow!(err))
+}
+
+// the sighash lock args of the multisig members, in the order of the multisig config.
+pub fn get_multisig_member_lock_args(multisig_config: &MultisigConfig) -> Vec<H160> {
+    multisig_config
+        .sighash_addresses()
+        .iter()
+        .map(|address| match address {
+            AddressPayload::Short { hash, .. } => hash.clone(),
+            _ => panic!("multisig config should only have short payload format addresses"),
+        })
+        .collect()
+}
+
+// the init tx pins the bridge status cell, the token allowlist cell and the consumed event
+// registry cell, which can never be changed afterwards, so they are checked against the deployed
+// ones instead of being taken from the relayer.
+fn verify_light_client_init_data(
+    deployed_contracts: &DeployedContracts,
+    mint_confirm: u64,
+    output_data: &[u8],
+) -> Result<()> {
+    let confirm = parse_merkle_cell_confirm(output_data.to_vec())?;
+    if confirm != mint_confirm {
+        bail!(
+            "confirm should be initialized to {}, got {}",
+            mint_confirm,
+            confirm
+        );
+    }
+    if parse_merkle_cell_bridge_status_hash(output_data.to_vec())?
+        != get_cell_script_hash(
+            deployed_contracts
+                .bridge_status_cell_script
+                .cell_script
+                .as_str(),
+        )?
+    {
+        bail!("bridge status cell should be pinned to the deployed one");
+    }
+    if parse_merkle_cell_token_allowlist_hash(output_data.to_vec())?
+        != get_cell_script_hash(
+            deployed_contracts
+                .token_allowlist_cell_script
+                .cell_script
+                .as_str(),
+        )?
+    {
+        bail!("token allowlist cell should be pinned to the deployed one");
+    }
+    if parse_merkle_cell_consumed_event_registry_hash(output_data.to_vec())?
+        != get_cell_script_hash(
+            deployed_contracts
+                .consumed_event_registry_cell_script
+                .cell_script
+                .as_str(),
+        )?
+    {
+        bail!("consumed event registry cell should be pinned to the deployed one");
+    }
+    Ok(())
+}
+
+// return the index of the multisig member which signed the message.
+fn verify_multisig_signature(
+    message: &H256,
+    signature: &[u8],
+    member_lock_args: &[H160],
+) -> Result<usize> {
+    let lock_arg = recover_lock_arg(message, signature).map_err(|err| anyhow!(err))?;
+    member_lock_args
+        .iter()
+        .position(|member| member == &lock_arg)
+        .ok_or_else(|| anyhow!("{:#x} is not a member of the multisig", lock_arg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_types::prelude::{Builder, Pack};
+    use force_eth_types::generated::basic;
+    use force_eth_types::generated::eth_header_cell::ETHHeaderCellMerkleData;
+
+    fn new_cell_script(arg: u8) -> String {
+        hex::encode(
+            Script::new_builder()
+                .args(Bytes::from(vec![arg]).pack())
+                .build()
+                .as_slice(),
+        )
+    }
+
+    fn new_init_data(
+        confirm: u64,
+        bridge_status_hash: [u8; 32],
+        token_allowlist_hash: [u8; 32],
+        consumed_event_registry_hash: [u8; 32],
+    ) -> Vec<u8> {
+        ETHHeaderCellMerkleData::new_builder()
+            .confirm(confirm.into())
+            .bridge_status_typescript_hash(
+                basic::Byte32::from_slice(&bridge_status_hash).expect("hash should be right"),
+            )
+            .token_allowlist_typescript_hash(
+                basic::Byte32::from_slice(&token_allowlist_hash).expect("hash should be right"),
+            )
+            .consumed_event_registry_typescript_hash(
+                basic::Byte32::from_slice(&consumed_event_registry_hash)
+                    .expect("hash should be right"),
+            )
+            .build()
+            .as_slice()
+            .to_vec()
+    }
+
+    #[test]
+    fn test_verify_light_client_init_data() {
+        let mut deployed_contracts = DeployedContracts::default();
+        deployed_contracts.bridge_status_cell_script.cell_script = new_cell_script(1);
+        deployed_contracts.token_allowlist_cell_script.cell_script = new_cell_script(2);
+        deployed_contracts
+            .consumed_event_registry_cell_script
+            .cell_script = new_cell_script(3);
+        let bridge_status_hash = get_cell_script_hash(&new_cell_script(1)).unwrap();
+        let token_allowlist_hash = get_cell_script_hash(&new_cell_script(2)).unwrap();
+        let consumed_event_registry_hash = get_cell_script_hash(&new_cell_script(3)).unwrap();
+        let wrong_hash = get_cell_script_hash(&new_cell_script(4)).unwrap();
+
+        let data = new_init_data(
+            15,
+            bridge_status_hash,
+            token_allowlist_hash,
+            consumed_event_registry_hash,
+        );
+        assert!(verify_light_client_init_data(&deployed_contracts, 15, &data).is_ok());
+        assert!(verify_light_client_init_data(&deployed_contracts, 10, &data).is_err());
+
+        let data = new_init_data(
+            15,
+            wrong_hash,
+            token_allowlist_hash,
+            consumed_event_registry_hash,
+        );
+        assert!(verify_light_client_init_data(&deployed_contracts, 15, &data).is_err());
+        let data = new_init_data(
+            15,
+            bridge_status_hash,
+            wrong_hash,
+            consumed_event_registry_hash,
+        );
+        assert!(verify_light_client_init_data(&deployed_contracts, 15, &data).is_err());
+        let data = new_init_data(15, bridge_status_hash, token_allowlist_hash, wrong_hash);
+        assert!(verify_light_client_init_data(&deployed_contracts, 15, &data).is_err());
+    }
+}
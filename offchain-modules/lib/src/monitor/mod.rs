@@ -1 +1,2 @@
 pub mod relay_monitor;
+pub mod supply_reconciler;
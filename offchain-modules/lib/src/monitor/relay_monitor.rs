@@ -1,4 +1,5 @@
 use crate::dapp::db::indexer::get_height_info;
+use crate::monitor::supply_reconciler::SupplyReconciler;
 use crate::util::ckb_tx_generator::Generator;
 use crate::util::ckb_util::{get_secret_key, parse_cell, parse_merkle_cell_data, parse_privkey};
 use crate::util::eth_util::{convert_eth_address, secret_key_address, Web3Client};
@@ -20,6 +21,7 @@ pub struct RelayMonitor {
     eth_alarm_number: u64,
     header_args: Option<HeaderMonitorArgs>,
     indexer_args: Option<IndexerMonitorArgs>,
+    supply_args: Option<SupplyMonitorArgs>,
     account_monitor_args: AccountMonitorArgs,
 }
 
@@ -98,6 +100,11 @@ pub struct IndexerMonitorArgs {
     db: MySqlPool,
 }
 
+pub struct SupplyMonitorArgs {
+    reconciler: SupplyReconciler,
+    supply_conservator: Vec<String>,
+}
+
 impl RelayMonitor {
     #[allow(clippy::too_many_arguments)]
     pub async fn new(
@@ -115,6 +122,8 @@ impl RelayMonitor {
         eth_indexer_conservator: Option<Vec<String>>,
         ckb_indexer_conservator: Option<Vec<String>>,
         db_path: Option<String>,
+        supply_reconciler: Option<SupplyReconciler>,
+        supply_conservator: Option<Vec<String>>,
         account_monitor_args: AccountMonitorArgs,
     ) -> Result<RelayMonitor> {
         let web3_client = Web3Client::new(eth_rpc_url);
@@ -123,6 +132,7 @@ impl RelayMonitor {
 
         let mut header_args: Option<HeaderMonitorArgs> = None;
         let mut indexer_args: Option<IndexerMonitorArgs> = None;
+        let mut supply_args: Option<SupplyMonitorArgs> = None;
         log::info!("mode : {}", mode);
         match mode.as_str() {
             "all" => {
@@ -181,6 +191,16 @@ impl RelayMonitor {
                     .await?,
                 });
             }
+            "supply" => {
+                supply_args = Option::from(SupplyMonitorArgs {
+                    reconciler: supply_reconciler.ok_or_else(|| {
+                        anyhow!("the supply reconciler can not be none in supply mode")
+                    })?,
+                    supply_conservator: supply_conservator.ok_or_else(|| {
+                        anyhow!("the supply_conservator can not be none in supply mode")
+                    })?,
+                });
+            }
             _ => bail!("the mode arg is wrong in constructor "),
         }
 
@@ -193,6 +213,7 @@ impl RelayMonitor {
             eth_alarm_number,
             header_args,
             indexer_args,
+            supply_args,
             account_monitor_args,
         });
     }
@@ -201,10 +222,11 @@ impl RelayMonitor {
     pub async fn start(&mut self) -> Result<()> {
         let mut msg = " ".to_string();
         let header_monitor_msg = self.get_header_monitor_info().await?;
+        let supply_monitor_msg = self.get_supply_monitor_info().await?;
         let account_monitor_msg = self.get_account_monitor_info().await?;
         msg = format!(
-            "{} {} %0A {} %0A",
-            msg, header_monitor_msg, account_monitor_msg
+            "{} {} {} %0A {} %0A",
+            msg, header_monitor_msg, supply_monitor_msg, account_monitor_msg
         );
         let res = reqwest::get(format!("{}{}", self.alarm_url, msg).as_str())
             .await?
@@ -256,11 +278,43 @@ impl RelayMonitor {
                     msg = format!("{} {} ", msg, index_msg);
                 }
             },
+            // the supply mode only reports the supply and the accounts
+            "supply" => {}
 
             _ => bail!("the mode arg is wrong in monitor"),
         }
         Ok(msg)
     }
+    pub async fn get_supply_monitor_info(&mut self) -> Result<String> {
+        let mut msg = " ".to_string();
+        let args = match self.supply_args.as_mut() {
+            None => return Ok(msg),
+            Some(args) => args,
+        };
+        for (supply, exceeded) in args.reconciler.reconcile().await?.iter() {
+            msg = format!(
+                "{} token {} locker balance : {:?}  %0A ckb sudt supply : {:?}  %0A ",
+                msg,
+                hex::encode(supply.token),
+                supply.locker_balance,
+                supply.ckb_supply
+            );
+            msg = format!(
+                "{} in flight locks : {:?}, in flight burns : {:?}  %0A supply drift is {:?} %0A ",
+                msg,
+                supply.in_flight_locks,
+                supply.in_flight_burns,
+                supply.drift()
+            );
+            if *exceeded {
+                for conservator in args.supply_conservator.iter() {
+                    msg = format!("{} @{} ", msg, conservator,);
+                }
+                msg = format!("{} %0A ", msg);
+            }
+        }
+        Ok(msg)
+    }
     pub async fn get_account_monitor_info(&mut self) -> Result<String> {
         let mut msg = " ".to_string();
         let eth_decimal: U256 = U256::from(10u128.pow(18));
@@ -0,0 +1,240 @@
+use crate::dapp::db::indexer::{get_in_flight_burn_amounts, get_in_flight_lock_amounts};
+use crate::util::ckb_tx_generator::Generator;
+use crate::util::ckb_util::get_sudt_type_script;
+use crate::util::config::DeployedContracts;
+use crate::util::eth_util::{convert_eth_address, Web3Client};
+use anyhow::{anyhow, bail, Result};
+use ethereum_types::{H160, U256};
+use force_sdk::cell_collector::collect_sudt_supply;
+use sqlx::MySqlPool;
+use std::convert::TryFrom;
+
+// the bridge invariant of a token: the balance of the locker equals the sudt supply on ckb plus
+// the amounts in flight, which are locked but not minted yet, or burned but not unlocked yet.
+#[derive(Debug, Clone)]
+pub struct TokenSupply {
+    pub token: H160,
+    pub locker_balance: u128,
+    pub ckb_supply: u128,
+    pub in_flight_locks: u128,
+    pub in_flight_burns: u128,
+}
+
+impl TokenSupply {
+    // the locker balance minus the expected one, it is positive if the locker holds more.
+    pub fn drift(&self) -> i128 {
+        let expected = self
+            .ckb_supply
+            .saturating_add(self.in_flight_locks)
+            .saturating_add(self.in_flight_burns);
+        if self.locker_balance >= expected {
+            i128::try_from(self.locker_balance - expected).unwrap_or(i128::MAX)
+        } else {
+            -i128::try_from(expected - self.locker_balance).unwrap_or(i128::MAX)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReconcileToken {
+    pub token: H160,
+    pub drift_threshold: u128,
+}
+
+// parse the tokens to reconcile, each in the format of <token_address>:<drift_threshold>. the
+// threshold is in the smallest unit of the token.
+pub fn parse_reconcile_tokens(tokens: &[String]) -> Result<Vec<ReconcileToken>> {
+    tokens
+        .iter()
+        .map(|token| {
+            let parts = token.split(':').collect::<Vec<_>>();
+            if parts.len() != 2 {
+                bail!(
+                    "invalid reconcile token {}, should be <token_address>:<drift_threshold>",
+                    token
+                );
+            }
+            Ok(ReconcileToken {
+                token: convert_eth_address(parts[0])?,
+                drift_threshold: parts[1]
+                    .parse::<u128>()
+                    .map_err(|e| anyhow!("invalid drift threshold of {}: {}", token, e))?,
+            })
+        })
+        .collect()
+}
+
+pub struct SupplyReconciler {
+    web3_client: Web3Client,
+    generator: Generator,
+    db: MySqlPool,
+    deployed_contracts: DeployedContracts,
+    locker_addr: H160,
+    tokens: Vec<ReconcileToken>,
+}
+
+impl SupplyReconciler {
+    pub async fn new(
+        ckb_rpc_url: String,
+        ckb_indexer_url: String,
+        eth_rpc_url: String,
+        db_path: String,
+        deployed_contracts: DeployedContracts,
+        tokens: Vec<ReconcileToken>,
+    ) -> Result<Self> {
+        if tokens.is_empty() {
+            bail!("the tokens to reconcile can not be empty");
+        }
+        let web3_client = Web3Client::new(eth_rpc_url);
+        let generator = Generator::new(ckb_rpc_url, ckb_indexer_url, deployed_contracts.clone())
+            .map_err(|e| anyhow!("failed to crate generator: {}", e))?;
+        let db = MySqlPool::connect(db_path.as_str()).await?;
+        let locker_addr = convert_eth_address(&deployed_contracts.eth_token_locker_addr)?;
+        Ok(SupplyReconciler {
+            web3_client,
+            generator,
+            db,
+            deployed_contracts,
+            locker_addr,
+            tokens,
+        })
+    }
+
+    // reconcile all the tokens, each with whether its drift exceeds the threshold.
+    pub async fn reconcile(&mut self) -> Result<Vec<(TokenSupply, bool)>> {
+        let mut reports = vec![];
+        for reconcile_token in self.tokens.clone() {
+            let supply = self.reconcile_token(reconcile_token.token).await?;
+            let exceeded = supply.drift().checked_abs().unwrap_or(i128::MAX) as u128
+                > reconcile_token.drift_threshold;
+            if exceeded {
+                log::warn!(
+                    "supply drift of token {:?} exceeds the threshold: {:?}",
+                    reconcile_token.token,
+                    supply
+                );
+            } else {
+                log::info!("supply of token {:?}: {:?}", reconcile_token.token, supply);
+            }
+            reports.push((supply, exceeded));
+        }
+        Ok(reports)
+    }
+
+    pub async fn reconcile_token(&mut self, token: H160) -> Result<TokenSupply> {
+        let locker_balance = self
+            .web3_client
+            .get_token_balance(token, self.locker_addr)
+            .await?;
+        if locker_balance > U256::from(u128::MAX) {
+            bail!(
+                "locker balance {} of token {:?} overflows",
+                locker_balance,
+                token
+            );
+        }
+        let sudt_typescript =
+            get_sudt_type_script(&self.deployed_contracts, token, self.locker_addr)?;
+        let ckb_supply =
+            collect_sudt_supply(&mut self.generator.indexer_client, sudt_typescript)
+                .map_err(|e| anyhow!("collect sudt supply of token {:?} error: {}", token, e))?;
+        let token_addr = hex::encode(token.as_bytes());
+        let in_flight_locks =
+            sum_hex_amounts(get_in_flight_lock_amounts(&self.db, &token_addr).await?)?;
+        let in_flight_burns =
+            sum_hex_amounts(get_in_flight_burn_amounts(&self.db, &token_addr).await?)?;
+        Ok(TokenSupply {
+            token,
+            locker_balance: locker_balance.as_u128(),
+            ckb_supply,
+            in_flight_locks,
+            in_flight_burns,
+        })
+    }
+}
+
+// the amounts are recorded in hex by the indexers
+fn sum_hex_amounts(amounts: Vec<String>) -> Result<u128> {
+    let mut total = 0u128;
+    for amount in amounts {
+        let value = u128::from_str_radix(amount.trim_start_matches("0x"), 16)
+            .map_err(|e| anyhow!("invalid amount {} in db: {}", amount, e))?;
+        total = total.saturating_add(value);
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_supply(
+        locker_balance: u128,
+        ckb_supply: u128,
+        in_flight_locks: u128,
+        in_flight_burns: u128,
+    ) -> TokenSupply {
+        TokenSupply {
+            token: H160::zero(),
+            locker_balance,
+            ckb_supply,
+            in_flight_locks,
+            in_flight_burns,
+        }
+    }
+
+    #[test]
+    fn test_drift() {
+        assert_eq!(new_supply(100, 70, 20, 10).drift(), 0);
+        assert_eq!(new_supply(105, 70, 20, 10).drift(), 5);
+        assert_eq!(new_supply(95, 70, 20, 10).drift(), -5);
+        // the expected balance saturates instead of overflowing
+        assert_eq!(new_supply(0, u128::MAX, 1, 0).drift(), -i128::MAX);
+        assert_eq!(new_supply(u128::MAX, 0, 0, 0).drift(), i128::MAX);
+    }
+
+    #[test]
+    fn test_sum_hex_amounts() {
+        assert_eq!(sum_hex_amounts(vec![]).unwrap(), 0);
+        assert_eq!(
+            sum_hex_amounts(vec!["0x10".to_string(), "ff".to_string()]).unwrap(),
+            0x10f
+        );
+        assert_eq!(
+            sum_hex_amounts(vec![format!("{:x}", u128::MAX), "1".to_string()]).unwrap(),
+            u128::MAX
+        );
+        assert!(sum_hex_amounts(vec!["0xzz".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_reconcile_tokens() {
+        let tokens = parse_reconcile_tokens(&[
+            "0x7Af456bf0065aADAB2E6BEc6DaD3731899550b84:1000".to_string(),
+            "0000000000000000000000000000000000000000:0".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(
+            hex::encode(tokens[0].token.as_bytes()),
+            "7af456bf0065aadab2e6bec6dad3731899550b84"
+        );
+        assert_eq!(tokens[0].drift_threshold, 1000);
+        assert_eq!(tokens[1].token, H160::zero());
+        assert_eq!(tokens[1].drift_threshold, 0);
+
+        assert!(parse_reconcile_tokens(
+            &["0x7Af456bf0065aADAB2E6BEc6DaD3731899550b84".to_string()]
+        )
+        .is_err());
+        assert!(parse_reconcile_tokens(&[
+            "0x7Af456bf0065aADAB2E6BEc6DaD3731899550b84:1:2".to_string()
+        ])
+        .is_err());
+        assert!(parse_reconcile_tokens(&[
+            "0x7Af456bf0065aADAB2E6BEc6DaD3731899550b84:-1".to_string()
+        ])
+        .is_err());
+        assert!(parse_reconcile_tokens(&["0xzz:1".to_string()]).is_err());
+    }
+}
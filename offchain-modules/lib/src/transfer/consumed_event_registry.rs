@@ -0,0 +1,296 @@
+use crate::util::ckb_util::{parse_cell, parse_consumed_event_registry_cell_data};
+use crate::util::config::ForceConfig;
+use crate::util::eth_util::keccak256;
+use crate::util::rocksdb::{RocksDBStore, RocksDBValue};
+use anyhow::{anyhow, bail, Result};
+use ckb_sdk::HttpRpcClient;
+use ckb_types::packed::{OutPoint, Script, Transaction, WitnessArgs};
+use ckb_types::prelude::{Entity, Unpack};
+use eth_spv_lib::eth_types::Receipt;
+use force_eth_types::consumed_event_registry::{consumed_event_key, is_registry_mode};
+use force_eth_types::eth_lock_event::ETHLockEvent;
+use force_eth_types::generated::eth_consumed_event_registry_cell::ETHConsumedEventRegistryWitness;
+use force_eth_types::generated::witness::{ETHSPVProof, MintTokenBatchWitness, MintTokenWitness};
+use force_sdk::cell_collector::get_live_cell_by_typescript;
+use force_sdk::indexer::IndexerRpcClient;
+use log::info;
+use shellexpand::tilde;
+use std::path::Path;
+
+#[derive(Debug, Clone, Default)]
+pub struct RegistryRebuildReport {
+    // the root in the consumed event registry cell
+    pub cell_root: [u8; 32],
+    // the root of the rebuilt smt, or of the local smt in dry run mode
+    pub local_root: [u8; 32],
+    // the count of the consumed lock events, only for rebuild mode
+    pub consumed_events: usize,
+}
+
+impl RegistryRebuildReport {
+    pub fn is_consistent(&self) -> bool {
+        self.local_root == self.cell_root
+    }
+}
+
+// the mint tx of the lock events in registry mode, and the root it puts in the registry cell.
+struct RegistryMintTx {
+    keys: Vec<[u8; 32]>,
+    new_root: [u8; 32],
+}
+
+// Rebuild the consumed event registry smt from the ckb chain, replaying the lock events consumed
+// by the mint txs from the init tx of the registry cell to its live cell. The rebuilt db replaces
+// `consumed_event_rocksdb_path` only if its root matches the registry cell, and the old db is kept
+// at `<consumed_event_rocksdb_path>.bak`. In dry run mode, the local db is only compared with the
+// registry cell and nothing is written.
+pub async fn rebuild_consumed_event_registry(
+    config_path: String,
+    network: Option<String>,
+    dry_run: bool,
+) -> Result<RegistryRebuildReport> {
+    let config_path = tilde(config_path.as_str()).into_owned();
+    let force_config = ForceConfig::new(config_path.as_str())?;
+    let deployed_contracts = force_config
+        .deployed_contracts
+        .as_ref()
+        .ok_or_else(|| anyhow!("contracts should be deployed"))?;
+    let cell_script = parse_cell(
+        deployed_contracts
+            .consumed_event_registry_cell_script
+            .cell_script
+            .as_str(),
+    )?;
+    let mut rpc_client = HttpRpcClient::new(force_config.get_ckb_rpc_url(&network)?);
+    let mut indexer_client = IndexerRpcClient::new(force_config.get_ckb_indexer_url(&network)?);
+    let cell = get_live_cell_by_typescript(&mut indexer_client, cell_script.clone())
+        .map_err(|err| anyhow!(err))?
+        .ok_or_else(|| anyhow!("the consumed event registry cell is not exist"))?;
+    let cell_root = parse_consumed_event_registry_cell_data(cell.output_data.as_bytes().to_vec())?;
+    info!(
+        "consumed event registry cell root: {}",
+        hex::encode(cell_root)
+    );
+
+    let db_path = force_config.consumed_event_rocksdb_path.clone();
+    if dry_run {
+        let db_dir = tilde(db_path.as_str()).into_owned();
+        let local_root = if Path::new(db_dir.as_str()).exists() {
+            let rocksdb_store: RocksDBStore<RocksDBValue> = RocksDBStore::open_readonly(db_path)?;
+            rocksdb_store
+                .get_committed_root()?
+                .map(|(root, _)| root)
+                .unwrap_or([0u8; 32])
+        } else {
+            info!("the consumed event rocksdb {} is not exist", db_dir);
+            [0u8; 32]
+        };
+        return Ok(RegistryRebuildReport {
+            cell_root,
+            local_root,
+            consumed_events: 0,
+        });
+    }
+
+    let mint_txs = get_registry_mint_txs(
+        &mut rpc_client,
+        &cell_script,
+        OutPoint::from(cell.out_point),
+    )?;
+    rebuild_and_replace_registry(db_path, cell_root, mint_txs)
+}
+
+// walk back the registry cell from the live one to the init tx, return the mint txs in the order
+// they are committed.
+fn get_registry_mint_txs(
+    rpc_client: &mut HttpRpcClient,
+    cell_script: &Script,
+    live_out_point: OutPoint,
+) -> Result<Vec<RegistryMintTx>> {
+    let mut mint_txs = vec![];
+    let mut tx = get_transaction(rpc_client, &live_out_point)?;
+    let mut new_root = get_registry_root(&tx, live_out_point.index().unpack())?;
+    loop {
+        let mut registry_input = None;
+        for (index, input) in tx.raw().inputs().into_iter().enumerate() {
+            let previous_output = input.previous_output();
+            let previous_tx = get_transaction(rpc_client, &previous_output)?;
+            let output_index: u32 = previous_output.index().unpack();
+            let output = previous_tx
+                .raw()
+                .outputs()
+                .get(output_index as usize)
+                .ok_or_else(|| anyhow!("the input {} of tx is not exist", index))?;
+            let is_registry_cell = output
+                .type_()
+                .to_opt()
+                .map(|script| script.as_slice() == cell_script.as_slice())
+                .unwrap_or(false);
+            if is_registry_cell {
+                registry_input = Some((index, previous_tx, output_index));
+                break;
+            }
+        }
+        // the init tx of the registry cell has no registry cell input
+        let (input_index, previous_tx, output_index) = match registry_input {
+            Some(registry_input) => registry_input,
+            None => break,
+        };
+        let keys = get_consumed_event_keys(&tx, input_index)?;
+        info!(
+            "mint tx {} consumes {} lock events",
+            hex::encode(tx.calc_tx_hash().as_slice()),
+            keys.len()
+        );
+        mint_txs.push(RegistryMintTx { keys, new_root });
+        new_root = get_registry_root(&previous_tx, output_index)?;
+        tx = previous_tx;
+    }
+    if new_root != [0u8; 32] {
+        bail!(
+            "the registry cell is initialized with the root {}, not the empty one",
+            hex::encode(new_root)
+        );
+    }
+    mint_txs.reverse();
+    Ok(mint_txs)
+}
+
+fn get_transaction(rpc_client: &mut HttpRpcClient, out_point: &OutPoint) -> Result<Transaction> {
+    let tx_hash = out_point.tx_hash().unpack();
+    let tx = rpc_client
+        .get_transaction(tx_hash)
+        .map_err(|e| anyhow!("failed to get ckb tx: {}", e))?
+        .ok_or_else(|| anyhow!("tx {} not exists", out_point.tx_hash()))?;
+    Ok(tx.transaction.inner.into())
+}
+
+fn get_registry_root(tx: &Transaction, output_index: u32) -> Result<[u8; 32]> {
+    let data = tx
+        .raw()
+        .outputs_data()
+        .get(output_index as usize)
+        .ok_or_else(|| anyhow!("the registry cell output {} is not exist", output_index))?;
+    parse_consumed_event_registry_cell_data(data.raw_data().to_vec())
+}
+
+// the keys of the lock events in registry mode minted by the tx, the i-th transaction proof in
+// the registry cell witness is the lock tx of the i-th lock event in registry mode.
+fn get_consumed_event_keys(tx: &Transaction, registry_input_index: usize) -> Result<Vec<[u8; 32]>> {
+    let registry_witness =
+        ETHConsumedEventRegistryWitness::from_slice(&get_witness_lock(tx, registry_input_index)?)
+            .map_err(|e| anyhow!("invalid consumed event registry witness: {}", e))?;
+    let transaction_proofs = registry_witness.transaction_proofs();
+
+    let mint_witness = get_witness_lock(tx, 0)?;
+    let spv_proofs = if let Ok(witness) = MintTokenWitness::from_slice(&mint_witness) {
+        vec![witness.spv_proof().raw_data()]
+    } else {
+        let witness = MintTokenBatchWitness::from_slice(&mint_witness)
+            .map_err(|e| anyhow!("invalid mint witness: {}", e))?;
+        witness
+            .spv_proofs()
+            .into_iter()
+            .map(|spv_proof| spv_proof.raw_data())
+            .collect()
+    };
+
+    let mut keys = vec![];
+    for spv_proof in spv_proofs {
+        let spv_proof = ETHSPVProof::from_slice(&spv_proof)
+            .map_err(|e| anyhow!("invalid eth spv proof: {}", e))?;
+        let mut log_index = [0u8; 8];
+        log_index.copy_from_slice(&spv_proof.log_index().raw_data());
+        let log_index = u64::from_le_bytes(log_index);
+        let receipt = Receipt::decode_envelope(&spv_proof.receipt_data().raw_data())
+            .map_err(|e| anyhow!("decode receipt err: {:?}", e))?;
+        let log_entry = receipt
+            .logs
+            .get(log_index as usize)
+            .ok_or_else(|| anyhow!("log index {} out of the receipt logs", log_index))?;
+        let lock_event = ETHLockEvent::parse_from_event_data(log_entry);
+        if !is_registry_mode(lock_event.replay_resist_outpoint.as_ref()) {
+            continue;
+        }
+        let transaction_proof = transaction_proofs
+            .get(keys.len())
+            .ok_or_else(|| anyhow!("transaction proof of lock event not found"))?;
+        let tx_hash = keccak256(&transaction_proof.transaction_data().raw_data());
+        keys.push(consumed_event_key(&tx_hash, log_index));
+    }
+    Ok(keys)
+}
+
+fn get_witness_lock(tx: &Transaction, index: usize) -> Result<Vec<u8>> {
+    let witness = tx
+        .witnesses()
+        .get(index)
+        .ok_or_else(|| anyhow!("the witness {} of tx is not exist", index))?;
+    let witness_args = WitnessArgs::from_slice(&witness.raw_data())
+        .map_err(|e| anyhow!("invalid witness args: {}", e))?;
+    let lock = witness_args
+        .lock()
+        .to_opt()
+        .ok_or_else(|| anyhow!("the witness {} lock is none", index))?;
+    Ok(lock.raw_data().to_vec())
+}
+
+fn rebuild_and_replace_registry(
+    db_path: String,
+    cell_root: [u8; 32],
+    mint_txs: Vec<RegistryMintTx>,
+) -> Result<RegistryRebuildReport> {
+    let db_dir = tilde(db_path.as_str()).into_owned();
+    let rebuild_dir = format!("{}.rebuild", db_dir);
+    let backup_dir = format!("{}.bak", db_dir);
+    if Path::new(rebuild_dir.as_str()).exists() {
+        std::fs::remove_dir_all(rebuild_dir.as_str())
+            .map_err(|e| anyhow!("remove {} err: {:?}", rebuild_dir, e))?;
+    }
+
+    let mut consumed_events = 0;
+    let mut local_root = [0u8; 32];
+    {
+        let rocksdb_store: RocksDBStore<RocksDBValue> = RocksDBStore::new(rebuild_dir.clone())?;
+        for mint_tx in mint_txs.iter() {
+            local_root = rocksdb_store.commit_consumed_keys(local_root, &mint_tx.keys)?;
+            if local_root != mint_tx.new_root {
+                std::fs::remove_dir_all(rebuild_dir.as_str())
+                    .map_err(|e| anyhow!("remove {} err: {:?}", rebuild_dir, e))?;
+                bail!(
+                    "the rebuilt root {} is not the root {} of the mint tx, the local db is not replaced",
+                    hex::encode(local_root),
+                    hex::encode(mint_tx.new_root)
+                );
+            }
+            consumed_events += mint_tx.keys.len();
+        }
+    }
+
+    if local_root != cell_root {
+        std::fs::remove_dir_all(rebuild_dir.as_str())
+            .map_err(|e| anyhow!("remove {} err: {:?}", rebuild_dir, e))?;
+        bail!(
+            "the rebuilt root {} is not in line with the registry cell root {}, the local db is not replaced",
+            hex::encode(local_root),
+            hex::encode(cell_root)
+        );
+    }
+
+    if Path::new(db_dir.as_str()).exists() {
+        if Path::new(backup_dir.as_str()).exists() {
+            std::fs::remove_dir_all(backup_dir.as_str())
+                .map_err(|e| anyhow!("remove {} err: {:?}", backup_dir, e))?;
+        }
+        std::fs::rename(db_dir.as_str(), backup_dir.as_str())
+            .map_err(|e| anyhow!("backup {} err: {:?}", db_dir, e))?;
+        info!("the old consumed event rocksdb is moved to {}", backup_dir);
+    }
+    std::fs::rename(rebuild_dir.as_str(), db_dir.as_str())
+        .map_err(|e| anyhow!("replace {} err: {:?}", db_dir, e))?;
+    Ok(RegistryRebuildReport {
+        cell_root,
+        local_root,
+        consumed_events,
+    })
+}
@@ -1,2 +1,3 @@
+pub mod consumed_event_registry;
 pub mod to_ckb;
 pub mod to_eth;
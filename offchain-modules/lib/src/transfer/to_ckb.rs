@@ -1,16 +1,19 @@
+use crate::header_relay::eth_relay::get_light_client_multisig_config;
+use crate::header_relay::multisig_signer::sign_with_multisig_signers;
 use crate::util::ckb_tx_generator::Generator;
 use crate::util::ckb_util::{
-    build_lockscript_from_address, clear_0x, create_bridge_lockscript, parse_privkey,
-    parse_privkey_path, ETHSPVProofJson,
+    build_lockscript_from_address, clear_0x, create_bridge_lockscript, create_registry_lockscript,
+    is_token_allowed, parse_privkey, parse_privkey_path, ETHSPVProofJson,
 };
 use crate::util::config::{
     CellScript, DeployedContracts, ForceConfig, MultisigConf, OutpointConf, ScriptConf,
 };
+use crate::util::eth_trie::get_trie_proof;
 use crate::util::eth_util::{
     build_lock_eth_payload, build_lock_token_payload, convert_eth_address, convert_hex_to_h256,
-    parse_private_key, Web3Client,
+    keccak256, parse_private_key, EthFeeOptions, Web3Client,
 };
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use ckb_hash::{blake2b_256, new_blake2b};
 use ckb_sdk::{Address, AddressPayload, GenesisInfo, HttpRpcClient, HumanCapacity, SECP256K1};
 use ckb_types::bytes::Bytes;
@@ -18,7 +21,10 @@ use ckb_types::core::{BlockView, ScriptHashType, TransactionView};
 use ckb_types::packed::{Byte32, CellOutput, OutPoint, Script, ScriptOpt};
 use ckb_types::prelude::{Builder, Entity, Pack};
 use cmd_lib::run_fun;
+use eth_spv_lib::eth_types::{LogEntry, Receipt};
 use ethabi::{Function, Param, ParamType, Token};
+use force_eth_types::consumed_event_registry::{is_registry_mode, REGISTRY_MODE_OUTPOINT};
+use force_eth_types::eth_lock_event::ETHLockEvent;
 use force_eth_types::generated::basic;
 use force_eth_types::generated::basic::ETHAddress;
 use force_eth_types::generated::eth_bridge_lock_cell::ETHBridgeLockArgs;
@@ -29,23 +35,26 @@ use force_sdk::indexer::IndexerRpcClient;
 use force_sdk::tx_helper::{sign, MultisigConfig, TxHelper};
 use force_sdk::util::{ensure_indexer_sync, send_tx_sync, send_tx_sync_with_response};
 use log::info;
+use rlp::Rlp;
 use rusty_receipt_proof_maker::generate_eth_proof;
 use rusty_receipt_proof_maker::types::EthSpvProof;
 use secp256k1::SecretKey;
 use serde_json::Value;
 use shellexpand::tilde;
-use std::convert::TryFrom;
+use std::convert::{TryFrom, TryInto};
 use std::str::FromStr;
 use web3::types::{H160, H256, U256};
 
 pub const MAX_RETRY_TIMES: u64 = 10;
+pub const LOCKED_EVENT_SIGNATURE: &str =
+    "Locked(address,address,uint256,uint256,bytes,bytes,bytes)";
 
 pub async fn approve(
     config_path: String,
     network: Option<String>,
     key_path: String,
     erc20_addr: String,
-    gas_price: u64,
+    fee_options: EthFeeOptions,
     wait: bool,
 ) -> Result<H256> {
     let force_config = ForceConfig::new(config_path.as_str())?;
@@ -87,7 +96,7 @@ pub async fn approve(
             eth_address,
             eth_private_key,
             input_data,
-            U256::from(gas_price),
+            &fee_options,
             U256::zero(),
             U256::zero(),
             wait,
@@ -96,6 +105,18 @@ pub async fn approve(
     Ok(res)
 }
 
+// the replay resist outpoint of the lock, which is the all-zero outpoint in registry mode.
+pub fn get_replay_resist_outpoint(
+    replay_resist_outpoint: Option<String>,
+    registry_mode: bool,
+) -> Result<String> {
+    match (replay_resist_outpoint, registry_mode) {
+        (Some(outpoint), false) => Ok(outpoint),
+        (None, true) => Ok(hex::encode(REGISTRY_MODE_OUTPOINT)),
+        _ => bail!("either the replay resist outpoint or the registry mode should be set"),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn lock_token(
     config_path: String,
@@ -107,7 +128,7 @@ pub async fn lock_token(
     bridge_fee: u128,
     sudt_extra_data: String,
     replay_resist_outpoint: String,
-    gas_price: u64,
+    fee_options: EthFeeOptions,
     wait: bool,
 ) -> Result<H256> {
     let force_config = ForceConfig::new(config_path.as_str())?;
@@ -136,7 +157,7 @@ pub async fn lock_token(
             eth_address,
             parse_private_key(key_path.as_str(), &force_config, &network)?,
             input_data,
-            U256::from(gas_price),
+            &fee_options,
             U256::zero(),
             U256::zero(),
             wait,
@@ -155,7 +176,7 @@ pub async fn lock_eth(
     bridge_fee: u128,
     sudt_extra_data: String,
     replay_resist_outpoint: String,
-    gas_price: u64,
+    fee_options: EthFeeOptions,
     wait: bool,
 ) -> Result<H256> {
     let force_config = ForceConfig::new(config_path.as_str())?;
@@ -179,7 +200,7 @@ pub async fn lock_eth(
             eth_address,
             parse_private_key(key_path.as_str(), &force_config, &network)?,
             input_data,
-            U256::from(gas_price),
+            &fee_options,
             U256::from(amount),
             U256::zero(),
             wait,
@@ -193,35 +214,46 @@ pub async fn get_header_rlp(url: String, hash: H256) -> Result<String> {
     Ok(rpc_client.get_header_rlp(hash.into()).await?)
 }
 
-pub async fn generate_eth_spv_proof_json(
+pub fn generate_eth_proof_with_retry(
     hash: String,
     ethereum_rpc_url: String,
     eth_token_locker_addr: String,
-) -> Result<ETHSPVProofJson> {
-    let eth_spv_proof_retry = |max_retry_times| {
-        for retry in 0..max_retry_times {
-            let ret = generate_eth_proof(
-                hash.clone(),
-                ethereum_rpc_url.clone(),
-                String::from(clear_0x(eth_token_locker_addr.as_str())),
-            );
-            match ret {
-                Ok(proof) => return Ok(proof),
-                Err(e) => {
-                    info!(
-                        "get eth receipt proof failed, retried {} times, err: {}",
-                        retry, e
-                    );
-                }
+    max_retry_times: u64,
+) -> Result<EthSpvProof> {
+    for retry in 0..max_retry_times {
+        let ret = generate_eth_proof(
+            hash.clone(),
+            ethereum_rpc_url.clone(),
+            String::from(clear_0x(eth_token_locker_addr.as_str())),
+        );
+        match ret {
+            Ok(proof) => return Ok(proof),
+            Err(e) => {
+                info!(
+                    "get eth receipt proof failed, retried {} times, err: {}",
+                    retry, e
+                );
             }
         }
-        Err(anyhow!(
-            "Failed to generate eth proof for lock tx:{}, after retry {} times",
-            hash.as_str(),
-            max_retry_times
-        ))
-    };
-    let eth_spv_proof = eth_spv_proof_retry(3)?;
+    }
+    Err(anyhow!(
+        "Failed to generate eth proof for lock tx:{}, after retry {} times",
+        hash.as_str(),
+        max_retry_times
+    ))
+}
+
+pub async fn generate_eth_spv_proof_json(
+    hash: String,
+    ethereum_rpc_url: String,
+    eth_token_locker_addr: String,
+) -> Result<ETHSPVProofJson> {
+    let eth_spv_proof = generate_eth_proof_with_retry(
+        hash,
+        ethereum_rpc_url.clone(),
+        eth_token_locker_addr.clone(),
+        3,
+    )?;
     to_eth_spv_proof_json(
         // hash.clone(),
         eth_spv_proof,
@@ -231,6 +263,119 @@ pub async fn generate_eth_spv_proof_json(
     .await
 }
 
+// The proofs of all the Locked events in the lock tx, a tx which locks through a router contract
+// may emit several of them. they are ordered by the log index in the receipt.
+pub async fn generate_eth_spv_proofs_json(
+    hash: String,
+    ethereum_rpc_url: String,
+    eth_token_locker_addr: String,
+) -> Result<Vec<ETHSPVProofJson>> {
+    let log_indexes =
+        get_locked_log_indexes(&hash, ethereum_rpc_url.clone(), &eth_token_locker_addr).await?;
+    if log_indexes.is_empty() {
+        return Ok(vec![]);
+    }
+    let eth_spv_proof = generate_eth_proof_with_retry(
+        hash,
+        ethereum_rpc_url.clone(),
+        eth_token_locker_addr.clone(),
+        3,
+    )?;
+    let mut proofs = vec![];
+    for log_index in log_indexes {
+        proofs.push(
+            to_eth_spv_proof_json_of_log(
+                eth_spv_proof.clone(),
+                log_index,
+                eth_token_locker_addr.clone(),
+                ethereum_rpc_url.clone(),
+            )
+            .await?,
+        );
+    }
+    Ok(proofs)
+}
+
+// The proof of the Locked event at `log_index` of the lock tx receipt.
+pub async fn generate_eth_spv_proof_json_of_log(
+    hash: String,
+    log_index: u64,
+    ethereum_rpc_url: String,
+    eth_token_locker_addr: String,
+) -> Result<ETHSPVProofJson> {
+    let eth_spv_proof = generate_eth_proof_with_retry(
+        hash,
+        ethereum_rpc_url.clone(),
+        eth_token_locker_addr.clone(),
+        3,
+    )?;
+    to_eth_spv_proof_json_of_log(
+        eth_spv_proof,
+        log_index,
+        eth_token_locker_addr,
+        ethereum_rpc_url,
+    )
+    .await
+}
+
+async fn to_eth_spv_proof_json_of_log(
+    mut eth_spv_proof: EthSpvProof,
+    log_index: u64,
+    eth_token_locker_addr: String,
+    ethereum_rpc_url: String,
+) -> Result<ETHSPVProofJson> {
+    eth_spv_proof.log_index = log_index
+        .try_into()
+        .map_err(|e| anyhow!("invalid log index {}: {:?}", log_index, e))?;
+    to_eth_spv_proof_json(eth_spv_proof, eth_token_locker_addr, ethereum_rpc_url).await
+}
+
+// The indexes in the receipt of the Locked events emitted by the token locker.
+pub async fn get_locked_log_indexes(
+    hash: &str,
+    ethereum_rpc_url: String,
+    eth_token_locker_addr: &str,
+) -> Result<Vec<u64>> {
+    let eth_token_locker_addr = convert_eth_address(eth_token_locker_addr)?;
+    let locked_topic = H256::from(keccak256(LOCKED_EVENT_SIGNATURE.as_bytes()));
+    let mut rpc_client = Web3Client::new(ethereum_rpc_url);
+    let receipt = rpc_client
+        .get_receipt(convert_hex_to_h256(hash)?)
+        .await?
+        .ok_or_else(|| anyhow!("the lock tx {} is not exist", hash))?;
+    Ok(receipt
+        .logs
+        .iter()
+        .enumerate()
+        .filter(|(_, log)| {
+            log.address == eth_token_locker_addr && log.topics.first() == Some(&locked_topic)
+        })
+        .map(|(index, _)| index as u64)
+        .collect())
+}
+
+// Parse the Locked event from the rlp of the log entry, which must be emitted by the token locker.
+pub fn parse_locked_event(
+    log_entry_data: &str,
+    eth_token_locker_addr: &str,
+) -> Result<ETHLockEvent> {
+    let log_entry_raw = hex::decode(clear_0x(log_entry_data))?;
+    let log_entry: LogEntry = rlp::decode(log_entry_raw.as_slice())
+        .map_err(|e| anyhow!("decode log entry err: {:?}", e))?;
+    let eth_token_locker_addr = convert_eth_address(eth_token_locker_addr)?;
+    if (log_entry.address.0).0 != eth_token_locker_addr.0 {
+        bail!(
+            "the log is emitted by {}, not the token locker",
+            hex::encode((log_entry.address.0).0)
+        );
+    }
+    let locked_topic = keccak256(LOCKED_EVENT_SIGNATURE.as_bytes());
+    if log_entry.topics.len() != 3 || (log_entry.topics[0].0).0 != locked_topic {
+        bail!("the log is not a Locked event");
+    }
+    Ok(ETHLockEvent::parse_from_event_data(&log_entry))
+}
+
 pub async fn to_eth_spv_proof_json(
     // hash: String,
     eth_spv_proof: EthSpvProof,
@@ -258,24 +403,111 @@ pub async fn to_eth_spv_proof_json(
     for item in proof_json["proof"].as_array().unwrap() {
         proof_vec.push(item.as_str().unwrap().to_owned());
     }
+    let log_index = u64::try_from(log_index).unwrap();
+    let receipt_data = get_receipt_data_from_proof(&proof_vec, log_index)?;
+    if clear_0x(proof_json["receipt_data"].as_str().unwrap()) != receipt_data {
+        info!(
+            "the receipt data is not the one in the proof, use the proof's: {}",
+            receipt_data
+        );
+    }
+    // the event fields are parsed from the proven log, the tx may have several Locked events.
+    let log_entry_data = String::from(proof_json["log_data"].as_str().unwrap());
+    let event = parse_locked_event(&log_entry_data, &eth_token_locker_addr)?;
+    if event.locked_amount.bits() > 128 || event.bridge_fee.bits() > 128 {
+        bail!(
+            "the locked amount {} or bridge fee {} overflows u128",
+            event.locked_amount,
+            event.bridge_fee
+        );
+    }
+    let (transaction_data, transaction_proof) = if is_registry_mode(&event.replay_resist_outpoint) {
+        get_transaction_proof(
+            ethereum_rpc_url,
+            eth_spv_proof.block_hash.as_str(),
+            eth_spv_proof.receipt_index,
+        )
+        .await?
+    } else {
+        (String::new(), vec![])
+    };
     Ok(ETHSPVProofJson {
-        log_index: u64::try_from(log_index).unwrap(),
-        log_entry_data: String::from(proof_json["log_data"].as_str().unwrap()),
+        log_index,
+        log_entry_data,
         receipt_index: eth_spv_proof.receipt_index,
-        receipt_data: String::from(proof_json["receipt_data"].as_str().unwrap()),
+        receipt_data,
         header_data: header_rlp,
         proof: proof_vec,
-        token: eth_spv_proof.token,
-        lock_amount: eth_spv_proof.lock_amount,
-        recipient_lockscript: eth_spv_proof.recipient_lockscript,
-        sudt_extra_data: eth_spv_proof.sudt_extra_data,
-        bridge_fee: eth_spv_proof.bridge_fee,
-        replay_resist_outpoint: eth_spv_proof.replay_resist_outpoint,
+        token: event.token.into(),
+        lock_amount: event.locked_amount.as_u128(),
+        recipient_lockscript: event.recipient_lockscript,
+        sudt_extra_data: event.sudt_extra_data,
+        bridge_fee: event.bridge_fee.as_u128(),
+        replay_resist_outpoint: event.replay_resist_outpoint,
         eth_address: convert_eth_address(&eth_token_locker_addr)?,
-        sender: eth_spv_proof.sender,
+        sender: event.sender.into(),
+        transaction_data,
+        transaction_proof,
     })
 }
 
+// the lock tx of the receipt and its proof in the transactions trie of the block, the lock events
+// in registry mode are consumed by the lock tx hash.
+pub async fn get_transaction_proof(
+    ethereum_rpc_url: String,
+    block_hash: &str,
+    tx_index: u64,
+) -> Result<(String, Vec<String>)> {
+    let block_hash = convert_hex_to_h256(block_hash)?;
+    let mut rpc_client = Web3Client::new(ethereum_rpc_url);
+    let block = rpc_client.get_block(block_hash.into()).await?;
+    let raw_transactions = rpc_client.get_raw_transactions(&block.transactions).await?;
+    let (root, proof) = get_trie_proof(&raw_transactions, tx_index)?;
+    if root != block.transactions_root.0 {
+        bail!(
+            "the transactions root of block {:?} does not match its txs",
+            block_hash
+        );
+    }
+    Ok((
+        hex::encode(&raw_transactions[tx_index as usize]),
+        proof.iter().map(hex::encode).collect(),
+    ))
+}
+
+// The receipt stored in the last node of the receipt proof. The typed receipt is stored as the
+// EIP-2718 envelope, the ckb contract verifies the proof against these raw bytes.
+pub fn get_receipt_data_from_proof(proof: &[String], log_index: u64) -> Result<String> {
+    let node = proof
+        .last()
+        .ok_or_else(|| anyhow!("the receipt proof is empty"))?;
+    let node = hex::decode(clear_0x(node))?;
+    let node = Rlp::new(node.as_slice());
+    let value_index = match node
+        .item_count()
+        .map_err(|e| anyhow!("decode receipt proof node err: {:?}", e))?
+    {
+        // leaf node
+        2 => 1,
+        // branch node
+        17 => 16,
+        count => bail!("invalid receipt proof node with {} items", count),
+    };
+    let receipt_data: Vec<u8> = node
+        .val_at(value_index)
+        .map_err(|e| anyhow!("decode receipt proof node value err: {:?}", e))?;
+    let receipt = Receipt::decode_envelope(receipt_data.as_slice())
+        .map_err(|e| anyhow!("decode receipt err: {:?}", e))?;
+    if log_index as usize >= receipt.logs.len() {
+        bail!(
+            "log index {} out of the {} logs of the receipt",
+            log_index,
+            receipt.logs.len()
+        );
+    }
+    Ok(hex::encode(receipt_data))
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn send_eth_spv_proof_tx_single(
     generator: &mut Generator,
@@ -287,7 +519,16 @@ pub async fn send_eth_spv_proof_tx_single(
     manual_capacity_cell: Option<OutPoint>,
     rocksdb_path: String,
 ) -> Result<ckb_types::H256> {
-    let unsigned_tx = generator.generate_eth_spv_tx(
+    if is_registry_mode(&eth_proof.replay_resist_outpoint) {
+        ensure_registry_mode_bridge_cell(
+            generator,
+            eth_proof,
+            from_lockscript.clone(),
+            &from_privkey,
+        )
+        .await?;
+    }
+    let (unsigned_tx, consumed_events) = generator.generate_eth_spv_tx(
         config_path.clone(),
         from_lockscript.clone(),
         eth_proof,
@@ -302,16 +543,102 @@ pub async fn send_eth_spv_proof_tx_single(
         serde_json::to_string_pretty(&ckb_jsonrpc_types::TransactionView::from(tx.clone()))
             .map_err(|err| anyhow!(err))?
     );
-    send_tx_sync_with_response(&mut generator.rpc_client, &tx, 600)
+    let tx_hash = send_tx_sync_with_response(&mut generator.rpc_client, &tx, 600)
         .await
-        .map_err(|e| {
-            let error = e.to_string();
-            if error.contains("CKBInternalError") || error.contains("TransactionFailedToVerify") {
-                anyhow!("irreparable error: {:?}", error)
-            } else {
+        .map_err(map_mint_tx_error)?;
+    // the lock event is minted anyway, the local registry smt out of sync with the registry cell
+    // is rebuilt from the chain.
+    if let Some(consumed_events) = consumed_events {
+        if let Err(e) = consumed_events.commit() {
+            log::error!(
+                "failed to commit the lock events consumed by mint tx {}, please rebuild the registry smt with `force-cli rebuild-consumed-event-registry`: {:?}",
+                tx_hash,
                 e
-            }
-        })
+            );
+        }
+    }
+    Ok(tx_hash)
+}
+
+// the lock event in registry mode is minted by a typeless cell of the bridge lockscript, create
+// one if there is none. it is not safe to run concurrently for the same bridge lockscript, the
+// relayer mints the lock events in registry mode one by one.
+async fn ensure_registry_mode_bridge_cell(
+    generator: &mut Generator,
+    eth_proof: &ETHSPVProofJson,
+    from_lockscript: Script,
+    from_privkey: &SecretKey,
+) -> Result<()> {
+    let bridge_lockscript = create_bridge_lockscript(
+        &generator.deployed_contracts,
+        &eth_proof.token,
+        &eth_proof.eth_address,
+    )?;
+    if !generator
+        .get_registry_mode_bridge_cells(bridge_lockscript.clone())?
+        .is_empty()
+    {
+        return Ok(());
+    }
+    let unsigned_tx =
+        generator.create_registry_mode_bridge_cell(from_lockscript, bridge_lockscript)?;
+    let tx =
+        sign(unsigned_tx, &mut generator.rpc_client, from_privkey).map_err(|err| anyhow!(err))?;
+    let tx_hash = send_tx_sync(&mut generator.rpc_client, &tx, 120)
+        .await
+        .map_err(|err| anyhow!(err))?;
+    info!(
+        "create the bridge cell to mint in registry mode, tx hash: {}",
+        hex::encode(tx_hash.as_bytes())
+    );
+    // the mint tx collects the bridge cell from the indexer right after.
+    ensure_indexer_sync(&mut generator.rpc_client, &mut generator.indexer_client, 60)
+        .await
+        .map_err(|e| anyhow!("failed to ensure indexer sync : {}", e))?;
+    Ok(())
+}
+
+fn map_mint_tx_error(e: anyhow::Error) -> anyhow::Error {
+    let error = e.to_string();
+    if error.contains("CKBInternalError") || error.contains("TransactionFailedToVerify") {
+        anyhow!("irreparable error: {:?}", error)
+    } else {
+        e
+    }
+}
+
+/// send a tx which mints the sudt of several lock events with the same bridge lockscript, the
+/// tx is sent only once, the caller should retry the lock events one by one if it fails.
+pub async fn send_eth_spv_proof_batch_tx(
+    generator: &mut Generator,
+    config_path: String,
+    eth_lock_txs: Vec<String>,
+    eth_proofs: &[ETHSPVProofJson],
+    from_privkey: SecretKey,
+    manual_capacity_cell: Option<OutPoint>,
+    rocksdb_path: String,
+) -> Result<ckb_types::H256> {
+    let from_public_key = secp256k1::PublicKey::from_secret_key(&SECP256K1, &from_privkey);
+    let address_payload = AddressPayload::from_pubkey(&from_public_key);
+    let from_lockscript = Script::from(&address_payload);
+    let unsigned_tx = generator.generate_eth_spv_batch_tx(
+        config_path,
+        from_lockscript,
+        eth_proofs,
+        manual_capacity_cell,
+        rocksdb_path,
+    )?;
+    let tx =
+        sign(unsigned_tx, &mut generator.rpc_client, &from_privkey).map_err(|err| anyhow!(err))?;
+    log::info!(
+        "lock txs {:?} to batch mint tx: \n{}",
+        &eth_lock_txs,
+        serde_json::to_string_pretty(&ckb_jsonrpc_types::TransactionView::from(tx.clone()))
+            .map_err(|err| anyhow!(err))?
+    );
+    send_tx_sync_with_response(&mut generator.rpc_client, &tx, 600)
+        .await
+        .map_err(map_mint_tx_error)
 }
 
 pub async fn send_eth_spv_proof_tx(
@@ -538,9 +865,61 @@ pub async fn init_multi_sign_address(
     let address_payload = AddressPayload::from_pubkey(&from_public_key);
     let from_lockscript = Script::from(&address_payload);
 
-    let unsigned_tx = generator.init_eth_light_client_cell(multisig_script, from_lockscript)?;
+    // the light client cell pins the governance cells, so they are created first
+    let unsigned_tx =
+        generator.init_bridge_status_cell(multisig_script.clone(), from_lockscript.clone())?;
+    let status_typescript = send_init_cell_tx(&mut generator, unsigned_tx, &secret_key).await?;
+    info!(
+        "Succeed to init bridge status cell. cell type_script: {:?}",
+        status_typescript
+    );
+    deployed_contracts.bridge_status_cell_script.cell_script = status_typescript;
+
+    let unsigned_tx =
+        generator.init_token_allowlist_cell(multisig_script.clone(), from_lockscript.clone())?;
+    let allowlist_typescript = send_init_cell_tx(&mut generator, unsigned_tx, &secret_key).await?;
+    info!(
+        "Succeed to init token allowlist cell. cell type_script: {:?}",
+        allowlist_typescript
+    );
+    deployed_contracts.token_allowlist_cell_script.cell_script = allowlist_typescript;
+
+    let unsigned_tx =
+        generator.init_eth_light_client_cell(multisig_script, from_lockscript.clone())?;
+    let typescript = send_init_cell_tx(&mut generator, unsigned_tx, &secret_key).await?;
+    info!(
+        "Succeed to init eth light client cell. cell type_script: {:?}",
+        typescript
+    );
+
+    deployed_contracts.light_client_cell_script.cell_script = typescript;
+
+    // the registry cell is locked by the bridge lockscript with the light client typescript hash
+    let registry_lockscript = create_registry_lockscript(&deployed_contracts)?;
+    let unsigned_tx =
+        generator.init_consumed_event_registry_cell(registry_lockscript, from_lockscript)?;
+    let registry_typescript = send_init_cell_tx(&mut generator, unsigned_tx, &secret_key).await?;
+    info!(
+        "Succeed to init consumed event registry cell. cell type_script: {:?}",
+        registry_typescript
+    );
+    deployed_contracts
+        .consumed_event_registry_cell_script
+        .cell_script = registry_typescript;
+    force_config.deployed_contracts = Some(deployed_contracts);
+    let config_path = tilde(config_path.as_str()).into_owned();
+    force_config.write(&config_path)?;
+    Ok(())
+}
+
+// sign and send the tx which creates a cell at output 0, return the hex encoded typescript of the cell.
+async fn send_init_cell_tx(
+    generator: &mut Generator,
+    unsigned_tx: TransactionView,
+    secret_key: &SecretKey,
+) -> Result<String> {
     let tx =
-        sign(unsigned_tx, &mut generator.rpc_client, &secret_key).map_err(|err| anyhow!(err))?;
+        sign(unsigned_tx, &mut generator.rpc_client, secret_key).map_err(|err| anyhow!(err))?;
     send_tx_sync(&mut generator.rpc_client, &tx, 120)
         .await
         .map_err(|err| anyhow!(err))?;
@@ -550,16 +929,94 @@ pub async fn init_multi_sign_address(
         .type_()
         .to_opt()
         .ok_or_else(|| anyhow!("type script is not found."))?;
-    info!(
-        "Succeed to init eth light client cell. cell type_script: {:?}",
-        hex::encode(typescript.as_slice())
-    );
+    Ok(hex::encode(typescript.as_slice()))
+}
 
-    deployed_contracts.light_client_cell_script.cell_script = hex::encode(typescript.as_slice());
-    force_config.deployed_contracts = Some(deployed_contracts);
+// pause or resume the bridge by updating the bridge status cell locked by the multisig address.
+// the tx fee is paid by priv_key_path.
+pub async fn set_bridge_paused(
+    config_path: String,
+    network: Option<String>,
+    priv_key_path: String,
+    multisig_privkeys: Vec<String>,
+    paused: bool,
+) -> Result<String> {
+    send_governance_tx(
+        config_path,
+        network,
+        priv_key_path,
+        multisig_privkeys,
+        |generator, from_lockscript| generator.generate_bridge_status_tx(from_lockscript, paused),
+    )
+    .await
+}
+
+// add or remove the token in the token allowlist cell locked by the multisig address.
+// the tx fee is paid by priv_key_path.
+pub async fn set_token_allowed(
+    config_path: String,
+    network: Option<String>,
+    priv_key_path: String,
+    multisig_privkeys: Vec<String>,
+    token: String,
+    allowed: bool,
+) -> Result<String> {
+    let token = convert_eth_address(&token)?;
+    send_governance_tx(
+        config_path,
+        network,
+        priv_key_path,
+        multisig_privkeys,
+        |generator, from_lockscript| {
+            generator.generate_token_allowlist_tx(from_lockscript, token, allowed)
+        },
+    )
+    .await
+}
+
+async fn send_governance_tx<F>(
+    config_path: String,
+    network: Option<String>,
+    priv_key_path: String,
+    multisig_privkeys: Vec<String>,
+    build_tx: F,
+) -> Result<String>
+where
+    F: FnOnce(&mut Generator, Script) -> Result<TransactionView>,
+{
     let config_path = tilde(config_path.as_str()).into_owned();
-    force_config.write(&config_path)?;
-    Ok(())
+    let force_config = ForceConfig::new(config_path.as_str())?;
+    let deployed_contracts = force_config
+        .deployed_contracts
+        .as_ref()
+        .ok_or_else(|| anyhow!("contracts should be deployed"))?;
+    let ckb_rpc_url = force_config.get_ckb_rpc_url(&network)?;
+    let indexer_url = force_config.get_ckb_indexer_url(&network)?;
+    let mut generator = Generator::new(ckb_rpc_url, indexer_url, deployed_contracts.clone())
+        .map_err(|e| anyhow!("failed to crate generator: {}", e))?;
+    let multisig_config = get_light_client_multisig_config(deployed_contracts)?;
+
+    let secret_key = parse_privkey_path(&priv_key_path, &force_config, &network)?;
+    let multisig_privkeys = multisig_privkeys
+        .iter()
+        .map(|k| parse_privkey_path(k, &force_config, &network))
+        .collect::<Result<Vec<SecretKey>>>()?;
+
+    let unsigned_tx = build_tx(&mut generator, parse_privkey(&secret_key))?;
+    let mut privkeys = vec![&secret_key];
+    privkeys.extend(multisig_privkeys.iter());
+    let tx = sign_with_multisig_signers(
+        unsigned_tx,
+        &mut generator.rpc_client,
+        privkeys,
+        multisig_config,
+        &[],
+    )
+    .await?;
+    let tx_hash = send_tx_sync(&mut generator.rpc_client, &tx, 120)
+        .await
+        .map_err(|err| anyhow!(err))?;
+    Ok(hex::encode(tx_hash.as_bytes()))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -572,6 +1029,7 @@ pub async fn get_or_create_bridge_cell(
     eth_token_address_str: String,
     recipient_address: String,
     bridge_fee: u128,
+    fee_recipients: Vec<(String, u64)>,
     simple_typescript: bool,
     cell_num: usize,
     is_create: bool,
@@ -596,10 +1054,29 @@ pub async fn get_or_create_bridge_cell(
     let tx_fee: u64 = HumanCapacity::from_str(&tx_fee)
         .map_err(|e| anyhow!(e))?
         .into();
+    let mut fee_recipient_lockscripts = vec![];
+    for (address, basis_points) in fee_recipients {
+        let lockscript = Script::from(
+            Address::from_str(&address)
+                .map_err(|err| anyhow!("invalid fee recipient address: {}", err))?
+                .payload(),
+        );
+        fee_recipient_lockscripts.push((lockscript, basis_points));
+    }
 
     let eth_contract_address =
         convert_eth_address(deployed_contracts.eth_token_locker_addr.as_str())?;
     let eth_token_address = convert_eth_address(eth_token_address_str.as_str())?;
+    if !is_token_allowed(
+        &mut generator,
+        deployed_contracts
+            .token_allowlist_cell_script
+            .cell_script
+            .clone(),
+        &eth_token_address,
+    )? {
+        bail!("token {} is not in the allowlist", eth_token_address_str);
+    }
     let bridge_lockscript = create_bridge_lockscript(
         &deployed_contracts,
         &eth_token_address,
@@ -671,6 +1148,7 @@ pub async fn get_or_create_bridge_cell(
             bridge_typescript,
             bridge_lockscript,
             bridge_fee,
+            fee_recipient_lockscripts,
             simple_typescript,
             cell_num,
         )
@@ -949,3 +1427,55 @@ pub async fn recycle_recipient_cell(
         .sign_and_send_transaction(unsigned_tx, private_key)
         .await
 }
+
+#[test]
+fn test_parse_locked_event() {
+    let log_entry_data = "f9024194eab52f0d5c0f03c273372309b522a935f5b6ce12f863a0413055b58d692937cc2a7d80ca019c17e8d01175e58d11f157ae9124078b01d6a00000000000000000000000003dc3d2369b6d9879e593c3a133055e0f03a52a74a000000000000000000000000046beac96b726a51c5703f99ec787ce12793dae11b901c00000000000000000000000000000000000000000000000000000000000000064000000000000000000000000000000000000000000000000000000000000000a00000000000000000000000000000000000000000000000000000000000000a0000000000000000000000000000000000000000000000000000000000000012000000000000000000000000000000000000000000000000000000000000001800000000000000000000000000000000000000000000000000000000000000049490000001000000030000000310000009bd7e06f3ecf4be0f2fcd2188b23f1b9fcc88e5d4b65a8637b17723bbda3cce80114000000c8328aabcd9b9e8e64fbc566c4385c3bdeb219d70000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000002418fe25034cbc1f69c53df12f2083a23091d2e1830b911ae873265f03dd61b0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000f737564745f65787472615f64617461000000000000000000000000000000000083307832";
+    let event =
+        parse_locked_event(log_entry_data, "0xeab52f0d5c0f03c273372309b522a935f5b6ce12").unwrap();
+    assert_eq!(
+        hex::encode(event.token),
+        "3dc3d2369b6d9879e593c3a133055e0f03a52a74"
+    );
+    assert_eq!(event.locked_amount, 100.into());
+    assert_eq!(event.bridge_fee, 10.into());
+    assert_eq!(event.replay_resist_outpoint.len(), 36);
+    assert!(
+        parse_locked_event(log_entry_data, "0x3dc3d2369b6d9879e593c3a133055e0f03a52a74").is_err()
+    );
+}
+
+#[test]
+fn test_get_receipt_data_from_typed_receipt_proof() {
+    // the EIP-1559 receipt of the mainnet tx 0x21f6554c28453a01e7276c1db2fc1695bb512b170818bfa98fa8136433100616
+    // at index 0x7f of block 0x129f4b9, with a USDT approval log.
+    let receipt_data = "02f901a70183a42aecb9010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000200000000000000000040000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000400000800000000000000000000000000000000004000000000000000000800000000100000020000000000000000000080000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000010000000000000000000000000000f89df89b94dac17f958d2ee523a2206206994597c13d831ec7f863a08c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925a00000000000000000000000009a53bfba35269414f3b2d20b52ca01b15932c7b2a000000000000000000000000039e5dbb9d2fead31234d7c647d6ce77d85826f76a000000000000000000000000000000000000000000052b7d2dcc80cd2e4000000";
+    let receipt = hex::decode(receipt_data).unwrap();
+    assert_eq!(receipt[0], 2);
+
+    let mut leaf = rlp::RlpStream::new_list(2);
+    leaf.append(&hex::decode("207f").unwrap());
+    leaf.append(&receipt);
+    let mut branch = rlp::RlpStream::new_list(17);
+    for _ in 0..16 {
+        branch.append_empty_data();
+    }
+    branch.append(&receipt);
+    for node in vec![leaf.out(), branch.out()] {
+        let proof = vec!["0x00".to_owned(), format!("0x{}", hex::encode(node))];
+        assert_eq!(
+            get_receipt_data_from_proof(&proof, 0).unwrap(),
+            receipt_data
+        );
+        assert!(get_receipt_data_from_proof(&proof, 1).is_err());
+    }
+
+    let parsed = Receipt::decode_envelope(&receipt).unwrap();
+    assert_eq!(parsed.tx_type, 2);
+    assert!(parsed.status);
+    assert_eq!(
+        hex::encode(parsed.logs[0].address.as_bytes()),
+        "dac17f958d2ee523a2206206994597c13d831ec7"
+    );
+    assert!(get_receipt_data_from_proof(&[], 0).is_err());
+}
@@ -1,11 +1,13 @@
-use crate::util::ckb_proof_helper::CBMT as CKB_CBMT;
 use crate::util::ckb_tx_generator::{Generator, CONFIRM};
 use crate::util::ckb_types::{CKBHistoryTxProof, CKBHistoryTxRootProof, CKBUnlockTokenParam};
 use crate::util::ckb_util::{covert_to_h256, parse_privkey, parse_privkey_path};
 use crate::util::config::ForceConfig;
-use crate::util::eth_util::{convert_eth_address, parse_private_key, Web3Client};
+use crate::util::eth_tx_manager::EthTxManager;
+use crate::util::eth_util::{
+    convert_eth_address, parse_private_key, EthFee, EthFeeOptions, Web3Client,
+};
 use crate::util::generated::ckb_tx_proof;
-use crate::util::rocksdb::open_readonly_rocksdb;
+use crate::util::history_tx_root::HistoryTxRootAccumulator;
 use anyhow::anyhow;
 use anyhow::Result;
 use ckb_sdk::rpc::{BlockView, TransactionView};
@@ -18,7 +20,6 @@ use ethabi::{Function, Param, ParamType, Token};
 use ethereum_types::U256;
 use force_sdk::util::ensure_indexer_sync;
 use log::{debug, info};
-use rocksdb::ops::Get;
 use secp256k1::{Message, Secp256k1, SecretKey};
 use std::str::FromStr;
 use web3::signing::keccak256;
@@ -87,7 +88,7 @@ pub async fn init_light_client(
             eth_ckb_chain_addr,
             eth_private_key,
             init_header_abi,
-            U256::from(gas_price),
+            &EthFeeOptions::legacy(U256::from(gas_price)),
             U256::zero(),
             U256::zero(),
             wait,
@@ -218,7 +219,7 @@ pub async fn unlock_with_retry(
     light_client_addr: String,
     token_locker_addr: String,
     ckb_rocksdb_path: String,
-    gas_price: u64,
+    fee_options: EthFeeOptions,
     wait: bool,
 ) -> Result<()> {
     let light_client_addr = convert_eth_address(&light_client_addr)?;
@@ -243,7 +244,7 @@ pub async fn unlock_with_retry(
             eth_rpc_url.clone(),
             token_locker_addr.clone(),
             proof,
-            gas_price,
+            &fee_options,
             U256::zero(),
             wait,
         )
@@ -265,12 +266,46 @@ pub async fn unlock(
     eth_url: String,
     to: String,
     proof: String,
-    gas_price: u64,
+    fee_options: &EthFeeOptions,
     asec_nonce: U256,
     wait: bool,
 ) -> Result<String> {
     let to = convert_eth_address(&to)?;
     let mut rpc_client = Web3Client::new(eth_url);
+    let input_data = get_unlock_input_data(proof)?;
+    let res = rpc_client
+        .send_transaction(
+            to,
+            eth_private_key,
+            input_data,
+            fee_options,
+            U256::zero(),
+            asec_nonce,
+            wait,
+        )
+        .await?;
+    let tx_hash = hex::encode(res);
+    Ok(tx_hash)
+}
+
+// unlock with the eth tx manager, which replaces the tx with a bumped fee if it gets stuck.
+pub async fn unlock_with_tx_manager(
+    tx_manager: &EthTxManager,
+    eth_private_key: ethereum_types::H256,
+    to: String,
+    proof: String,
+    nonce: U256,
+    fee: EthFee,
+) -> Result<String> {
+    let to = convert_eth_address(&to)?;
+    let input_data = get_unlock_input_data(proof)?;
+    let res = tx_manager
+        .send_transaction(to, eth_private_key, input_data, U256::zero(), nonce, fee)
+        .await?;
+    Ok(hex::encode(res))
+}
+
+fn get_unlock_input_data(proof: String) -> Result<Vec<u8>> {
     info!("unlock proof: {}", &proof);
     let proof = hex::decode(proof).map_err(|err| anyhow!(err))?;
 
@@ -284,20 +319,7 @@ pub async fn unlock(
         constant: false,
     };
     let tokens = [Token::Bytes(proof)];
-    let input_data = function.encode_input(&tokens)?;
-    let res = rpc_client
-        .send_transaction(
-            to,
-            eth_private_key,
-            input_data,
-            U256::from(gas_price),
-            U256::zero(),
-            asec_nonce,
-            wait,
-        )
-        .await?;
-    let tx_hash = hex::encode(res);
-    Ok(tx_hash)
+    Ok(function.encode_input(&tokens)?)
 }
 
 pub fn get_add_ckb_headers_func() -> Function {
@@ -639,55 +661,6 @@ pub fn generate_ckb_history_tx_root_proof(
     block_numbers: Vec<u64>,
     ckb_db_path: String,
 ) -> Result<CKBHistoryTxRootProof> {
-    let mut tx_roots_indices: Vec<u32> = vec![];
-    let mut proof_leaves: Vec<H256> = vec![];
-
-    let db = open_readonly_rocksdb(ckb_db_path)?;
-    let mut all_tx_roots = vec![];
-    for number in init_block_number..=latest_block_number {
-        let db_root = db
-            .get(number.to_le_bytes())
-            .map_err(|err| anyhow!(err))?
-            .ok_or_else(|| anyhow!("db ckb root should not be none"))?;
-        let mut db_root_raw = [0u8; 32];
-        db_root_raw.copy_from_slice(db_root.as_ref());
-        all_tx_roots.push(db_root_raw);
-    }
-
-    for number in block_numbers {
-        if number < init_block_number || number > latest_block_number {
-            return Err(anyhow!(
-                "block number {} not yet between init_block_number {} and latest_block_number {}",
-                number,
-                init_block_number,
-                latest_block_number
-            ));
-        }
-
-        let index = (number - init_block_number) as u32;
-        tx_roots_indices.push(index);
-        proof_leaves.push(all_tx_roots[index as usize].clone().into())
-    }
-
-    let tx_roots_num = latest_block_number - init_block_number + 1;
-    // dbg!(format!("{:#x}", retrieved_block_hash));
-    dbg!(format!("tx_roots_num: {}", tx_roots_num));
-
-    let proof = CKB_CBMT::build_merkle_proof(
-        &all_tx_roots,
-        &tx_roots_indices.into_iter().collect::<Vec<_>>(),
-    )
-    .ok_or_else(|| anyhow!("build proof with verified inputs should be OK"))?;
-
-    let mut indices = proof.indices().to_vec();
-    indices.sort_by(|a, b| b.cmp(a));
-    proof_leaves.reverse();
-
-    Ok(CKBHistoryTxRootProof {
-        init_block_number,
-        latest_block_number,
-        indices: indices.iter().map(|i| *i as u64).collect(),
-        proof_leaves,
-        lemmas: proof.lemmas().iter().map(|&p| p.into()).collect(),
-    })
+    let mut accumulator = HistoryTxRootAccumulator::open_readonly(ckb_db_path)?;
+    accumulator.get_proof(init_block_number, latest_block_number, &block_numbers)
 }
@@ -1,15 +1,22 @@
 use crate::util::ckb_util::{
-    create_bridge_lockscript, get_sudt_type_script, handle_unconfirmed_headers, parse_cell,
-    parse_main_raw_data, parse_uncle_raw_data, ETHSPVProofJson, EthWitness,
+    clear_0x, create_bridge_lockscript, get_cell_script_hash, get_sudt_type_script,
+    handle_unconfirmed_headers, parse_bridge_status_cell_data, parse_cell,
+    parse_consumed_event_registry_cell_data, parse_main_raw_data,
+    parse_merkle_cell_bridge_status_hash, parse_merkle_cell_confirm,
+    parse_merkle_cell_consumed_event_registry_hash, parse_merkle_cell_data,
+    parse_merkle_cell_roots, parse_merkle_cell_token_allowlist_hash,
+    parse_token_allowlist_cell_data, parse_uncle_raw_data, rotate_merkle_cell_history,
+    ETHSPVProofJson, EthBatchWitness, EthWitness,
 };
 use crate::util::config::{DeployedContracts, ForceConfig, OutpointConf};
 use crate::util::eth_proof_helper::Witness;
-use crate::util::eth_util::convert_to_header_rlp;
+use crate::util::eth_util::{convert_to_header_rlp, keccak256, HeaderExtension};
 use crate::util::rocksdb;
 use anyhow::{anyhow, bail, Result};
+use ckb_hash::new_blake2b;
 use ckb_sdk::constants::ONE_CKB;
 use ckb_sdk::{GenesisInfo, HttpRpcClient};
-use ckb_types::core::{BlockView, Capacity, DepType, TransactionView};
+use ckb_types::core::{BlockView, Capacity, DepType, ScriptHashType, TransactionView};
 use ckb_types::packed::{HeaderVec, WitnessArgs};
 use ckb_types::prelude::{Builder, Entity, Pack, Reader, Unpack};
 use ckb_types::{
@@ -17,19 +24,32 @@ use ckb_types::{
     packed::{self, Byte32, CellDep, CellOutput, OutPoint, Script},
 };
 use ethereum_types::H160;
+use force_eth_types::bridge_fee::{
+    fee_outputs, parse_bridge_type_data, split_fee, BASIS_POINTS_DENOMINATOR,
+};
+use force_eth_types::consumed_event_registry::{consumed_event_key, is_registry_mode};
 use force_eth_types::eth_recipient_cell::{ETHAddress, ETHRecipientDataView};
 use force_eth_types::generated::basic;
 use force_eth_types::generated::basic::BytesVec;
-use force_eth_types::generated::eth_bridge_type_cell::ETHBridgeTypeData;
+use force_eth_types::generated::eth_bridge_status_cell::ETHBridgeStatusCellData;
+use force_eth_types::generated::eth_bridge_type_cell::{
+    ETHBridgeFeeRecipient, ETHBridgeFeeRecipientVec, ETHBridgeTypeData,
+};
+use force_eth_types::generated::eth_consumed_event_registry_cell::{
+    ETHConsumedEventRegistryCellData, ETHConsumedEventRegistryWitness, ETHTransactionProof,
+    ETHTransactionProofVec,
+};
 use force_eth_types::generated::eth_header_cell::{
-    ETHChain, ETHHeaderCellData, ETHHeaderCellMerkleData, ETHHeaderCellMerkleDataReader,
-    ETHHeaderInfo, ETHHeaderInfoReader,
+    ETHChain, ETHHeaderCellData, ETHHeaderCellMerkleData, ETHHeaderInfo, ETHHeaderInfoReader,
 };
-use force_eth_types::generated::witness::MintTokenWitness;
+use force_eth_types::generated::eth_token_allowlist_cell::ETHTokenAllowlistCellData;
+use force_eth_types::generated::witness::{MintTokenBatchWitness, MintTokenWitness};
 use force_eth_types::hasher::Blake2bHasher;
 use force_sdk::cell_collector::{
-    collect_sudt_amount, get_live_cell_by_typescript, get_recipient_cell,
+    collect_sudt_amount, get_all_live_cells_by_lockscript, get_live_cell_by_typescript,
+    get_recipient_cell,
 };
+use force_sdk::constants::TYPE_ID;
 use force_sdk::indexer::{Cell, IndexerRpcClient};
 use force_sdk::tx_helper::{sign, TxHelper};
 use force_sdk::util::{get_live_cell_with_cache, send_tx_sync};
@@ -47,6 +67,23 @@ pub const MAIN_HEADER_CACHE_LIMIT: usize = 500;
 pub const CONFIRM: usize = 15;
 pub const UNCLE_HEADER_CACHE_LIMIT: usize = 10;
 
+// the lock events consumed by a mint tx in registry mode, they are committed to the local registry
+// smt only after the tx is committed on chain, so that a failed tx does not mark them consumed.
+#[derive(Debug, Clone)]
+pub struct ConsumedEvents {
+    pub rocksdb_path: String,
+    // the registry root the tx consumes the lock events from
+    pub root: [u8; 32],
+    pub keys: Vec<[u8; 32]>,
+}
+
+impl ConsumedEvents {
+    pub fn commit(&self) -> Result<[u8; 32]> {
+        let rocksdb_store = rocksdb::RocksDBStore::open(self.rocksdb_path.clone())?;
+        rocksdb_store.commit_consumed_keys(self.root, &self.keys)
+    }
+}
+
 pub struct Generator {
     pub rpc_client: HttpRpcClient,
     pub indexer_client: IndexerRpcClient,
@@ -96,6 +133,7 @@ impl Generator {
         merkle_root: &[u8],
         start_height: u64,
         latest_height: u64,
+        confirm: u64,
     ) -> Result<TransactionView> {
         let mut rng = rand::thread_rng();
         let tx_fee = rng.gen_range(ONE_CKB / 2000, ONE_CKB / 1000);
@@ -136,15 +174,80 @@ impl Generator {
             .type_(cell_output.type_())
             .build();
 
+        let history = rotate_merkle_cell_history(
+            cell.output_data.as_bytes().to_vec(),
+            merkle_root,
+            latest_height,
+        )?;
+        // the bridge status cell, the token allowlist cell and the consumed event registry cell
+        // are pinned when the light client cell data is initialized
+        let (
+            bridge_status_typescript_hash,
+            token_allowlist_typescript_hash,
+            consumed_event_registry_typescript_hash,
+        ) = if cell.output_data.as_bytes().is_empty() {
+            (
+                get_cell_script_hash(
+                    self.deployed_contracts
+                        .bridge_status_cell_script
+                        .cell_script
+                        .as_str(),
+                )?,
+                get_cell_script_hash(
+                    self.deployed_contracts
+                        .token_allowlist_cell_script
+                        .cell_script
+                        .as_str(),
+                )?,
+                get_cell_script_hash(
+                    self.deployed_contracts
+                        .consumed_event_registry_cell_script
+                        .cell_script
+                        .as_str(),
+                )?,
+            )
+        } else {
+            let last_data = cell.output_data.as_bytes().to_vec();
+            (
+                parse_merkle_cell_bridge_status_hash(last_data.clone())?,
+                parse_merkle_cell_token_allowlist_hash(last_data.clone())?,
+                parse_merkle_cell_consumed_event_registry_hash(last_data)?,
+            )
+        };
         let output_data = ETHHeaderCellMerkleData::new_builder()
             .merkle_root(
                 basic::Byte32::from_slice(merkle_root).expect("merkle root should be right"),
             )
             .start_height(start_height.into())
             .latest_height(latest_height.into())
+            .confirm(confirm.into())
+            .bridge_status_typescript_hash(
+                basic::Byte32::from_slice(&bridge_status_typescript_hash)
+                    .expect("bridge status typescript hash should be right"),
+            )
+            .token_allowlist_typescript_hash(
+                basic::Byte32::from_slice(&token_allowlist_typescript_hash)
+                    .expect("token allowlist typescript hash should be right"),
+            )
+            .consumed_event_registry_typescript_hash(
+                basic::Byte32::from_slice(&consumed_event_registry_typescript_hash)
+                    .expect("consumed event registry typescript hash should be right"),
+            )
+            .history(history)
             .build()
             .as_bytes();
-        helper.add_output_with_auto_capacity(output, output_data);
+        // keep the capacity of the light client cell, the multisig signers reject the txs taking
+        // capacity out of it
+        let input_capacity: u64 = cell_output.capacity().unpack();
+        let occupied_capacity = output
+            .occupied_capacity(Capacity::bytes(output_data.len()).map_err(|err| anyhow!(err))?)
+            .map_err(|err| anyhow!(err))?
+            .as_u64();
+        let output = output
+            .as_builder()
+            .capacity(std::cmp::max(input_capacity, occupied_capacity).pack())
+            .build();
+        helper.add_output(output, output_data);
         // make tx
         let tx = helper
             .supply_capacity(
@@ -211,7 +314,9 @@ impl Generator {
             .expect("should have input")
             .previous_output()
             .as_bytes();
-        let typescript_args = first_outpoint.as_ref();
+        // the typescript only allows the cell to be locked by the multisig script
+        let mut typescript_args = first_outpoint.to_vec();
+        typescript_args.extend_from_slice(multisig_script.calc_script_hash().raw_data().as_ref());
         let new_typescript = typescript.as_builder().args(typescript_args.pack()).build();
         let new_output = CellOutput::new_builder()
             .capacity(output.capacity())
@@ -227,10 +332,212 @@ impl Generator {
         Ok(unsigned_tx)
     }
 
+    pub fn init_bridge_status_cell(
+        &mut self,
+        multisig_script: Script,
+        from_lockscript: Script,
+    ) -> Result<TransactionView> {
+        self.init_governance_cell(
+            multisig_script,
+            from_lockscript,
+            ETHBridgeStatusCellData::default().as_bytes(),
+        )
+    }
+
+    pub fn init_token_allowlist_cell(
+        &mut self,
+        multisig_script: Script,
+        from_lockscript: Script,
+    ) -> Result<TransactionView> {
+        self.init_governance_cell(
+            multisig_script,
+            from_lockscript,
+            ETHTokenAllowlistCellData::default().as_bytes(),
+        )
+    }
+
+    // the consumed event registry cell is locked by the registry form of the bridge lockscript,
+    // so it can only be updated by the mint txs of the lock events in registry mode.
+    pub fn init_consumed_event_registry_cell(
+        &mut self,
+        registry_lockscript: Script,
+        from_lockscript: Script,
+    ) -> Result<TransactionView> {
+        self.init_governance_cell(
+            registry_lockscript,
+            from_lockscript,
+            ETHConsumedEventRegistryCellData::default().as_bytes(),
+        )
+    }
+
+    // the governance cell is identified by the type id script, and locked by the given lockscript,
+    // which is the multisig script so that only the multisig owners can update it.
+    fn init_governance_cell(
+        &mut self,
+        lockscript: Script,
+        from_lockscript: Script,
+        output_data: Bytes,
+    ) -> Result<TransactionView> {
+        let mut helper = TxHelper::default();
+        let cap = 200 * ONE_CKB;
+        let output = CellOutput::new_builder()
+            .capacity(Capacity::shannons(cap).pack())
+            .build();
+        helper.add_output(output.clone(), output_data);
+
+        let unsigned_tx = helper
+            .supply_capacity(
+                &mut self.rpc_client,
+                &mut self.indexer_client,
+                from_lockscript,
+                &self.genesis_info,
+                99_999,
+                None,
+            )
+            .map_err(|err| anyhow!(err))?;
+        let first_input = unsigned_tx.inputs().get(0).expect("should have input");
+        // the type id args of the first output
+        let mut typescript_args = [0u8; 32];
+        let mut blake2b = new_blake2b();
+        blake2b.update(first_input.as_slice());
+        blake2b.update(0u64.to_le_bytes().as_ref());
+        blake2b.finalize(&mut typescript_args);
+        let typescript = Script::new_builder()
+            .code_hash(Byte32::from_slice(&hex::decode(TYPE_ID)?)?)
+            .hash_type(ScriptHashType::Type.into())
+            .args(typescript_args.to_vec().pack())
+            .build();
+        let new_output = CellOutput::new_builder()
+            .capacity(output.capacity())
+            .type_(Some(typescript).pack())
+            .lock(lockscript)
+            .build();
+        let mut new_outputs = unsigned_tx.outputs().into_iter().collect::<Vec<_>>();
+        new_outputs[0] = new_output;
+        let unsigned_tx = unsigned_tx
+            .as_advanced_builder()
+            .set_outputs(new_outputs)
+            .build();
+        Ok(unsigned_tx)
+    }
+
+    pub fn generate_bridge_status_tx(
+        &mut self,
+        from_lockscript: Script,
+        paused: bool,
+    ) -> Result<TransactionView> {
+        let cell_script = parse_cell(
+            self.deployed_contracts
+                .bridge_status_cell_script
+                .cell_script
+                .as_str(),
+        )?;
+        let cell = get_live_cell_by_typescript(&mut self.indexer_client, cell_script)
+            .map_err(|err| anyhow!(err))?
+            .ok_or_else(|| anyhow!("bridge status cell not found"))?;
+        if parse_bridge_status_cell_data(cell.output_data.as_bytes().to_vec())? == paused {
+            bail!(
+                "bridge is already {}",
+                if paused { "paused" } else { "resumed" }
+            );
+        }
+
+        let output_data = ETHBridgeStatusCellData::new_builder()
+            .paused(Byte::new(paused as u8))
+            .build()
+            .as_bytes();
+        self.generate_governance_cell_tx(from_lockscript, cell, output_data)
+    }
+
+    pub fn generate_token_allowlist_tx(
+        &mut self,
+        from_lockscript: Script,
+        token: H160,
+        allowed: bool,
+    ) -> Result<TransactionView> {
+        let cell_script = parse_cell(
+            self.deployed_contracts
+                .token_allowlist_cell_script
+                .cell_script
+                .as_str(),
+        )?;
+        let cell = get_live_cell_by_typescript(&mut self.indexer_client, cell_script)
+            .map_err(|err| anyhow!(err))?
+            .ok_or_else(|| anyhow!("token allowlist cell not found"))?;
+        let mut tokens = parse_token_allowlist_cell_data(cell.output_data.as_bytes().to_vec())?;
+        match (tokens.contains(&token), allowed) {
+            (true, true) => bail!("token {:x} is already in the allowlist", token),
+            (false, false) => bail!("token {:x} is not in the allowlist", token),
+            (false, true) => tokens.push(token),
+            (true, false) => tokens.retain(|t| t != &token),
+        }
+
+        let output_data = ETHTokenAllowlistCellData::new_builder()
+            .set(
+                tokens
+                    .iter()
+                    .map(|t| {
+                        basic::ETHAddress::from_slice(t.as_bytes())
+                            .expect("token address should be right")
+                    })
+                    .collect(),
+            )
+            .build()
+            .as_bytes();
+        self.generate_governance_cell_tx(from_lockscript, cell, output_data)
+    }
+
+    // update the data of the governance cell, the tx fee is paid by from_lockscript.
+    fn generate_governance_cell_tx(
+        &mut self,
+        from_lockscript: Script,
+        cell: Cell,
+        output_data: Bytes,
+    ) -> Result<TransactionView> {
+        let mut rng = rand::thread_rng();
+        let tx_fee = rng.gen_range(ONE_CKB / 2000, ONE_CKB / 1000);
+        let mut helper = TxHelper::default();
+
+        let mut live_cell_cache: HashMap<(OutPoint, bool), (CellOutput, Bytes)> =
+            Default::default();
+        let rpc_client = &mut self.rpc_client;
+        let mut get_live_cell_fn = |out_point: OutPoint, with_data: bool| {
+            get_live_cell_with_cache(&mut live_cell_cache, rpc_client, out_point, with_data)
+                .map(|(output, _)| output)
+        };
+        helper
+            .add_input(
+                OutPoint::from(cell.clone().out_point),
+                None,
+                &mut get_live_cell_fn,
+                &self.genesis_info,
+                true,
+            )
+            .map_err(|err| anyhow!(err))?;
+
+        let cell_output = CellOutput::from(cell.output);
+        let output = CellOutput::new_builder()
+            .lock(cell_output.lock())
+            .type_(cell_output.type_())
+            .build();
+        helper.add_output_with_auto_capacity(output, output_data);
+        let tx = helper
+            .supply_capacity(
+                &mut self.rpc_client,
+                &mut self.indexer_client,
+                from_lockscript,
+                &self.genesis_info,
+                tx_fee,
+                None,
+            )
+            .map_err(|err| anyhow!(err))?;
+        Ok(tx)
+    }
+
     #[allow(clippy::mutable_key_type)]
     pub fn generate_eth_light_client_tx(
         &mut self,
-        headers: &[Block<ethereum_types::H256>],
+        headers: &[(Block<ethereum_types::H256>, HeaderExtension)],
         cell: &Cell,
         _witness: &[Witness],
         un_confirmed_headers: &[BlockHeader],
@@ -270,8 +577,8 @@ impl Generator {
                 .build();
             if un_confirmed_headers.is_empty() {
                 let mut main_chain_data: Vec<basic::Bytes> = vec![];
-                for item in headers {
-                    let header_rlp = convert_to_header_rlp(item)?;
+                for (item, extension) in headers {
+                    let header_rlp = convert_to_header_rlp(item, extension)?;
                     let header_info = ETHHeaderInfo::new_builder()
                         .header(hex::decode(header_rlp)?.into())
                         .total_difficulty(item.total_difficulty.unwrap().as_u64().into())
@@ -296,7 +603,7 @@ impl Generator {
                 let (mut unconfirmed, mut confirmed) = parse_main_raw_data(&input_cell_data)?;
                 let mut uncle_raw_data = parse_uncle_raw_data(&input_cell_data)?;
                 let header_infos;
-                if tip.hash.unwrap() == headers[0].parent_hash {
+                if tip.hash.unwrap() == headers[0].0.parent_hash {
                     // the main chain is not reorg.
                     if unconfirmed.len().add(headers.len()) > CONFIRM {
                         let mut idx = unconfirmed.len().add(headers.len()) - CONFIRM;
@@ -337,7 +644,7 @@ impl Generator {
                     let mut idx = un_confirmed_headers.len() - 1;
                     while idx > 0 {
                         let header = &un_confirmed_headers[idx - 1];
-                        if header.hash.unwrap() == headers[0].parent_hash {
+                        if header.hash.unwrap() == headers[0].0.parent_hash {
                             break;
                         }
                         idx -= 1;
@@ -414,7 +721,6 @@ impl Generator {
         Ok(tx)
     }
 
-    #[allow(clippy::mutable_key_type)]
     pub fn generate_eth_spv_tx(
         &mut self,
         config_path: String,
@@ -422,43 +728,182 @@ impl Generator {
         eth_proof: &ETHSPVProofJson,
         manual_capacity_cell: Option<OutPoint>,
         rocksdb_path: String,
-    ) -> Result<TransactionView> {
+    ) -> Result<(TransactionView, Option<ConsumedEvents>)> {
         let mut rng = rand::thread_rng();
         let tx_fee = rng.gen_range(ONE_CKB / 4, ONE_CKB / 2);
         // let tx_fee: u64 = ONE_CKB / 2;
         let mut helper = TxHelper::default();
+        let cell_data = self.add_eth_spv_cell_deps(&mut helper, config_path.clone())?;
+
+        // input bridge cells, in registry mode the registry cell is the second input
+        let registry_mode = is_registry_mode(&eth_proof.replay_resist_outpoint);
+        let (bridge_cell, bridge_cell_data) = if registry_mode {
+            self.add_registry_mode_bridge_input(&mut helper, eth_proof)?
+        } else {
+            self.add_replay_resist_input(&mut helper, eth_proof)?
+        };
+        let registry_cell = if registry_mode {
+            Some(self.add_consumed_event_registry_input(
+                &mut helper,
+                config_path,
+                std::slice::from_ref(eth_proof),
+                &cell_data,
+            )?)
+        } else {
+            None
+        };
+
+        // 1 xt cells
+        add_mint_sudt_outputs(
+            &self.deployed_contracts,
+            &mut helper,
+            &from_lockscript,
+            eth_proof,
+            &bridge_cell_data,
+        )?;
+        // 2 create new bridge cell for user
+        helper.add_output(bridge_cell, bridge_cell_data);
+        // 3 the registry cell with the lock event consumed
+        if let Some((registry_cell, registry_cell_data, _, _)) = registry_cell.clone() {
+            helper.add_output(registry_cell, registry_cell_data);
+        }
+
+        // add witness
+        {
+            let compiled_merkle_proof = build_eth_spv_merkle_proof(
+                std::slice::from_ref(eth_proof),
+                &cell_data,
+                rocksdb_path,
+            )?;
+            let witness = EthWitness {
+                cell_dep_index_list: vec![0, 1, 2],
+                spv_proof: eth_proof.clone(),
+                compiled_merkle_proof,
+            }
+            .as_bytes();
+            let mut builder = helper.transaction.as_advanced_builder();
+            builder = builder.witness(witness.pack());
+            if let Some((_, _, registry_witness, _)) = registry_cell.clone() {
+                builder = builder.witness(registry_witness.pack());
+            }
+            helper.transaction = builder.build();
+        }
+        let tx =
+            self.supply_mint_tx_capacity(helper, from_lockscript, tx_fee, manual_capacity_cell)?;
+        Ok((
+            tx,
+            registry_cell.map(|(_, _, _, consumed_events)| consumed_events),
+        ))
+    }
+
+    /// generate a tx which mints the sudt of all the lock events of the same bridge lockscript.
+    /// the i-th input is the replay resist cell of the i-th event, the output 2i is the sudt cell
+    /// of its recipient, and the output 2i+1 is the fee sudt cell, or the new bridge cell if the
+    /// bridge fee is 0. the bridge cells whose fee is split across recipients are minted alone.
+    pub fn generate_eth_spv_batch_tx(
+        &mut self,
+        config_path: String,
+        from_lockscript: Script,
+        eth_proofs: &[ETHSPVProofJson],
+        manual_capacity_cell: Option<OutPoint>,
+        rocksdb_path: String,
+    ) -> Result<TransactionView> {
+        let first_proof = eth_proofs
+            .first()
+            .ok_or_else(|| anyhow!("no eth spv proof to mint"))?;
+        if eth_proofs.iter().any(|eth_proof| {
+            eth_proof.token != first_proof.token || eth_proof.eth_address != first_proof.eth_address
+        }) {
+            bail!("the lock events minted in one tx should have the same bridge lockscript");
+        }
+        if eth_proofs
+            .iter()
+            .any(|eth_proof| is_registry_mode(&eth_proof.replay_resist_outpoint))
+        {
+            bail!("the lock events in registry mode should be minted one by one");
+        }
+        let mut rng = rand::thread_rng();
+        let tx_fee = rng.gen_range(ONE_CKB / 4, ONE_CKB / 2);
+        let mut helper = TxHelper::default();
+        let cell_data = self.add_eth_spv_cell_deps(&mut helper, config_path)?;
+
+        // input bridge cells in the order of the lock events
+        let mut bridge_cells = vec![];
+        for eth_proof in eth_proofs {
+            bridge_cells.push(self.add_replay_resist_input(&mut helper, eth_proof)?);
+        }
+
+        // xt cells of each lock event, and the new bridge cells for user
+        add_mint_batch_outputs(
+            &self.deployed_contracts,
+            &mut helper,
+            &from_lockscript,
+            eth_proofs,
+            bridge_cells,
+        )?;
+
+        // add witnesses, the bridge typescript of the other inputs requires a batch witness too
+        {
+            let compiled_merkle_proof =
+                build_eth_spv_merkle_proof(eth_proofs, &cell_data, rocksdb_path)?;
+            let witness = EthBatchWitness {
+                cell_dep_index_list: vec![0, 1, 2],
+                spv_proofs: eth_proofs.to_vec(),
+                compiled_merkle_proof,
+            }
+            .as_bytes()?;
+            let mut builder = helper.transaction.as_advanced_builder();
+            builder = builder.witness(witness.pack());
+            for _ in 1..eth_proofs.len() {
+                let witness = WitnessArgs::new_builder()
+                    .lock(Some(MintTokenBatchWitness::default().as_bytes()).pack())
+                    .build();
+                builder = builder.witness(witness.as_bytes().pack());
+            }
+            helper.transaction = builder.build();
+        }
+        self.supply_mint_tx_capacity(helper, from_lockscript, tx_fee, manual_capacity_cell)
+    }
+
+    // add the light client cell, the governance cells and the bridge contracts as cell deps,
+    // return the data of the light client cell.
+    fn add_eth_spv_cell_deps(
+        &mut self,
+        helper: &mut TxHelper,
+        config_path: String,
+    ) -> Result<Bytes> {
         let config_path = tilde(config_path.as_str()).into_owned();
         let force_cli_config = ForceConfig::new(config_path.as_str())?;
         let deployed_contracts = force_cli_config
             .deployed_contracts
             .as_ref()
             .ok_or_else(|| anyhow!("contracts should be deployed"))?;
-        // add cell deps.
-        let cell_script = parse_cell(
-            deployed_contracts
-                .light_client_cell_script
-                .cell_script
-                .as_str(),
-        )?;
-        let cell = get_live_cell_by_typescript(&mut self.indexer_client, cell_script)
-            .map_err(|err| anyhow!(err))?
-            .ok_or_else(|| anyhow!("no cell found for cell dep"))?;
-        let cell_merkle_root = {
-            let cell_data = cell.output_data.as_bytes();
-            let eth_header_cell_data = ETHHeaderCellMerkleDataReader::new_unchecked(cell_data);
-            let merkle_root = eth_header_cell_data.merkle_root().raw_data();
-
-            let mut merkle_root_raw = [0u8; 32];
-            merkle_root_raw.copy_from_slice(merkle_root);
-            merkle_root_raw
-        };
+        // add cell deps. the bridge lockscript reads the light client cell, the bridge status
+        // cell and the token allowlist cell from the first three cell deps.
+        let mut cell_data = Bytes::default();
         let mut builder = helper.transaction.as_advanced_builder();
-        builder = builder.cell_dep(
-            CellDep::new_builder()
-                .out_point(cell.out_point.into())
-                .dep_type(DepType::Code.into())
-                .build(),
-        );
+        for (index, cell_script) in [
+            &deployed_contracts.light_client_cell_script,
+            &deployed_contracts.bridge_status_cell_script,
+            &deployed_contracts.token_allowlist_cell_script,
+        ]
+        .iter()
+        .enumerate()
+        {
+            let cell_script = parse_cell(cell_script.cell_script.as_str())?;
+            let cell = get_live_cell_by_typescript(&mut self.indexer_client, cell_script)
+                .map_err(|err| anyhow!(err))?
+                .ok_or_else(|| anyhow!("no cell found for cell dep {}", index))?;
+            if index == 0 {
+                cell_data = cell.output_data.clone().into_bytes();
+            }
+            builder = builder.cell_dep(
+                CellDep::new_builder()
+                    .out_point(cell.out_point.into())
+                    .dep_type(DepType::Code.into())
+                    .build(),
+            );
+        }
         helper.transaction = builder.build();
 
         let outpoints = vec![
@@ -470,17 +915,18 @@ impl Generator {
                 .clone(),
             self.deployed_contracts.sudt.outpoint.clone(),
         ];
-        self.add_cell_deps(&mut helper, outpoints)
+        self.add_cell_deps(helper, outpoints)
             .map_err(|err| anyhow!(err))?;
+        Ok(cell_data)
+    }
 
-        let lockscript = create_bridge_lockscript(
-            &self.deployed_contracts,
-            &eth_proof.token,
-            &eth_proof.eth_address,
-            // cell_script,
-        )?;
-
-        // input bridge cells
+    // add the replay resist cell of the lock event as input, return the cell and its data.
+    #[allow(clippy::mutable_key_type)]
+    fn add_replay_resist_input(
+        &mut self,
+        helper: &mut TxHelper,
+        eth_proof: &ETHSPVProofJson,
+    ) -> Result<(CellOutput, Bytes)> {
         let rpc_client = &mut self.rpc_client;
         let mut live_cell_cache: HashMap<(OutPoint, bool), (CellOutput, Bytes)> =
             Default::default();
@@ -526,108 +972,194 @@ impl Generator {
         // if owner_lock_script.raw_data() != from_lockscript.as_bytes() {
         //     bail!("only support use bridge cell we created as lock outpoint");
         // }
+        Ok((bridge_cell, bridge_cell_data))
+    }
 
-        // 1 xt cells
-        {
-            let recipient_lockscript = Script::from_slice(&eth_proof.recipient_lockscript)
-                .map_err(|e| {
-                    anyhow!(
-                        "irreparable error: molecule decode recipient lockscript error, {:?}",
-                        e
-                    )
-                })?;
+    // the typeless cells of the bridge lockscript, which mint the lock events in registry mode.
+    pub fn get_registry_mode_bridge_cells(
+        &mut self,
+        bridge_lockscript: Script,
+    ) -> Result<Vec<Cell>> {
+        get_all_live_cells_by_lockscript(&mut self.indexer_client, bridge_lockscript)
+            .map_err(|err| anyhow!(err))
+    }
 
-            let sudt_typescript_code_hash = hex::decode(&self.deployed_contracts.sudt.code_hash)?;
-            let code_hash = Byte32::from_slice(&sudt_typescript_code_hash)?;
-            let sudt_typescript = Script::new_builder()
-                .code_hash(code_hash)
-                .hash_type(self.deployed_contracts.sudt.hash_type.into())
-                .args(lockscript.calc_script_hash().as_bytes().pack())
-                .build();
+    // create a typeless cell of the bridge lockscript to mint the lock events in registry mode.
+    pub fn create_registry_mode_bridge_cell(
+        &mut self,
+        from_lockscript: Script,
+        bridge_lockscript: Script,
+    ) -> Result<TransactionView> {
+        let mut rng = rand::thread_rng();
+        let tx_fee = rng.gen_range(ONE_CKB / 2000, ONE_CKB / 1000);
+        let mut helper = TxHelper::default();
+        let output = CellOutput::new_builder().lock(bridge_lockscript).build();
+        helper.add_output_with_auto_capacity(output, Bytes::default());
+        helper
+            .supply_capacity(
+                &mut self.rpc_client,
+                &mut self.indexer_client,
+                from_lockscript,
+                &self.genesis_info,
+                tx_fee,
+                None,
+            )
+            .map_err(|err| anyhow!(err))
+    }
 
-            // recipient
-            let sudt_user_output = CellOutput::new_builder()
-                .type_(Some(sudt_typescript.clone()).pack())
-                .lock(recipient_lockscript)
-                .build();
-            let mut to_user_amount_data = (eth_proof.lock_amount - eth_proof.bridge_fee)
-                .to_le_bytes()
-                .to_vec();
-            to_user_amount_data.extend(eth_proof.sudt_extra_data.clone());
-            helper.add_output_with_auto_capacity(sudt_user_output, to_user_amount_data.into());
-            // fee
-            if eth_proof.bridge_fee != 0 {
-                let sudt_fee_output = CellOutput::new_builder()
-                    .type_(Some(sudt_typescript).pack())
-                    .lock(from_lockscript.clone())
-                    .build();
-                helper.add_output_with_auto_capacity(
-                    sudt_fee_output,
-                    eth_proof.bridge_fee.to_le_bytes().to_vec().into(),
-                );
-            }
-        }
-        // 2 create new bridge cell for user
-        helper.add_output(bridge_cell, bridge_cell_data);
+    // in registry mode the lock event is minted by a typeless cell of the bridge lockscript in the
+    // first input, so the registry cell lock checks the mint witness of it. return the cell and
+    // its data.
+    #[allow(clippy::mutable_key_type)]
+    fn add_registry_mode_bridge_input(
+        &mut self,
+        helper: &mut TxHelper,
+        eth_proof: &ETHSPVProofJson,
+    ) -> Result<(CellOutput, Bytes)> {
+        let bridge_lockscript = create_bridge_lockscript(
+            &self.deployed_contracts,
+            &eth_proof.token,
+            &eth_proof.eth_address,
+        )?;
+        let cell = self
+            .get_registry_mode_bridge_cells(bridge_lockscript)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("no bridge cell to mint the lock event in registry mode"))?;
 
-        // add witness
+        let rpc_client = &mut self.rpc_client;
+        let mut live_cell_cache: HashMap<(OutPoint, bool), (CellOutput, Bytes)> =
+            Default::default();
+        let mut get_live_cell_fn = |out_point: OutPoint, with_data: bool| {
+            get_live_cell_with_cache(&mut live_cell_cache, rpc_client, out_point, with_data)
+                .map(|(output, _)| output)
+        };
+        helper
+            .add_input(
+                OutPoint::from(cell.out_point.clone()),
+                None,
+                &mut get_live_cell_fn,
+                &self.genesis_info,
+                true,
+            )
+            .map_err(|err| anyhow!(err))?;
+        Ok((CellOutput::from(cell.output), cell.output_data.into_bytes()))
+    }
+
+    // add the consumed event registry cell pinned by the light client cell as input, and put the
+    // lock events into its smt. return the registry cell with the new root, its data, the witness
+    // with the proofs of the lock txs and the merkle proof of the lock events, and the lock events
+    // to commit to the local smt once the tx is committed.
+    #[allow(clippy::mutable_key_type)]
+    fn add_consumed_event_registry_input(
+        &mut self,
+        helper: &mut TxHelper,
+        config_path: String,
+        eth_proofs: &[ETHSPVProofJson],
+        light_client_data: &[u8],
+    ) -> Result<(CellOutput, Bytes, Bytes, ConsumedEvents)> {
+        let config_path = tilde(config_path.as_str()).into_owned();
+        let force_cli_config = ForceConfig::new(config_path.as_str())?;
+        let cell_script = parse_cell(
+            self.deployed_contracts
+                .consumed_event_registry_cell_script
+                .cell_script
+                .as_str(),
+        )?;
+        if cell_script.calc_script_hash().as_slice()
+            != parse_merkle_cell_consumed_event_registry_hash(light_client_data.to_vec())?
         {
-            let header: eth_spv_lib::eth_types::BlockHeader = rlp::decode(
-                hex::decode(eth_proof.header_data.as_str())
-                    .map_err(|e| anyhow!("hex decode eth proof header data error: {}", e))?
-                    .to_vec()
-                    .as_slice(),
+            bail!(
+                "the consumed event registry cell is not the one pinned by the light client cell"
+            );
+        }
+        let cell = get_live_cell_by_typescript(&mut self.indexer_client, cell_script)
+            .map_err(|err| anyhow!(err))?
+            .ok_or_else(|| anyhow!("consumed event registry cell not found"))?;
+
+        let rpc_client = &mut self.rpc_client;
+        let mut live_cell_cache: HashMap<(OutPoint, bool), (CellOutput, Bytes)> =
+            Default::default();
+        let mut get_live_cell_fn = |out_point: OutPoint, with_data: bool| {
+            get_live_cell_with_cache(&mut live_cell_cache, rpc_client, out_point, with_data)
+                .map(|(output, _)| output)
+        };
+        helper
+            .add_input(
+                OutPoint::from(cell.out_point.clone()),
+                None,
+                &mut get_live_cell_fn,
+                &self.genesis_info,
+                true,
             )
-            .map_err(|e| anyhow!("rlp decode eth proof header data error: {}", e))?;
-            let mut key = [0u8; 32];
-            let mut height = [0u8; 8];
-            height.copy_from_slice(header.number.to_le_bytes().as_ref());
-            key[..8].clone_from_slice(&height);
-
-            let rocksdb_store = rocksdb::RocksDBStore::open_readonly(rocksdb_path)?;
-            let smt_tree = rocksdb::SMT::new(cell_merkle_root.into(), rocksdb_store);
-
-            let block_hash: [u8; 32] = smt_tree
-                .get(&key.into())
-                .map_err(|err| anyhow::anyhow!(err))?
-                .into();
-            let merkle_proof = smt_tree
-                .merkle_proof(vec![key.into()])
-                .map_err(|err| anyhow::anyhow!(err))?;
-            let compiled_merkle_proof = merkle_proof
-                .compile(vec![(key.into(), block_hash.into())])
-                .map_err(|err| anyhow::anyhow!(err))?;
-
-            {
-                let mut compiled_leaves = vec![];
-
-                let mut leaf_index = [0u8; 32];
-                leaf_index[..8].copy_from_slice(header.number.to_le_bytes().as_ref());
-
-                let mut leaf_value = [0u8; 32];
-                leaf_value.copy_from_slice(header.hash.expect("header hash is none").0.as_bytes());
-
-                compiled_leaves.push((leaf_index.into(), leaf_value.into()));
-                if !compiled_merkle_proof
-                    .verify::<Blake2bHasher>(&cell_merkle_root.into(), compiled_leaves)
-                    .map_err(|e| anyhow!("get verify error: {}", e))?
-                {
-                    return Err(anyhow!("pre merkle proof verify fail"));
+            .map_err(|err| anyhow!(err))?;
+
+        let root = parse_consumed_event_registry_cell_data(cell.output_data.as_bytes().to_vec())?;
+        let eth_proofs = eth_proofs
+            .iter()
+            .filter(|eth_proof| is_registry_mode(&eth_proof.replay_resist_outpoint))
+            .collect::<Vec<_>>();
+        let keys = eth_proofs
+            .iter()
+            .map(|eth_proof| get_consumed_event_key(eth_proof))
+            .collect::<Result<Vec<_>>>()?;
+        let transaction_proofs = eth_proofs
+            .iter()
+            .map(|eth_proof| get_transaction_proof(eth_proof))
+            .collect::<Result<Vec<_>>>()?;
+        let db_path = force_cli_config.consumed_event_rocksdb_path;
+        let db_dir = tilde(db_path.as_str()).into_owned();
+        let rocksdb_store = if std::path::Path::new(db_dir.as_str()).exists() {
+            rocksdb::RocksDBStore::open(db_path.clone())?
+        } else {
+            rocksdb::RocksDBStore::new(db_path.clone())?
+        };
+        let (compiled_merkle_proof, new_root) =
+            rocksdb_store.consume_keys(root, &keys).map_err(|err| {
+                if err.to_string().contains("already consumed") {
+                    anyhow!("irreparable error: {:?}", err)
+                } else {
+                    err
                 }
-            }
+            })?;
 
-            let witness = EthWitness {
-                cell_dep_index_list: vec![0],
-                spv_proof: eth_proof.clone(),
-                compiled_merkle_proof: compiled_merkle_proof.0,
-            }
+        let cell_output = CellOutput::from(cell.output);
+        let output = CellOutput::new_builder()
+            .capacity(cell_output.capacity())
+            .lock(cell_output.lock())
+            .type_(cell_output.type_())
+            .build();
+        let output_data = ETHConsumedEventRegistryCellData::new_builder()
+            .consumed_events_root(basic::Byte32::from_slice(&new_root)?)
+            .build()
             .as_bytes();
-            helper.transaction = helper
-                .transaction
-                .as_advanced_builder()
-                .witness(witness.pack())
-                .build();
-        }
+        let registry_witness = ETHConsumedEventRegistryWitness::new_builder()
+            .transaction_proofs(
+                ETHTransactionProofVec::new_builder()
+                    .set(transaction_proofs)
+                    .build(),
+            )
+            .merkle_proof(compiled_merkle_proof.0.into())
+            .build();
+        let witness = WitnessArgs::new_builder()
+            .lock(Some(registry_witness.as_bytes()).pack())
+            .build()
+            .as_bytes();
+        let consumed_events = ConsumedEvents {
+            rocksdb_path: db_path,
+            root,
+            keys,
+        };
+        Ok((output, output_data, witness, consumed_events))
+    }
+
+    fn supply_mint_tx_capacity(
+        &mut self,
+        mut helper: TxHelper,
+        from_lockscript: Script,
+        tx_fee: u64,
+        manual_capacity_cell: Option<OutPoint>,
+    ) -> Result<TransactionView> {
         // build tx
         let tx = if let Some(manual_capacity_cell) = manual_capacity_cell {
             helper
@@ -751,9 +1283,20 @@ impl Generator {
         bridge_typescript: Script,
         bridge_lockscript: Script,
         bridge_fee: u128,
+        fee_recipients: Vec<(Script, u64)>,
         simple_typescript: bool,
         cell_num: usize,
     ) -> Result<TransactionView> {
+        let basis_points = fee_recipients
+            .iter()
+            .map(|(_, basis_points)| *basis_points)
+            .collect::<Vec<_>>();
+        if !fee_recipients.is_empty() && split_fee(bridge_fee, &basis_points).is_none() {
+            bail!(
+                "the basis points of the fee recipients should sum to {}",
+                BASIS_POINTS_DENOMINATOR
+            );
+        }
         let mut tx_helper = TxHelper::default();
         // add cell deps
         let mut outpoints = vec![self.deployed_contracts.bridge_lockscript.outpoint.clone()];
@@ -773,6 +1316,21 @@ impl Generator {
         let bridge_data = ETHBridgeTypeData::new_builder()
             .owner_lock_script(owner_lockscript.as_slice().to_vec().into())
             .fee(bridge_fee.into())
+            .fee_recipients(
+                ETHBridgeFeeRecipientVec::new_builder()
+                    .set(
+                        fee_recipients
+                            .into_iter()
+                            .map(|(lockscript, basis_points)| {
+                                ETHBridgeFeeRecipient::new_builder()
+                                    .lock_script(lockscript.as_slice().to_vec().into())
+                                    .basis_points(basis_points.into())
+                                    .build()
+                            })
+                            .collect(),
+                    )
+                    .build(),
+            )
             .build();
         // build output
         let output = CellOutput::new_builder()
@@ -1216,3 +1774,330 @@ impl Generator {
         Ok(all_recipient_capacity)
     }
 }
+
+// add the recipient sudt cell of the lock event and the fee sudt cells, return the number of
+// the fee sudt cells. the fee goes to the relayer if the bridge cell has no bridge data,
+// otherwise it goes to the owner or the fee recipients of the bridge cell.
+fn add_mint_sudt_outputs(
+    deployed_contracts: &DeployedContracts,
+    helper: &mut TxHelper,
+    from_lockscript: &Script,
+    eth_proof: &ETHSPVProofJson,
+    bridge_cell_data: &Bytes,
+) -> Result<usize> {
+    let lockscript = create_bridge_lockscript(
+        deployed_contracts,
+        &eth_proof.token,
+        &eth_proof.eth_address,
+        // cell_script,
+    )?;
+    let recipient_lockscript =
+        Script::from_slice(&eth_proof.recipient_lockscript).map_err(|e| {
+            anyhow!(
+                "irreparable error: molecule decode recipient lockscript error, {:?}",
+                e
+            )
+        })?;
+    let fee_cells = if bridge_cell_data.is_empty() {
+        vec![(from_lockscript.clone(), eth_proof.bridge_fee)]
+    } else {
+        let bridge_data = parse_bridge_type_data(bridge_cell_data.as_ref())
+            .map_err(|e| anyhow!("irreparable error: invalid bridge cell data, {:?}", e))?;
+        let mut fee_cells = vec![];
+        for (lockscript, amount) in fee_outputs(&bridge_data).ok_or_else(|| {
+            anyhow!("irreparable error: invalid basis points of the bridge cell fee recipients")
+        })? {
+            let lockscript = Script::from_slice(lockscript.as_ref()).map_err(|e| {
+                anyhow!(
+                    "irreparable error: invalid fee recipient lockscript, {:?}",
+                    e
+                )
+            })?;
+            fee_cells.push((lockscript, amount));
+        }
+        fee_cells
+    };
+    let fee_cells = fee_cells
+        .into_iter()
+        .filter(|(_, amount)| *amount != 0)
+        .collect::<Vec<_>>();
+    let total_fee = fee_cells.iter().map(|(_, amount)| amount).sum::<u128>();
+    let to_user_amount = eth_proof
+        .lock_amount
+        .checked_sub(total_fee)
+        .ok_or_else(|| anyhow!("irreparable error: bridge fee exceeds the lock amount"))?;
+
+    let sudt_typescript_code_hash = hex::decode(&deployed_contracts.sudt.code_hash)?;
+    let code_hash = Byte32::from_slice(&sudt_typescript_code_hash)?;
+    let sudt_typescript = Script::new_builder()
+        .code_hash(code_hash)
+        .hash_type(deployed_contracts.sudt.hash_type.into())
+        .args(lockscript.calc_script_hash().as_bytes().pack())
+        .build();
+
+    // recipient
+    let sudt_user_output = CellOutput::new_builder()
+        .type_(Some(sudt_typescript.clone()).pack())
+        .lock(recipient_lockscript)
+        .build();
+    let mut to_user_amount_data = to_user_amount.to_le_bytes().to_vec();
+    to_user_amount_data.extend(eth_proof.sudt_extra_data.clone());
+    helper.add_output_with_auto_capacity(sudt_user_output, to_user_amount_data.into());
+    // fee
+    for (fee_lockscript, fee) in fee_cells.iter() {
+        let sudt_fee_output = CellOutput::new_builder()
+            .type_(Some(sudt_typescript.clone()).pack())
+            .lock(fee_lockscript.clone())
+            .build();
+        helper.add_output_with_auto_capacity(sudt_fee_output, fee.to_le_bytes().to_vec().into());
+    }
+    Ok(fee_cells.len())
+}
+
+// add the outputs of the lock events minted in one tx in the order checked by the bridge
+// lockscript: the output 2i is the recipient sudt cell of the i-th lock event, and the output 2i+1
+// is its fee sudt cell, or its new bridge cell if it has no fee. the new bridge cells of the lock
+// events with fee follow.
+fn add_mint_batch_outputs(
+    deployed_contracts: &DeployedContracts,
+    helper: &mut TxHelper,
+    from_lockscript: &Script,
+    eth_proofs: &[ETHSPVProofJson],
+    bridge_cells: Vec<(CellOutput, Bytes)>,
+) -> Result<()> {
+    let mut remaining_bridge_cells = vec![];
+    for (eth_proof, bridge_cell) in eth_proofs.iter().zip(bridge_cells) {
+        let fee_cell_count = add_mint_sudt_outputs(
+            deployed_contracts,
+            helper,
+            from_lockscript,
+            eth_proof,
+            &bridge_cell.1,
+        )?;
+        if fee_cell_count > 1 {
+            bail!("the bridge cell whose fee is split should be minted alone");
+        }
+        if fee_cell_count == 0 {
+            helper.add_output(bridge_cell.0, bridge_cell.1);
+        } else {
+            remaining_bridge_cells.push(bridge_cell);
+        }
+    }
+    for (bridge_cell, bridge_cell_data) in remaining_bridge_cells {
+        helper.add_output(bridge_cell, bridge_cell_data);
+    }
+    Ok(())
+}
+
+// the key of the lock event in the consumed event registry, which is the lock tx hash and the log
+// index, so the lock tx included again in another block after a reorg has the same key.
+fn get_consumed_event_key(eth_proof: &ETHSPVProofJson) -> Result<[u8; 32]> {
+    if eth_proof.transaction_data.is_empty() {
+        bail!("the lock event in registry mode has no transaction proof");
+    }
+    let transaction_data = hex::decode(clear_0x(&eth_proof.transaction_data))
+        .map_err(|e| anyhow!("hex decode eth proof transaction data error: {}", e))?;
+    Ok(consumed_event_key(
+        &keccak256(&transaction_data),
+        eth_proof.log_index,
+    ))
+}
+
+// the proof of the lock tx in the transactions trie, the registry cell lock verifies it to get the
+// lock tx hash.
+fn get_transaction_proof(eth_proof: &ETHSPVProofJson) -> Result<ETHTransactionProof> {
+    let mut proof: Vec<basic::Bytes> = vec![];
+    for node in eth_proof.transaction_proof.iter() {
+        proof.push(
+            hex::decode(clear_0x(node))
+                .map_err(|e| anyhow!("hex decode eth proof transaction proof error: {}", e))?
+                .into(),
+        );
+    }
+    Ok(ETHTransactionProof::new_builder()
+        .transaction_data(hex::decode(clear_0x(&eth_proof.transaction_data))?.into())
+        .proof(BytesVec::new_builder().set(proof).build())
+        .build())
+}
+
+// build the merkle proof of the headers of the lock events against the root of the light client
+// cell dep, which may be older than the local smt if the relayer has moved the cell since.
+fn build_eth_spv_merkle_proof(
+    eth_proofs: &[ETHSPVProofJson],
+    cell_data: &[u8],
+    rocksdb_path: String,
+) -> Result<Vec<u8>> {
+    let (cell_start_height, _, _) = parse_merkle_cell_data(cell_data.to_vec())?;
+    let cell_confirm = parse_merkle_cell_confirm(cell_data.to_vec())?;
+    let cell_roots = parse_merkle_cell_roots(cell_data.to_vec())?;
+    // several lock events may be in the same block, its header is only proved once.
+    let mut leaves: Vec<(u64, [u8; 32])> = vec![];
+    for eth_proof in eth_proofs {
+        let header: eth_spv_lib::eth_types::BlockHeader = rlp::decode(
+            hex::decode(eth_proof.header_data.as_str())
+                .map_err(|e| anyhow!("hex decode eth proof header data error: {}", e))?
+                .to_vec()
+                .as_slice(),
+        )
+        .map_err(|e| anyhow!("rlp decode eth proof header data error: {}", e))?;
+        if header.number < cell_start_height {
+            bail!(
+                "irreparable error: header {} is pruned from light client, start height {}",
+                header.number,
+                cell_start_height
+            );
+        }
+        let mut leaf_value = [0u8; 32];
+        leaf_value.copy_from_slice(header.hash.expect("header hash is none").0.as_bytes());
+        match leaves.iter().find(|(number, _)| *number == header.number) {
+            Some((_, value)) if *value != leaf_value => bail!(
+                "irreparable error: lock events in different headers of the same height {}",
+                header.number
+            ),
+            Some(_) => {}
+            None => leaves.push((header.number, leaf_value)),
+        }
+    }
+    leaves.sort_by_key(|(number, _)| *number);
+    let heights = leaves.iter().map(|(number, _)| *number).collect::<Vec<_>>();
+
+    let rocksdb_store = rocksdb::RocksDBStore::open_readonly(rocksdb_path)?;
+    // the lockscript accepts the proof against any root of the light client cell, pick the latest
+    // root whose smt snapshot is kept in local db and confirms all the headers.
+    let max_height = heights.last().copied().unwrap_or_default();
+    let mut cell_merkle_root = None;
+    for (root, latest_height) in cell_roots {
+        if max_height.saturating_add(cell_confirm) > latest_height {
+            continue;
+        }
+        if rocksdb_store.has_snapshot(root)? {
+            cell_merkle_root = Some(root);
+            break;
+        }
+    }
+    let cell_merkle_root = cell_merkle_root
+        .ok_or_else(|| anyhow!("no root of the light client cell can be proved by local smt"))?;
+    let compiled_merkle_proof = rocksdb_store.merkle_proof_for(cell_merkle_root, &heights)?;
+
+    let mut compiled_leaves = vec![];
+    for (number, value) in leaves {
+        let mut leaf_index = [0u8; 32];
+        leaf_index[..8].copy_from_slice(number.to_le_bytes().as_ref());
+        compiled_leaves.push((leaf_index.into(), value.into()));
+    }
+    if !compiled_merkle_proof
+        .verify::<Blake2bHasher>(&cell_merkle_root.into(), compiled_leaves)
+        .map_err(|e| anyhow!("get verify error: {}", e))?
+    {
+        return Err(anyhow!("pre merkle proof verify fail"));
+    }
+    Ok(compiled_merkle_proof.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_lockscript(arg: u8) -> Script {
+        Script::new_builder()
+            .args(Bytes::from(vec![arg]).pack())
+            .build()
+    }
+
+    fn new_eth_proof(recipient: u8, lock_amount: u128, bridge_fee: u128) -> ETHSPVProofJson {
+        ETHSPVProofJson {
+            token: H160::from([1u8; 20]),
+            eth_address: H160::from([2u8; 20]),
+            lock_amount,
+            bridge_fee,
+            recipient_lockscript: new_lockscript(recipient).as_slice().to_vec(),
+            sudt_extra_data: vec![recipient],
+            ..Default::default()
+        }
+    }
+
+    fn sudt_amount(data: &[u8]) -> u128 {
+        let mut amount = [0u8; 16];
+        amount.copy_from_slice(&data[..16]);
+        u128::from_le_bytes(amount)
+    }
+
+    #[test]
+    fn test_batch_mint_outputs_order() {
+        let mut deployed_contracts = DeployedContracts::default();
+        deployed_contracts.bridge_lockscript.code_hash = hex::encode([3u8; 32]);
+        deployed_contracts.sudt.code_hash = hex::encode([4u8; 32]);
+        deployed_contracts.light_client_cell_script.cell_script =
+            hex::encode(Script::default().as_slice());
+        let relayer_lockscript = new_lockscript(0xff);
+        let eth_proofs = vec![
+            new_eth_proof(0, 100, 10),
+            new_eth_proof(1, 200, 0),
+            new_eth_proof(2, 300, 5),
+        ];
+        let bridge_cells = (0..eth_proofs.len())
+            .map(|index| {
+                let output = CellOutput::new_builder()
+                    .lock(new_lockscript(0x10 + index as u8))
+                    .build();
+                (output, Bytes::new())
+            })
+            .collect::<Vec<_>>();
+
+        let mut helper = TxHelper::default();
+        add_mint_batch_outputs(
+            &deployed_contracts,
+            &mut helper,
+            &relayer_lockscript,
+            &eth_proofs,
+            bridge_cells.clone(),
+        )
+        .unwrap();
+        let tx = helper.transaction;
+        let outputs = tx.outputs().into_iter().collect::<Vec<_>>();
+        let outputs_data = tx.outputs_data().into_iter().collect::<Vec<_>>();
+        assert_eq!(outputs.len(), 2 * eth_proofs.len() + 2);
+
+        let sudt_typescript = outputs[0].type_().to_opt().unwrap();
+        let mut minted = 0u128;
+        for (index, (output, data)) in outputs.iter().zip(outputs_data.iter()).enumerate() {
+            let data = data.raw_data();
+            if output.type_().to_opt() == Some(sudt_typescript.clone()) {
+                minted += sudt_amount(&data);
+            }
+            // the rule of the bridge lockscript: the output 2i is the recipient sudt cell of the
+            // i-th lock event with the sudt extra data of it.
+            if index % 2 != 0 || index / 2 >= eth_proofs.len() {
+                continue;
+            }
+            let eth_proof = &eth_proofs[index / 2];
+            assert_eq!(output.type_().to_opt(), Some(sudt_typescript.clone()));
+            assert_eq!(
+                output.lock().as_slice(),
+                eth_proof.recipient_lockscript.as_slice()
+            );
+            assert_eq!(
+                sudt_amount(&data),
+                eth_proof.lock_amount - eth_proof.bridge_fee
+            );
+            assert_eq!(&data[16..], eth_proof.sudt_extra_data.as_slice());
+        }
+        assert_eq!(
+            minted,
+            eth_proofs
+                .iter()
+                .map(|proof| proof.lock_amount)
+                .sum::<u128>()
+        );
+
+        // the output 2i+1 is the fee sudt cell, or the bridge cell of the lock event without fee
+        assert_eq!(outputs[1].lock(), relayer_lockscript);
+        assert_eq!(sudt_amount(&outputs_data[1].raw_data()), 10);
+        assert_eq!(outputs[3].lock(), bridge_cells[1].0.lock());
+        assert!(outputs[3].type_().to_opt().is_none());
+        assert_eq!(outputs[5].lock(), relayer_lockscript);
+        assert_eq!(sudt_amount(&outputs_data[5].raw_data()), 5);
+        assert_eq!(outputs[6].lock(), bridge_cells[0].0.lock());
+        assert_eq!(outputs[7].lock(), bridge_cells[2].0.lock());
+    }
+}
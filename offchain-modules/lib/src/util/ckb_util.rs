@@ -1,7 +1,7 @@
 use crate::util::ckb_tx_generator::{Generator, CONFIRM};
 use crate::util::config::{DeployedContracts, ForceConfig, OutpointConf};
 use crate::util::eth_proof_helper::{DoubleNodeWithMerkleProofJson, Witness};
-use crate::util::eth_util::{convert_to_header_rlp, decode_block_header};
+use crate::util::eth_util::{convert_to_header_rlp, decode_block_header, HeaderExtension};
 use anyhow::{anyhow, bail, Result};
 use ckb_sdk::{Address, AddressPayload, SECP256K1};
 use ckb_types::packed::{Byte, ScriptReader, WitnessArgs};
@@ -16,10 +16,14 @@ use faster_hex::hex_decode;
 use force_eth_types::eth_recipient_cell::ETHAddress;
 use force_eth_types::generated::basic::BytesVec;
 use force_eth_types::generated::eth_bridge_lock_cell::ETHBridgeLockArgs;
+use force_eth_types::generated::eth_bridge_status_cell::ETHBridgeStatusCellDataReader;
+use force_eth_types::generated::eth_consumed_event_registry_cell::ETHConsumedEventRegistryCellDataReader;
 use force_eth_types::generated::eth_header_cell::{
-    DoubleNodeWithMerkleProof, ETHHeaderCellDataReader, ETHHeaderCellMerkleDataReader,
-    ETHHeaderInfo, ETHHeaderInfoReader, MerkleProof,
+    DoubleNodeWithMerkleProof, ETHHeaderCellDataReader, ETHHeaderCellMerkleData,
+    ETHHeaderCellMerkleDataReader, ETHHeaderInfo, ETHHeaderInfoReader, ETHHeaderRoot,
+    ETHHeaderRootHistory, MerkleProof,
 };
+use force_eth_types::generated::eth_token_allowlist_cell::ETHTokenAllowlistCellDataReader;
 use force_eth_types::generated::{basic, witness};
 use force_sdk::cell_collector::get_live_cell_by_typescript;
 use rlp::Rlp;
@@ -196,6 +200,84 @@ pub fn get_eth_client_tip_number(
     Ok(latest_height)
 }
 
+// the latest height on the light client minus the confirm stored in its cell data,
+// headers above this height can not be used to mint yet.
+pub fn get_eth_client_confirmed_number(
+    generator: &mut Generator,
+    client_cell_script: String,
+) -> Result<u64> {
+    let script = parse_cell(client_cell_script.as_str())
+        .map_err(|e| anyhow!("get typescript fail {:?}", e))?;
+    let cell = get_live_cell_by_typescript(&mut generator.indexer_client, script)
+        .map_err(|e| anyhow!("get live cell fail: {}", e))?
+        .ok_or_else(|| anyhow!("eth header cell not exist"))?;
+    let data = cell.output_data.as_bytes().to_vec();
+    let (_, latest_height, _) = parse_merkle_cell_data(data.clone())?;
+    let confirm = parse_merkle_cell_confirm(data)?;
+    Ok(latest_height.saturating_sub(confirm))
+}
+
+// mints are refused by the bridge lockscript while the bridge status cell is paused.
+pub fn get_bridge_paused(
+    generator: &mut Generator,
+    bridge_status_cell_script: String,
+) -> Result<bool> {
+    let script = parse_cell(bridge_status_cell_script.as_str())
+        .map_err(|e| anyhow!("get typescript fail {:?}", e))?;
+    let cell = get_live_cell_by_typescript(&mut generator.indexer_client, script)
+        .map_err(|e| anyhow!("get live cell fail: {}", e))?
+        .ok_or_else(|| anyhow!("bridge status cell not exist"))?;
+    parse_bridge_status_cell_data(cell.output_data.as_bytes().to_vec())
+}
+
+pub fn parse_bridge_status_cell_data(data: Vec<u8>) -> Result<bool> {
+    ETHBridgeStatusCellDataReader::verify(&data, false).map_err(|err| anyhow!(err))?;
+    let paused = ETHBridgeStatusCellDataReader::new_unchecked(&data)
+        .paused()
+        .as_slice()[0];
+    Ok(paused != 0)
+}
+
+// only the tokens in the token allowlist cell can be minted by the bridge lockscript.
+pub fn is_token_allowed(
+    generator: &mut Generator,
+    token_allowlist_cell_script: String,
+    token: &H160,
+) -> Result<bool> {
+    let script = parse_cell(token_allowlist_cell_script.as_str())
+        .map_err(|e| anyhow!("get typescript fail {:?}", e))?;
+    let cell = get_live_cell_by_typescript(&mut generator.indexer_client, script)
+        .map_err(|e| anyhow!("get live cell fail: {}", e))?
+        .ok_or_else(|| anyhow!("token allowlist cell not exist"))?;
+    let tokens = parse_token_allowlist_cell_data(cell.output_data.as_bytes().to_vec())?;
+    Ok(tokens.contains(token))
+}
+
+pub fn parse_token_allowlist_cell_data(data: Vec<u8>) -> Result<Vec<H160>> {
+    ETHTokenAllowlistCellDataReader::verify(&data, false).map_err(|err| anyhow!(err))?;
+    Ok(ETHTokenAllowlistCellDataReader::new_unchecked(&data)
+        .iter()
+        .map(|token| H160::from_slice(token.as_slice()))
+        .collect())
+}
+
+pub fn parse_consumed_event_registry_cell_data(data: Vec<u8>) -> Result<[u8; 32]> {
+    ETHConsumedEventRegistryCellDataReader::verify(&data, false).map_err(|err| anyhow!(err))?;
+    let mut root = [0u8; 32];
+    root.copy_from_slice(
+        ETHConsumedEventRegistryCellDataReader::new_unchecked(&data)
+            .consumed_events_root()
+            .raw_data(),
+    );
+    Ok(root)
+}
+
+pub fn get_cell_script_hash(cell: &str) -> Result<[u8; 32]> {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(parse_cell(cell)?.calc_script_hash().as_slice());
+    Ok(hash)
+}
+
 pub fn parse_cell(cell: &str) -> Result<Script> {
     let cell_bytes =
         hex::decode(cell).map_err(|e| anyhow!("cell shoule be hex format, err: {}", e))?;
@@ -257,7 +339,7 @@ pub fn parse_main_chain_headers(data: Vec<u8>) -> Result<(Vec<BlockHeader>, Vec<
             ETHHeaderInfoReader::verify(&header_raw, false).map_err(|err| anyhow!(err))?;
             let header_info_header = ETHHeaderInfoReader::new_unchecked(header_raw);
             let rlp = Rlp::new(header_info_header.header().raw_data());
-            let header: BlockHeader = decode_block_header(&rlp).map_err(|err| anyhow!(err))?;
+            let (header, _) = decode_block_header(&rlp).map_err(|err| anyhow!(err))?;
             un_confirmed.push(header);
         } else {
             confirmed.push(main_reader.get_unchecked(i).raw_data().to_vec())
@@ -285,6 +367,117 @@ pub fn parse_merkle_cell_data(data: Vec<u8>) -> Result<(u64, u64, [u8; 32])> {
     Ok((start_height, last_cell_latest_height, merkle_root))
 }
 
+pub fn parse_merkle_cell_confirm(data: Vec<u8>) -> Result<u64> {
+    ETHHeaderCellMerkleDataReader::verify(&data, false).map_err(|err| anyhow!(err))?;
+    let eth_cell_data_reader = ETHHeaderCellMerkleDataReader::new_unchecked(&data);
+
+    let mut confirm_raw = [0u8; 8];
+    confirm_raw.copy_from_slice(eth_cell_data_reader.confirm().raw_data());
+    Ok(u64::from_le_bytes(confirm_raw))
+}
+
+pub fn parse_merkle_cell_bridge_status_hash(data: Vec<u8>) -> Result<[u8; 32]> {
+    ETHHeaderCellMerkleDataReader::verify(&data, false).map_err(|err| anyhow!(err))?;
+    let eth_cell_data_reader = ETHHeaderCellMerkleDataReader::new_unchecked(&data);
+
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(
+        eth_cell_data_reader
+            .bridge_status_typescript_hash()
+            .raw_data(),
+    );
+    Ok(hash)
+}
+
+pub fn parse_merkle_cell_token_allowlist_hash(data: Vec<u8>) -> Result<[u8; 32]> {
+    ETHHeaderCellMerkleDataReader::verify(&data, false).map_err(|err| anyhow!(err))?;
+    let eth_cell_data_reader = ETHHeaderCellMerkleDataReader::new_unchecked(&data);
+
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(
+        eth_cell_data_reader
+            .token_allowlist_typescript_hash()
+            .raw_data(),
+    );
+    Ok(hash)
+}
+
+pub fn parse_merkle_cell_consumed_event_registry_hash(data: Vec<u8>) -> Result<[u8; 32]> {
+    ETHHeaderCellMerkleDataReader::verify(&data, false).map_err(|err| anyhow!(err))?;
+    let eth_cell_data_reader = ETHHeaderCellMerkleDataReader::new_unchecked(&data);
+
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(
+        eth_cell_data_reader
+            .consumed_event_registry_typescript_hash()
+            .raw_data(),
+    );
+    Ok(hash)
+}
+
+// the current root of the light client cell and the previous roots in its history, the latest
+// first. the empty slots of history are skipped.
+pub fn parse_merkle_cell_roots(data: Vec<u8>) -> Result<Vec<([u8; 32], u64)>> {
+    ETHHeaderCellMerkleDataReader::verify(&data, false).map_err(|err| anyhow!(err))?;
+    let eth_cell_data_reader = ETHHeaderCellMerkleDataReader::new_unchecked(&data);
+    let history = eth_cell_data_reader.history();
+
+    let mut roots = vec![];
+    for (merkle_root, latest_height) in vec![
+        (
+            eth_cell_data_reader.merkle_root(),
+            eth_cell_data_reader.latest_height(),
+        ),
+        (history.nth0().merkle_root(), history.nth0().latest_height()),
+        (history.nth1().merkle_root(), history.nth1().latest_height()),
+        (history.nth2().merkle_root(), history.nth2().latest_height()),
+        (history.nth3().merkle_root(), history.nth3().latest_height()),
+    ] {
+        let mut merkle_root_raw = [0u8; 32];
+        merkle_root_raw.copy_from_slice(merkle_root.raw_data());
+        let mut latest_height_raw = [0u8; 8];
+        latest_height_raw.copy_from_slice(latest_height.raw_data());
+        let latest_height = u64::from_le_bytes(latest_height_raw);
+        if latest_height != 0 {
+            roots.push((merkle_root_raw, latest_height));
+        }
+    }
+    Ok(roots)
+}
+
+// the history of the light client cell after its root is updated, the root of the last cell is
+// pushed to the front of the history if the root is changed, which is required by the typescript.
+pub fn rotate_merkle_cell_history(
+    last_data: Vec<u8>,
+    merkle_root: &[u8],
+    latest_height: u64,
+) -> Result<ETHHeaderRootHistory> {
+    if last_data.is_empty() {
+        return Ok(ETHHeaderRootHistory::default());
+    }
+    let last_data = ETHHeaderCellMerkleData::from_slice(&last_data).map_err(|err| anyhow!(err))?;
+    let last_history = last_data.history();
+    let mut last_latest_height_raw = [0u8; 8];
+    last_latest_height_raw.copy_from_slice(last_data.latest_height().raw_data().as_ref());
+    if last_data.merkle_root().raw_data().as_ref() == merkle_root
+        && u64::from_le_bytes(last_latest_height_raw) == latest_height
+    {
+        return Ok(last_history);
+    }
+    let last_root = ETHHeaderRoot::new_builder()
+        .merkle_root(last_data.merkle_root())
+        .latest_height(last_data.latest_height())
+        .build();
+    Ok(ETHHeaderRootHistory::new_builder()
+        .set([
+            last_root,
+            last_history.nth0(),
+            last_history.nth1(),
+            last_history.nth2(),
+        ])
+        .build())
+}
+
 pub fn create_bridge_lockscript(
     deployed_contracts: &DeployedContracts,
     token: &H160,
@@ -315,16 +508,34 @@ pub fn create_bridge_lockscript(
     Ok(lockscript)
 }
 
+// the consumed event registry cell is locked by the bridge lockscript whose args are the light
+// client typescript hash only.
+pub fn create_registry_lockscript(deployed_contracts: &DeployedContracts) -> Result<Script> {
+    let cell_script = parse_cell(
+        deployed_contracts
+            .light_client_cell_script
+            .cell_script
+            .as_str(),
+    )?;
+    let lockscript_code_hash = hex::decode(&deployed_contracts.bridge_lockscript.code_hash)?;
+    let lockscript = Script::new_builder()
+        .code_hash(Byte32::from_slice(&lockscript_code_hash)?)
+        .hash_type(deployed_contracts.bridge_lockscript.hash_type.into())
+        .args(cell_script.calc_script_hash().as_bytes().pack())
+        .build();
+    Ok(lockscript)
+}
+
 pub fn handle_unconfirmed_headers(
     input_tail_raw: &[u8],
-    headers: &[Block<ethereum_types::H256>],
+    headers: &[(Block<ethereum_types::H256>, HeaderExtension)],
 ) -> Result<Vec<ETHHeaderInfo>> {
     let mut header_infos = vec![];
     ETHHeaderInfoReader::verify(&input_tail_raw, false).map_err(|err| anyhow!(err))?;
     let input_tail_reader = ETHHeaderInfoReader::new_unchecked(&input_tail_raw);
     let mut total_difficulty = to_u64(input_tail_reader.total_difficulty().raw_data());
-    for item in headers {
-        let header_rlp = convert_to_header_rlp(item).unwrap();
+    for (item, extension) in headers {
+        let header_rlp = convert_to_header_rlp(item, extension)?;
         total_difficulty = item.difficulty.as_u64().add(total_difficulty);
         let header_info = ETHHeaderInfo::new_builder()
             .header(hex::decode(header_rlp)?.into())
@@ -358,6 +569,12 @@ pub struct ETHSPVProofJson {
     pub sudt_extra_data: Vec<u8>,
     pub eth_address: H160,
     pub sender: H160,
+    // the lock tx and its proof in the transactions trie, only for the lock events in registry
+    // mode, which are identified by the lock tx hash.
+    #[serde(default)]
+    pub transaction_data: String,
+    #[serde(default)]
+    pub transaction_proof: Vec<String>,
 }
 
 impl TryFrom<ETHSPVProofJson> for witness::ETHSPVProof {
@@ -412,3 +629,29 @@ impl EthWitness {
         witness.as_bytes()
     }
 }
+
+#[derive(Clone)]
+pub struct EthBatchWitness {
+    pub cell_dep_index_list: Vec<u8>,
+    pub spv_proofs: Vec<ETHSPVProofJson>,
+    pub compiled_merkle_proof: Vec<u8>,
+}
+
+impl EthBatchWitness {
+    pub fn as_bytes(&self) -> Result<Bytes> {
+        let mut spv_proofs: Vec<basic::Bytes> = vec![];
+        for spv_proof in self.spv_proofs.iter() {
+            let spv_proof: witness::ETHSPVProof = spv_proof.clone().try_into()?;
+            spv_proofs.push(spv_proof.as_slice().to_vec().into());
+        }
+        let witness_data = witness::MintTokenBatchWitness::new_builder()
+            .spv_proofs(BytesVec::new_builder().set(spv_proofs).build())
+            .cell_dep_index_list(self.cell_dep_index_list.clone().into())
+            .merkle_proof(self.compiled_merkle_proof.clone().into())
+            .build();
+        let witness = WitnessArgs::new_builder()
+            .lock(Some(witness_data.as_bytes()).pack())
+            .build();
+        Ok(witness.as_bytes())
+    }
+}
@@ -1,7 +1,14 @@
-use anyhow::{anyhow, Result};
+use crate::util::rocksdb::DEFAULT_SNAPSHOT_LIMIT;
+use anyhow::{anyhow, bail, Result};
 use config::{Config, ConfigError, Environment, File};
+use force_sdk::constants::{
+    BURN_TX_MAX_NUM, BURN_TX_MAX_WAITING_BLOCKS, MAINNET_CKB_WAITING_BLOCKS,
+    TESTNET_CKB_WAITING_BLOCKS,
+};
 use serde_derive::{Deserialize, Serialize};
 use shellexpand::tilde;
+use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::path::PathBuf;
 use toml::value::{Table, Value};
 
@@ -82,9 +89,13 @@ pub async fn init_config(
         project_path,
         eth_rocksdb_path,
         ckb_rocksdb_path,
+        eth_smt_snapshot_limit: default_eth_smt_snapshot_limit(),
+        consumed_event_rocksdb_path: default_consumed_event_rocksdb_path(),
         default_network,
         networks_config,
         deployed_contracts: None,
+        token_amount_limits: Default::default(),
+        dapp_admin_token: None,
     };
     force_cli_config.write(config_path.as_str())
 }
@@ -94,19 +105,215 @@ pub struct ForceConfig {
     pub project_path: String,
     pub eth_rocksdb_path: String,
     pub ckb_rocksdb_path: String,
+    // The number of recent eth smt roots kept in the rocksdb, so that the mint proofs can still be
+    // built against the root of the light client cell dep after the relayer moves on.
+    #[serde(default = "default_eth_smt_snapshot_limit")]
+    pub eth_smt_snapshot_limit: usize,
+    // the smt of the lock events minted with the consumed event registry
+    #[serde(default = "default_consumed_event_rocksdb_path")]
+    pub consumed_event_rocksdb_path: String,
     pub default_network: String,
     pub deployed_contracts: Option<DeployedContracts>,
+    #[serde(default)]
+    pub token_amount_limits: TokenAmountLimits,
+    // The token to authorize the admin api of the dapp server, it is disabled if not set.
+    #[serde(default)]
+    pub dapp_admin_token: Option<String>,
     #[serde(serialize_with = "toml::ser::tables_last")]
     pub networks_config: Table,
 }
 
+fn default_eth_smt_snapshot_limit() -> usize {
+    DEFAULT_SNAPSHOT_LIMIT
+}
+
+fn default_consumed_event_rocksdb_path() -> String {
+    "~/.force-bridge/consumed-event-rocksdb".to_string()
+}
+
 #[derive(Deserialize, Serialize, Default, Debug, Clone)]
 pub struct NetworkConfig {
     pub ckb_rpc_url: String,
     pub ckb_indexer_url: String,
     pub ethereum_rpc_url: String,
+    pub ethereum_rpc_urls: Vec<String>,
+    pub ethereum_rpc_quorum: usize,
     pub ckb_private_keys: Vec<Value>,
     pub ethereum_private_keys: Vec<Value>,
+    pub ckb_relay_policy: CKBRelayPolicy,
+    pub eth_tx_policy: EthTxPolicy,
+}
+
+// When the ckb relayer submits the ckb headers to the ethereum chain.
+// It relays when `waiting_blocks` blocks passed since the last submission, or when
+// `burn_tx_max_num` burn txs are waiting, or when the oldest waiting burn tx is more than
+// `burn_tx_max_waiting_blocks` blocks or `burn_tx_max_latency_secs` seconds old.
+#[derive(Deserialize, Serialize, Default, Debug, Clone)]
+pub struct CKBRelayPolicy {
+    pub waiting_blocks: u64,
+    pub burn_tx_max_num: u64,
+    pub burn_tx_max_waiting_blocks: u64,
+    pub burn_tx_max_latency_secs: Option<u64>,
+}
+
+pub const MIN_FEE_BUMP_PERCENT: u64 = 10;
+
+// How the eth tx manager replaces a stuck tx: it is rebroadcast with the fee bumped by
+// `fee_bump_percent` every `resend_interval_secs`, the fee per gas never exceeds
+// `max_fee_per_gas_gwei`, and it gives up after `max_wait_secs`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct EthTxPolicy {
+    pub resend_interval_secs: u64,
+    pub fee_bump_percent: u64,
+    pub max_fee_per_gas_gwei: u64,
+    pub max_wait_secs: u64,
+    pub confirmations: u64,
+}
+
+impl Default for EthTxPolicy {
+    fn default() -> Self {
+        EthTxPolicy {
+            resend_interval_secs: 180,
+            fee_bump_percent: 15,
+            max_fee_per_gas_gwei: 1000,
+            max_wait_secs: 1800,
+            confirmations: 1,
+        }
+    }
+}
+
+impl CKBRelayPolicy {
+    pub fn default_for_network(network: &str) -> Self {
+        let waiting_blocks = match network {
+            "mainnet" => MAINNET_CKB_WAITING_BLOCKS,
+            _ => TESTNET_CKB_WAITING_BLOCKS,
+        };
+        CKBRelayPolicy {
+            waiting_blocks,
+            burn_tx_max_num: BURN_TX_MAX_NUM,
+            burn_tx_max_waiting_blocks: BURN_TX_MAX_WAITING_BLOCKS,
+            burn_tx_max_latency_secs: None,
+        }
+    }
+}
+
+// The amount limits of each token to bridge, keyed by the eth token address in hex without 0x.
+// The lock events and the burns out of the limits are rejected, the tokens without limits are
+// not limited. The limits are parsed and the keys are normalized when the config is loaded, so an
+// invalid limit fails the startup.
+#[derive(Deserialize, Serialize, Default, Debug, Clone)]
+#[serde(
+    try_from = "HashMap<String, TokenAmountLimit>",
+    into = "HashMap<String, TokenAmountLimit>"
+)]
+pub struct TokenAmountLimits(pub HashMap<String, TokenAmountLimit>);
+
+// The amounts are in the smallest unit of the token, they are decimal strings in the config as
+// toml has no u128.
+#[derive(Deserialize, Serialize, Default, Debug, Clone)]
+pub struct TokenAmountLimit {
+    #[serde(default, with = "decimal_amount")]
+    pub min_amount: Option<u128>,
+    #[serde(default, with = "decimal_amount")]
+    pub max_amount: Option<u128>,
+    #[serde(default, with = "decimal_amount")]
+    pub min_fee: Option<u128>,
+    // The rolling window cap of the volume minted on ckb, and of the volume unlocked on ethereum,
+    // the transfers over it are held till the window frees up or an operator releases them.
+    pub rate_limit_window_secs: Option<u64>,
+    #[serde(default, with = "decimal_amount")]
+    pub rate_limit_cap: Option<u128>,
+}
+
+mod decimal_amount {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        amount: &Option<u128>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match amount {
+            Some(amount) => serializer.serialize_some(&amount.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u128>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|amount| {
+                amount
+                    .parse::<u128>()
+                    .map_err(|e| D::Error::custom(format!("invalid amount {}: {}", amount, e)))
+            })
+            .transpose()
+    }
+}
+
+fn normalize_token(token: &str) -> String {
+    token.trim_start_matches("0x").to_lowercase()
+}
+
+impl TryFrom<HashMap<String, TokenAmountLimit>> for TokenAmountLimits {
+    type Error = String;
+
+    fn try_from(limits: HashMap<String, TokenAmountLimit>) -> Result<Self, Self::Error> {
+        let mut normalized = HashMap::new();
+        for (token, limit) in limits {
+            if limit.rate_limit_window_secs.is_some() != limit.rate_limit_cap.is_some() {
+                return Err(format!(
+                    "the rate limit window and cap of token {} should be set together",
+                    token
+                ));
+            }
+            if normalized.insert(normalize_token(&token), limit).is_some() {
+                return Err(format!("duplicated amount limits of token {}", token));
+            }
+        }
+        Ok(TokenAmountLimits(normalized))
+    }
+}
+
+impl From<TokenAmountLimits> for HashMap<String, TokenAmountLimit> {
+    fn from(limits: TokenAmountLimits) -> Self {
+        limits.0
+    }
+}
+
+impl TokenAmountLimits {
+    // check the amount and the bridge fee of a lock event or a burn of the token, the error is
+    // the reason to reject it.
+    pub fn check(&self, token: &str, amount: u128, fee: u128) -> Result<()> {
+        let limit = match self.0.get(&normalize_token(token)) {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+        if let Some(min_amount) = limit.min_amount {
+            if amount < min_amount {
+                bail!("amount {} is less than the minimum {}", amount, min_amount);
+            }
+        }
+        if let Some(max_amount) = limit.max_amount {
+            if amount > max_amount {
+                bail!("amount {} is more than the maximum {}", amount, max_amount);
+            }
+        }
+        if let Some(min_fee) = limit.min_fee {
+            if fee < min_fee {
+                bail!("bridge fee {} is less than the minimum {}", fee, min_fee);
+            }
+        }
+        Ok(())
+    }
+
+    // the rolling window in seconds and the cap of the volume in it of the token, None if the
+    // token is not rate limited.
+    pub fn rate_limit(&self, token: &str) -> Option<(u64, u128)> {
+        let limit = self.0.get(&normalize_token(token))?;
+        Some((limit.rate_limit_window_secs?, limit.rate_limit_cap?))
+    }
 }
 
 #[derive(Deserialize, Serialize, Default, Debug, Clone)]
@@ -121,6 +328,12 @@ pub struct DeployedContracts {
     pub simple_bridge_typescript: ScriptConf,
     pub sudt: ScriptConf,
     pub light_client_cell_script: CellScript,
+    #[serde(default)]
+    pub bridge_status_cell_script: CellScript,
+    #[serde(default)]
+    pub token_allowlist_cell_script: CellScript,
+    #[serde(default)]
+    pub consumed_event_registry_cell_script: CellScript,
     pub multisig_address: MultisigConf,
     pub ckb_relay_mutlisig_threshold: CKBRelayMultisigConf,
     pub pw_locks: PwLocks,
@@ -136,6 +349,9 @@ pub struct MultisigConf {
 #[derive(Deserialize, Serialize, Default, Debug, Clone)]
 pub struct CKBRelayMultisigConf {
     pub threshold: usize,
+    // the validators the CKBChain contract is initialized with, the contract does not expose them
+    #[serde(default)]
+    pub validators: Vec<String>,
 }
 
 #[derive(Deserialize, Serialize, Default, Debug, Clone)]
@@ -214,6 +430,43 @@ impl ForceConfig {
             } else {
                 return Err(anyhow!("ethereum rpc url should be Value::String"));
             };
+            // the ethereum_rpc_urls and ethereum_rpc_quorum are optional, if not set, only the
+            // ethereum_rpc_url is used and the quorum is 1.
+            let ethereum_rpc_urls = match network_config.get("ethereum_rpc_urls") {
+                Some(Value::Array(urls)) => urls
+                    .iter()
+                    .map(|v| {
+                        if let Value::String(url) = v {
+                            Ok(url.to_owned())
+                        } else {
+                            Err(anyhow!("ethereum rpc url should be Value::String"))
+                        }
+                    })
+                    .collect::<Result<Vec<String>>>()?,
+                Some(_) => return Err(anyhow!("ethereum_rpc_urls should be Value::Array")),
+                None => vec![],
+            };
+            let ethereum_rpc_urls = if ethereum_rpc_urls.is_empty() {
+                vec![ethereum_rpc_url.clone()]
+            } else {
+                ethereum_rpc_urls
+            };
+            let ethereum_rpc_quorum = match network_config.get("ethereum_rpc_quorum") {
+                Some(Value::Integer(quorum)) => *quorum as usize,
+                Some(_) => return Err(anyhow!("ethereum_rpc_quorum should be Value::Integer")),
+                None => ethereum_rpc_urls.len() / 2 + 1,
+            };
+            // a quorum of a strict majority ensures at most one block hash can reach it.
+            if ethereum_rpc_quorum <= ethereum_rpc_urls.len() / 2
+                || ethereum_rpc_quorum > ethereum_rpc_urls.len()
+            {
+                return Err(anyhow!(
+                    "invalid ethereum_rpc_quorum {}, should be in [{}, {}]",
+                    ethereum_rpc_quorum,
+                    ethereum_rpc_urls.len() / 2 + 1,
+                    ethereum_rpc_urls.len()
+                ));
+            }
             let ckb_private_keys = network_config
                 .get("ckb_private_keys")
                 .ok_or_else(|| anyhow!("invalid config file: ckb_private_keys not existed"))?;
@@ -231,12 +484,67 @@ impl ForceConfig {
                 } else {
                     return Err(anyhow!("ethereum_private_keys should be Value::Array"));
                 };
+            // the ckb_relay_policy is optional, the unset items fall back to the defaults of the
+            // network.
+            let mut ckb_relay_policy = CKBRelayPolicy::default_for_network(network);
+            match network_config.get("ckb_relay_policy") {
+                Some(Value::Table(policy)) => {
+                    if let Some(v) = get_optional_u64(policy, "waiting_blocks")? {
+                        ckb_relay_policy.waiting_blocks = v;
+                    }
+                    if let Some(v) = get_optional_u64(policy, "burn_tx_max_num")? {
+                        ckb_relay_policy.burn_tx_max_num = v;
+                    }
+                    if let Some(v) = get_optional_u64(policy, "burn_tx_max_waiting_blocks")? {
+                        ckb_relay_policy.burn_tx_max_waiting_blocks = v;
+                    }
+                    if let Some(v) = get_optional_u64(policy, "burn_tx_max_latency_secs")? {
+                        ckb_relay_policy.burn_tx_max_latency_secs = Some(v);
+                    }
+                }
+                Some(_) => return Err(anyhow!("ckb_relay_policy should be Value::Table")),
+                None => {}
+            }
+            let mut eth_tx_policy = EthTxPolicy::default();
+            match network_config.get("eth_tx_policy") {
+                Some(Value::Table(policy)) => {
+                    if let Some(v) = get_optional_u64(policy, "resend_interval_secs")? {
+                        eth_tx_policy.resend_interval_secs = v;
+                    }
+                    if let Some(v) = get_optional_u64(policy, "fee_bump_percent")? {
+                        eth_tx_policy.fee_bump_percent = v;
+                    }
+                    if let Some(v) = get_optional_u64(policy, "max_fee_per_gas_gwei")? {
+                        eth_tx_policy.max_fee_per_gas_gwei = v;
+                    }
+                    if let Some(v) = get_optional_u64(policy, "max_wait_secs")? {
+                        eth_tx_policy.max_wait_secs = v;
+                    }
+                    if let Some(v) = get_optional_u64(policy, "confirmations")? {
+                        eth_tx_policy.confirmations = v;
+                    }
+                }
+                Some(_) => return Err(anyhow!("eth_tx_policy should be Value::Table")),
+                None => {}
+            }
+            // geth and most nodes only accept a replacement tx with at least 10% higher fees
+            if eth_tx_policy.fee_bump_percent < MIN_FEE_BUMP_PERCENT {
+                return Err(anyhow!(
+                    "invalid eth_tx_policy.fee_bump_percent {}, should be at least {}",
+                    eth_tx_policy.fee_bump_percent,
+                    MIN_FEE_BUMP_PERCENT
+                ));
+            }
             Ok(NetworkConfig {
                 ckb_rpc_url,
                 ckb_indexer_url,
                 ethereum_rpc_url,
+                ethereum_rpc_urls,
+                ethereum_rpc_quorum,
                 ckb_private_keys,
                 ethereum_private_keys,
+                ckb_relay_policy,
+                eth_tx_policy,
             })
         } else {
             Err(anyhow!("chain config should be Value::Table"))
@@ -258,6 +566,26 @@ impl ForceConfig {
         Ok(chain_config.ethereum_rpc_url)
     }
 
+    pub fn get_ethereum_rpc_urls(&self, network: &Option<String>) -> Result<Vec<String>> {
+        let chain_config = self.get_network_config(network)?;
+        Ok(chain_config.ethereum_rpc_urls)
+    }
+
+    pub fn get_ethereum_rpc_quorum(&self, network: &Option<String>) -> Result<usize> {
+        let chain_config = self.get_network_config(network)?;
+        Ok(chain_config.ethereum_rpc_quorum)
+    }
+
+    pub fn get_ckb_relay_policy(&self, network: &Option<String>) -> Result<CKBRelayPolicy> {
+        let chain_config = self.get_network_config(network)?;
+        Ok(chain_config.ckb_relay_policy)
+    }
+
+    pub fn get_eth_tx_policy(&self, network: &Option<String>) -> Result<EthTxPolicy> {
+        let chain_config = self.get_network_config(network)?;
+        Ok(chain_config.eth_tx_policy)
+    }
+
     pub fn get_ckb_private_keys(&self, network: &Option<String>) -> Result<Vec<String>> {
         self.get_network_config(network)?
             .ckb_private_keys
@@ -374,3 +702,64 @@ impl ForceConfig {
             .map_err(|e| anyhow!("fail to write scripts config. err: {}", e))
     }
 }
+
+fn get_optional_u64(table: &Table, key: &str) -> Result<Option<u64>> {
+    match table.get(key) {
+        Some(Value::Integer(v)) if *v >= 0 => Ok(Some(*v as u64)),
+        Some(_) => Err(anyhow!("{} should be a non-negative Value::Integer", key)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "0x7Af456bf0065aADAB2E6BEc6DaD3731899550b84";
+
+    fn new_limits(limit: &str) -> std::result::Result<TokenAmountLimits, toml::de::Error> {
+        toml::from_str(&format!("[{}]\n{}", TOKEN, limit))
+    }
+
+    #[test]
+    fn test_check_amount_limits() {
+        let limits =
+            new_limits("min_amount = \"100\"\nmax_amount = \"1000\"\nmin_fee = \"10\"").unwrap();
+        // the token is matched in any case, with or without 0x
+        for token in &[TOKEN, &TOKEN[2..], &TOKEN.to_lowercase()] {
+            assert!(limits.check(token, 100, 10).is_ok());
+            assert!(limits.check(token, 1000, 10).is_ok());
+            assert!(limits.check(token, 99, 10).is_err());
+            assert!(limits.check(token, 1001, 10).is_err());
+            assert!(limits.check(token, 100, 9).is_err());
+        }
+        // the tokens without limits are not limited
+        assert!(limits.check(&"00".repeat(20), 1, 0).is_ok());
+        assert_eq!(limits.rate_limit(TOKEN), None);
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let limits =
+            new_limits("rate_limit_window_secs = 3600\nrate_limit_cap = \"5000\"").unwrap();
+        assert_eq!(
+            limits.rate_limit(&TOKEN.to_uppercase()[2..]),
+            Some((3600, 5000))
+        );
+        assert!(limits.check(TOKEN, u128::MAX, 0).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_limits_rejected_at_load() {
+        assert!(new_limits("max_amount = \"1e18\"").is_err());
+        assert!(new_limits("min_fee = \"-1\"").is_err());
+        assert!(new_limits("rate_limit_window_secs = 3600").is_err());
+        assert!(new_limits("rate_limit_cap = \"5000\"").is_err());
+        let duplicated: std::result::Result<TokenAmountLimits, _> = toml::from_str(&format!(
+            "[{}]\nmin_fee = \"1\"\n[{}]\nmin_fee = \"2\"",
+            TOKEN,
+            TOKEN.to_lowercase()
+        ));
+        assert!(duplicated.is_err());
+    }
+}
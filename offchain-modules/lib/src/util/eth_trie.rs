@@ -0,0 +1,171 @@
+use crate::util::eth_util::keccak256;
+use anyhow::{bail, Result};
+use rlp::RlpStream;
+
+// The merkle patricia trie of the txs or the receipts of a block, whose keys are rlp(index). The
+// eth rpc does not serve the proofs of the txs, so the trie is rebuilt from the block to prove the
+// lock tx. The rlp keys are prefix free, so the values are all stored in the leaves.
+
+// the root of the trie and the proof of the value at index, from the root node to the leaf.
+pub fn get_trie_proof(values: &[Vec<u8>], index: u64) -> Result<([u8; 32], Vec<Vec<u8>>)> {
+    if index >= values.len() as u64 {
+        bail!(
+            "the index {} is out of the {} values of the trie",
+            index,
+            values.len()
+        );
+    }
+    let items = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| (to_nibbles(&rlp::encode(&(i as u64))), value.as_slice()))
+        .collect::<Vec<_>>();
+    let target = to_nibbles(&rlp::encode(&index));
+    let mut proof = vec![];
+    let root_node = encode_node(
+        &items.iter().collect::<Vec<_>>(),
+        0,
+        Some(&target),
+        &mut proof,
+    );
+    // the nodes are pushed from the leaf up to the root
+    proof.reverse();
+    Ok((keccak256(&root_node), proof))
+}
+
+pub fn get_trie_root(values: &[Vec<u8>]) -> [u8; 32] {
+    if values.is_empty() {
+        return keccak256(&rlp::NULL_RLP);
+    }
+    let (root, _) = get_trie_proof(values, 0).expect("the index 0 is in the trie");
+    root
+}
+
+fn to_nibbles(key: &[u8]) -> Vec<u8> {
+    key.iter()
+        .flat_map(|byte| vec![byte >> 4, byte & 0x0f])
+        .collect()
+}
+
+// the hex prefix encoding of the path, the flag 2 is for the leaf and 0 for the extension, plus 1
+// if the path has an odd number of nibbles.
+fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 2 } else { 0 };
+    let (mut path, rest) = if nibbles.len() % 2 == 1 {
+        (vec![(flag + 1) << 4 | nibbles[0]], &nibbles[1..])
+    } else {
+        (vec![flag << 4], nibbles)
+    };
+    for pair in rest.chunks(2) {
+        path.push(pair[0] << 4 | pair[1]);
+    }
+    path
+}
+
+// the node is embedded in its parent if its rlp is shorter than 32 bytes, or else referred by hash.
+fn append_child(stream: &mut RlpStream, child: &[u8]) {
+    if child.len() < 32 {
+        stream.append_raw(child, 1);
+    } else {
+        stream.append(&keccak256(child).to_vec());
+    }
+}
+
+// encode the node of the items whose keys share the first depth nibbles. the node is pushed to
+// the proof if the target key goes through it.
+fn encode_node(
+    items: &[&(Vec<u8>, &[u8])],
+    depth: usize,
+    target: Option<&[u8]>,
+    proof: &mut Vec<Vec<u8>>,
+) -> Vec<u8> {
+    let node = if items.len() == 1 {
+        let (key, value) = items[0];
+        let mut stream = RlpStream::new_list(2);
+        stream.append(&hex_prefix(&key[depth..], true));
+        stream.append(&value.to_vec());
+        stream.out()
+    } else {
+        let (first_key, _) = items[0];
+        let shared = (depth..first_key.len())
+            .take_while(|i| {
+                items
+                    .iter()
+                    .all(|(key, _)| key.get(*i) == first_key.get(*i))
+            })
+            .count();
+        if shared > 0 {
+            let child = encode_node(items, depth + shared, target, proof);
+            let mut stream = RlpStream::new_list(2);
+            stream.append(&hex_prefix(&first_key[depth..depth + shared], false));
+            append_child(&mut stream, &child);
+            stream.out()
+        } else {
+            let mut stream = RlpStream::new_list(17);
+            for nibble in 0..16u8 {
+                let children = items
+                    .iter()
+                    .filter(|(key, _)| key[depth] == nibble)
+                    .copied()
+                    .collect::<Vec<_>>();
+                if children.is_empty() {
+                    stream.append_empty_data();
+                    continue;
+                }
+                let child_target = target.filter(|target| target[depth] == nibble);
+                let child = encode_node(&children, depth + 1, child_target, proof);
+                append_child(&mut stream, &child);
+            }
+            stream.append_empty_data();
+            stream.out()
+        }
+    };
+    if target.is_some() {
+        proof.push(node.clone());
+    }
+    node
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth_spv_lib::ethspv::verify_transaction;
+
+    fn new_values(count: usize) -> Vec<Vec<u8>> {
+        (0..count)
+            .map(|i| {
+                let mut value = vec![2u8; 100];
+                value[..8].copy_from_slice(&(i as u64).to_le_bytes());
+                value
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_empty_trie_root() {
+        assert_eq!(
+            hex::encode(get_trie_root(&[])),
+            "56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+        );
+    }
+
+    #[test]
+    fn test_trie_proof_verified_by_contract() {
+        // the keys rlp(index) are 0x80, 0x01..0x7f and then 0x8180.., across the length change
+        for count in &[1usize, 2, 17, 130] {
+            let values = new_values(*count);
+            for index in 0..*count {
+                let (root, proof) = get_trie_proof(&values, index as u64).unwrap();
+                assert_eq!(root, get_trie_root(&values));
+                let tx_hash =
+                    verify_transaction(index as u64, values[index].clone(), root.into(), proof);
+                assert_eq!(tx_hash.0.as_bytes(), keccak256(&values[index]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_trie_proof_index_out_of_range() {
+        assert!(get_trie_proof(&new_values(2), 2).is_err());
+    }
+}
@@ -0,0 +1,471 @@
+use crate::util::config::EthTxPolicy;
+use crate::util::eth_util::{
+    keccak256, secret_key_address, sign_transaction_with_fee, EthFee, EthFeeOptions, Web3Client,
+};
+use anyhow::{anyhow, bail, Result};
+use log::{error, info};
+use secp256k1::SecretKey;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use web3::types::{BlockNumber, Bytes, H160, H256, U256};
+
+const POLL_INTERVAL_SECS: u64 = 3;
+const GWEI: u64 = 1_000_000_000;
+
+#[derive(Clone, Debug)]
+pub struct PendingTx {
+    pub fee: EthFee,
+    // all the txs broadcast for the nonce, the last one has the highest fee
+    pub tx_hashes: Vec<H256>,
+}
+
+// The eth tx manager sends a tx and waits until it is mined. A tx not mined in
+// `resend_interval_secs` is replaced by one with the same nonce and a bumped fee, until one of
+// them is mined or `max_wait_secs` passed. It is cheap to clone and the clones share the pending
+// txs, so several txs of one sender can be sent concurrently with consecutive nonces.
+#[derive(Clone)]
+pub struct EthTxManager {
+    url: String,
+    policy: EthTxPolicy,
+    pending_txs: Arc<Mutex<HashMap<(H160, U256), PendingTx>>>,
+}
+
+impl EthTxManager {
+    pub fn new(url: String, policy: EthTxPolicy) -> Self {
+        EthTxManager {
+            url,
+            policy,
+            pending_txs: Arc::new(Mutex::new(HashMap::default())),
+        }
+    }
+
+    pub fn policy(&self) -> &EthTxPolicy {
+        &self.policy
+    }
+
+    pub fn max_fee_per_gas(&self) -> U256 {
+        U256::from(self.policy.max_fee_per_gas_gwei) * U256::from(GWEI)
+    }
+
+    pub fn get_pending_txs(&self) -> HashMap<(H160, U256), PendingTx> {
+        self.pending_txs.lock().expect("lock pending txs").clone()
+    }
+
+    // the next nonce of the sender, after both the txs in the node's pool and the ones tracked
+    // by the manager.
+    pub async fn next_nonce(&self, eth_private_key: &H256) -> Result<U256> {
+        let from = secret_key_address(&SecretKey::from_slice(&eth_private_key.0)?);
+        let mut web3_client = Web3Client::new(self.url.clone());
+        let pool_nonce = web3_client
+            .client()
+            .eth()
+            .transaction_count(from, Some(BlockNumber::Pending))
+            .await?;
+        let tracked_nonce = self
+            .pending_txs
+            .lock()
+            .expect("lock pending txs")
+            .keys()
+            .filter(|(sender, _)| sender == &from)
+            .map(|(_, nonce)| *nonce + 1)
+            .max()
+            .unwrap_or_default();
+        Ok(std::cmp::max(pool_nonce, tracked_nonce))
+    }
+
+    // the fee of the options, the unset prices are suggested by the node. capped by the policy.
+    pub async fn suggest_fee(&self, fee_options: &EthFeeOptions) -> Result<EthFee> {
+        let mut web3_client = Web3Client::new(self.url.clone());
+        let fee = web3_client.get_fee(fee_options).await?;
+        Ok(fee.cap(self.max_fee_per_gas()))
+    }
+
+    // send the tx and wait until it is mined with enough confirmations, return the hash of the
+    // tx which is finally mined.
+    pub async fn send_transaction(
+        &self,
+        to: H160,
+        eth_private_key: H256,
+        data: Vec<u8>,
+        eth_value: U256,
+        nonce: U256,
+        fee: EthFee,
+    ) -> Result<H256> {
+        let from = secret_key_address(&SecretKey::from_slice(&eth_private_key.0)?);
+        let max_fee_per_gas = self.max_fee_per_gas();
+        if fee.max_fee_per_gas() > max_fee_per_gas {
+            bail!(
+                "the fee {:?} exceeds the max fee per gas {}",
+                fee,
+                max_fee_per_gas
+            );
+        }
+        let mut web3_client = Web3Client::new(self.url.clone());
+        let chain_id = web3_client.client().eth().chain_id().await?;
+        let gas_limit = web3_client
+            .estimate_gas(to, &eth_private_key, data.clone(), eth_value)
+            .await?;
+
+        let key = (from, nonce);
+        self.pending_txs.lock().expect("lock pending txs").insert(
+            key,
+            PendingTx {
+                fee: fee.clone(),
+                tx_hashes: vec![],
+            },
+        );
+        let res = self
+            .send_until_mined(&mut web3_client, from, nonce, fee, |fee: &EthFee| {
+                sign_transaction_with_fee(
+                    to,
+                    nonce,
+                    data.clone(),
+                    fee,
+                    gas_limit,
+                    eth_value,
+                    &eth_private_key,
+                    chain_id,
+                )
+            })
+            .await;
+        self.pending_txs
+            .lock()
+            .expect("lock pending txs")
+            .remove(&key);
+        res
+    }
+
+    async fn send_until_mined<F: Fn(&EthFee) -> Result<Vec<u8>>>(
+        &self,
+        web3_client: &mut Web3Client,
+        from: H160,
+        nonce: U256,
+        mut fee: EthFee,
+        sign_tx: F,
+    ) -> Result<H256> {
+        let resend_interval = Duration::from_secs(self.policy.resend_interval_secs);
+        let max_wait = Duration::from_secs(self.policy.max_wait_secs);
+        let start = Instant::now();
+        let mut signed_tx = sign_tx(&fee)?;
+        let mut tx_hashes: Vec<H256> = vec![];
+        loop {
+            let tx_hash = H256::from(keccak256(signed_tx.as_slice()));
+            // the node may reject a rebroadcast as already known or underpriced, the txs sent
+            // before are still watched.
+            match web3_client
+                .client()
+                .eth()
+                .send_raw_transaction(Bytes::from(signed_tx.clone()))
+                .await
+            {
+                Ok(_) => info!(
+                    "send eth tx {:#x}, nonce: {}, fee: {:?}",
+                    tx_hash, nonce, fee
+                ),
+                Err(e) => error!(
+                    "send eth tx {:#x} with nonce {} err: {:?}",
+                    tx_hash, nonce, e
+                ),
+            }
+            if !tx_hashes.contains(&tx_hash) {
+                tx_hashes.push(tx_hash);
+            }
+            if let Some(pending_tx) = self
+                .pending_txs
+                .lock()
+                .expect("lock pending txs")
+                .get_mut(&(from, nonce))
+            {
+                pending_tx.fee = fee.clone();
+                pending_tx.tx_hashes = tx_hashes.clone();
+            }
+
+            let broadcast_at = Instant::now();
+            loop {
+                tokio::time::delay_for(Duration::from_secs(POLL_INTERVAL_SECS)).await;
+                let account_nonce = web3_client
+                    .client()
+                    .eth()
+                    .transaction_count(from, Some(BlockNumber::Latest))
+                    .await?;
+                // a tx mined but not confirmed yet should not be replaced any more
+                let mined = match self.get_mined_tx(web3_client, &tx_hashes).await? {
+                    Some((tx_hash, true)) => return Ok(tx_hash),
+                    Some((_, false)) => true,
+                    None => false,
+                };
+                if !mined && account_nonce > nonce {
+                    bail!(
+                        "the nonce {} of {:#x} is used by another tx, sent txs: {:?}",
+                        nonce,
+                        from,
+                        tx_hashes
+                    );
+                }
+                if start.elapsed() >= max_wait {
+                    bail!(
+                        "eth txs with nonce {} are not mined and confirmed in {:?}, sent txs: {:?}",
+                        nonce,
+                        max_wait,
+                        tx_hashes
+                    );
+                }
+                if !mined && broadcast_at.elapsed() >= resend_interval {
+                    break;
+                }
+            }
+            // rebroadcast the last tx if the fee is already at the cap
+            if let Some(bumped_fee) = fee.bump(self.policy.fee_bump_percent, self.max_fee_per_gas())
+            {
+                info!(
+                    "eth tx with nonce {} is not mined in {:?}, bump fee from {:?} to {:?}",
+                    nonce, resend_interval, fee, bumped_fee
+                );
+                fee = bumped_fee;
+                signed_tx = sign_tx(&fee)?;
+            }
+        }
+    }
+
+    // return the hash of the tx which is mined and whether it is confirmed, error if it is
+    // reverted.
+    async fn get_mined_tx(
+        &self,
+        web3_client: &mut Web3Client,
+        tx_hashes: &[H256],
+    ) -> Result<Option<(H256, bool)>> {
+        for &tx_hash in tx_hashes {
+            let receipt = match web3_client.get_receipt(tx_hash).await? {
+                Some(receipt) => receipt,
+                None => continue,
+            };
+            let block_number = match receipt.block_number {
+                Some(block_number) => block_number.as_u64(),
+                None => continue,
+            };
+            let tip_number = web3_client.client().eth().block_number().await?.as_u64();
+            if tip_number + 1 < block_number + self.policy.confirmations {
+                return Ok(Some((tx_hash, false)));
+            }
+            let status = receipt
+                .status
+                .ok_or_else(|| anyhow!("tx receipt status is none"))?;
+            if status.as_u64() == 0 {
+                bail!("eth tx {:#x} failed! receipt: {:?}", tx_hash, &receipt);
+            }
+            info!("eth tx {:#x} is mined at block {}", tx_hash, block_number);
+            return Ok(Some((tx_hash, true)));
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+
+    const PRIVATE_KEY: &str = "c4ad657963930fbff2e9de3404b30a4e21432c89952ed430b56bf802945ed37a";
+    const TIP_NUMBER: u64 = 0x10;
+
+    type Handler = dyn Fn(&str, &Value, &[Vec<u8>]) -> Value + Send + Sync;
+
+    // a stand-in eth node serving the json rpc over http. the result of a call is made by the
+    // handler from the method, the params and the raw txs sent to the node so far.
+    struct MockNode {
+        url: String,
+        sent_txs: Arc<Mutex<Vec<Vec<u8>>>>,
+    }
+
+    impl MockNode {
+        fn start(handler: Box<Handler>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let sent_txs = Arc::new(Mutex::new(vec![]));
+            let handler: Arc<Handler> = Arc::from(handler);
+            let node_sent_txs = sent_txs.clone();
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(_) => break,
+                    };
+                    let handler = handler.clone();
+                    let sent_txs = node_sent_txs.clone();
+                    std::thread::spawn(move || serve(stream, handler, sent_txs));
+                }
+            });
+            MockNode { url, sent_txs }
+        }
+
+        fn sent_txs(&self) -> Vec<Vec<u8>> {
+            self.sent_txs.lock().unwrap().clone()
+        }
+    }
+
+    // serve the requests of a keep-alive connection until it is closed.
+    fn serve(stream: TcpStream, handler: Arc<Handler>, sent_txs: Arc<Mutex<Vec<Vec<u8>>>>) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut writer = stream;
+        loop {
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                    return;
+                }
+                let line = line.trim_end().to_lowercase();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some(length) = line.strip_prefix("content-length:") {
+                    content_length = length.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0u8; content_length];
+            reader.read_exact(&mut body).unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+            let method = request["method"].as_str().unwrap();
+            let params = &request["params"];
+            let result = if method == "eth_sendRawTransaction" {
+                let raw_tx =
+                    hex::decode(params[0].as_str().unwrap().trim_start_matches("0x")).unwrap();
+                let tx_hash = H256::from(keccak256(raw_tx.as_slice()));
+                sent_txs.lock().unwrap().push(raw_tx);
+                json!(tx_hash)
+            } else {
+                let sent_txs = sent_txs.lock().unwrap().clone();
+                handler(method, params, &sent_txs)
+            };
+            let response = json!({"jsonrpc": "2.0", "id": request["id"].clone(), "result": result})
+                .to_string();
+            let written = write!(
+                writer,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                response.len(),
+                response
+            );
+            if written.is_err() {
+                return;
+            }
+        }
+    }
+
+    // the results of the calls other than sending txs and getting receipts
+    fn node_result(method: &str, account_nonce: u64) -> Value {
+        match method {
+            "eth_chainId" => json!("0x1"),
+            "eth_estimateGas" => json!("0x5208"),
+            "eth_getTransactionCount" => json!(format!("{:#x}", account_nonce)),
+            "eth_blockNumber" => json!(format!("{:#x}", TIP_NUMBER)),
+            _ => panic!("unexpected method {}", method),
+        }
+    }
+
+    fn receipt(raw_tx: &[u8], block_number: u64) -> Value {
+        json!({
+            "transactionHash": H256::from(keccak256(raw_tx)),
+            "transactionIndex": "0x0",
+            "blockHash": H256::repeat_byte(0x11),
+            "blockNumber": format!("{:#x}", block_number),
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "contractAddress": null,
+            "logs": [],
+            "status": "0x1",
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+        })
+    }
+
+    fn is_receipt_of(params: &Value, raw_tx: &[u8]) -> bool {
+        params[0].as_str().unwrap() == format!("{:#x}", H256::from(keccak256(raw_tx)))
+    }
+
+    fn legacy_gas_price(raw_tx: &[u8]) -> U256 {
+        U256::from_big_endian(rlp::Rlp::new(raw_tx).at(1).unwrap().data().unwrap())
+    }
+
+    fn policy(resend_interval_secs: u64, max_wait_secs: u64, confirmations: u64) -> EthTxPolicy {
+        EthTxPolicy {
+            resend_interval_secs,
+            fee_bump_percent: 20,
+            max_fee_per_gas_gwei: 100,
+            max_wait_secs,
+            confirmations,
+        }
+    }
+
+    async fn send(manager: &EthTxManager) -> Result<H256> {
+        let private_key = H256::from_slice(&hex::decode(PRIVATE_KEY).unwrap());
+        manager
+            .send_transaction(
+                H160::repeat_byte(0x22),
+                private_key,
+                vec![],
+                U256::zero(),
+                U256::zero(),
+                EthFee::Legacy {
+                    gas_price: U256::from(10 * GWEI),
+                },
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_replace_stuck_tx_with_bumped_fee() {
+        // only the replacement tx is mined
+        let node = MockNode::start(Box::new(|method, params, sent_txs| match method {
+            "eth_getTransactionReceipt" => match sent_txs.get(1) {
+                Some(raw_tx) if is_receipt_of(params, raw_tx) => receipt(raw_tx, TIP_NUMBER),
+                _ => Value::Null,
+            },
+            _ => node_result(method, 0),
+        }));
+        let manager = EthTxManager::new(node.url.clone(), policy(1, 60, 1));
+        let tx_hash = send(&manager).await.unwrap();
+
+        let sent_txs = node.sent_txs();
+        assert_eq!(sent_txs.len(), 2);
+        assert_eq!(tx_hash, H256::from(keccak256(sent_txs[1].as_slice())));
+        assert_eq!(legacy_gas_price(&sent_txs[0]), U256::from(10 * GWEI));
+        assert_eq!(legacy_gas_price(&sent_txs[1]), U256::from(12 * GWEI));
+        assert!(manager.get_pending_txs().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_give_up_unconfirmed_tx_after_max_wait() {
+        // the tx is mined at the tip but never gets the confirmations
+        let node = MockNode::start(Box::new(|method, params, sent_txs| match method {
+            "eth_getTransactionReceipt" => match sent_txs.first() {
+                Some(raw_tx) if is_receipt_of(params, raw_tx) => receipt(raw_tx, TIP_NUMBER),
+                _ => Value::Null,
+            },
+            _ => node_result(method, 1),
+        }));
+        let manager = EthTxManager::new(node.url.clone(), policy(1, 1, 3));
+        let err = send(&manager).await.unwrap_err();
+        assert!(
+            err.to_string().contains("are not mined and confirmed"),
+            "{}",
+            err
+        );
+        // the mined tx is never replaced
+        assert_eq!(node.sent_txs().len(), 1);
+        assert!(manager.get_pending_txs().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_nonce_used_by_another_tx() {
+        let node = MockNode::start(Box::new(|method, _, _| match method {
+            "eth_getTransactionReceipt" => Value::Null,
+            _ => node_result(method, 1),
+        }));
+        let manager = EthTxManager::new(node.url.clone(), policy(60, 60, 1));
+        let err = send(&manager).await.unwrap_err();
+        assert!(err.to_string().contains("is used by another tx"), "{}", err);
+        assert_eq!(node.sent_txs().len(), 1);
+    }
+}
@@ -2,12 +2,16 @@ use crate::util::config::ForceConfig;
 use anyhow::{anyhow, bail, Result};
 use ethabi::{FixedBytes, Function, Param, ParamType, Token, Uint};
 use ethereum_tx_sign::RawTransaction;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use rlp::{DecoderError, Rlp, RlpStream};
-use secp256k1::{PublicKey, Secp256k1, SecretKey};
+use secp256k1::recovery::{RecoverableSignature, RecoveryId};
+use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::BufReader;
+use std::str::FromStr;
 use std::time::Duration;
 use web3::contract::{Contract, Options};
 use web3::transports::Http;
@@ -15,9 +19,11 @@ use web3::types::{
     Address, Block, BlockHeader, BlockId, Bytes, CallRequest, TransactionReceipt, H160, H256, U256,
     U64,
 };
-use web3::Web3;
+use web3::{Transport, Web3};
 
 pub const ETH_ADDRESS_LENGTH: usize = 40;
+pub const EIP2930_TX_TYPE: u8 = 1;
+pub const EIP1559_TX_TYPE: u8 = 2;
 
 const CKB_CHAIN_ABI: &[u8] = include_bytes!("ckb_chain_abi.json");
 const TOKEN_LOCKER_ABI: &[u8] = include_bytes!("token_locker_abi.json");
@@ -49,7 +55,7 @@ impl Web3Client {
         to: H160,
         eth_private_key: H256,
         data: Vec<u8>,
-        gas_price: U256,
+        fee_options: &EthFeeOptions,
         eth_value: U256,
         asec_nonce: U256,
         wait: bool,
@@ -59,7 +65,7 @@ impl Web3Client {
                 to,
                 eth_private_key,
                 data,
-                gas_price,
+                fee_options,
                 None,
                 eth_value,
                 asec_nonce,
@@ -97,7 +103,7 @@ impl Web3Client {
         to: H160,
         eth_private_key: H256,
         data: Vec<u8>,
-        gas_price: U256,
+        fee_options: &EthFeeOptions,
         gas_limit: Option<U256>,
         eth_value: U256,
         asec_nonce: U256,
@@ -111,37 +117,117 @@ impl Web3Client {
         info!("tx current nonce :{}", &nonce);
         let chain_id = self.client().eth().chain_id().await?;
         debug!("chain id :{}", &chain_id);
-        let tx_gas_price;
-        if gas_price.is_zero() {
-            tx_gas_price = self.client.eth().gas_price().await?;
-        } else {
-            tx_gas_price = gas_price;
-        }
+        let fee = self.get_fee(fee_options).await?;
+        debug!("tx fee :{:?}", &fee);
 
         let gas_limit = if let Some(gas_limit) = gas_limit {
             gas_limit
         } else {
-            let eth_key = SecretKey::from_slice(&eth_private_key.0)?;
-            let from = secret_key_address(&eth_key);
-            self.client()
-                .eth()
-                .estimate_gas(
-                    CallRequest {
-                        from: Some(from),
-                        to: Some(to),
-                        gas: None,
-                        gas_price: None,
-                        value: Some(eth_value),
-                        data: Some(Bytes::from(data.clone())),
-                    },
-                    None,
-                )
+            self.estimate_gas(to, &eth_private_key, data.clone(), eth_value)
                 .await?
         };
 
-        let tx = make_transaction(to, nonce, data, tx_gas_price, gas_limit, eth_value);
-        let signed_tx = tx.sign(&eth_private_key, &chain_id.as_u32());
-        Ok(signed_tx)
+        sign_transaction_with_fee(
+            to,
+            nonce,
+            data,
+            &fee,
+            gas_limit,
+            eth_value,
+            &eth_private_key,
+            chain_id,
+        )
+    }
+
+    // build the fee of the tx from the options, the zero prices are filled with the ones
+    // suggested by the node.
+    pub async fn get_fee(&mut self, fee_options: &EthFeeOptions) -> Result<EthFee> {
+        let gas_price =
+            if fee_options.gas_price.is_zero() && fee_options.fee_mode != FeeMode::Eip1559 {
+                self.client.eth().gas_price().await?
+            } else {
+                fee_options.gas_price
+            };
+        match fee_options.fee_mode {
+            FeeMode::Legacy => Ok(EthFee::Legacy { gas_price }),
+            FeeMode::Eip2930 => Ok(EthFee::Eip2930 { gas_price }),
+            FeeMode::Eip1559 => {
+                let max_priority_fee_per_gas = if fee_options.max_priority_fee_per_gas.is_zero() {
+                    self.get_max_priority_fee_per_gas().await?
+                } else {
+                    fee_options.max_priority_fee_per_gas
+                };
+                // the max fee leaves room for the base fee to double before the tx is mined
+                let max_fee_per_gas = if fee_options.max_fee_per_gas.is_zero() {
+                    self.get_base_fee_per_gas().await? * U256::from(2u64) + max_priority_fee_per_gas
+                } else {
+                    fee_options.max_fee_per_gas
+                };
+                if max_priority_fee_per_gas > max_fee_per_gas {
+                    bail!(
+                        "max priority fee per gas {} is greater than max fee per gas {}",
+                        max_priority_fee_per_gas,
+                        max_fee_per_gas
+                    );
+                }
+                Ok(EthFee::Eip1559 {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                })
+            }
+        }
+    }
+
+    pub async fn get_max_priority_fee_per_gas(&mut self) -> Result<U256> {
+        let res = self
+            .client
+            .transport()
+            .execute("eth_maxPriorityFeePerGas", vec![])
+            .await?;
+        Ok(serde_json::from_value(res)?)
+    }
+
+    // the base fee of the latest block, it is only there after the london fork.
+    pub async fn get_base_fee_per_gas(&mut self) -> Result<U256> {
+        let block = self
+            .client
+            .transport()
+            .execute(
+                "eth_getBlockByNumber",
+                vec![Value::String("latest".to_owned()), Value::Bool(false)],
+            )
+            .await?;
+        let base_fee = block
+            .get("baseFeePerGas")
+            .cloned()
+            .ok_or_else(|| anyhow!("the latest block has no base fee, is london activated?"))?;
+        Ok(serde_json::from_value(base_fee)?)
+    }
+
+    pub async fn estimate_gas(
+        &mut self,
+        to: H160,
+        eth_private_key: &H256,
+        data: Vec<u8>,
+        eth_value: U256,
+    ) -> Result<U256> {
+        let eth_key = SecretKey::from_slice(&eth_private_key.0)?;
+        let from = secret_key_address(&eth_key);
+        Ok(self
+            .client()
+            .eth()
+            .estimate_gas(
+                CallRequest {
+                    from: Some(from),
+                    to: Some(to),
+                    gas: None,
+                    gas_price: None,
+                    value: Some(eth_value),
+                    data: Some(Bytes::from(data)),
+                },
+                None,
+            )
+            .await?)
     }
 
     pub async fn get_eth_nonce(&mut self, eth_private_key: &H256) -> Result<U256> {
@@ -171,11 +257,69 @@ impl Web3Client {
         Ok(result)
     }
 
+    // the block with the header fields appended since london, which are dropped by `get_block`.
+    pub async fn get_block_with_extension(
+        &mut self,
+        hash_or_number: BlockId,
+    ) -> Result<(Block<H256>, HeaderExtension)> {
+        let (method, id) = match hash_or_number {
+            BlockId::Hash(hash) => ("eth_getBlockByHash", serde_json::to_value(hash)?),
+            BlockId::Number(number) => ("eth_getBlockByNumber", serde_json::to_value(number)?),
+        };
+        let res = self
+            .client
+            .transport()
+            .execute(method, vec![id, Value::Bool(false)])
+            .await?;
+        if res.is_null() {
+            bail!("the block is not exist.");
+        }
+        let block: Block<H256> =
+            serde_json::from_value(res.clone()).map_err(|e| anyhow!("parse block err: {:?}", e))?;
+        let extension: HeaderExtension = serde_json::from_value(res)
+            .map_err(|e| anyhow!("parse block header extension err: {:?}", e))?;
+        Ok((block, extension))
+    }
+
+    // the raw txs of the hashes, the raw txs of a block in order are the values of its
+    // transactions trie.
+    pub async fn get_raw_transactions(&mut self, tx_hashes: &[H256]) -> Result<Vec<Vec<u8>>> {
+        let mut raw_transactions = vec![];
+        for tx_hash in tx_hashes {
+            let res = self
+                .client
+                .transport()
+                .execute(
+                    "eth_getRawTransactionByHash",
+                    vec![serde_json::to_value(tx_hash)?],
+                )
+                .await?;
+            let raw_transaction: Bytes = serde_json::from_value(res)
+                .map_err(|e| anyhow!("parse raw tx {:?} err: {:?}", tx_hash, e))?;
+            if keccak256(&raw_transaction.0) != tx_hash.0 {
+                bail!("the raw tx of {:?} does not match its hash", tx_hash);
+            }
+            raw_transactions.push(raw_transaction.0);
+        }
+        Ok(raw_transactions)
+    }
+
     pub async fn get_header_rlp(&mut self, hash_or_number: BlockId) -> Result<String> {
-        let block = self.get_block(hash_or_number).await?;
-        let mut stream = RlpStream::new();
-        rlp_append(&block, &mut stream);
-        Ok(hex::encode(stream.out().as_slice()))
+        let (block, extension) = self.get_block_with_extension(hash_or_number).await?;
+        convert_to_header_rlp(&block, &extension)
+    }
+
+    pub async fn get_blocks_with_extension(
+        &mut self,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<(Block<H256>, HeaderExtension)>> {
+        let mut result = vec![];
+        for i in start..end {
+            let block = self.get_block_with_extension(U64::from(i).into()).await?;
+            result.push(block);
+        }
+        Ok(result)
     }
 
     #[allow(clippy::clone_double_ref)]
@@ -278,7 +422,139 @@ impl Web3Client {
         let height: u64 = result.await?;
         Ok(height)
     }
+
+    // the balance of the eth or the erc20 token held by the owner, eth is the zero token address.
+    pub async fn get_token_balance(&mut self, token: Address, owner: Address) -> Result<U256> {
+        if token == Address::zero() {
+            return Ok(self.client.eth().balance(owner, None).await?);
+        }
+        let function = Function {
+            name: "balanceOf".to_owned(),
+            inputs: vec![Param {
+                name: "account".to_owned(),
+                kind: ParamType::Address,
+            }],
+            outputs: vec![Param {
+                name: "".to_owned(),
+                kind: ParamType::Uint(256),
+            }],
+            constant: true,
+        };
+        let data = function.encode_input(&[Token::Address(owner)])?;
+        let output = self
+            .client
+            .eth()
+            .call(
+                CallRequest {
+                    from: None,
+                    to: Some(token),
+                    gas: None,
+                    gas_price: None,
+                    value: None,
+                    data: Some(Bytes::from(data)),
+                },
+                None,
+            )
+            .await?;
+        if output.0.len() != 32 {
+            bail!(
+                "invalid balanceOf output of token {:?}: {:?}",
+                token,
+                output
+            );
+        }
+        Ok(U256::from_big_endian(&output.0))
+    }
+}
+
+// QuorumWeb3Client queries several ethereum endpoints and only trusts the result which at least
+// `quorum` of them agree on. The quorum must be a strict majority of the endpoints, so that two
+// different results can never both reach it.
+pub struct QuorumWeb3Client {
+    clients: Vec<Web3Client>,
+    quorum: usize,
+}
+
+impl QuorumWeb3Client {
+    pub fn new(urls: Vec<String>, quorum: usize) -> Result<QuorumWeb3Client> {
+        if quorum <= urls.len() / 2 || quorum > urls.len() {
+            bail!(
+                "invalid quorum {} of {} ethereum endpoints, should be a strict majority",
+                quorum,
+                urls.len()
+            );
+        }
+        let clients = urls.into_iter().map(Web3Client::new).collect();
+        Ok(QuorumWeb3Client { clients, quorum })
+    }
+
+    pub fn from_config(force_config: &ForceConfig, network: &Option<String>) -> Result<Self> {
+        let urls = force_config.get_ethereum_rpc_urls(network)?;
+        let quorum = force_config.get_ethereum_rpc_quorum(network)?;
+        QuorumWeb3Client::new(urls, quorum)
+    }
+
+    // Get the highest block number which at least `quorum` endpoints have reached.
+    pub async fn get_tip_number(&mut self) -> Result<u64> {
+        let mut tips = vec![];
+        for client in self.clients.iter_mut() {
+            match client.client().eth().block_number().await {
+                Ok(number) => tips.push(number.as_u64()),
+                Err(e) => error!("failed to get tip number from {}: {}", client.url(), e),
+            }
+        }
+        if tips.len() < self.quorum {
+            bail!(
+                "only {} of {} ethereum endpoints returned tip number, quorum: {}",
+                tips.len(),
+                self.clients.len(),
+                self.quorum
+            );
+        }
+        tips.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(tips[self.quorum - 1])
+    }
+
+    // Get the block hash of `number` which at least `quorum` endpoints agree on.
+    // Return an error when the endpoints diverge, so that a compromised or buggy node can not
+    // poison the header root.
+    pub async fn get_block_hash(&mut self, number: u64) -> Result<H256> {
+        let mut votes: HashMap<H256, Vec<String>> = HashMap::new();
+        for client in self.clients.iter_mut() {
+            match client.get_block(U64::from(number).into()).await {
+                Ok(block) => match block.hash {
+                    Some(hash) => votes
+                        .entry(hash)
+                        .or_default()
+                        .push(client.url().to_string()),
+                    // an endpoint which has not reached the block yet does not vote.
+                    None => warn!("block {} is not found from {}", number, client.url()),
+                },
+                Err(e) => error!(
+                    "failed to get block {} from {}: {}",
+                    number,
+                    client.url(),
+                    e
+                ),
+            }
+        }
+        let agreed = votes
+            .iter()
+            .filter(|(_, urls)| urls.len() >= self.quorum)
+            .map(|(hash, _)| *hash)
+            .collect::<Vec<_>>();
+        if agreed.len() == 1 {
+            return Ok(agreed[0]);
+        }
+        bail!(
+            "ethereum endpoints diverge on block {}, quorum: {}, votes: {:?}",
+            number,
+            self.quorum,
+            votes
+        )
+    }
 }
+
 #[deny(clippy::clone_double_ref)]
 pub fn get_contract_abi_json(path: String) -> Result<String> {
     let file = File::open(path)?;
@@ -333,10 +609,76 @@ pub fn transfer_to_header(block: &Block<H256>) -> BlockHeader {
     }
 }
 
-pub fn convert_to_header_rlp(block: &Block<H256>) -> Result<String> {
+// the header fields appended by the forks since london, in the order of the header rlp. they are
+// absent in the blocks before the fork which appends them.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderExtension {
+    #[serde(default)]
+    pub base_fee_per_gas: Option<U256>,
+    #[serde(default)]
+    pub withdrawals_root: Option<H256>,
+    #[serde(default)]
+    pub blob_gas_used: Option<U64>,
+    #[serde(default)]
+    pub excess_blob_gas: Option<U64>,
+    #[serde(default)]
+    pub parent_beacon_block_root: Option<H256>,
+    #[serde(default)]
+    pub requests_hash: Option<H256>,
+}
+
+impl HeaderExtension {
+    // the rlp of the appended fields, a fork only appends fields after the ones of the earlier
+    // forks, so the first absent field ends the header.
+    pub fn rlp_fields(&self) -> Vec<Vec<u8>> {
+        let mut fields = vec![];
+        match &self.base_fee_per_gas {
+            Some(v) => fields.push(rlp::encode(v)),
+            None => return fields,
+        }
+        match &self.withdrawals_root {
+            Some(v) => fields.push(rlp::encode(v)),
+            None => return fields,
+        }
+        match (&self.blob_gas_used, &self.excess_blob_gas) {
+            (Some(used), Some(excess)) => {
+                fields.push(rlp::encode(&used.as_u64()));
+                fields.push(rlp::encode(&excess.as_u64()));
+            }
+            _ => return fields,
+        }
+        match &self.parent_beacon_block_root {
+            Some(v) => fields.push(rlp::encode(v)),
+            None => return fields,
+        }
+        if let Some(v) = &self.requests_hash {
+            fields.push(rlp::encode(v));
+        }
+        fields
+    }
+}
+
+// the blocks got by `get_block` lack the fields appended since london, get the extension of the
+// block by `Web3Client::get_block_with_extension`, it is empty for the blocks before london.
+pub fn convert_to_header_rlp(block: &Block<H256>, extension: &HeaderExtension) -> Result<String> {
     let mut stream = RlpStream::new();
-    rlp_append(&block, &mut stream);
-    Ok(hex::encode(stream.out().as_slice()))
+    rlp_append(&block, extension, &mut stream);
+    let header_rlp = stream.out();
+    check_header_hash(block, header_rlp.as_slice())?;
+    Ok(hex::encode(header_rlp.as_slice()))
+}
+
+fn check_header_hash(block: &Block<H256>, header_rlp: &[u8]) -> Result<()> {
+    let hash = H256::from(keccak256(header_rlp));
+    if block.hash != Some(hash) {
+        bail!(
+            "the hash {:?} of the header rlp mismatches the block hash {:?}, the header may have fields unknown to the relayer",
+            hash,
+            block.hash
+        );
+    }
+    Ok(())
 }
 
 pub fn make_transaction(
@@ -357,6 +699,251 @@ pub fn make_transaction(
     }
 }
 
+// The type of the eth txs to send: the legacy tx, or the typed txs of EIP-2930 and EIP-1559.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeMode {
+    Legacy,
+    Eip2930,
+    Eip1559,
+}
+
+impl Default for FeeMode {
+    fn default() -> Self {
+        FeeMode::Legacy
+    }
+}
+
+impl FromStr for FeeMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "legacy" => Ok(FeeMode::Legacy),
+            "eip2930" => Ok(FeeMode::Eip2930),
+            "eip1559" => Ok(FeeMode::Eip1559),
+            _ => bail!("invalid fee mode {}, expect legacy, eip2930 or eip1559", s),
+        }
+    }
+}
+
+// The fee options given by the user, the zero prices are filled with the ones suggested by the
+// node when the tx is built.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EthFeeOptions {
+    pub fee_mode: FeeMode,
+    pub gas_price: U256,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+}
+
+impl EthFeeOptions {
+    pub fn legacy(gas_price: U256) -> Self {
+        EthFeeOptions {
+            gas_price,
+            ..Default::default()
+        }
+    }
+}
+
+// The fee of an eth tx, a gas price for the legacy and EIP-2930 txs or the EIP-1559 fee caps.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EthFee {
+    Legacy {
+        gas_price: U256,
+    },
+    Eip2930 {
+        gas_price: U256,
+    },
+    Eip1559 {
+        max_fee_per_gas: U256,
+        max_priority_fee_per_gas: U256,
+    },
+}
+
+impl EthFee {
+    pub fn fee_mode(&self) -> FeeMode {
+        match self {
+            EthFee::Legacy { .. } => FeeMode::Legacy,
+            EthFee::Eip2930 { .. } => FeeMode::Eip2930,
+            EthFee::Eip1559 { .. } => FeeMode::Eip1559,
+        }
+    }
+
+    // the highest price per gas the tx may pay
+    pub fn max_fee_per_gas(&self) -> U256 {
+        match self {
+            EthFee::Legacy { gas_price } | EthFee::Eip2930 { gas_price } => *gas_price,
+            EthFee::Eip1559 {
+                max_fee_per_gas, ..
+            } => *max_fee_per_gas,
+        }
+    }
+
+    // the fee with every price capped by max_fee_per_gas.
+    pub fn cap(&self, max_fee_per_gas: U256) -> EthFee {
+        let cap_value = |v: U256| std::cmp::min(v, max_fee_per_gas);
+        match self {
+            EthFee::Legacy { gas_price } => EthFee::Legacy {
+                gas_price: cap_value(*gas_price),
+            },
+            EthFee::Eip2930 { gas_price } => EthFee::Eip2930 {
+                gas_price: cap_value(*gas_price),
+            },
+            EthFee::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => EthFee::Eip1559 {
+                max_fee_per_gas: cap_value(*max_fee_per_gas),
+                max_priority_fee_per_gas: cap_value(*max_priority_fee_per_gas),
+            },
+        }
+    }
+
+    // the fee of the replacement tx: every price is raised by bump_percent and capped by
+    // max_fee_per_gas. return none if the fee is already at the cap.
+    pub fn bump(&self, bump_percent: u64, max_fee_per_gas: U256) -> Option<EthFee> {
+        if self.max_fee_per_gas() >= max_fee_per_gas {
+            return None;
+        }
+        let bump_value =
+            |v: U256| v + (v * U256::from(bump_percent) + U256::from(99u64)) / U256::from(100u64);
+        let bumped = match self {
+            EthFee::Legacy { gas_price } => EthFee::Legacy {
+                gas_price: bump_value(*gas_price),
+            },
+            EthFee::Eip2930 { gas_price } => EthFee::Eip2930 {
+                gas_price: bump_value(*gas_price),
+            },
+            EthFee::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => EthFee::Eip1559 {
+                max_fee_per_gas: bump_value(*max_fee_per_gas),
+                max_priority_fee_per_gas: bump_value(*max_priority_fee_per_gas),
+            },
+        };
+        Some(bumped.cap(max_fee_per_gas))
+    }
+}
+
+// append the fields of the typed tx before the signature, return the tx type. the access list is
+// always empty.
+#[allow(clippy::too_many_arguments)]
+fn append_typed_transaction_fields(
+    stream: &mut RlpStream,
+    to: H160,
+    nonce: U256,
+    data: &[u8],
+    fee: &EthFee,
+    gas_limit: U256,
+    eth_value: U256,
+    chain_id: U256,
+) -> Result<u8> {
+    stream.append(&chain_id);
+    stream.append(&nonce);
+    let tx_type = match fee {
+        EthFee::Legacy { .. } => bail!("the legacy tx is not a typed tx"),
+        EthFee::Eip2930 { gas_price } => {
+            stream.append(gas_price);
+            EIP2930_TX_TYPE
+        }
+        EthFee::Eip1559 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        } => {
+            stream.append(max_priority_fee_per_gas);
+            stream.append(max_fee_per_gas);
+            EIP1559_TX_TYPE
+        }
+    };
+    stream.append(&gas_limit);
+    stream.append(&to);
+    stream.append(&eth_value);
+    stream.append(&data);
+    stream.begin_list(0);
+    Ok(tx_type)
+}
+
+// the payload to be signed by the sender's wallet. it is the rlp of the legacy tx without the
+// chain id, which the wallet adds as EIP-155 requires, or the typed tx envelope without signature.
+#[allow(clippy::too_many_arguments)]
+pub fn encode_unsigned_transaction(
+    to: H160,
+    nonce: U256,
+    data: Vec<u8>,
+    fee: &EthFee,
+    gas_limit: U256,
+    eth_value: U256,
+    chain_id: U256,
+) -> Result<Vec<u8>> {
+    if let EthFee::Legacy { gas_price } = fee {
+        let tx = make_transaction(to, nonce, data, *gas_price, gas_limit, eth_value);
+        return Ok(hex::decode(rlp_transaction(&tx))?);
+    }
+    let mut stream = RlpStream::new();
+    stream.begin_unbounded_list();
+    let tx_type = append_typed_transaction_fields(
+        &mut stream,
+        to,
+        nonce,
+        &data,
+        fee,
+        gas_limit,
+        eth_value,
+        chain_id,
+    )?;
+    stream.finalize_unbounded_list();
+    let mut unsigned_tx = vec![tx_type];
+    unsigned_tx.extend_from_slice(stream.out().as_slice());
+    Ok(unsigned_tx)
+}
+
+// sign the tx with the fee, the EIP-2930 and EIP-1559 txs are encoded as the EIP-2718 envelope.
+#[allow(clippy::too_many_arguments)]
+pub fn sign_transaction_with_fee(
+    to: H160,
+    nonce: U256,
+    data: Vec<u8>,
+    fee: &EthFee,
+    gas_limit: U256,
+    eth_value: U256,
+    eth_private_key: &H256,
+    chain_id: U256,
+) -> Result<Vec<u8>> {
+    if let EthFee::Legacy { gas_price } = fee {
+        let tx = make_transaction(to, nonce, data, *gas_price, gas_limit, eth_value);
+        return Ok(tx.sign(eth_private_key, &chain_id.as_u32()));
+    }
+    let unsigned_tx =
+        encode_unsigned_transaction(to, nonce, data.clone(), fee, gas_limit, eth_value, chain_id)?;
+    let eth_key = SecretKey::from_slice(&eth_private_key.0)?;
+    let message = secp256k1::Message::from_slice(&keccak256(unsigned_tx.as_slice()))?;
+    let (recovery, sig_bytes) = Secp256k1::signing_only()
+        .sign_recoverable(&message, &eth_key)
+        .serialize_compact();
+
+    let mut stream = RlpStream::new();
+    stream.begin_unbounded_list();
+    let tx_type = append_typed_transaction_fields(
+        &mut stream,
+        to,
+        nonce,
+        &data,
+        fee,
+        gas_limit,
+        eth_value,
+        chain_id,
+    )?;
+    stream.append(&(recovery.to_i32() as u64));
+    stream.append(&U256::from_big_endian(&sig_bytes[..32]));
+    stream.append(&U256::from_big_endian(&sig_bytes[32..]));
+    stream.finalize_unbounded_list();
+    let mut signed_tx = vec![tx_type];
+    signed_tx.extend_from_slice(stream.out().as_slice());
+    Ok(signed_tx)
+}
+
 pub fn parse_private_key(
     path: &str,
     config: &ForceConfig,
@@ -388,6 +975,22 @@ pub fn secret_key_address(key: &SecretKey) -> Address {
     public_key_address(&public_key)
 }
 
+/// Recovers the address which signed the message hash, the signature is `r || s || v`.
+pub fn recover_signature_address(msg_hash: &[u8], signature: &[u8]) -> Result<Address> {
+    if signature.len() != 65 {
+        bail!("invalid signature length: {}", signature.len());
+    }
+    let recovery_id = RecoveryId::from_i32(i32::from(signature[64]) - 27)
+        .map_err(|e| anyhow!("invalid signature recovery id: {}", e))?;
+    let signature = RecoverableSignature::from_compact(&signature[..64], recovery_id)
+        .map_err(|e| anyhow!("invalid signature: {}", e))?;
+    let message = Message::from_slice(msg_hash).map_err(|e| anyhow!("invalid message: {}", e))?;
+    let public_key = Secp256k1::verification_only()
+        .recover(&message, &signature)
+        .map_err(|e| anyhow!("failed to recover signature: {}", e))?;
+    Ok(public_key_address(&public_key))
+}
+
 fn public_key_address(public_key: &PublicKey) -> Address {
     let public_key = public_key.serialize_uncompressed();
 
@@ -419,8 +1022,9 @@ fn convert_account(value: web3::types::H160) -> ethereum_types::H160 {
     ethereum_types::H160::from(value.0)
 }
 
-fn rlp_append<TX>(header: &Block<TX>, stream: &mut RlpStream) {
-    stream.begin_list(15);
+fn rlp_append<TX>(header: &Block<TX>, extension: &HeaderExtension, stream: &mut RlpStream) {
+    let appended_fields = extension.rlp_fields();
+    stream.begin_list(15 + appended_fields.len());
     stream.append(&header.parent_hash);
     stream.append(&header.uncles_hash);
     stream.append(&header.author);
@@ -436,9 +1040,19 @@ fn rlp_append<TX>(header: &Block<TX>, stream: &mut RlpStream) {
     stream.append(&header.extra_data.0);
     stream.append(&header.mix_hash.unwrap());
     stream.append(&header.nonce.unwrap());
+    for field in appended_fields.iter() {
+        stream.append_raw(field, 1);
+    }
 }
 
-pub fn decode_block_header(serialized: &Rlp) -> Result<BlockHeader, DecoderError> {
+// decode the header with the fields appended since london, the fields unknown to the relayer are
+// ignored, they are still covered by the hash.
+pub fn decode_block_header(
+    serialized: &Rlp,
+) -> Result<(BlockHeader, HeaderExtension), DecoderError> {
+    if serialized.item_count()? < 15 {
+        return Err(DecoderError::RlpIncorrectListLen);
+    }
     let block_header = BlockHeader {
         parent_hash: serialized.val_at(0)?,
         uncles_hash: serialized.val_at(1)?,
@@ -452,13 +1066,32 @@ pub fn decode_block_header(serialized: &Rlp) -> Result<BlockHeader, DecoderError
         gas_limit: serialized.val_at(9)?,
         gas_used: serialized.val_at(10)?,
         timestamp: serialized.val_at(11)?,
-        extra_data: Bytes::from(serialized.as_raw()),
+        extra_data: Bytes(serialized.val_at(12)?),
         mix_hash: Some(serialized.val_at(13)?),
         nonce: Some(serialized.val_at(14)?),
         hash: Some(keccak256(serialized.as_raw()).into()),
     };
+    let extension = HeaderExtension {
+        base_fee_per_gas: optional_val_at(serialized, 15)?,
+        withdrawals_root: optional_val_at(serialized, 16)?,
+        blob_gas_used: optional_val_at::<u64>(serialized, 17)?.map(U64::from),
+        excess_blob_gas: optional_val_at::<u64>(serialized, 18)?.map(U64::from),
+        parent_beacon_block_root: optional_val_at(serialized, 19)?,
+        requests_hash: optional_val_at(serialized, 20)?,
+    };
 
-    Ok(block_header)
+    Ok((block_header, extension))
+}
+
+fn optional_val_at<T: rlp::Decodable>(
+    serialized: &Rlp,
+    index: usize,
+) -> Result<Option<T>, DecoderError> {
+    if index < serialized.item_count()? {
+        Ok(Some(serialized.val_at(index)?))
+    } else {
+        Ok(None)
+    }
 }
 
 pub fn convert_eth_address(mut address: &str) -> Result<H160> {
@@ -572,3 +1205,44 @@ pub fn rlp_transaction(tx: &RawTransaction) -> String {
 pub fn parse_secret_key(privkey: H256) -> Result<SecretKey> {
     Ok(SecretKey::from_slice(&privkey.0)?)
 }
+
+#[test]
+fn test_header_rlp_with_extension() {
+    // the mainnet block 19449567 after cancun
+    let block = r#"{
+        "baseFeePerGas": "0x886b221ad",
+        "blobGasUsed": "0x0",
+        "difficulty": "0x0",
+        "excessBlobGas": "0x0",
+        "extraData": "0x6265617665726275696c642e6f7267",
+        "gasLimit": "0x1c9c380",
+        "gasUsed": "0xb0033c",
+        "hash": "0x85cdcbe36217fd57bf2c33731d8460657a7ce512401f49c9f6392c82a7ccf7ac",
+        "logsBloom": "0xc36919406572730518285284f2293101104140c0d42c4a786c892467868a8806f40159d29988002870403902413a1d04321320308da2e845438429e0012a00b419d8ccc8584a1c28f82a415d04eab8a5ae75c00d07761acf233414c08b6d9b571c06156086c70ea5186e9b989b0c2d55c0213c936805cd2ab331589c90194d070c00867549b1e1be14cb24500b0386cd901197c1ef5a00da453234fa48f3003dcaa894e3111c22b80e17f7d4388385a10720cda1140c0400f9e084ca34fc4870fb16b472340a2a6a63115a82522f506c06c2675080508834828c63defd06bc2331b4aa708906a06a560457b114248041e40179ebc05c6846c1e922125982f427",
+        "miner": "0x95222290dd7278aa3ddd389cc1e1d165cc4bafe5",
+        "mixHash": "0x4c068e902990f21f92a2456fc75c59bec8be03b7f13682b6ebd27da56269beb5",
+        "nonce": "0x0000000000000000",
+        "number": "0x128c6df",
+        "parentBeaconBlockRoot": "0x2843cb9f7d001bd58816a915e685ed96a555c9aeec1217736bd83a96ebd409cc",
+        "parentHash": "0x90926e0298d418181bd20c23b332451e35fd7d696b5dcdc5a3a0a6b715f4c717",
+        "receiptsRoot": "0xd43aa19ecb03571d1b86d89d9bb980139d32f2f2ba59646cd5c1de9e80c68c90",
+        "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+        "stateRoot": "0x707875120a7103621fb4131df59904cda39de948dfda9084a1e3da44594d5404",
+        "timestamp": "0x65f5f4c3",
+        "transactions": [],
+        "transactionsRoot": "0x889a1c26dc42ba829dab552b779620feac231cde8a6c79af022bdc605c23a780",
+        "uncles": [],
+        "withdrawalsRoot": "0x360c33f20eeed5efbc7d08be46e58f8440af5db503e40908ef3d1eb314856ef7"
+    }"#;
+    let value: serde_json::Value = serde_json::from_str(block).unwrap();
+    let block: Block<H256> = serde_json::from_value(value.clone()).unwrap();
+    let extension: HeaderExtension = serde_json::from_value(value).unwrap();
+    assert_eq!(extension.rlp_fields().len(), 5);
+    assert!(convert_to_header_rlp(&block, &HeaderExtension::default()).is_err());
+
+    let header_rlp = hex::decode(convert_to_header_rlp(&block, &extension).unwrap()).unwrap();
+    let (header, decoded_extension) = decode_block_header(&Rlp::new(&header_rlp)).unwrap();
+    assert_eq!(header.hash, block.hash);
+    assert_eq!(header.number, block.number);
+    assert_eq!(decoded_extension, extension);
+}
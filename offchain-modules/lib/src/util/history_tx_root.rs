@@ -0,0 +1,330 @@
+use crate::util::ckb_proof_helper::Keccak256;
+use crate::util::ckb_types::CKBHistoryTxRootProof;
+use crate::util::rocksdb::{open_readonly_rocksdb, open_rocksdb};
+use anyhow::{anyhow, bail, Result};
+use ckb_types::H256;
+use merkle_cbt::merkle_tree::Merge;
+use rocksdb::ops::{Get, Iterate, WriteOps};
+use rocksdb::{Direction, IteratorMode, ReadOnlyDB, WriteBatch, DB};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+// key prefix of the cached subtree nodes: prefix | height (1 byte) | first block number (8 bytes
+// big endian), so that the nodes of one height are sorted by their first block.
+pub const HISTORY_NODE_PREFIX: &[u8] = b"history_node";
+// the subtrees lower than this are cheap to rebuild from the tx roots and are not cached.
+pub const MIN_CACHED_NODE_HEIGHT: u8 = 4;
+pub const MAX_NODE_HEIGHT: u8 = 63;
+
+// The history tx root is the root of a CBMT over the transactions roots of the ckb blocks in
+// [init_block_number, latest_block_number], the layout is fixed by the TokenLocker contract.
+// The CBMT of n leaves is not a prefix of the one of n + 1 leaves, but every perfect subtree in
+// it is the merkle root of 2^height consecutive blocks, which is the same in all the trees it
+// shows up in. The accumulator caches these subtree roots in the ckb rocksdb, keyed by height and
+// first block, so the root and proofs of a tree only merge the few nodes missing from the cache.
+pub struct HistoryTxRootAccumulator {
+    db: Option<Arc<DB>>,
+    read_only_db: Option<Arc<ReadOnlyDB>>,
+    // the subtree roots computed but not committed to db, keyed by (height, first block)
+    inserted_nodes: HashMap<(u8, u64), [u8; 32]>,
+}
+
+impl HistoryTxRootAccumulator {
+    pub fn open(db_path: String) -> Result<Self> {
+        Ok(HistoryTxRootAccumulator {
+            db: Some(open_rocksdb(db_path)?),
+            read_only_db: None,
+            inserted_nodes: HashMap::default(),
+        })
+    }
+
+    // the nodes computed by a read only accumulator are dropped, it never writes the db.
+    pub fn open_readonly(db_path: String) -> Result<Self> {
+        Ok(HistoryTxRootAccumulator {
+            db: None,
+            read_only_db: Some(open_readonly_rocksdb(db_path)?),
+            inserted_nodes: HashMap::default(),
+        })
+    }
+
+    fn get_raw(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let value = match self.db.as_ref() {
+            Some(db) => db
+                .get(key)
+                .map_err(|e| anyhow!("get {:?} err: {:?}", key, e))?,
+            None => self
+                .read_only_db
+                .as_ref()
+                .expect("should be read only db when db is none")
+                .get(key)
+                .map_err(|e| anyhow!("get {:?} err: {:?}", key, e))?,
+        };
+        Ok(value.map(|v| v.to_vec()))
+    }
+
+    pub fn get_tx_root(&self, block_number: u64) -> Result<[u8; 32]> {
+        let db_root = self
+            .get_raw(&block_number.to_le_bytes())?
+            .ok_or_else(|| anyhow!("db ckb root should not be none"))?;
+        let mut db_root_raw = [0u8; 32];
+        db_root_raw.copy_from_slice(db_root.as_ref());
+        Ok(db_root_raw)
+    }
+
+    // the merkle root of the perfect subtree over the 2^height blocks from first_block.
+    pub fn get_subtree_root(&mut self, height: u8, first_block: u64) -> Result<[u8; 32]> {
+        if height == 0 {
+            return self.get_tx_root(first_block);
+        }
+        if height > MAX_NODE_HEIGHT {
+            bail!("invalid subtree height {}", height);
+        }
+        if height >= MIN_CACHED_NODE_HEIGHT {
+            if let Some(node) = self.inserted_nodes.get(&(height, first_block)) {
+                return Ok(*node);
+            }
+            if let Some(v) = self.get_raw(&get_db_key_for_node(height, first_block))? {
+                let mut node = [0u8; 32];
+                node.copy_from_slice(v.as_ref());
+                return Ok(node);
+            }
+        }
+        let left = self.get_subtree_root(height - 1, first_block)?;
+        let right = self.get_subtree_root(height - 1, first_block + (1u64 << (height - 1)))?;
+        let node = Keccak256::merge(&left, &right);
+        if height >= MIN_CACHED_NODE_HEIGHT {
+            self.inserted_nodes.insert((height, first_block), node);
+        }
+        Ok(node)
+    }
+
+    // the node at `index` of the CBMT over `leaves_count` blocks from start_block, the nodes are
+    // indexed as the merkle-cbt crate does: the children of node i are 2i + 1 and 2i + 2, and the
+    // leaves are [leaves_count - 1, 2 * leaves_count - 1).
+    fn get_node(&mut self, index: u64, leaves_count: u64, start_block: u64) -> Result<[u8; 32]> {
+        let first_leaf = leaves_count - 1;
+        if index >= first_leaf {
+            return self.get_tx_root(start_block + index - first_leaf);
+        }
+        // walk down the leftmost and rightmost paths, the subtree is perfect if both reach the
+        // leaves at the same depth.
+        let (mut left, mut right, mut height) = (index, index, 0u8);
+        while left < first_leaf && right < first_leaf {
+            left = 2 * left + 1;
+            right = 2 * right + 2;
+            height += 1;
+        }
+        if left >= first_leaf && right >= first_leaf {
+            return self.get_subtree_root(height, start_block + left - first_leaf);
+        }
+        let left_node = self.get_node(2 * index + 1, leaves_count, start_block)?;
+        let right_node = self.get_node(2 * index + 2, leaves_count, start_block)?;
+        Ok(Keccak256::merge(&left_node, &right_node))
+    }
+
+    pub fn get_root(&mut self, start_block: u64, latest_block: u64) -> Result<[u8; 32]> {
+        if start_block > latest_block {
+            bail!(
+                "start block {} is greater than latest block {}",
+                start_block,
+                latest_block
+            );
+        }
+        let leaves_count = latest_block - start_block + 1;
+        self.get_node(0, leaves_count, start_block)
+    }
+
+    // build the proof of the blocks against the CBMT over [start_block, latest_block]. the
+    // indices and lemmas are in the order the TokenLocker contract consumes them.
+    pub fn get_proof(
+        &mut self,
+        start_block: u64,
+        latest_block: u64,
+        block_numbers: &[u64],
+    ) -> Result<CKBHistoryTxRootProof> {
+        if block_numbers.is_empty() {
+            bail!("no block to prove");
+        }
+        for &number in block_numbers {
+            if number < start_block || number > latest_block {
+                bail!(
+                    "block number {} not yet between init_block_number {} and latest_block_number {}",
+                    number,
+                    start_block,
+                    latest_block
+                );
+            }
+        }
+        let leaves_count = latest_block - start_block + 1;
+        let mut indices = block_numbers
+            .iter()
+            .map(|number| number - start_block + leaves_count - 1)
+            .collect::<Vec<u64>>();
+        indices.sort_by(|a, b| b.cmp(a));
+        indices.dedup();
+
+        let mut proof_leaves: Vec<H256> = vec![];
+        for &index in indices.iter() {
+            proof_leaves.push(self.get_node(index, leaves_count, start_block)?.into());
+        }
+        let mut lemmas: Vec<H256> = vec![];
+        let mut queue: VecDeque<u64> = indices.clone().into();
+        while let Some(index) = queue.pop_front() {
+            if index == 0 {
+                break;
+            }
+            let sibling = ((index + 1) ^ 1) - 1;
+            if queue.front() == Some(&sibling) {
+                queue.pop_front();
+            } else {
+                lemmas.push(self.get_node(sibling, leaves_count, start_block)?.into());
+            }
+            let parent = (index - 1) >> 1;
+            if parent != 0 || !queue.is_empty() {
+                queue.push_back(parent);
+            }
+        }
+        Ok(CKBHistoryTxRootProof {
+            init_block_number: start_block,
+            latest_block_number: latest_block,
+            indices,
+            proof_leaves,
+            lemmas,
+        })
+    }
+
+    pub fn commit(&mut self) -> Result<()> {
+        let db = match self.db.as_ref() {
+            Some(db) => db,
+            None => {
+                self.inserted_nodes.clear();
+                return Ok(());
+            }
+        };
+        let mut batch = WriteBatch::default();
+        for ((height, first_block), node) in self.inserted_nodes.iter() {
+            batch
+                .put(get_db_key_for_node(*height, *first_block), node)
+                .map_err(|e| anyhow!("put history node err: {:?}", e))?;
+        }
+        db.write(&batch)
+            .map_err(|e| anyhow!("write batch commit err: {:?}", e))?;
+        self.inserted_nodes.clear();
+        Ok(())
+    }
+}
+
+// Delete the cached subtree roots which cover the block, it is called when the transactions root
+// of the block is overwritten by a reorg.
+pub fn invalidate_history_nodes(db: &DB, block_number: u64) -> Result<()> {
+    let mut batch = WriteBatch::default();
+    for height in MIN_CACHED_NODE_HEIGHT..=MAX_NODE_HEIGHT {
+        let lowest_first_block = block_number.saturating_sub((1u64 << height) - 1);
+        let from_key = get_db_key_for_node(height, lowest_first_block);
+        let to_key = get_db_key_for_node(height, block_number);
+        for (key, _) in db.iterator(IteratorMode::From(&from_key, Direction::Forward)) {
+            if key.as_ref() > to_key.as_slice() {
+                break;
+            }
+            batch
+                .delete(key)
+                .map_err(|e| anyhow!("delete history node err: {:?}", e))?;
+        }
+    }
+    db.write(&batch)
+        .map_err(|e| anyhow!("write batch commit err: {:?}", e))?;
+    Ok(())
+}
+
+fn get_db_key_for_node(height: u8, first_block: u64) -> Vec<u8> {
+    let mut db_key = vec![];
+    db_key.extend_from_slice(HISTORY_NODE_PREFIX.as_ref());
+    db_key.push(height);
+    db_key.extend_from_slice(&first_block.to_be_bytes());
+    db_key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::ckb_proof_helper::CBMT;
+
+    fn tx_root(block_number: u64) -> [u8; 32] {
+        let mut root = [0xabu8; 32];
+        root[..8].copy_from_slice(&block_number.to_le_bytes());
+        root
+    }
+
+    #[test]
+    fn test_accumulator_matches_cbmt() {
+        let db_path =
+            std::env::temp_dir().join(format!("force-history-tx-root-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&db_path);
+        let db_path = db_path.to_str().unwrap().to_owned();
+        let start_block = 100u64;
+        let max_leaves_count = 40u64;
+        {
+            let db = open_rocksdb(db_path.clone()).unwrap();
+            let mut batch = WriteBatch::default();
+            for number in start_block..start_block + max_leaves_count {
+                batch.put(number.to_le_bytes(), tx_root(number)).unwrap();
+            }
+            db.write(&batch).unwrap();
+        }
+
+        let mut accumulator = HistoryTxRootAccumulator::open(db_path).unwrap();
+        // the trees grow one block at a time like the relayer does, so the larger ones are built
+        // from the subtrees cached by the smaller ones.
+        for leaves_count in 1..=max_leaves_count {
+            let latest_block = start_block + leaves_count - 1;
+            let leaves = (start_block..=latest_block)
+                .map(tx_root)
+                .collect::<Vec<_>>();
+            assert_eq!(
+                accumulator.get_root(start_block, latest_block).unwrap(),
+                CBMT::build_merkle_root(&leaves),
+                "root of {} leaves",
+                leaves_count
+            );
+
+            let mut cases = vec![vec![0], vec![leaves_count / 2], vec![leaves_count - 1]];
+            if leaves_count > 2 {
+                cases.push(vec![0, leaves_count - 1]);
+                cases.push(vec![1, leaves_count / 2, leaves_count - 2]);
+            }
+            for leaf_indices in cases {
+                let block_numbers = leaf_indices
+                    .iter()
+                    .map(|i| start_block + i)
+                    .collect::<Vec<_>>();
+                let proof = accumulator
+                    .get_proof(start_block, latest_block, &block_numbers)
+                    .unwrap();
+                let cbmt_proof = CBMT::build_merkle_proof(
+                    &leaves,
+                    &leaf_indices.iter().map(|i| *i as u32).collect::<Vec<_>>(),
+                )
+                .unwrap();
+                let mut cbmt_indices = cbmt_proof
+                    .indices()
+                    .iter()
+                    .map(|i| *i as u64)
+                    .collect::<Vec<_>>();
+                cbmt_indices.sort_by(|a, b| b.cmp(a));
+                let cbmt_proof_leaves = cbmt_indices
+                    .iter()
+                    .map(|i| tx_root(start_block + i + 1 - leaves_count).into())
+                    .collect::<Vec<H256>>();
+                let cbmt_lemmas = cbmt_proof
+                    .lemmas()
+                    .iter()
+                    .map(|lemma| (*lemma).into())
+                    .collect::<Vec<H256>>();
+                assert_eq!(proof.indices, cbmt_indices, "{:?}", leaf_indices);
+                assert_eq!(proof.proof_leaves, cbmt_proof_leaves, "{:?}", leaf_indices);
+                assert_eq!(proof.lemmas, cbmt_lemmas, "{:?}", leaf_indices);
+            }
+            accumulator.commit().unwrap();
+        }
+    }
+}
@@ -4,8 +4,11 @@ pub mod ckb_types;
 pub mod ckb_util;
 pub mod config;
 pub mod eth_proof_helper;
+pub mod eth_trie;
+pub mod eth_tx_manager;
 pub mod eth_util;
 pub mod generated;
+pub mod history_tx_root;
 pub mod rocksdb;
 
 use self::config::ForceConfig;
@@ -1,4 +1,5 @@
 use anyhow::{anyhow, Result};
+use force_eth_types::consumed_event_registry::CONSUMED_EVENT_VALUE;
 use force_eth_types::hasher::Blake2bHasher;
 use rocksdb::ops::{Get, Open, WriteOps};
 use rocksdb::{ReadOnlyDB, WriteBatch, DB};
@@ -6,12 +7,20 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sparse_merkle_tree::error::Error;
 use sparse_merkle_tree::traits::{Store, Value};
 use sparse_merkle_tree::tree::{BranchNode, LeafNode};
-use sparse_merkle_tree::H256;
+use sparse_merkle_tree::{CompiledMerkleProof, H256};
 use std::path::Path;
 use std::sync::Arc;
 
 pub const BRANCH_PREFIX: &[u8] = b"branch";
 pub const LEAF_PREFIX: &[u8] = b"leaf";
+// the merkle root and latest height which the committed smt nodes belong to
+pub const COMMITTED_ROOT_KEY: &[u8] = b"committed_root";
+pub const COMMITTED_HEIGHT_KEY: &[u8] = b"committed_height";
+// the roots of the smt snapshots kept in db, and the nodes to delete when a snapshot expires
+pub const SNAPSHOT_ROOTS_KEY: &[u8] = b"snapshot_roots";
+pub const SNAPSHOT_REMOVED_PREFIX: &[u8] = b"snapshot_removed";
+// keep the smt of the last DEFAULT_SNAPSHOT_LIMIT committed roots by default
+pub const DEFAULT_SNAPSHOT_LIMIT: usize = 32;
 
 pub type SMT =
     sparse_merkle_tree::SparseMerkleTree<Blake2bHasher, RocksDBValue, RocksDBStore<RocksDBValue>>;
@@ -28,6 +37,7 @@ pub struct RocksDBStore<V> {
     pub inserted_leaves_map: Map<H256, LeafNode<V>>,
     pub removed_branch_set: Set<H256>,
     pub removed_leaves_set: Set<H256>,
+    pub snapshot_limit: usize,
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -44,6 +54,26 @@ pub struct DBLeafNode<V> {
     pub value: V,
 }
 
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct DBSnapshot {
+    pub root: [u8; 32],
+    pub latest_height: u64,
+}
+
+// the nodes of a snapshot which are removed from the later smt, they are deleted from db only
+// when the snapshot expires.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct DBSnapshotRemoved {
+    pub branches: Vec<[u8; 32]>,
+    pub leaves: Vec<[u8; 32]>,
+}
+
+impl DBSnapshotRemoved {
+    fn is_empty(&self) -> bool {
+        self.branches.is_empty() && self.leaves.is_empty()
+    }
+}
+
 impl<V: Clone + Serialize> RocksDBStore<V> {
     pub fn open_readonly(path: String) -> Result<Self> {
         let db_dir = shellexpand::tilde(path.as_str()).into_owned();
@@ -63,6 +93,7 @@ impl<V: Clone + Serialize> RocksDBStore<V> {
             inserted_leaves_map: Map::default(),
             removed_branch_set: Set::default(),
             removed_leaves_set: Set::default(),
+            snapshot_limit: DEFAULT_SNAPSHOT_LIMIT,
         })
     }
     pub fn open(path: String) -> Result<Self> {
@@ -82,6 +113,7 @@ impl<V: Clone + Serialize> RocksDBStore<V> {
             inserted_leaves_map: Map::default(),
             removed_branch_set: Set::default(),
             removed_leaves_set: Set::default(),
+            snapshot_limit: DEFAULT_SNAPSHOT_LIMIT,
         })
     }
     pub fn new(path: String) -> Result<Self> {
@@ -103,10 +135,237 @@ impl<V: Clone + Serialize> RocksDBStore<V> {
             inserted_leaves_map: Map::default(),
             removed_branch_set: Set::default(),
             removed_leaves_set: Set::default(),
+            snapshot_limit: DEFAULT_SNAPSHOT_LIMIT,
         })
     }
 
+    pub fn set_snapshot_limit(&mut self, snapshot_limit: usize) {
+        self.snapshot_limit = snapshot_limit;
+    }
+
+    // commit the staged nodes, the removed nodes are deleted at once and no snapshot is kept.
     pub fn commit(&mut self) -> Result<()> {
+        let mut batch = self.staged_batch()?;
+        self.delete_removed_nodes(&mut batch)?;
+        self.write_batch(batch)
+    }
+
+    // commit the staged nodes together with the merkle root and latest height they belong to in
+    // one write batch, so the committed nodes and root can never be out of sync after a crash.
+    // The smt of the last `snapshot_limit` roots are kept in db, the nodes removed by this commit
+    // are deleted when the snapshot of the previous root expires.
+    pub fn commit_with_root(&mut self, merkle_root: [u8; 32], latest_height: u64) -> Result<()> {
+        let mut batch = self.staged_batch()?;
+        self.update_snapshots(&mut batch, merkle_root, latest_height)?;
+        batch
+            .put(COMMITTED_ROOT_KEY, merkle_root)
+            .map_err(|e| anyhow!("put committed root err: {:?}", e))?;
+        batch
+            .put(COMMITTED_HEIGHT_KEY, latest_height.to_le_bytes())
+            .map_err(|e| anyhow!("put committed height err: {:?}", e))?;
+        self.write_batch(batch)
+    }
+
+    // drop the staged changes which are not committed to db yet.
+    pub fn rollback(&mut self) {
+        self.inserted_branch_map.clear();
+        self.inserted_leaves_map.clear();
+        self.removed_branch_set.clear();
+        self.removed_leaves_set.clear();
+    }
+
+    // get the merkle root and latest height of the last commit_with_root.
+    pub fn get_committed_root(&self) -> Result<Option<([u8; 32], u64)>> {
+        let root = self.get_raw(COMMITTED_ROOT_KEY)?;
+        let height = self.get_raw(COMMITTED_HEIGHT_KEY)?;
+        match (root, height) {
+            (Some(root), Some(height)) => {
+                if root.len() != 32 || height.len() != 8 {
+                    return Err(anyhow!("invalid committed root in db"));
+                }
+                let mut root_raw = [0u8; 32];
+                root_raw.copy_from_slice(root.as_slice());
+                let mut height_raw = [0u8; 8];
+                height_raw.copy_from_slice(height.as_slice());
+                Ok(Some((root_raw, u64::from_le_bytes(height_raw))))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    // the snapshots kept in db, from the oldest to the latest.
+    pub fn get_snapshots(&self) -> Result<Vec<DBSnapshot>> {
+        match self.get_raw(SNAPSHOT_ROOTS_KEY)? {
+            Some(v) => serde_json::from_slice(v.as_slice())
+                .map_err(|e| anyhow!("deserialize snapshot roots err: {:?}", e)),
+            None => Ok(vec![]),
+        }
+    }
+
+    // whether the smt of the root can be read from db.
+    pub fn has_snapshot(&self, root: [u8; 32]) -> Result<bool> {
+        if self.get_snapshots()?.iter().any(|s| s.root == root) {
+            return Ok(true);
+        }
+        Ok(
+            matches!(self.get_committed_root()?, Some((committed_root, _)) if committed_root == root),
+        )
+    }
+
+    fn get_snapshot_removed(&self, root: &[u8; 32]) -> Result<DBSnapshotRemoved> {
+        match self.get_raw(get_db_key_for_snapshot_removed(root).as_slice())? {
+            Some(v) => serde_json::from_slice(v.as_slice())
+                .map_err(|e| anyhow!("deserialize snapshot removed nodes err: {:?}", e)),
+            None => Ok(DBSnapshotRemoved::default()),
+        }
+    }
+
+    // attach the removed nodes to the snapshot of the previous committed root, append the new root
+    // to the snapshots and delete the nodes of the expired snapshots.
+    fn update_snapshots(
+        &self,
+        batch: &mut WriteBatch,
+        merkle_root: [u8; 32],
+        latest_height: u64,
+    ) -> Result<()> {
+        let mut snapshots = self.get_snapshots()?;
+        let previous_root = self.get_committed_root()?.map(|(root, _)| root);
+        let previous_snapshot_kept = self.snapshot_limit > 0
+            && previous_root.is_some()
+            && snapshots.last().map(|s| s.root) == previous_root;
+
+        let mut removed_map = Map::default();
+        for snapshot in snapshots.iter() {
+            let mut removed = self.get_snapshot_removed(&snapshot.root)?;
+            // the nodes inserted again are in the latest smt, they should not be deleted anymore
+            removed
+                .branches
+                .retain(|k| !self.inserted_branch_map.contains_key(&H256::from(*k)));
+            removed
+                .leaves
+                .retain(|k| !self.inserted_leaves_map.contains_key(&H256::from(*k)));
+            removed_map.insert(snapshot.root, removed);
+        }
+        if previous_snapshot_kept {
+            let previous_removed = removed_map
+                .get_mut(&previous_root.expect("previous root should be some"))
+                .expect("previous snapshot should be kept");
+            previous_removed.branches.extend(
+                self.removed_branch_set
+                    .iter()
+                    .map(|k| -> [u8; 32] { (*k).into() }),
+            );
+            previous_removed.leaves.extend(
+                self.removed_leaves_set
+                    .iter()
+                    .map(|k| -> [u8; 32] { (*k).into() }),
+            );
+        } else {
+            // no snapshot refers to the removed nodes, delete them at once
+            self.delete_removed_nodes(batch)?;
+            for (root, removed) in removed_map.drain() {
+                delete_snapshot_nodes(batch, &root, &removed)?;
+            }
+            snapshots.clear();
+        }
+        // the root is committed again, e.g. the smt is reset to the light client cell
+        if let Some(index) = snapshots.iter().position(|s| s.root == merkle_root) {
+            let duplicated = snapshots.remove(index);
+            let duplicated_removed = removed_map.remove(&duplicated.root).unwrap_or_default();
+            match snapshots.last() {
+                Some(last) => {
+                    let last_removed = removed_map.entry(last.root).or_default();
+                    last_removed.branches.extend(duplicated_removed.branches);
+                    last_removed.leaves.extend(duplicated_removed.leaves);
+                }
+                None => delete_snapshot_nodes(batch, &duplicated.root, &duplicated_removed)?,
+            }
+        }
+        snapshots.push(DBSnapshot {
+            root: merkle_root,
+            latest_height,
+        });
+        removed_map.insert(merkle_root, DBSnapshotRemoved::default());
+
+        let expired_count = snapshots.len().saturating_sub(self.snapshot_limit.max(1));
+        for expired in snapshots.drain(..expired_count) {
+            let removed = removed_map.remove(&expired.root).unwrap_or_default();
+            delete_snapshot_nodes(batch, &expired.root, &removed)?;
+        }
+        for (root, removed) in removed_map.iter() {
+            let db_key = get_db_key_for_snapshot_removed(root);
+            if removed.is_empty() {
+                batch
+                    .delete(db_key)
+                    .map_err(|e| anyhow!("delete snapshot removed nodes err: {:?}", e))?;
+            } else {
+                let removed_raw = serde_json::to_vec(removed)
+                    .map_err(|e| anyhow!("serialize snapshot removed nodes err: {:?}", e))?;
+                batch
+                    .put(db_key, removed_raw)
+                    .map_err(|e| anyhow!("put snapshot removed nodes err: {:?}", e))?;
+            }
+        }
+        let snapshots_raw = serde_json::to_vec(&snapshots)
+            .map_err(|e| anyhow!("serialize snapshot roots err: {:?}", e))?;
+        batch
+            .put(SNAPSHOT_ROOTS_KEY, snapshots_raw)
+            .map_err(|e| anyhow!("put snapshot roots err: {:?}", e))?;
+        Ok(())
+    }
+
+    fn delete_removed_nodes(&self, batch: &mut WriteBatch) -> Result<()> {
+        for key in &self.removed_branch_set {
+            batch
+                .delete(get_db_key_for_branch(key.as_slice()))
+                .map_err(|e| anyhow!("delete branch err: {:?}", e))?;
+        }
+        for key in &self.removed_leaves_set {
+            batch
+                .delete(get_db_key_for_leaf(key.as_slice()))
+                .map_err(|e| anyhow!("delete leaf err: {:?}", e))?;
+        }
+        Ok(())
+    }
+
+    // a store reading the committed nodes only, the staged changes are not visible.
+    fn committed_store(&self) -> Self {
+        RocksDBStore {
+            db: self.db.clone(),
+            read_only_db: self.read_only_db.clone(),
+            inserted_branch_map: Map::default(),
+            inserted_leaves_map: Map::default(),
+            removed_branch_set: Set::default(),
+            removed_leaves_set: Set::default(),
+            snapshot_limit: self.snapshot_limit,
+        }
+    }
+
+    fn get_raw(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let value = match self.db.as_ref() {
+            Some(db) => db
+                .get(key)
+                .map_err(|e| anyhow!("get {:?} err: {:?}", key, e))?,
+            None => self
+                .read_only_db
+                .as_ref()
+                .expect("should be read only db when db is none")
+                .get(key)
+                .map_err(|e| anyhow!("get {:?} err: {:?}", key, e))?,
+        };
+        Ok(value.map(|v| v.to_vec()))
+    }
+
+    fn write_batch(&mut self, batch: WriteBatch) -> Result<()> {
+        let db = self.db.as_ref().expect("write mode should use db");
+        db.write(&batch)
+            .map_err(|e| anyhow!("write batch commit err: {:?}", e))?;
+        // the staged changes are in db now
+        self.rollback();
+        Ok(())
+    }
+
+    fn staged_batch(&self) -> Result<WriteBatch> {
         let mut batch = WriteBatch::default();
         for (key, branch) in &self.inserted_branch_map {
             let db_branch_node = DBBranchNode {
@@ -121,11 +380,6 @@ impl<V: Clone + Serialize> RocksDBStore<V> {
                 .put(get_db_key_for_branch(key.as_slice()), db_branch_node_raw)
                 .map_err(|e| anyhow!("put branch err: {:?}", e))?;
         }
-        for key in &self.removed_branch_set {
-            batch
-                .delete(get_db_key_for_branch(key.as_slice()))
-                .map_err(|e| anyhow!("put branch err: {:?}", e))?;
-        }
         for (key, leaf) in &self.inserted_leaves_map {
             let db_leaf_node = DBLeafNode {
                 key: leaf.key.into(),
@@ -137,15 +391,100 @@ impl<V: Clone + Serialize> RocksDBStore<V> {
                 .put(get_db_key_for_leaf(key.as_slice()), db_leaf_node_raw)
                 .map_err(|e| anyhow!("put leaf err: {:?}", e))?;
         }
-        for key in &self.removed_leaves_set {
-            batch
-                .delete(get_db_key_for_branch(key.as_slice()))
-                .map_err(|e| anyhow!("put branch err: {:?}", e))?;
+        Ok(batch)
+    }
+}
+
+impl RocksDBStore<RocksDBValue> {
+    // build the merkle proof of the heights against the smt snapshot of root, which is the root
+    // in the light client cell the tx refers to, rather than the latest local smt.
+    pub fn merkle_proof_for(&self, root: [u8; 32], heights: &[u64]) -> Result<CompiledMerkleProof> {
+        if !self.has_snapshot(root)? {
+            return Err(anyhow!(
+                "the smt snapshot of root {} is not kept in db",
+                hex::encode(root)
+            ));
         }
-        let db = self.db.as_ref().expect("write mode should use db");
-        db.write(&batch)
-            .map_err(|e| anyhow!("write batch commit err: {:?}", e))?;
-        Ok(())
+        let smt_tree = SMT::new(root.into(), self.committed_store());
+        let mut keys = vec![];
+        let mut leaves = vec![];
+        for height in heights {
+            let key: H256 = get_smt_key_for_height(*height).into();
+            let value = smt_tree
+                .get(&key)
+                .map_err(|e| anyhow!("get smt leaf of height {} err: {:?}", height, e))?;
+            keys.push(key);
+            leaves.push((key, value.to_h256()));
+        }
+        smt_tree
+            .merkle_proof(keys)
+            .map_err(|e| anyhow!("build merkle proof err: {:?}", e))?
+            .compile(leaves)
+            .map_err(|e| anyhow!("compile merkle proof err: {:?}", e))
+    }
+
+    // put the consumed event keys into the smt snapshot of root, which is the root in the consumed
+    // event registry cell. nothing is written to db, the keys are committed by
+    // `commit_consumed_keys` only after the tx consuming them is committed on chain. return the
+    // merkle proof of the keys before they are consumed and the new root.
+    pub fn consume_keys(
+        &self,
+        root: [u8; 32],
+        keys: &[[u8; 32]],
+    ) -> Result<(CompiledMerkleProof, [u8; 32])> {
+        let (smt_tree, proof) = self.stage_consumed_keys(root, keys)?;
+        Ok((proof, (*smt_tree.root()).into()))
+    }
+
+    // commit the keys consumed by a tx committed on chain, the new smt is committed with its root
+    // and the smt of root is kept as a snapshot. return the new root.
+    pub fn commit_consumed_keys(&self, root: [u8; 32], keys: &[[u8; 32]]) -> Result<[u8; 32]> {
+        let (mut smt_tree, _) = self.stage_consumed_keys(root, keys)?;
+        let new_root: [u8; 32] = (*smt_tree.root()).into();
+        smt_tree.store_mut().commit_with_root(new_root, 0)?;
+        Ok(new_root)
+    }
+
+    // the smt of root with the keys consumed in its staged nodes, and the merkle proof of the keys
+    // before they are consumed.
+    fn stage_consumed_keys(
+        &self,
+        root: [u8; 32],
+        keys: &[[u8; 32]],
+    ) -> Result<(SMT, CompiledMerkleProof)> {
+        // the zero root is the empty smt of a new registry
+        if root != [0u8; 32] && !self.has_snapshot(root)? {
+            return Err(anyhow!(
+                "the smt snapshot of root {} is not kept in db, please rebuild it with `force-cli rebuild-consumed-event-registry`",
+                hex::encode(root)
+            ));
+        }
+        let mut smt_tree = SMT::new(root.into(), self.committed_store());
+        let keys: Vec<H256> = keys.iter().map(|key| (*key).into()).collect();
+        let mut leaves = vec![];
+        for key in keys.iter() {
+            let value = smt_tree
+                .get(key)
+                .map_err(|e| anyhow!("get smt leaf err: {:?}", e))?;
+            if value.to_h256() != H256::zero() {
+                return Err(anyhow!(
+                    "lock event {} is already consumed",
+                    hex::encode(key.as_slice())
+                ));
+            }
+            leaves.push((*key, H256::zero()));
+        }
+        let proof = smt_tree
+            .merkle_proof(keys.clone())
+            .map_err(|e| anyhow!("build merkle proof err: {:?}", e))?
+            .compile(leaves)
+            .map_err(|e| anyhow!("compile merkle proof err: {:?}", e))?;
+        for key in keys {
+            smt_tree
+                .update(key, CONSUMED_EVENT_VALUE.into())
+                .map_err(|e| anyhow!("update smt leaf err: {:?}", e))?;
+        }
+        Ok((smt_tree, proof))
     }
 }
 
@@ -273,6 +612,47 @@ impl Into<[u8; 32]> for RocksDBValue {
     }
 }
 
+// the smt key of an eth block is its height in little endian, padding to 32 bytes.
+pub fn get_smt_key_for_height(height: u64) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    key[..8].copy_from_slice(height.to_le_bytes().as_ref());
+    key
+}
+
+// remove the leaves of heights in [from_height, to_height) out of the retention window, the
+// removed nodes are deleted from the rocksdb when the smt is committed.
+pub fn prune_smt_range(smt_tree: &mut SMT, from_height: u64, to_height: u64) -> Result<()> {
+    for height in from_height..to_height {
+        smt_tree
+            .update(get_smt_key_for_height(height).into(), [0u8; 32].into())
+            .map_err(|e| anyhow!("prune smt leaf of height {} err: {:?}", height, e))?;
+    }
+    Ok(())
+}
+
+// remove the leaves below start_height, which are pruned from the light client cell. The leaves
+// kept are contiguous heights, so the walk goes down from start_height - 1 till an empty leaf.
+// Return the number of the pruned leaves.
+pub fn prune_smt_below(smt_tree: &mut SMT, start_height: u64) -> Result<u64> {
+    let mut pruned = 0;
+    let mut height = start_height;
+    while height > 0 {
+        height -= 1;
+        let key: H256 = get_smt_key_for_height(height).into();
+        let value = smt_tree
+            .get(&key)
+            .map_err(|e| anyhow!("get smt leaf of height {} err: {:?}", height, e))?;
+        if value.to_h256().is_zero() {
+            break;
+        }
+        smt_tree
+            .update(key, [0u8; 32].into())
+            .map_err(|e| anyhow!("prune smt leaf of height {} err: {:?}", height, e))?;
+        pruned += 1;
+    }
+    Ok(pruned)
+}
+
 fn get_db_key_for_branch(key: &[u8]) -> Vec<u8> {
     let mut db_key = vec![];
     db_key.extend_from_slice(BRANCH_PREFIX.as_ref());
@@ -287,6 +667,34 @@ fn get_db_key_for_leaf(key: &[u8]) -> Vec<u8> {
     db_key
 }
 
+fn get_db_key_for_snapshot_removed(root: &[u8; 32]) -> Vec<u8> {
+    let mut db_key = vec![];
+    db_key.extend_from_slice(SNAPSHOT_REMOVED_PREFIX.as_ref());
+    db_key.extend_from_slice(root);
+    db_key
+}
+
+fn delete_snapshot_nodes(
+    batch: &mut WriteBatch,
+    root: &[u8; 32],
+    removed: &DBSnapshotRemoved,
+) -> Result<()> {
+    for key in removed.branches.iter() {
+        batch
+            .delete(get_db_key_for_branch(key))
+            .map_err(|e| anyhow!("delete branch err: {:?}", e))?;
+    }
+    for key in removed.leaves.iter() {
+        batch
+            .delete(get_db_key_for_leaf(key))
+            .map_err(|e| anyhow!("delete leaf err: {:?}", e))?;
+    }
+    batch
+        .delete(get_db_key_for_snapshot_removed(root))
+        .map_err(|e| anyhow!("delete snapshot removed nodes err: {:?}", e))?;
+    Ok(())
+}
+
 pub fn open_rocksdb(path: String) -> Result<Arc<DB>> {
     let db_dir = shellexpand::tilde(path.as_str()).into_owned();
     let db_path = Path::new(db_dir.as_str());
@@ -340,3 +748,136 @@ pub fn open_readonly_rocksdb(path: String) -> Result<Arc<ReadOnlyDB>> {
 //     assert!(v2.is_none());
 //     assert!(v3.is_some());
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_smt(name: &str, snapshot_limit: usize) -> SMT {
+        let path =
+            std::env::temp_dir().join(format!("force-rocksdb-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        let mut store = RocksDBStore::new(path.to_str().unwrap().to_owned()).unwrap();
+        store.set_snapshot_limit(snapshot_limit);
+        SMT::new(H256::zero(), store)
+    }
+
+    // set the leaves of height 1 and `value` to `value`, and commit the new root.
+    fn commit_value(smt_tree: &mut SMT, value: u8) -> [u8; 32] {
+        for height in &[1, value as u64] {
+            smt_tree
+                .update(get_smt_key_for_height(*height).into(), [value; 32].into())
+                .unwrap();
+        }
+        let root: [u8; 32] = (*smt_tree.root()).into();
+        smt_tree
+            .store_mut()
+            .commit_with_root(root, value as u64)
+            .unwrap();
+        root
+    }
+
+    fn get_value(store: &RocksDBStore<RocksDBValue>, root: [u8; 32], height: u64) -> [u8; 32] {
+        SMT::new(root.into(), store.committed_store())
+            .get(&get_smt_key_for_height(height).into())
+            .map(Into::into)
+            .unwrap_or([0u8; 32])
+    }
+
+    fn snapshot_roots(store: &RocksDBStore<RocksDBValue>) -> Vec<[u8; 32]> {
+        store
+            .get_snapshots()
+            .unwrap()
+            .iter()
+            .map(|s| s.root)
+            .collect()
+    }
+
+    #[test]
+    fn test_expired_snapshots_are_deleted() {
+        let mut smt_tree = new_smt("expired-snapshots", 2);
+        let roots = (1..=3)
+            .map(|value| commit_value(&mut smt_tree, value))
+            .collect::<Vec<_>>();
+        let store = smt_tree.store();
+        assert_eq!(snapshot_roots(store), roots[1..].to_vec());
+
+        // the nodes removed after the expired root are deleted together with its snapshot
+        assert!(!store.has_snapshot(roots[0]).unwrap());
+        assert!(store
+            .get_raw(get_db_key_for_snapshot_removed(&roots[0]).as_slice())
+            .unwrap()
+            .is_none());
+        assert_ne!(get_value(store, roots[0], 1), [1u8; 32]);
+        assert!(store.merkle_proof_for(roots[0], &[1]).is_err());
+
+        // the kept snapshots are still readable and provable
+        assert_eq!(get_value(store, roots[1], 1), [2u8; 32]);
+        assert_eq!(get_value(store, roots[2], 1), [3u8; 32]);
+        assert!(store.merkle_proof_for(roots[1], &[1, 2]).is_ok());
+        assert!(store.merkle_proof_for(roots[2], &[1, 2, 3]).is_ok());
+    }
+
+    #[test]
+    fn test_root_committed_again() {
+        let mut smt_tree = new_smt("root-committed-again", 4);
+        let first = commit_value(&mut smt_tree, 1);
+        let second = commit_value(&mut smt_tree, 2);
+
+        // reset the smt to the first root, as the relayer does when the cell is rolled back
+        smt_tree
+            .update(get_smt_key_for_height(1).into(), [1u8; 32].into())
+            .unwrap();
+        smt_tree
+            .update(get_smt_key_for_height(2).into(), [0u8; 32].into())
+            .unwrap();
+        let root: [u8; 32] = (*smt_tree.root()).into();
+        assert_eq!(root, first);
+        smt_tree.store_mut().commit_with_root(first, 1).unwrap();
+
+        let store = smt_tree.store();
+        assert_eq!(snapshot_roots(store), vec![second, first]);
+        assert_eq!(get_value(store, first, 1), [1u8; 32]);
+        assert_eq!(get_value(store, first, 2), [0u8; 32]);
+        assert_eq!(get_value(store, second, 1), [2u8; 32]);
+        assert_eq!(get_value(store, second, 2), [2u8; 32]);
+    }
+
+    #[test]
+    fn test_consumed_keys_committed_after_confirmed() {
+        let smt_tree = new_smt("consumed-keys", DEFAULT_SNAPSHOT_LIMIT);
+        let store = smt_tree.store();
+        let keys = [[1u8; 32], [2u8; 32]];
+
+        // the keys are not written before the tx consuming them is committed, so they can be
+        // consumed again if the tx fails
+        let (_, new_root) = store.consume_keys([0u8; 32], &keys).unwrap();
+        assert!(store.get_committed_root().unwrap().is_none());
+        let (_, retried_root) = store.consume_keys([0u8; 32], &keys).unwrap();
+        assert_eq!(retried_root, new_root);
+
+        assert_eq!(
+            store.commit_consumed_keys([0u8; 32], &keys).unwrap(),
+            new_root
+        );
+        assert_eq!(store.get_committed_root().unwrap(), Some((new_root, 0)));
+        assert!(store
+            .consume_keys(new_root, &keys[..1])
+            .unwrap_err()
+            .to_string()
+            .contains("already consumed"));
+        assert!(store.consume_keys(new_root, &[[3u8; 32]]).is_ok());
+    }
+
+    #[test]
+    fn test_no_snapshot_kept() {
+        let mut smt_tree = new_smt("no-snapshot", 0);
+        let first = commit_value(&mut smt_tree, 1);
+        let second = commit_value(&mut smt_tree, 2);
+        let store = smt_tree.store();
+        assert_eq!(snapshot_roots(store), vec![second]);
+        assert!(!store.has_snapshot(first).unwrap());
+        assert_ne!(get_value(store, first, 1), [1u8; 32]);
+        assert_eq!(get_value(store, second, 1), [2u8; 32]);
+    }
+}
@@ -261,6 +261,32 @@ pub fn collect_sudt_amount(
     Ok(collected_amount)
 }
 
+// the total amount of the sudt held by all the lockscripts.
+pub fn collect_sudt_supply(
+    indexer_client: &mut IndexerRpcClient,
+    sudt_typescript: Script,
+) -> Result<u128, String> {
+    let mut collected_amount = 0u128;
+    let terminator = |_, cell: &Cell| {
+        if cell.output_data.len() >= UDT_LEN {
+            collected_amount = collected_amount.saturating_add({
+                let mut buf = [0u8; UDT_LEN];
+                buf.copy_from_slice(&cell.output_data.as_bytes()[..UDT_LEN]);
+                u128::from_le_bytes(buf)
+            });
+        }
+        (false, false)
+    };
+    let search_key = SearchKey {
+        script: sudt_typescript.into(),
+        script_type: ScriptType::Type,
+        args_len: None,
+    };
+
+    get_live_cells(indexer_client, search_key, terminator)?;
+    Ok(collected_amount)
+}
+
 pub fn collect_bridge_cells(
     indexer_client: &mut IndexerRpcClient,
     lockscript: Script,
@@ -344,6 +344,48 @@ impl TxHelper {
         Ok(signatures)
     }
 
+    // The message signed by the members of the multisig lock, it is used to verify the signatures
+    // collected from other members.
+    #[allow(clippy::mutable_key_type)]
+    pub fn multisig_message<C>(
+        &self,
+        get_live_cell: C,
+        skip_check: bool,
+        multisig_hash160: &H160,
+    ) -> Result<H256, String>
+    where
+        C: FnMut(OutPoint, bool) -> Result<CellOutput, String>,
+    {
+        let witnesses = self.init_witnesses();
+        let input_size = self.transaction.inputs().len();
+        for ((code_hash, lock_arg), idxs) in
+            self.input_group(get_live_cell, skip_check)?.into_iter()
+        {
+            if code_hash != MULTISIG_TYPE_HASH.pack()
+                || &lock_arg[..20] != multisig_hash160.as_bytes()
+            {
+                continue;
+            }
+            let mut multisig_message = None;
+            build_signature(
+                &self.transaction,
+                input_size,
+                &idxs,
+                &witnesses,
+                self.multisig_configs.get(multisig_hash160),
+                |message: &H256, _tx: &rpc_types::Transaction| {
+                    multisig_message = Some(message.clone());
+                    Ok([0u8; SECP_SIGNATURE_SIZE])
+                },
+            )?;
+            return multisig_message.ok_or_else(|| "multisig message is not built".to_string());
+        }
+        Err(format!(
+            "No input locked by the multisig lock_arg: {:#x}",
+            multisig_hash160
+        ))
+    }
+
     #[allow(clippy::mutable_key_type)]
     pub fn build_tx<F: FnMut(OutPoint, bool) -> Result<CellOutput, String>>(
         &self,
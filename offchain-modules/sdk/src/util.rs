@@ -180,6 +180,25 @@ pub fn serialize_signature(signature: &secp256k1::recovery::RecoverableSignature
     signature_bytes
 }
 
+// recover the sighash lock arg of the key which signed the message.
+pub fn recover_lock_arg(message: &H256, signature: &[u8]) -> Result<H160, String> {
+    if signature.len() != 65 {
+        return Err(format!("invalid signature length {}", signature.len()));
+    }
+    let recov_id = secp256k1::recovery::RecoveryId::from_i32(signature[64] as i32)
+        .map_err(|err| err.to_string())?;
+    let signature =
+        secp256k1::recovery::RecoverableSignature::from_compact(&signature[0..64], recov_id)
+            .map_err(|err| err.to_string())?;
+    let message =
+        secp256k1::Message::from_slice(message.as_bytes()).map_err(|err| err.to_string())?;
+    let pubkey = SECP256K1
+        .recover(&message, &signature)
+        .map_err(|err| err.to_string())?;
+    H160::from_slice(&blake2b_256(&pubkey.serialize()[..])[0..20])
+        .map_err(|err| format!("{:?}", err))
+}
+
 pub fn check_capacity(capacity: u64, to_data_len: usize) -> Result<(), String> {
     if capacity < MIN_SECP_CELL_CAPACITY {
         return Err(format!(